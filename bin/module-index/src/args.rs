@@ -69,6 +69,10 @@ pub(crate) struct Args {
     #[arg(long, env)]
     pub(crate) s3_path_prefix: Option<String>,
 
+    /// Stores modules in this local directory instead of S3 [example: /var/lib/module-index]
+    #[arg(long, env)]
+    pub(crate) local_storage_path: Option<String>,
+
     /// The path to the JWT public signing key
     #[arg(long, env)]
     pub(crate) jwt_public_key: Option<String>,
@@ -124,6 +128,10 @@ impl TryFrom<Args> for Config {
             if let Some(s3_path_prefix) = args.s3_path_prefix {
                 config_map.set("s3.path_prefix", s3_path_prefix);
             }
+            if let Some(local_storage_path) = args.local_storage_path {
+                config_map.set("storage.backend", "localDirectory");
+                config_map.set("storage.path", local_storage_path);
+            }
             if let Some(jwt_public_key) = args.jwt_public_key {
                 config_map.set("jwt_signing_public_key_path", jwt_public_key);
             }
@@ -1,5 +1,5 @@
-use clap::{ArgAction, Parser};
-use pinga_server::{Config, ConfigError, ConfigFile, StandardConfigFile};
+use clap::{builder::PossibleValuesParser, ArgAction, Parser};
+use pinga_server::{Config, ConfigError, ConfigFile, JobQueueTransport, StandardConfigFile};
 
 const NAME: &str = "pinga";
 
@@ -53,10 +53,36 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) cyclone_encryption_key_path: Option<String>,
 
+    /// Symmetric crypto active key file location [default: /run/pinga/donkey.key]
+    #[arg(long)]
+    pub(crate) symmetric_crypto_active_key: Option<String>,
+
+    /// Extra symmetric crypto key file location, used only to decrypt data (can be repeated)
+    ///
+    /// Will error if set when `symmetric_crypto_active_key` is not set
+    #[arg(long, requires = "symmetric_crypto_active_key")]
+    pub(crate) symmetric_crypto_extra_key: Vec<String>,
+
     /// The number of concurrent jobs that can be processed [default: 10]
     #[arg(long)]
     pub(crate) concurrency: Option<u32>,
 
+    /// How often, in seconds, to poll for due job schedules [default: 10]
+    #[arg(long)]
+    pub(crate) schedule_poll_interval_secs: Option<u32>,
+
+    /// The transport jobs are received and sent over [default: nats]
+    #[arg(long, value_parser = PossibleValuesParser::new(JobQueueTransport::variants()))]
+    pub(crate) job_queue: Option<String>,
+
+    /// How many times a job is delivered over JetStream before giving up on it [default: 5]
+    #[arg(long)]
+    pub(crate) jetstream_max_deliver: Option<u32>,
+
+    /// How long, in seconds, JetStream waits for a job acknowledgement [default: 30]
+    #[arg(long)]
+    pub(crate) jetstream_ack_wait_secs: Option<u32>,
+
     /// Instance ID [example: 01GWEAANW5BVFK5KDRVS6DEY0F"]
     ///
     /// And instance ID is used when tracking the execution of jobs in a way that can be traced
@@ -91,9 +117,34 @@ impl TryFrom<Args> for Config {
             if let Some(cyclone_encyption_key_path) = args.cyclone_encryption_key_path {
                 config_map.set("cyclone_encryption_key_path", cyclone_encyption_key_path);
             }
+            if let Some(active_key) = args.symmetric_crypto_active_key {
+                config_map.set("symmetric_crypto_service.active_key", active_key);
+                config_map.set(
+                    "symmetric_crypto_service.extra_keys",
+                    args.symmetric_crypto_extra_key,
+                );
+            }
             if let Some(concurrency) = args.concurrency {
                 config_map.set("concurrency_limit", i64::from(concurrency));
             }
+            if let Some(schedule_poll_interval_secs) = args.schedule_poll_interval_secs {
+                config_map.set(
+                    "schedule_poll_interval_secs",
+                    i64::from(schedule_poll_interval_secs),
+                );
+            }
+            if let Some(job_queue) = args.job_queue {
+                config_map.set("job_queue", job_queue);
+            }
+            if let Some(jetstream_max_deliver) = args.jetstream_max_deliver {
+                config_map.set("jetstream_max_deliver", i64::from(jetstream_max_deliver));
+            }
+            if let Some(jetstream_ack_wait_secs) = args.jetstream_ack_wait_secs {
+                config_map.set(
+                    "jetstream_ack_wait_secs",
+                    i64::from(jetstream_ack_wait_secs),
+                );
+            }
             if let Some(instance_id) = args.instance_id {
                 config_map.set("instance_id", instance_id);
             }
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use clap::{builder::PossibleValuesParser, ArgAction, Parser};
-use sdf_server::{Config, ConfigError, ConfigFile, MigrationMode, StandardConfigFile};
+use sdf_server::{
+    Config, ConfigError, ConfigFile, JobQueueTransport, MigrationMode, StandardConfigFile,
+};
 
 const NAME: &str = "sdf";
 
@@ -51,6 +53,19 @@ pub(crate) struct Args {
     #[arg(long, value_parser = PossibleValuesParser::new(MigrationMode::variants()))]
     pub(crate) migration_mode: Option<String>,
 
+    /// The transport jobs are sent to pinga over [default: nats]
+    #[arg(long, value_parser = PossibleValuesParser::new(JobQueueTransport::variants()))]
+    pub(crate) job_queue: Option<String>,
+
+    /// Runs jobs inside sdf, so that neither pinga nor council are needed
+    #[arg(long)]
+    pub(crate) standalone_jobs: bool,
+
+    /// Periodically refreshes the resources of every component on this cron schedule [example:
+    /// "*/5 * * * *"] (resources aren't refreshed periodically if unset)
+    #[arg(long)]
+    pub(crate) resource_refresh_cron: Option<String>,
+
     /// Disable OpenTelemetry on startup
     #[arg(long)]
     pub(crate) disable_opentelemetry: bool,
@@ -71,6 +86,21 @@ pub(crate) struct Args {
     #[arg(long, requires = "generate_cyclone_secret_key_path")]
     pub(crate) generate_cyclone_public_key_path: Option<PathBuf>,
 
+    /// Symmetric crypto active key file location [default: /run/sdf/donkey.key]
+    #[arg(long)]
+    pub(crate) symmetric_crypto_active_key: Option<String>,
+
+    /// Extra symmetric crypto key file location, used only to decrypt data (can be repeated)
+    ///
+    /// Will error if set when `symmetric_crypto_active_key` is not set
+    #[arg(long, requires = "symmetric_crypto_active_key")]
+    pub(crate) symmetric_crypto_extra_key: Vec<String>,
+
+    /// Re-encrypts all data under the symmetric crypto active key, reports which key hashes are
+    /// still referenced and exits (does not run server)
+    #[arg(long)]
+    pub(crate) reencrypt_symmetric_data: bool,
+
     /// Location on disk of available packages
     pub(crate) pkgs_path: Option<String>,
 
@@ -102,12 +132,28 @@ impl TryFrom<Args> for Config {
             if let Some(migration_mode) = args.migration_mode {
                 config_map.set("migration_mode", migration_mode);
             }
+            if let Some(job_queue) = args.job_queue {
+                config_map.set("job_queue", job_queue);
+            }
+            if args.standalone_jobs {
+                config_map.set("standalone_jobs", true);
+            }
+            if let Some(resource_refresh_cron) = args.resource_refresh_cron {
+                config_map.set("resource_refresh_cron", resource_refresh_cron);
+            }
             if let Some(url) = args.nats_url {
                 config_map.set("nats.url", url);
             }
             if let Some(cyclone_encyption_key_path) = args.cyclone_encryption_key_path {
                 config_map.set("cyclone_encryption_key_path", cyclone_encyption_key_path);
             }
+            if let Some(active_key) = args.symmetric_crypto_active_key {
+                config_map.set("symmetric_crypto_service.active_key", active_key);
+                config_map.set(
+                    "symmetric_crypto_service.extra_keys",
+                    args.symmetric_crypto_extra_key,
+                );
+            }
             if let Some(pkgs_path) = args.pkgs_path {
                 config_map.set("pkgs_path", pkgs_path);
             }
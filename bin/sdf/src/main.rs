@@ -5,8 +5,8 @@ use std::sync::Arc;
 
 use color_eyre::Result;
 use sdf_server::{
-    Config, IncomingStream, JobProcessorClientCloser, JobProcessorConnector, MigrationMode, Server,
-    ServicesContext,
+    Config, IncomingStream, JetStreamProcessor, JobProcessorClientCloser, JobProcessorConnector,
+    JobQueueProcessor, JobQueueTransport, MigrationMode, NatsProcessor, Server, ServicesContext,
 };
 use telemetry_application::{
     prelude::*, start_tracing_level_signal_handler_task, ApplicationTelemetryClient,
@@ -15,8 +15,6 @@ use telemetry_application::{
 
 mod args;
 
-type JobProcessor = sdf_server::NatsProcessor;
-
 const RT_DEFAULT_THREAD_STACK_SIZE: usize = 2 * 1024 * 1024 * 10;
 
 fn main() -> Result<()> {
@@ -71,6 +69,8 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         return Ok(());
     }
 
+    let reencrypt_symmetric_data = args.reencrypt_symmetric_data;
+
     let config = Config::try_from(args)?;
 
     let encryption_key = Server::load_encryption_key(config.cyclone_encryption_key_path()).await?;
@@ -79,7 +79,18 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
 
     let nats_conn = Server::connect_to_nats(config.nats()).await?;
 
-    let (job_client, job_processor) = JobProcessor::connect(&config).await?;
+    let standalone_jobs = config
+        .standalone_jobs()
+        .then(Server::create_local_job_processor);
+
+    let (job_client, job_processor) = match (&standalone_jobs, config.job_queue()) {
+        (Some(local_processor), _) => (
+            nats_conn.clone(),
+            Box::new(local_processor.clone()) as Box<dyn JobQueueProcessor + Send + Sync>,
+        ),
+        (None, JobQueueTransport::JetStream) => JetStreamProcessor::connect(&config).await?,
+        (None, JobQueueTransport::Nats) => NatsProcessor::connect(&config).await?,
+    };
 
     let pg_pool = Server::create_pg_pool(config.pg_pool()).await?;
 
@@ -88,6 +99,8 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
     let symmetric_crypto_service =
         Server::create_symmetric_crypto_service(config.symmetric_crypto_service()).await?;
 
+    let secret_providers = Server::create_secret_providers(config.secret_providers())?;
+
     let pkgs_path: PathBuf = config.pkgs_path().try_into()?;
 
     let module_index_url = config.module_index_url().to_string();
@@ -101,8 +114,21 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         Some(pkgs_path),
         Some(module_index_url),
         symmetric_crypto_service,
+        secret_providers,
     );
 
+    // The processor and council are started before migrating, as installing builtins runs jobs
+    let services_context = match standalone_jobs {
+        Some(local_processor) => {
+            info!("running jobs in-process, pinga and council are not needed");
+            let services_context =
+                services_context.with_local_council(Server::create_local_council());
+            Server::start_standalone_jobs(local_processor, services_context.clone()).await?;
+            services_context
+        }
+        None => services_context,
+    };
+
     if let MigrationMode::Run | MigrationMode::RunAndQuit = config.migration_mode() {
         Server::migrate_database(&services_context).await?;
         if let MigrationMode::RunAndQuit = config.migration_mode() {
@@ -116,47 +142,78 @@ async fn run(args: args::Args, mut telemetry: ApplicationTelemetryClient) -> Res
         trace!("migration mode is skip, not running migrations");
     }
 
+    if reencrypt_symmetric_data {
+        Server::reencrypt_symmetric_data(&services_context).await?;
+        info!("symmetric data re-encryption complete, shutting down");
+        return Ok(());
+    }
+
     start_tracing_level_signal_handler_task(&telemetry)?;
 
     let posthog_client = Server::start_posthog(config.posthog()).await?;
 
+    let standalone_jobs = config.standalone_jobs();
+    let resource_refresh_cron = config.resource_refresh_cron().map(ToOwned::to_owned);
+
     match config.incoming_stream() {
         IncomingStream::HTTPSocket(_) => {
-            let (server, initial_shutdown_broadcast_rx) = Server::http(
+            let (server, shutdown_broadcast_rx) = Server::http(
                 config,
                 services_context.clone(),
                 jwt_public_signing_key,
                 posthog_client,
             )?;
-            let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
-            Server::start_resource_refresh_scheduler(
+            Server::ensure_resource_refresh_schedule(
                 services_context.clone(),
-                initial_shutdown_broadcast_rx,
+                resource_refresh_cron.as_deref(),
             )
-            .await;
-
-            Server::start_status_updater(services_context, second_shutdown_broadcast_rx).await?;
+            .await?;
+            Server::ensure_secret_expiry_check_schedule(services_context.clone()).await?;
+
+            if standalone_jobs {
+                Server::start_standalone_job_schedules(
+                    services_context.clone(),
+                    shutdown_broadcast_rx.resubscribe(),
+                );
+            }
+            Server::start_audit_log_forwarder(
+                services_context.clone(),
+                shutdown_broadcast_rx.resubscribe(),
+            )
+            .await?;
+            Server::start_status_updater(services_context, shutdown_broadcast_rx).await?;
 
             server.run().await?;
         }
         IncomingStream::UnixDomainSocket(_) => {
-            let (server, initial_shutdown_broadcast_rx) = Server::uds(
+            let (server, shutdown_broadcast_rx) = Server::uds(
                 config,
                 services_context.clone(),
                 jwt_public_signing_key,
                 posthog_client,
             )
             .await?;
-            let second_shutdown_broadcast_rx = initial_shutdown_broadcast_rx.resubscribe();
 
-            Server::start_resource_refresh_scheduler(
+            Server::ensure_resource_refresh_schedule(
                 services_context.clone(),
-                initial_shutdown_broadcast_rx,
+                resource_refresh_cron.as_deref(),
             )
-            .await;
-
-            Server::start_status_updater(services_context, second_shutdown_broadcast_rx).await?;
+            .await?;
+            Server::ensure_secret_expiry_check_schedule(services_context.clone()).await?;
+
+            if standalone_jobs {
+                Server::start_standalone_job_schedules(
+                    services_context.clone(),
+                    shutdown_broadcast_rx.resubscribe(),
+                );
+            }
+            Server::start_audit_log_forwarder(
+                services_context.clone(),
+                shutdown_broadcast_rx.resubscribe(),
+            )
+            .await?;
+            Server::start_status_updater(services_context, shutdown_broadcast_rx).await?;
 
             server.run().await?;
         }
@@ -145,7 +145,14 @@ pub(crate) struct StatusArgs {
 }
 
 #[derive(Debug, clap::Args)]
-pub(crate) struct StartArgs {}
+pub(crate) struct StartArgs {
+    /// Generates a new symmetric crypto key and makes it the active key.
+    ///
+    /// The current key is kept as the previous key, and is still loaded until its data has been
+    /// re-encrypted with `sdf --reencrypt-symmetric-data` and the previous key file is removed.
+    #[clap(long)]
+    pub rotate_symmetric_crypto_key: bool,
+}
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct RestartArgs {}
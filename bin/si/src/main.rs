@@ -119,8 +119,8 @@ async fn main() -> Result<()> {
         Commands::Launch(args) => {
             state.launch(args.metrics).await?;
         }
-        Commands::Start(_args) => {
-            state.start().await?;
+        Commands::Start(args) => {
+            state.start(args.rotate_symmetric_crypto_key).await?;
         }
         Commands::Configure(args) => {
             state.configure(args.force_reconfigure).await?;
@@ -2,8 +2,9 @@ use futures::StreamExt;
 use si_data_nats::{NatsClient, Subscriber};
 use std::time::Duration;
 use telemetry::prelude::*;
+use tokio::sync::mpsc;
 
-use crate::{Graph, Id, Request, Response};
+use crate::{Graph, Id, LocalCouncil, Request, Response};
 
 #[remain::sorted]
 #[derive(Debug)]
@@ -12,55 +13,73 @@ pub enum State {
     Shutdown,
 }
 
+/// How a client sends its requests to council.
+#[derive(Debug, Clone)]
+enum Transport {
+    Nats(NatsClient),
+    Local(LocalCouncil),
+}
+
+/// How a client receives council's responses.
+#[derive(Debug)]
+enum Responses {
+    Nats(Subscriber),
+    Local(mpsc::UnboundedReceiver<Response>),
+}
+
 #[derive(Debug, Clone)]
 pub struct PubClient {
     change_set_id: Id,
     pub_channel: String,
     reply_channel: String,
-    nats: NatsClient,
+    transport: Transport,
 }
 
 impl PubClient {
     pub async fn register_dependency_graph(&self, dependency_graph: Graph) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ValueDependencyGraph {
+        self.send(Request::ValueDependencyGraph {
             change_set_id: self.change_set_id,
             dependency_graph,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        })
+        .await
     }
 
     pub async fn processed_value(&self, node_id: Id) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ProcessedValue {
+        self.send(Request::ProcessedValue {
             change_set_id: self.change_set_id,
             node_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        })
+        .await
     }
 
     pub async fn failed_processing_value(&self, node_id: Id) -> Result<()> {
-        let message = serde_json::to_vec(&Request::ValueProcessingFailed {
+        self.send(Request::ValueProcessingFailed {
             change_set_id: self.change_set_id,
             node_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
-        Ok(())
+        })
+        .await
     }
 
     pub async fn bye(self) -> Result<()> {
-        let message = serde_json::to_vec(&Request::Bye {
+        self.send(Request::Bye {
             change_set_id: self.change_set_id,
-        })?;
-        self.nats
-            .publish_with_reply(&self.pub_channel, &self.reply_channel, message)
-            .await?;
+        })
+        .await
+    }
+
+    async fn send(&self, request: Request) -> Result<()> {
+        match &self.transport {
+            Transport::Nats(nats) => {
+                let message = serde_json::to_vec(&request)?;
+                nats.publish_with_reply(&self.pub_channel, &self.reply_channel, message)
+                    .await?;
+            }
+            Transport::Local(council) => {
+                if !council.send(&self.reply_channel, request) {
+                    return Err(Error::NoListenerAvailable);
+                }
+            }
+        }
         Ok(())
     }
 }
@@ -70,8 +89,8 @@ pub struct Client {
     change_set_id: Id,
     pub_channel: String,
     reply_channel: String,
-    subscriber: Subscriber,
-    nats: NatsClient,
+    responses: Responses,
+    transport: Transport,
 }
 
 impl Client {
@@ -86,18 +105,32 @@ impl Client {
         Ok(Self {
             pub_channel,
             change_set_id,
-            subscriber: nats.subscribe(&reply_channel).await?,
+            responses: Responses::Nats(nats.subscribe(&reply_channel).await?),
             reply_channel,
-            nats,
+            transport: Transport::Nats(nats),
         })
     }
 
+    /// Creates a client of the in-process council served for `council`, rather than of council
+    /// over NATS.
+    pub fn new_local(council: LocalCouncil, id: Id, change_set_id: Id) -> Self {
+        let pub_channel = format!("council.{id}");
+        let reply_channel = format!("{pub_channel}.reply");
+        Self {
+            pub_channel,
+            change_set_id,
+            responses: Responses::Local(council.register(&reply_channel)),
+            reply_channel,
+            transport: Transport::Local(council),
+        }
+    }
+
     pub fn clone_into_pub(&self) -> PubClient {
         PubClient {
             pub_channel: self.pub_channel.clone(),
             reply_channel: self.reply_channel.clone(),
             change_set_id: self.change_set_id,
-            nats: self.nats.clone(),
+            transport: self.transport.clone(),
         }
     }
 
@@ -105,8 +138,21 @@ impl Client {
     pub async fn fetch_response(&mut self) -> Result<Option<Response>> {
         // TODO: timeout so we don't get stuck here forever if council goes away
         // TODO: handle message.data() empty with Status header as 503: https://github.com/nats-io/nats.go/pull/576
+        let subscriber = match &mut self.responses {
+            Responses::Nats(subscriber) => subscriber,
+            Responses::Local(responses) => loop {
+                let res = tokio::time::timeout(Duration::from_secs(60), responses.recv()).await;
+
+                match res {
+                    Ok(response) => return Ok(response),
+                    Err(_) => {
+                        warn!(change_set_id = ?self.change_set_id, pub_channel = ?self.pub_channel, reply_channel = ?self.reply_channel, "Council client waiting for response for 60 seconds");
+                    }
+                }
+            },
+        };
         let msg = loop {
-            let res = tokio::time::timeout(Duration::from_secs(60), self.subscriber.next()).await;
+            let res = tokio::time::timeout(Duration::from_secs(60), subscriber.next()).await;
 
             match res {
                 Ok(msg) => break msg,
@@ -142,6 +188,14 @@ impl Client {
     }
 }
 
+impl Drop for Client {
+    fn drop(&mut self) {
+        if let Transport::Local(council) = &self.transport {
+            council.unregister(&self.reply_channel);
+        }
+    }
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[remain::sorted]
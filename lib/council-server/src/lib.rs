@@ -3,9 +3,11 @@ use std::collections::HashMap;
 use ulid::Ulid;
 
 pub mod client;
+pub mod local;
 pub mod server;
 
 pub use client::{Client, PubClient};
+pub use local::LocalCouncil;
 pub use server::Server;
 
 #[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash)]
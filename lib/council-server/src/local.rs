@@ -0,0 +1,84 @@
+//! An in-process council, for running dependent values updates in the same process as the jobs
+//! which send requests to it, without NATS. See [`Server::from_local`](crate::Server::from_local)
+//! and [`Client::new_local`](crate::Client::new_local).
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::mpsc;
+
+use crate::{Request, Response};
+
+/// A request sent to a [`LocalCouncil`], with the reply channel its responses are sent to.
+pub(crate) type LocalRequest = (String, Request);
+
+/// The channels between an in-process council [`Server`](crate::Server) and its
+/// [`Clients`](crate::Client). Clones share the same channels.
+#[derive(Clone, Debug)]
+pub struct LocalCouncil {
+    requests_tx: mpsc::UnboundedSender<LocalRequest>,
+    requests_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<LocalRequest>>>>,
+    reply_channels: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<Response>>>>,
+}
+
+impl LocalCouncil {
+    pub fn new() -> Self {
+        let (requests_tx, requests_rx) = mpsc::unbounded_channel();
+        Self {
+            requests_tx,
+            requests_rx: Arc::new(Mutex::new(Some(requests_rx))),
+            reply_channels: Default::default(),
+        }
+    }
+
+    /// Takes the receiving end of the requests, which only one server can do.
+    pub(crate) fn take_requests(&self) -> Option<mpsc::UnboundedReceiver<LocalRequest>> {
+        self.requests_rx
+            .lock()
+            .expect("local council lock is poisoned")
+            .take()
+    }
+
+    pub(crate) fn send(&self, reply_channel: &str, request: Request) -> bool {
+        self.requests_tx
+            .send((reply_channel.to_owned(), request))
+            .is_ok()
+    }
+
+    pub(crate) fn register(&self, reply_channel: &str) -> mpsc::UnboundedReceiver<Response> {
+        let (responses_tx, responses_rx) = mpsc::unbounded_channel();
+        self.reply_channels
+            .lock()
+            .expect("local council lock is poisoned")
+            .insert(reply_channel.to_owned(), responses_tx);
+        responses_rx
+    }
+
+    pub(crate) fn unregister(&self, reply_channel: &str) {
+        self.reply_channels
+            .lock()
+            .expect("local council lock is poisoned")
+            .remove(reply_channel);
+    }
+
+    /// Sends a response to a client. Responses to clients which have gone away are dropped, like
+    /// NATS would.
+    pub(crate) fn reply(&self, reply_channel: &str, response: Response) {
+        if let Some(responses_tx) = self
+            .reply_channels
+            .lock()
+            .expect("local council lock is poisoned")
+            .get(reply_channel)
+        {
+            let _ = responses_tx.send(response);
+        }
+    }
+}
+
+impl Default for LocalCouncil {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,9 +2,14 @@ use crate::{Graph, Id, Request, Response};
 use std::time::Duration;
 
 use futures::StreamExt;
-use si_data_nats::NatsClient;
+use si_data_nats::{propagation, HeaderMap, NatsClient, Subscriber};
 use telemetry::prelude::*;
-use tokio::{signal, sync::watch};
+use tokio::{
+    signal,
+    sync::{mpsc, watch},
+};
+
+use crate::local::{LocalCouncil, LocalRequest};
 
 pub mod config;
 mod graph;
@@ -14,35 +19,55 @@ use graph::ChangeSetGraph;
 
 #[derive(Debug, Clone)]
 pub struct Server {
-    nats: NatsClient,
+    transport: Transport,
 }
 
 impl Server {
     pub async fn new_with_config(config: config::Config) -> Result<Self> {
         Ok(Self {
-            nats: NatsClient::new(config.nats()).await?,
+            transport: Transport::Nats(NatsClient::new(config.nats()).await?),
         })
     }
 
+    /// Builds a server which serves the [`Clients`](crate::Client) of a [`LocalCouncil`] rather
+    /// than NATS, for running jobs in-process.
+    pub fn from_local(council: LocalCouncil) -> Self {
+        Self {
+            transport: Transport::Local(council),
+        }
+    }
+
     pub async fn run(
         self,
         subscriber_started_tx: watch::Sender<()>,
         mut shutdown_request_rx: watch::Receiver<()>,
     ) -> Result<()> {
-        let channel_suffix = "council.*";
-        let subscriber_channel = if let Some(prefix) = self.nats.metadata().subject_prefix() {
-            format!("{}.{}", prefix, channel_suffix)
-        } else {
-            channel_suffix.to_string()
-        };
-        let mut subscriber = loop {
-            match self.nats.subscribe(subscriber_channel.clone()).await {
-                Ok(sub) => break sub,
-                Err(err) => {
-                    error!("Unable to subscribe to the council request channel on nats: {err}");
-                    tokio::time::sleep(Duration::from_millis(1000)).await;
-                }
+        let mut requests = match &self.transport {
+            Transport::Nats(nats) => {
+                let channel_suffix = "council.*";
+                let subscriber_channel = if let Some(prefix) = nats.metadata().subject_prefix() {
+                    format!("{}.{}", prefix, channel_suffix)
+                } else {
+                    channel_suffix.to_string()
+                };
+                let subscriber = loop {
+                    match nats.subscribe(subscriber_channel.clone()).await {
+                        Ok(sub) => break sub,
+                        Err(err) => {
+                            error!(
+                                "Unable to subscribe to the council request channel on nats: {err}"
+                            );
+                            tokio::time::sleep(Duration::from_millis(1000)).await;
+                        }
+                    }
+                };
+                Requests::Nats(subscriber)
             }
+            Transport::Local(council) => Requests::Local(
+                council
+                    .take_requests()
+                    .ok_or(Error::LocalCouncilAlreadyServed)?,
+            ),
         };
         let _ = subscriber_started_tx.send(());
 
@@ -72,37 +97,23 @@ impl Server {
         loop {
             for (reply_channel, node_ids) in complete_graph.fetch_all_available() {
                 info!(%reply_channel, ?node_ids, "Ok to process AttributeValue");
-                self.nats
-                    .publish(
-                        reply_channel,
-                        serde_json::to_vec(&Response::OkToProcess { node_ids }).unwrap(),
-                    )
-                    .await
-                    .unwrap();
+                self.transport
+                    .reply(reply_channel, Response::OkToProcess { node_ids })
+                    .await;
             }
 
             let sleep = tokio::time::sleep(Duration::from_secs(60));
             tokio::pin!(sleep);
             // FIXME: handle timeouts
-            let (reply_channel, request) = tokio::select! {
+            let (reply_channel, request, headers) = tokio::select! {
                 _ = &mut sleep => {
                     if !complete_graph.is_empty() {
                         warn!(?complete_graph, "Council has values in graph but has been waiting for messages for 60 seconds");
                     }
                     continue;
                 }
-                req = subscriber.next() => match req {
-                    Some(msg) => match (serde_json::from_slice::<Request>(msg.payload()), msg.reply()) {
-                        (Ok(req), Some(reply)) => (reply.to_owned(), req),
-                        (Err(err), _) => {
-                            error!("Unable to deserialize request: {err}");
-                            continue;
-                        }
-                        _ => {
-                            error!("No reply channel provided: {msg:?}");
-                            continue;
-                        }
-                    }
+                req = requests.next() => match req {
+                    Some(req) => req,
                     // FIXME: reconnect
                     None => break, // Happens if subscriber has been unsubscribed or if connection is closed
                 },
@@ -117,60 +128,122 @@ impl Server {
                 else => unreachable!(),
             };
 
-            match request {
-                Request::ValueDependencyGraph {
-                    change_set_id,
-                    dependency_graph,
-                } => {
-                    register_graph_from_job(
-                        &mut complete_graph,
-                        reply_channel,
+            // Continue the trace of the job which sent the request
+            let span = info_span!(
+                "council.process_request",
+                otel.kind = %FormattedSpanKind(SpanKind::Consumer),
+            );
+            propagation::set_parent_from_headers(&span, headers.as_ref());
+
+            async {
+                match request {
+                    Request::ValueDependencyGraph {
                         change_set_id,
                         dependency_graph,
-                    )
-                    .await
-                    .unwrap();
-                }
-                Request::ProcessedValue {
-                    change_set_id,
-                    node_id,
-                } => {
-                    job_processed_a_value(
-                        &self.nats,
-                        &mut complete_graph,
-                        reply_channel,
+                    } => {
+                        register_graph_from_job(
+                            &mut complete_graph,
+                            reply_channel,
+                            change_set_id,
+                            dependency_graph,
+                        )
+                        .await
+                        .unwrap();
+                    }
+                    Request::ProcessedValue {
                         change_set_id,
                         node_id,
-                    )
-                    .await
-                    .unwrap();
-                }
-                Request::Bye { change_set_id } => {
-                    job_is_going_away(&mut complete_graph, reply_channel, change_set_id)
+                    } => {
+                        job_processed_a_value(
+                            &self.transport,
+                            &mut complete_graph,
+                            reply_channel,
+                            change_set_id,
+                            node_id,
+                        )
                         .await
                         .unwrap();
-                }
-                Request::ValueProcessingFailed {
-                    change_set_id,
-                    node_id,
-                } => {
-                    job_failed_processing_a_value(
-                        &self.nats,
-                        &mut complete_graph,
-                        reply_channel,
+                    }
+                    Request::Bye { change_set_id } => {
+                        job_is_going_away(&mut complete_graph, reply_channel, change_set_id)
+                            .await
+                            .unwrap();
+                    }
+                    Request::ValueProcessingFailed {
                         change_set_id,
                         node_id,
-                    )
-                    .await
-                    .unwrap();
-                }
-            };
+                    } => {
+                        job_failed_processing_a_value(
+                            &self.transport,
+                            &mut complete_graph,
+                            reply_channel,
+                            change_set_id,
+                            node_id,
+                        )
+                        .await
+                        .unwrap();
+                    }
+                };
+            }
+            .instrument(span)
+            .await;
         }
 
         Ok(())
     }
 }
 
+/// How a [`Server`] receives requests and sends responses.
+#[derive(Debug, Clone)]
+enum Transport {
+    Nats(NatsClient),
+    Local(LocalCouncil),
+}
+
+impl Transport {
+    async fn reply(&self, reply_channel: String, response: Response) {
+        match self {
+            Self::Nats(nats) => nats
+                .publish(reply_channel, serde_json::to_vec(&response).unwrap())
+                .await
+                .unwrap(),
+            Self::Local(council) => council.reply(&reply_channel, response),
+        }
+    }
+}
+
+/// The incoming requests of a [`Server`].
+enum Requests {
+    Nats(Subscriber),
+    Local(mpsc::UnboundedReceiver<LocalRequest>),
+}
+
+impl Requests {
+    /// Returns the next valid request with its reply channel, or `None` once no more requests
+    /// can arrive.
+    async fn next(&mut self) -> Option<(String, Request, Option<HeaderMap>)> {
+        match self {
+            Self::Nats(subscriber) => loop {
+                let msg = subscriber.next().await?;
+                match (
+                    serde_json::from_slice::<Request>(msg.payload()),
+                    msg.reply(),
+                ) {
+                    (Ok(req), Some(reply)) => {
+                        return Some((reply.to_owned(), req, msg.headers().cloned()))
+                    }
+                    (Err(err), _) => error!("Unable to deserialize request: {err}"),
+                    _ => error!("No reply channel provided: {msg:?}"),
+                }
+            },
+            Self::Local(requests) => requests
+                .recv()
+                .await
+                .map(|(reply_channel, req)| (reply_channel, req, None)),
+        }
+    }
+}
+
 // Note: All messages from Pinga include the change set ID.
 //
 // | Pinga                                                                      | Council                                                                                                                                                                                                                        |
@@ -198,6 +271,8 @@ pub enum Error {
     Config(#[from] config::ConfigError),
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("the local council is already being served")]
+    LocalCouncilAlreadyServed,
     #[error(transparent)]
     Nats(#[from] si_data_nats::Error),
     #[error("Job reported finishing processing, but we expected a different job to be processing")]
@@ -219,9 +294,9 @@ pub async fn register_graph_from_job(
     complete_graph.merge_dependency_graph(reply_channel, new_dependency_data, change_set_id)
 }
 
-#[instrument(level = "info", skip(nats, complete_graph))]
-pub async fn job_processed_a_value(
-    nats: &NatsClient,
+#[instrument(level = "info", skip(transport, complete_graph))]
+async fn job_processed_a_value(
+    transport: &Transport,
     complete_graph: &mut ChangeSetGraph,
     reply_channel: String,
     change_set_id: Id,
@@ -232,20 +307,17 @@ pub async fn job_processed_a_value(
         complete_graph.mark_node_as_processed(&reply_channel, change_set_id, node_id)?
     {
         info!(%reply_channel, ?node_id, "AttributeValue has been processed by a job");
-        nats.publish(
-            reply_channel,
-            serde_json::to_vec(&Response::BeenProcessed { node_id }).unwrap(),
-        )
-        .await
-        .unwrap();
+        transport
+            .reply(reply_channel, Response::BeenProcessed { node_id })
+            .await;
     }
     debug!(?complete_graph);
     Ok(())
 }
 
-#[instrument(level = "info", skip(nats, complete_graph))]
-pub async fn job_failed_processing_a_value(
-    nats: &NatsClient,
+#[instrument(level = "info", skip(transport, complete_graph))]
+async fn job_failed_processing_a_value(
+    transport: &Transport,
     complete_graph: &mut ChangeSetGraph,
     reply_channel: String,
     change_set_id: Id,
@@ -256,15 +328,14 @@ pub async fn job_failed_processing_a_value(
     for (reply_channel, failed_node_id) in
         complete_graph.remove_node_and_dependents(reply_channel, change_set_id, node_id)?
     {
-        nats.publish(
-            reply_channel,
-            serde_json::to_vec(&Response::Failed {
-                node_id: failed_node_id,
-            })
-            .unwrap(),
-        )
-        .await
-        .unwrap();
+        transport
+            .reply(
+                reply_channel,
+                Response::Failed {
+                    node_id: failed_node_id,
+                },
+            )
+            .await;
     }
 
     Ok(())
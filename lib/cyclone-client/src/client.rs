@@ -26,12 +26,23 @@ use hyper::{
     Body, Method, Request, Response, StatusCode, Uri,
 };
 use hyperlocal::{UnixClientExt, UnixConnector, UnixStream};
+use telemetry::{
+    prelude::*,
+    propagation::{self, Injector},
+};
 use thiserror::Error;
 use tokio::{
     io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
     net::TcpStream,
 };
-use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::{
+    tungstenite::{
+        client::IntoClientRequest,
+        handshake::client::Request as WebsocketRequest,
+        http::{HeaderMap, HeaderName, HeaderValue},
+    },
+    WebSocketStream,
+};
 
 use crate::{execution, ping, watch, Execution, PingExecution, Watch};
 
@@ -378,22 +389,22 @@ where
         Ok(Request::builder().uri(uri))
     }
 
-    fn new_ws_request<P>(&self, path_and_query: P) -> Result<Uri>
+    fn new_ws_request<P>(&self, path_and_query: P) -> Result<WebsocketRequest>
     where
         P: TryInto<PathAndQuery, Error = InvalidUri>,
     {
         let uri = self.ws_request_uri(path_and_query)?;
 
         // Tokio Tungstenite now requires that the request be perfectly created
-        // for websocket upgrades. If you use a URL, everything works.
+        // for websocket upgrades, so it builds the request from the URL and we only add to it.
+        let mut request = uri
+            .into_client_request()
+            .map_err(ClientError::WebsocketConnection)?;
 
-        //let request = Request::builder()
-        //    .uri(uri)
-        //    .method(Method::GET)
-        //    .body(())
-        //    .map_err(ClientError::Request)?;
+        // Carry the trace context of the current span so the execution in cyclone continues it
+        propagation::inject(&Span::current(), &mut HeaderInjector(request.headers_mut()));
 
-        Ok(uri)
+        Ok(request)
     }
 
     async fn get<P>(&self, path_and_query: P) -> Result<Response<Body>>
@@ -442,8 +453,8 @@ where
             stream = self.connect(stream).await?;
         }
 
-        let uri = self.new_ws_request(path_and_query)?;
-        let (websocket_stream, response) = tokio_tungstenite::client_async(uri, stream)
+        let request = self.new_ws_request(path_and_query)?;
+        let (websocket_stream, response) = tokio_tungstenite::client_async(request, stream)
             .await
             .map_err(ClientError::WebsocketConnection)?;
         if response.status() != StatusCode::SWITCHING_PROTOCOLS {
@@ -454,6 +465,19 @@ where
     }
 }
 
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
 #[derive(Debug)]
 struct ClientConfig {
     firecracker_connect: bool,
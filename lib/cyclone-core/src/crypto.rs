@@ -1,5 +1,12 @@
-use std::collections::HashSet;
-
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    ops::Range,
+};
+
+use base64::{
+    engine::{general_purpose, GeneralPurpose},
+    Engine,
+};
 use serde_json::{json, Value};
 use si_crypto::{CycloneDecryptionKey, CycloneDecryptionKeyError, CycloneEncryptionKey};
 use si_std::SensitiveString;
@@ -10,6 +17,10 @@ const KEY_HASH_FIELD: &str = "keyHash";
 const CRYPTED_FIELD: &str = "crypted";
 const REDACTED_TXT: &str = "[redacted]";
 
+/// The shortest fragment of an encoded sensitive string which is redacted on its own. Shorter
+/// fragments would too often occur by chance.
+const MIN_ENCODED_FRAGMENT_LEN: usize = 4;
+
 #[derive(Debug, Error)]
 pub enum CycloneValueEncryptError {
     #[error("invalid json pointer: {0}")]
@@ -61,28 +72,274 @@ impl CycloneSensitiveStrings {
         self.0.extend(iter)
     }
 
+    /// Returns whether `s` contains any of the sensitive strings, in any of the forms described
+    /// in [`redact`](Self::redact).
     pub fn has_sensitive(&self, s: &str) -> bool {
+        !sensitive_ranges(&self.needles(), s).is_empty()
+    }
+
+    /// Replaces every occurrence of a sensitive string in `s` with a redaction marker.
+    ///
+    /// Besides the sensitive strings themselves, their base64, base64url, URL-encoded and
+    /// JSON-escaped forms are redacted, as are occurrences split across lines.
+    pub fn redact(&self, s: &str) -> String {
+        // Note: This brings a possibility of random substrings being matched out of context,
+        // exposing that we have a secret by censoring it But trying to infer word boundary
+        // might leak the plaintext credential which is arguably worse
+        redact_ranges(s, sensitive_ranges(&self.needles(), s))
+    }
+
+    /// Returns a [`SensitiveStreamRedactor`] for the current sensitive strings.
+    pub fn stream_redactor<T>(&self) -> SensitiveStreamRedactor<T> {
+        let needles = self.needles();
+        SensitiveStreamRedactor {
+            longest_needle: needles.iter().map(String::len).max().unwrap_or(0),
+            needles,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns every form of every sensitive string which is searched for, with line breaks
+    /// removed.
+    fn needles(&self) -> Vec<String> {
         self.0
             .iter()
-            .any(|sensitive_s| s.contains(sensitive_s.as_str()))
+            .flat_map(|sensitive_s| encoded_forms(sensitive_s.as_str()))
+            .map(|form| without_line_breaks(&form))
+            .filter(|form| !form.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
     }
+}
 
-    pub fn redact(&self, s: &str) -> String {
-        let mut redacted = s.to_string();
-
-        for redacted_str in self.0.iter() {
-            // Note: This brings a possibility of random substrings being matched out of context,
-            // exposing that we have a secret by censoring it But trying to infer word boundary
-            // might leak the plaintext credential which is arguably worse
-            if s.contains(redacted_str.as_str()) {
-                redacted = redacted.replace(redacted_str.as_str(), REDACTED_TXT);
+/// Redacts [`CycloneSensitiveStrings`] from a stream of chunks of text (such as the lines a
+/// function writes to its output), including sensitive strings which are split across chunks.
+///
+/// Each chunk is pushed along with an item of type `T` which is handed back with it. A chunk is
+/// held back until enough text has followed it that no sensitive string can start in it and
+/// continue into a chunk which has not arrived yet. Chunks which are still held back when the
+/// stream ends are returned by [`flush`](Self::flush).
+#[derive(Debug)]
+pub struct SensitiveStreamRedactor<T> {
+    needles: Vec<String>,
+    longest_needle: usize,
+    pending: VecDeque<PendingChunk<T>>,
+}
+
+#[derive(Debug)]
+struct PendingChunk<T> {
+    item: T,
+    text: String,
+    sensitive_ranges: Vec<Range<usize>>,
+}
+
+impl<T> SensitiveStreamRedactor<T> {
+    /// Pushes the next chunk of the stream, returning the chunks (and their items) which are now
+    /// redacted and ready, in stream order.
+    pub fn push(&mut self, item: T, text: String) -> Vec<(T, String)> {
+        self.pending.push_back(PendingChunk {
+            item,
+            text,
+            sensitive_ranges: Vec::new(),
+        });
+        self.mark_sensitive_ranges();
+
+        let mut ready = Vec::new();
+        while !self.pending.is_empty() {
+            // A sensitive string starting in the front chunk ends within the chunks after it
+            // once they hold at least as much text as the longest needle
+            let following_len: usize = self
+                .pending
+                .iter()
+                .skip(1)
+                .map(|following| without_line_breaks(&following.text).len())
+                .sum();
+            if following_len < self.longest_needle {
+                break;
+            }
+            if let Some(chunk) = self.pending.pop_front() {
+                ready.push(chunk.redacted());
             }
         }
+        ready
+    }
 
-        redacted
+    /// Returns every chunk still held back, redacted, in stream order.
+    pub fn flush(&mut self) -> Vec<(T, String)> {
+        self.pending.drain(..).map(PendingChunk::redacted).collect()
+    }
+
+    /// Finds the sensitive strings in the pending chunks (as if they were separate lines of one
+    /// text) and records the part of each occurrence which falls in each chunk. Ranges recorded
+    /// earlier are kept, since the chunks holding the start of an occurrence may be gone.
+    fn mark_sensitive_ranges(&mut self) {
+        let mut joined = String::new();
+        let mut chunk_starts = Vec::with_capacity(self.pending.len());
+        for (index, chunk) in self.pending.iter().enumerate() {
+            if index > 0 {
+                joined.push('\n');
+            }
+            chunk_starts.push(joined.len());
+            joined.push_str(&chunk.text);
+        }
+
+        for range in sensitive_ranges(&self.needles, &joined) {
+            for (chunk, chunk_start) in self.pending.iter_mut().zip(chunk_starts.iter()) {
+                let chunk_end = chunk_start + chunk.text.len();
+                let start = range.start.max(*chunk_start);
+                let end = range.end.min(chunk_end);
+                if start < end {
+                    chunk
+                        .sensitive_ranges
+                        .push(start - chunk_start..end - chunk_start);
+                }
+            }
+        }
     }
 }
 
+impl<T> PendingChunk<T> {
+    fn redacted(self) -> (T, String) {
+        (self.item, redact_ranges(&self.text, self.sensitive_ranges))
+    }
+}
+
+/// Returns the byte ranges of `s` holding any of the `needles`, merged and in order. Line breaks
+/// in `s` are ignored, so that needles split across lines are found too.
+fn sensitive_ranges(needles: &[String], s: &str) -> Vec<Range<usize>> {
+    if needles.is_empty() {
+        return Vec::new();
+    }
+
+    // The text without line breaks, and the position in `s` of each of its bytes
+    let mut stripped = String::with_capacity(s.len());
+    let mut origins = Vec::with_capacity(s.len());
+    for (index, c) in s.char_indices() {
+        if !is_line_break(c) {
+            stripped.push(c);
+            origins.extend(index..index + c.len_utf8());
+        }
+    }
+
+    let mut ranges: Vec<Range<usize>> = needles
+        .iter()
+        .flat_map(|needle| {
+            stripped
+                .match_indices(needle.as_str())
+                .map(|(start, matched)| origins[start]..origins[start + matched.len() - 1] + 1)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Replaces the given byte ranges of `s` with a redaction marker.
+fn redact_ranges(s: &str, mut ranges: Vec<Range<usize>>) -> String {
+    if ranges.is_empty() {
+        return s.to_string();
+    }
+    ranges.sort_by_key(|range| range.start);
+
+    let mut redacted = String::with_capacity(s.len());
+    let mut position = 0;
+    for range in ranges {
+        if range.end <= position {
+            continue;
+        }
+        redacted.push_str(&s[position..range.start.max(position)]);
+        redacted.push_str(REDACTED_TXT);
+        position = range.end;
+    }
+    redacted.push_str(&s[position..]);
+    redacted
+}
+
+/// Returns the forms in which a sensitive string is commonly written out: as is, JSON-escaped,
+/// URL-encoded, and base64 or base64url encoded (on its own or as part of a longer encoded
+/// value).
+fn encoded_forms(sensitive_s: &str) -> Vec<String> {
+    let mut forms = vec![sensitive_s.to_string()];
+
+    if let Ok(json) = serde_json::to_string(sensitive_s) {
+        forms.push(json[1..json.len() - 1].to_string());
+    }
+
+    // RFC 3986 unreserved characters (as most libraries encode), JavaScript's
+    // `encodeURIComponent` and HTML form encoding
+    let rfc_3986 = |b: u8| b.is_ascii_alphanumeric() || b"-._~".contains(&b);
+    let uri_component = |b: u8| b.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&b);
+    let html_form = |b: u8| b.is_ascii_alphanumeric() || b"*-._".contains(&b);
+    forms.push(url_encode(sensitive_s, rfc_3986, false, false));
+    forms.push(url_encode(sensitive_s, rfc_3986, false, true));
+    forms.push(url_encode(sensitive_s, rfc_3986, true, false));
+    forms.push(url_encode(sensitive_s, uri_component, false, false));
+    forms.push(url_encode(sensitive_s, html_form, true, false));
+
+    for (padded, unpadded) in [
+        (general_purpose::STANDARD, general_purpose::STANDARD_NO_PAD),
+        (general_purpose::URL_SAFE, general_purpose::URL_SAFE_NO_PAD),
+    ] {
+        forms.push(padded.encode(sensitive_s));
+        forms.push(unpadded.encode(sensitive_s));
+        forms.extend(base64_fragments(&unpadded, sensitive_s.as_bytes()));
+    }
+
+    forms
+}
+
+fn url_encode(s: &str, keep: impl Fn(u8) -> bool, space_as_plus: bool, lowercase: bool) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if keep(byte) {
+            encoded.push(byte as char);
+        } else if space_as_plus && byte == b' ' {
+            encoded.push('+');
+        } else if lowercase {
+            encoded.push_str(&format!("%{byte:02x}"));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// Returns the part of the base64 encoding of `bytes` which is the same wherever `bytes` occur
+/// in a longer encoded value, for each of the three ways `bytes` can be aligned to the 3 byte
+/// groups of base64.
+fn base64_fragments(engine: &GeneralPurpose, bytes: &[u8]) -> Vec<String> {
+    (0..3)
+        .filter_map(|offset| {
+            let mut aligned = vec![0; offset];
+            aligned.extend_from_slice(bytes);
+            let encoded = engine.encode(&aligned);
+
+            // Skip the characters holding bits of the filler bytes and the trailing character
+            // holding bits of whatever follows `bytes`
+            let start = if offset == 0 { 0 } else { offset + 1 };
+            let end = aligned.len() * 8 / 6;
+            (end >= start + MIN_ENCODED_FRAGMENT_LEN).then(|| encoded[start..end].to_string())
+        })
+        .collect()
+}
+
+fn is_line_break(c: char) -> bool {
+    c == '\n' || c == '\r'
+}
+
+fn without_line_breaks(s: &str) -> String {
+    s.chars().filter(|c| !is_line_break(*c)).collect()
+}
+
 pub fn encrypt_value_tree(
     value: &mut Value,
     encryption_key: &CycloneEncryptionKey,
@@ -670,5 +927,89 @@ mod tests {
                 sensitive_strings.redact("One pony said to the other pony: 'I have an apple.'")
             );
         }
+
+        #[test]
+        fn redact_encoded_matches() {
+            let mut sensitive_strings = CycloneSensitiveStrings::default();
+            sensitive_strings.insert("s3cret/pass word\"");
+
+            for encoded in [
+                general_purpose::STANDARD.encode("s3cret/pass word\""),
+                general_purpose::URL_SAFE_NO_PAD.encode("s3cret/pass word\""),
+                "s3cret%2Fpass%20word%22".to_string(),
+                "s3cret%2fpass%20word%22".to_string(),
+                "s3cret%2Fpass+word%22".to_string(),
+                "s3cret/pass word\\\"".to_string(),
+            ] {
+                assert_eq!(
+                    "token=[redacted];",
+                    sensitive_strings.redact(&format!("token={encoded};")),
+                    "{encoded} was not redacted",
+                );
+            }
+        }
+
+        #[test]
+        fn redact_base64_inside_longer_value() {
+            let mut sensitive_strings = CycloneSensitiveStrings::default();
+            sensitive_strings.insert("hunter22");
+
+            let header = general_purpose::STANDARD.encode("admin:hunter22");
+            let redacted = sensitive_strings.redact(&format!("Authorization: Basic {header}"));
+
+            assert!(sensitive_strings.has_sensitive(&header));
+            assert!(!redacted.contains(&header));
+            assert!(redacted.contains(REDACTED_TXT));
+        }
+
+        #[test]
+        fn redact_match_split_across_lines() {
+            let mut sensitive_strings = CycloneSensitiveStrings::default();
+            sensitive_strings.insert("correcthorse");
+
+            assert_eq!(
+                "key: [redacted] done",
+                sensitive_strings.redact("key: correct\nhorse done")
+            );
+        }
+
+        #[test]
+        fn stream_redactor_match_split_across_chunks() {
+            let mut sensitive_strings = CycloneSensitiveStrings::default();
+            sensitive_strings.insert("correcthorse");
+            let mut redactor = sensitive_strings.stream_redactor();
+
+            let mut redacted = Vec::new();
+            for (index, chunk) in ["key: corr", "ect", "horse", "battery staple", "done"]
+                .into_iter()
+                .enumerate()
+            {
+                redacted.extend(redactor.push(index, chunk.to_string()));
+            }
+            redacted.extend(redactor.flush());
+
+            assert_eq!(
+                vec![
+                    (0, "key: [redacted]".to_string()),
+                    (1, "[redacted]".to_string()),
+                    (2, "[redacted]".to_string()),
+                    (3, "battery staple".to_string()),
+                    (4, "done".to_string()),
+                ],
+                redacted
+            );
+        }
+
+        #[test]
+        fn stream_redactor_without_sensitive_strings_holds_nothing_back() {
+            let sensitive_strings = CycloneSensitiveStrings::default();
+            let mut redactor = sensitive_strings.stream_redactor();
+
+            assert_eq!(
+                vec![((), "hello".to_string())],
+                redactor.push((), "hello".to_string())
+            );
+            assert!(redactor.flush().is_empty());
+        }
     }
 }
@@ -37,7 +37,7 @@ pub use canonical_command::{CanonicalCommand, CanonicalCommandError};
 pub use component_view::{ComponentKind, ComponentView};
 pub use crypto::{
     decrypt_value_tree, encrypt_value_tree, CycloneSensitiveStrings, CycloneValueDecryptError,
-    CycloneValueEncryptError,
+    CycloneValueEncryptError, SensitiveStreamRedactor,
 };
 pub use liveness::{LivenessStatus, LivenessStatusParseError};
 pub use progress::{
@@ -0,0 +1,311 @@
+//! Fuzz-style tests for the redaction of [`CycloneSensitiveStrings`] from function output.
+//!
+//! Each case embeds a random secret, in a random encoding, in random text, optionally wraps it
+//! across lines and splits the text into random chunks, then checks that nothing of the secret
+//! survives redaction. The cases come from a small seeded PRNG so that a failure can be
+//! reproduced from the seed and case number in its message.
+
+use base64::{
+    engine::{general_purpose, GeneralPurpose},
+    Engine,
+};
+use cyclone_core::CycloneSensitiveStrings;
+
+const SEED: u64 = 0x5eed_cafe_f00d_d00d;
+const CASES: usize = 2_000;
+
+/// The shortest secret generated. Shorter values (say, "a") can not be redacted without
+/// redacting everything.
+const MIN_SECRET_LEN: usize = 8;
+const MAX_SECRET_LEN: usize = 48;
+
+/// No run of this many bytes of a secret may be recoverable from the redacted text.
+const LEAKED_WINDOW_LEN: usize = 4;
+
+const SECRET_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789\
+    !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~ \té€ü😀";
+const TEXT_CHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 \
+    .,:;=\"'{}[]()/\n";
+
+/// A xorshift64* generator; plenty for picking test inputs and free of dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn between(&mut self, min: usize, max: usize) -> usize {
+        min + self.below(max - min + 1)
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn string(&mut self, chars: &str, min: usize, max: usize) -> String {
+        let chars: Vec<char> = chars.chars().collect();
+        (0..self.between(min, max))
+            .map(|_| chars[self.below(chars.len())])
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Encoding {
+    Raw,
+    JsonEscaped,
+    Base64(GeneralPurpose),
+    /// The secret encoded along with the bytes around it, as in a Basic auth header
+    Base64Embedded(GeneralPurpose),
+    UrlEncoded,
+    UrlEncodedLowercase,
+    /// As JavaScript's `encodeURIComponent` does
+    UriComponent,
+    /// As most libraries encode form values, such as Python's `quote_plus`
+    FormEncoded,
+    /// As a browser submitting an HTML form does
+    HtmlFormEncoded,
+}
+
+const ENGINES: [GeneralPurpose; 4] = [
+    general_purpose::STANDARD,
+    general_purpose::STANDARD_NO_PAD,
+    general_purpose::URL_SAFE,
+    general_purpose::URL_SAFE_NO_PAD,
+];
+
+impl Encoding {
+    fn random(rng: &mut Rng) -> Self {
+        let engine = ENGINES[rng.below(ENGINES.len())];
+        match rng.below(9) {
+            0 => Self::Raw,
+            1 => Self::JsonEscaped,
+            2 => Self::Base64(engine),
+            3 => Self::Base64Embedded(engine),
+            4 => Self::UrlEncoded,
+            5 => Self::UrlEncodedLowercase,
+            6 => Self::UriComponent,
+            7 => Self::FormEncoded,
+            _ => Self::HtmlFormEncoded,
+        }
+    }
+
+    fn encode(self, rng: &mut Rng, secret: &str) -> String {
+        match self {
+            Self::Raw => secret.to_string(),
+            Self::JsonEscaped => {
+                let json = serde_json::to_string(secret).expect("unable to serialize secret");
+                json[1..json.len() - 1].to_string()
+            }
+            Self::Base64(engine) => engine.encode(secret),
+            Self::Base64Embedded(engine) => {
+                let before = rng.string(TEXT_CHARS, 0, 12);
+                let after = rng.string(TEXT_CHARS, 0, 12);
+                engine.encode(format!("{before}{secret}{after}"))
+            }
+            Self::UrlEncoded => url_encode(secret, b"-._~", false, false),
+            Self::UrlEncodedLowercase => url_encode(secret, b"-._~", true, false),
+            Self::UriComponent => url_encode(secret, b"-_.!~*'()", false, false),
+            Self::FormEncoded => url_encode(secret, b"-._~", false, true),
+            Self::HtmlFormEncoded => url_encode(secret, b"*-._", false, true),
+        }
+    }
+}
+
+fn url_encode(s: &str, unreserved: &[u8], lowercase: bool, space_as_plus: bool) -> String {
+    s.bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || unreserved.contains(&byte) {
+                (byte as char).to_string()
+            } else if space_as_plus && byte == b' ' {
+                "+".to_string()
+            } else if lowercase {
+                format!("%{byte:02x}")
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Inserts line breaks at random places in `s`, as a function wrapping long output would.
+fn wrap(rng: &mut Rng, s: &str) -> String {
+    let mut wrapped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if rng.chance(8) {
+            wrapped.push(if rng.chance(20) { '\r' } else { '\n' });
+            wrapped.push('\n');
+        }
+        wrapped.push(c);
+    }
+    wrapped
+}
+
+/// Splits `s` into chunks at random character boundaries.
+fn chunks(rng: &mut Rng, s: &str) -> Vec<String> {
+    let mut chunks = vec![String::new()];
+    for c in s.chars() {
+        if rng.chance(10) {
+            chunks.push(String::new());
+        }
+        chunks.last_mut().expect("chunks is never empty").push(c);
+    }
+    chunks
+}
+
+fn without_line_breaks(s: &str) -> String {
+    s.chars().filter(|c| *c != '\n' && *c != '\r').collect()
+}
+
+/// Returns every byte string which can be base64 (or base64url) decoded from the runs of base64
+/// characters in `s`, at each of the four possible alignments.
+fn base64_decodings(s: &str) -> Vec<Vec<u8>> {
+    let normalized: String = s
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+
+    normalized
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '+' || c == '/'))
+        .flat_map(|run| {
+            (0..4).filter_map(move |start| {
+                let run = run.get(start..)?;
+                let usable = run.len() - run.len() % 4;
+                general_purpose::STANDARD_NO_PAD.decode(&run[..usable]).ok()
+            })
+        })
+        .collect()
+}
+
+fn assert_no_leak(case: usize, secret: &str, encoded: &str, redacted: &str) {
+    let context = format!(
+        "case {case} (seed {SEED:#x}): secret {secret:?}, encoded {encoded:?}, \
+        redacted {redacted:?}"
+    );
+    let redacted = without_line_breaks(redacted);
+
+    assert!(
+        !redacted.contains(&without_line_breaks(secret)),
+        "secret leaked in {context}"
+    );
+    assert!(
+        !redacted.contains(encoded),
+        "encoded secret leaked in {context}"
+    );
+
+    let secret_windows: Vec<&[u8]> = secret.as_bytes().windows(LEAKED_WINDOW_LEN).collect();
+    for decoded in base64_decodings(&redacted) {
+        if let Some(window) = decoded
+            .windows(LEAKED_WINDOW_LEN)
+            .find(|window| secret_windows.contains(window))
+        {
+            panic!("secret bytes {window:?} decodable in {context}");
+        }
+    }
+}
+
+#[test]
+fn redacts_encoded_secrets_in_random_text() {
+    let mut rng = Rng(SEED);
+
+    for case in 0..CASES {
+        let secret = rng.string(SECRET_CHARS, MIN_SECRET_LEN, MAX_SECRET_LEN);
+        let mut sensitive_strings = CycloneSensitiveStrings::default();
+        sensitive_strings.insert(secret.clone());
+
+        let encoded = Encoding::random(&mut rng).encode(&mut rng, &secret);
+        let embedded = if rng.chance(50) {
+            wrap(&mut rng, &encoded)
+        } else {
+            encoded.clone()
+        };
+        let text = format!(
+            "{}{embedded}{}",
+            rng.string(TEXT_CHARS, 0, 40),
+            rng.string(TEXT_CHARS, 0, 40)
+        );
+
+        assert!(
+            sensitive_strings.has_sensitive(&text),
+            "secret not found in case {case} (seed {SEED:#x}): {text:?}"
+        );
+        assert_no_leak(
+            case,
+            &secret,
+            &without_line_breaks(&encoded),
+            &sensitive_strings.redact(&text),
+        );
+    }
+}
+
+#[test]
+fn redacts_encoded_secrets_split_across_stream_chunks() {
+    let mut rng = Rng(SEED.rotate_left(17));
+
+    for case in 0..CASES {
+        let mut sensitive_strings = CycloneSensitiveStrings::default();
+        let secrets: Vec<String> = (0..rng.between(1, 3))
+            .map(|_| rng.string(SECRET_CHARS, MIN_SECRET_LEN, MAX_SECRET_LEN))
+            .collect();
+        sensitive_strings.extend(secrets.iter().cloned().map(Into::into));
+
+        let secret = &secrets[rng.below(secrets.len())];
+        let encoded = Encoding::random(&mut rng).encode(&mut rng, secret);
+        let text = format!(
+            "{}{}{}",
+            rng.string(TEXT_CHARS, 0, 80),
+            wrap(&mut rng, &encoded),
+            rng.string(TEXT_CHARS, 0, 80)
+        );
+
+        let mut redactor = sensitive_strings.stream_redactor();
+        let mut redacted = Vec::new();
+        for (index, chunk) in chunks(&mut rng, &text).into_iter().enumerate() {
+            redacted.extend(redactor.push(index, chunk));
+        }
+        redacted.extend(redactor.flush());
+
+        let indices: Vec<usize> = redacted.iter().map(|(index, _)| *index).collect();
+        assert!(
+            indices.windows(2).all(|pair| pair[0] + 1 == pair[1]),
+            "chunks out of order in case {case} (seed {SEED:#x}): {indices:?}"
+        );
+
+        let redacted: String = redacted.into_iter().map(|(_, chunk)| chunk).collect();
+        assert_no_leak(case, secret, &without_line_breaks(&encoded), &redacted);
+    }
+}
+
+#[test]
+fn leaves_text_without_secrets_alone() {
+    let mut rng = Rng(SEED.rotate_left(33));
+
+    for _ in 0..CASES {
+        let mut sensitive_strings = CycloneSensitiveStrings::default();
+        sensitive_strings.insert(rng.string("0123456789", MIN_SECRET_LEN, MAX_SECRET_LEN));
+
+        let text = rng.string("abcdefghijklmnopqrstuvwxyz .,\n", 0, 200);
+        let mut redactor = sensitive_strings.stream_redactor();
+        let mut redacted: Vec<String> = chunks(&mut rng, &text)
+            .into_iter()
+            .flat_map(|chunk| redactor.push((), chunk))
+            .map(|(_, chunk)| chunk)
+            .collect();
+        redacted.extend(redactor.flush().into_iter().map(|(_, chunk)| chunk));
+
+        assert_eq!(text, sensitive_strings.redact(&text));
+        assert_eq!(text, redacted.concat());
+    }
+}
@@ -228,11 +228,16 @@ async fn handle_stderr(
         mut stderr: FramedRead<ChildStderr, BytesLinesCodec>,
         sensitive_strings: Arc<CycloneSensitiveStrings>,
     ) -> Result<()> {
+        let mut redactor = sensitive_strings.stream_redactor();
         while let Some(line) = stderr.next().await {
             let line = line.map_err(ExecutionError::ChildRecvIO)?;
             let line = String::from_utf8(line.to_vec())?;
-            let line = sensitive_strings.redact(line.as_ref());
 
+            for ((), line) in redactor.push((), line) {
+                eprintln!("{line}");
+            }
+        }
+        for ((), line) in redactor.flush() {
             eprintln!("{line}");
         }
         Ok(())
@@ -249,37 +254,38 @@ where
     SymmetricalJson<SiMessage<LangServerSuccess>>: Deserializer<SiMessage<LangServerSuccess>>,
     SiDecoderError: From<SiJsonError<LangServerSuccess>>,
 {
-    pub async fn process(self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
+    pub async fn process(mut self, ws: &mut WebSocket) -> Result<ExecutionClosing<Success>> {
         tokio::spawn(handle_stderr(self.stderr, self.sensitive_strings.clone()));
 
-        let mut stream = self
+        // Output is redacted as a stream so that sensitive strings split across several output
+        // messages are caught too. Some messages are therefore held back until enough output
+        // follows them, or until the result is sent.
+        let mut output_redactor = self.sensitive_strings.stream_redactor();
+
+        while let Some(ls_msg) = self
             .stdout
-            .map(|ls_result| match ls_result {
-                Ok(ls_msg) => match ls_msg {
-                    LangServerMessage::Output(mut output) => {
-                        Self::filter_output(&mut output, &self.sensitive_strings)?;
-                        Ok(Message::OutputStream(output.into()))
+            .try_next()
+            .await
+            .map_err(ExecutionError::ChildRecvIO)?
+        {
+            match ls_msg {
+                LangServerMessage::Output(mut output) => {
+                    let message = std::mem::take(&mut output.message);
+                    for (output, message) in output_redactor.push(output, message) {
+                        Self::ws_send_output(ws, output, message).await?;
                     }
-                    LangServerMessage::Result(mut result) => {
-                        Self::filter_result(&mut result, &self.sensitive_strings)?;
-                        Ok(Message::Result(result.into()))
+                }
+                LangServerMessage::Result(mut result) => {
+                    for (output, message) in output_redactor.flush() {
+                        Self::ws_send_output(ws, output, message).await?;
                     }
-                },
-                Err(err) => Err(ExecutionError::ChildRecvIO(err)),
-            })
-            .map(|msg_result: Result<_>| match msg_result {
-                Ok(msg) => match msg
-                    .serialize_to_string()
-                    .map_err(ExecutionError::JSONSerialize)
-                {
-                    Ok(json_str) => Ok(WebSocketMessage::Text(json_str)),
-                    Err(err) => Err(err),
-                },
-                Err(err) => Err(err),
-            });
-
-        while let Some(msg) = stream.try_next().await? {
-            ws.send(msg).await.map_err(ExecutionError::WSSendIO)?;
+                    Self::filter_result(&mut result, &self.sensitive_strings)?;
+                    Self::ws_send(ws, Message::Result(result.into())).await?;
+                }
+            }
+        }
+        for (output, message) in output_redactor.flush() {
+            Self::ws_send_output(ws, output, message).await?;
         }
 
         Ok(ExecutionClosing {
@@ -288,15 +294,22 @@ where
         })
     }
 
-    fn filter_output(
-        output: &mut LangServerOutput,
-        sensitive_strings: &CycloneSensitiveStrings,
+    async fn ws_send_output(
+        ws: &mut WebSocket,
+        mut output: LangServerOutput,
+        redacted_message: String,
     ) -> Result<()> {
-        if sensitive_strings.has_sensitive(&output.message) {
-            output.message = sensitive_strings.redact(&output.message);
-        }
+        output.message = redacted_message;
+        Self::ws_send(ws, Message::OutputStream(output.into())).await
+    }
 
-        Ok(())
+    async fn ws_send(ws: &mut WebSocket, msg: Message<Success>) -> Result<()> {
+        let json_str = msg
+            .serialize_to_string()
+            .map_err(ExecutionError::JSONSerialize)?;
+        ws.send(WebSocketMessage::Text(json_str))
+            .await
+            .map_err(ExecutionError::WSSendIO)
     }
 
     fn filter_result(
@@ -10,6 +10,7 @@ use axum::{
         ws::{self, WebSocket},
         Extension, State, WebSocketUpgrade,
     },
+    http::HeaderMap,
     response::IntoResponse,
 };
 use cyclone_core::{
@@ -20,7 +21,10 @@ use cyclone_core::{
 };
 use hyper::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
-use telemetry::prelude::*;
+use telemetry::{
+    prelude::*,
+    propagation::{self, Extractor},
+};
 
 use super::extract::LimitRequestGuard;
 use crate::{
@@ -83,6 +87,7 @@ pub async fn ws_execute_ping(
 #[allow(clippy::unused_async)]
 pub async fn ws_execute_resolver(
     wsu: WebSocketUpgrade,
+    headers: HeaderMap,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
@@ -95,6 +100,7 @@ pub async fn ws_execute_resolver(
         let success: PhantomData<ResolverFunctionResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            headers,
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -110,6 +116,7 @@ pub async fn ws_execute_resolver(
 #[allow(clippy::unused_async)]
 pub async fn ws_execute_validation(
     wsu: WebSocketUpgrade,
+    headers: HeaderMap,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
@@ -122,6 +129,7 @@ pub async fn ws_execute_validation(
         let success: PhantomData<ValidationResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            headers,
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -137,6 +145,7 @@ pub async fn ws_execute_validation(
 #[allow(clippy::unused_async)]
 pub async fn ws_execute_action_run(
     wsu: WebSocketUpgrade,
+    headers: HeaderMap,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
@@ -149,6 +158,7 @@ pub async fn ws_execute_action_run(
         let success: PhantomData<ActionRunResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            headers,
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -164,6 +174,7 @@ pub async fn ws_execute_action_run(
 #[allow(clippy::unused_async)]
 pub async fn ws_execute_reconciliation(
     wsu: WebSocketUpgrade,
+    headers: HeaderMap,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
@@ -176,6 +187,7 @@ pub async fn ws_execute_reconciliation(
         let success: PhantomData<ReconciliationResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            headers,
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -191,6 +203,7 @@ pub async fn ws_execute_reconciliation(
 #[allow(clippy::unused_async)]
 pub async fn ws_execute_schema_variant_definition(
     wsu: WebSocketUpgrade,
+    headers: HeaderMap,
     State(lang_server_path): State<LangServerPath>,
     State(key): State<DecryptionKey>,
     State(telemetry_level): State<TelemetryLevel>,
@@ -203,6 +216,7 @@ pub async fn ws_execute_schema_variant_definition(
         let success: PhantomData<SchemaVariantDefinitionResultSuccess> = PhantomData;
         handle_socket(
             socket,
+            headers,
             lang_server_path,
             telemetry_level.is_debug_or_lower(),
             key.into(),
@@ -216,8 +230,18 @@ pub async fn ws_execute_schema_variant_definition(
 }
 
 #[allow(clippy::too_many_arguments)]
+#[instrument(
+    name = "cyclone.execute",
+    skip_all,
+    level = "info",
+    fields(
+        cyclone.sub_command = sub_command.as_str(),
+        otel.kind = %FormattedSpanKind(SpanKind::Server),
+    )
+)]
 async fn handle_socket<Request, LangServerSuccess, Success>(
     mut socket: WebSocket,
+    headers: HeaderMap,
     lang_server_path: PathBuf,
     lang_server_debugging: bool,
     key: Arc<cyclone_core::CycloneDecryptionKey>,
@@ -231,6 +255,9 @@ async fn handle_socket<Request, LangServerSuccess, Success>(
     Success: Serialize + Unpin + fmt::Debug,
     LangServerSuccess: Serialize + DeserializeOwned + Unpin + fmt::Debug + Into<Success>,
 {
+    // Continue the trace of the veritech request which this execution serves
+    propagation::set_parent(&Span::current(), &HeaderExtractor(&headers));
+
     let proto = {
         let execution: Execution<Request, LangServerSuccess, Success> =
             execution::new(lang_server_path, lang_server_debugging, key, sub_command);
@@ -278,3 +305,15 @@ async fn fail_to_process<Success: Serialize>(
     socket.close().await?;
     Ok(())
 }
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
@@ -12,13 +12,15 @@ use buck2_resources::Buck2Resources;
 use dal::{
     builtins::SelectedTestBuiltinSchemas,
     job::processor::{JobQueueProcessor, NatsProcessor},
-    DalContext, JwtPublicSigningKey, ServicesContext,
+    secret::provider::{LocalSecretProvider, LOCAL_SECRET_PROVIDER_NAME},
+    DalContext, JwtPublicSigningKey, SecretProviders, ServicesContext,
 };
 use derive_builder::Builder;
 use jwt_simple::prelude::RS256KeyPair;
 use lazy_static::lazy_static;
 use si_crypto::{
     SymmetricCryptoService, SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile,
+    SymmetricKey,
 };
 use si_data_nats::{NatsClient, NatsConfig};
 use si_data_pg::{PgPool, PgPoolConfig};
@@ -46,6 +48,9 @@ const ENV_VAR_PG_HOSTNAME: &str = "SI_TEST_PG_HOSTNAME";
 const ENV_VAR_PG_DBNAME: &str = "SI_TEST_PG_DBNAME";
 const ENV_VAR_BUILTIN_SCHEMAS: &str = "SI_TEST_BUILTIN_SCHEMAS";
 
+/// The prefix of the environment variables which local secret providers are given in tests.
+pub const TEST_SECRET_ENV_PREFIX: &str = "SI_TEST_SECRET_";
+
 pub static COLOR_EYRE_INIT: Once = Once::new();
 
 lazy_static! {
@@ -234,6 +239,16 @@ impl TestContext {
     pub async fn create_services_context(&self) -> ServicesContext {
         let veritech = veritech_client::Client::new(self.nats_conn.clone());
 
+        let mut secret_providers = SecretProviders::default();
+        secret_providers.register(
+            LOCAL_SECRET_PROVIDER_NAME,
+            LocalSecretProvider::new(
+                None,
+                Some(TEST_SECRET_ENV_PREFIX.to_string()),
+                Default::default(),
+            ),
+        );
+
         ServicesContext::new(
             self.pg_pool.clone(),
             self.nats_conn.clone(),
@@ -243,6 +258,7 @@ impl TestContext {
             self.config.pkgs_path.to_owned(),
             None,
             self.symmetric_crypto_service.clone(),
+            secret_providers,
         )
     }
 
@@ -250,6 +266,13 @@ impl TestContext {
     pub fn nats_config(&self) -> &NatsConfig {
         &self.config.nats
     }
+
+    /// Loads the active [`SymmetricKey`] of the [`SymmetricCryptoService`] used by tests.
+    pub async fn symmetric_crypto_service_active_key(&self) -> Result<SymmetricKey> {
+        let key =
+            SymmetricKey::load(&self.config.symmetric_crypto_service_config.active_key).await?;
+        Ok(key)
+    }
 }
 
 /// A builder for a [`TestContext`].
@@ -441,6 +464,9 @@ pub fn pinga_server(services_context: &ServicesContext) -> Result<pinga_server::
     let server = pinga_server::Server::from_services(
         config.instance_id(),
         config.concurrency(),
+        config.schedule_poll_interval(),
+        config.job_queue(),
+        config.jetstream_consumer(),
         services_context.clone(),
     )
     .wrap_err("failed to create Pinga server")?;
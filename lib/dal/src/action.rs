@@ -89,7 +89,7 @@ impl Action {
         }
 
         let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         };
 
@@ -10,6 +10,7 @@ use si_pkg::ActionFuncSpecKind;
 use telemetry::prelude::*;
 
 use crate::func::before::before_funcs_for_component;
+use crate::secret::SecretUsageSource;
 use crate::{
     component::view::ComponentViewError, func::backend::js_action::ActionRunResult,
     impl_standard_model, pk, standard_model, standard_model_accessor, Component, ComponentId,
@@ -370,14 +371,19 @@ impl ActionPrototype {
         context
     }
 
+    /// Runs the action's [`Func`] against the component. Any secrets it needs are recorded as
+    /// used for `source`, along with the action's [`Func`] and the component.
     pub async fn run(
         &self,
         ctx: &DalContext,
         component_id: ComponentId,
+        source: SecretUsageSource,
     ) -> ActionPrototypeResult<Option<ActionRunResult>> {
         let component_view = ComponentView::new(ctx, component_id).await?;
 
-        let before = before_funcs_for_component(ctx, &component_id).await?;
+        let before =
+            before_funcs_for_component(ctx, &component_id, source.with_func_id(self.func_id()))
+                .await?;
 
         let (_, return_value) = FuncBinding::create_and_execute(
             ctx,
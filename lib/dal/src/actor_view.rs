@@ -7,13 +7,23 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::{DalContext, HistoryActor, StandardModelError, User, UserPk};
+use crate::{ApiTokenPk, DalContext, HistoryActor, StandardModelError, User, UserPk};
 
 /// The actor entitiy that initiates an activitiy--this could represent be a person, service, etc.
 #[remain::sorted]
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum ActorView {
+    /// Represents an automation client using an [`ApiToken`](crate::ApiToken) on behalf of a
+    /// user
+    ApiToken {
+        /// The token's ID
+        pk: ApiTokenPk,
+        /// A display label
+        label: String,
+        /// The ID of the user who created the token
+        user_pk: UserPk,
+    },
     /// Represents a system-generated activity
     System {
         /// A display label
@@ -57,6 +67,19 @@ impl ActorView {
                     email: Some(user.email().to_string()),
                 })
             }
+            HistoryActor::ApiToken {
+                api_token_pk,
+                user_pk,
+            } => {
+                let user = User::get_by_pk(ctx, user_pk)
+                    .await?
+                    .ok_or(StandardModelError::UserNotFound(user_pk))?;
+                Ok(Self::ApiToken {
+                    pk: api_token_pk,
+                    label: format!("{} (api token)", user.name()),
+                    user_pk,
+                })
+            }
             HistoryActor::SystemInit => Ok(Self::System {
                 label: Self::system_label(),
             }),
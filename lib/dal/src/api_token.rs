@@ -0,0 +1,253 @@
+//! This module contains [`ApiToken`], a long-lived bearer token with which automation clients
+//! (for example CI pipelines) can use the sdf API without a browser session.
+//!
+//! A token belongs to a [`Workspace`](crate::Workspace) and acts on behalf of the
+//! [`User`](crate::User) who created it, but only with the [`WorkspacePermissions`](WorkspacePermission)
+//! it was created with and, optionally, only making changes in some
+//! [`ChangeSets`](crate::ChangeSet). Such a token can read head, and only writes to it by applying
+//! one of its change sets. Every token has the [`Read`](WorkspacePermission::Read) permission,
+//! without which it can not be used at all. Only a hash of the token is stored: the token itself
+//! is returned once, when it is created.
+
+use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::{PgError, PgPoolError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    pk, standard_model::objects_from_rows, ChangeSetPk, DalContext, HistoryActor,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, User, UserError, UserPk,
+    WorkspacePermission, WorkspacePk,
+};
+
+const API_TOKEN_FIND_BY_TOKEN_HASH: &str = include_str!("queries/api_token/find_by_token_hash.sql");
+const API_TOKEN_LIST: &str = include_str!("queries/api_token/list.sql");
+const API_TOKEN_REVOKE: &str = include_str!("queries/api_token/revoke.sql");
+
+/// Every raw token starts with this prefix, which tells it apart from a session JWT.
+pub const API_TOKEN_PREFIX: &str = "si_api_";
+
+const API_TOKEN_RANDOM_BYTES: usize = 32;
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ApiTokenError {
+    #[error("api tokens can only be created by a user")]
+    NotCreatedByUser,
+    #[error("api token not found: {0}")]
+    NotFound(ApiTokenPk),
+    #[error("user {0} can not grant the {1} permission")]
+    PermissionNotGranted(UserPk, WorkspacePermission),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[source] Box<PgPoolError>),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("standard model error: {0}")]
+    StandardModel(#[from] StandardModelError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("user error: {0}")]
+    User(#[from] UserError),
+}
+
+impl From<PgPoolError> for ApiTokenError {
+    fn from(value: PgPoolError) -> Self {
+        Self::PgPool(Box::new(value))
+    }
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+pk!(ApiTokenPk);
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ApiToken {
+    pk: ApiTokenPk,
+    name: String,
+    created_by_user_pk: UserPk,
+    permissions: Vec<WorkspacePermission>,
+    /// When set, the token can only make changes in these change sets. It can still read head.
+    change_set_pks: Option<Vec<ChangeSetPk>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl ApiToken {
+    /// Creates a token for the [`DalContext`]'s workspace on behalf of its user, returning the
+    /// token and the raw bearer token. The raw token is not stored, so it can not be retrieved
+    /// again later.
+    ///
+    /// The user's own [`WorkspaceRole`](crate::WorkspaceRole) must grant every permission given
+    /// to the token. The token is always given [`Read`](WorkspacePermission::Read).
+    #[instrument(skip(ctx, name))]
+    pub async fn new(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        mut permissions: Vec<WorkspacePermission>,
+        change_set_pks: Option<Vec<ChangeSetPk>>,
+    ) -> ApiTokenResult<(Self, String)> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::ApiToken { .. } | HistoryActor::SystemInit => {
+                return Err(ApiTokenError::NotCreatedByUser)
+            }
+        };
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(UserError::NoWorkspaceInTenancy)?;
+        let role = User::role_in_workspace(ctx, user_pk, workspace_pk)
+            .await?
+            .ok_or(UserError::NotAMember(user_pk, workspace_pk))?;
+        if let Some(permission) = permissions
+            .iter()
+            .find(|permission| !role.allows(**permission))
+        {
+            return Err(ApiTokenError::PermissionNotGranted(user_pk, *permission));
+        }
+        if !permissions.contains(&WorkspacePermission::Read) {
+            permissions.push(WorkspacePermission::Read);
+        }
+
+        let mut random_bytes = [0u8; API_TOKEN_RANDOM_BYTES];
+        rand::thread_rng().fill_bytes(&mut random_bytes);
+        let raw_token = format!(
+            "{API_TOKEN_PREFIX}{}",
+            general_purpose::URL_SAFE_NO_PAD.encode(random_bytes)
+        );
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM api_token_create_v1($1, $2, $3, $4, $5, $6)",
+                &[
+                    ctx.tenancy(),
+                    &name.as_ref(),
+                    &hash_token(&raw_token),
+                    &user_pk,
+                    &serde_json::to_value(&permissions)?,
+                    &change_set_pks
+                        .as_ref()
+                        .map(serde_json::to_value)
+                        .transpose()?,
+                ],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+        let object: Self = serde_json::from_value(json)?;
+
+        Ok((object, raw_token))
+    }
+
+    /// Finds the unrevoked token matching a raw bearer token, regardless of the [`DalContext`]'s
+    /// tenancy, and records that it was used.
+    pub async fn find_by_token(
+        ctx: &DalContext,
+        raw_token: impl AsRef<str>,
+    ) -> ApiTokenResult<Option<Self>> {
+        // This query explicitly uses its own connection to bypass/avoid a ctx's database
+        // transaction--authorizing a request does not commit it!
+        let row = ctx
+            .pg_pool()
+            .get()
+            .await?
+            .query_opt(
+                API_TOKEN_FIND_BY_TOKEN_HASH,
+                &[&hash_token(raw_token.as_ref())],
+            )
+            .await?;
+        match row {
+            Some(row) => {
+                let json: Value = row.try_get("object")?;
+                Ok(Some(serde_json::from_value(json)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Lists every token of the [`DalContext`]'s workspace, including revoked ones, newest first.
+    pub async fn list(ctx: &DalContext) -> ApiTokenResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(API_TOKEN_LIST, &[ctx.tenancy()])
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Revokes a token of the [`DalContext`]'s workspace. A revoked token can no longer be used.
+    pub async fn revoke(ctx: &DalContext, pk: ApiTokenPk) -> ApiTokenResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(API_TOKEN_REVOKE, &[ctx.tenancy(), &pk])
+            .await?
+            .ok_or(ApiTokenError::NotFound(pk))?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Returns whether the token was given `permission`. This does not check the role of the user
+    /// who created the token, which has to grant the permission too.
+    pub fn allows(&self, permission: WorkspacePermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// The [`HistoryActor`] recorded for everything done with this token.
+    pub fn history_actor(&self) -> HistoryActor {
+        HistoryActor::ApiToken {
+            api_token_pk: self.pk,
+            user_pk: self.created_by_user_pk,
+        }
+    }
+
+    pub fn pk(&self) -> ApiTokenPk {
+        self.pk
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn created_by_user_pk(&self) -> UserPk {
+        self.created_by_user_pk
+    }
+
+    pub fn permissions(&self) -> &[WorkspacePermission] {
+        &self.permissions
+    }
+
+    pub fn change_set_pks(&self) -> Option<&[ChangeSetPk]> {
+        self.change_set_pks.as_deref()
+    }
+
+    pub fn last_used_at(&self) -> Option<DateTime<Utc>> {
+        self.last_used_at
+    }
+
+    pub fn revoked_at(&self) -> Option<DateTime<Utc>> {
+        self.revoked_at
+    }
+
+    pub fn workspace_pk(&self) -> Option<WorkspacePk> {
+        self.tenancy.workspace_pk()
+    }
+}
+
+fn hash_token(raw_token: &str) -> String {
+    blake3::hash(raw_token.as_bytes()).to_hex().to_string()
+}
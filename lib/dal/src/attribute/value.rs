@@ -45,6 +45,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::func::before::before_funcs_for_component;
+use crate::secret::SecretUsageSource;
 use crate::{
     attribute::{
         context::{
@@ -1125,7 +1126,12 @@ impl AttributeValue {
             // prepare before functions
             let associated_component_id = self.context.component_id();
 
-            before_funcs_for_component(ctx, &associated_component_id).await?
+            before_funcs_for_component(
+                ctx,
+                &associated_component_id,
+                SecretUsageSource::default().with_func_id(func_id),
+            )
+            .await?
         } else {
             vec![]
         };
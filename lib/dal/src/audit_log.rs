@@ -0,0 +1,359 @@
+//! This module contains the workspace audit log: a single, time ordered view over the
+//! [`HistoryEvents`](HistoryEvent) and [`SecretUsages`](SecretUsage) recorded for a
+//! [`Workspace`](crate::Workspace), classified by [`AuditLogKind`] and exportable as
+//! [NDJSON](http://ndjson.org).
+//!
+//! Entries can also be forwarded as they are recorded to the [`AuditLogSink`] configured for
+//! their workspace, see [`AuditLogForwarder`](crate::tasks::AuditLogForwarder).
+
+use chrono::{DateTime, Utc};
+use nats_subscriber::SubscriberError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use thiserror::Error;
+
+use crate::{
+    history_event::HistoryEventPk,
+    secret::{SecretUsage, SecretUsagePk},
+    standard_model::objects_from_rows,
+    DalContext, HistoryActor, HistoryEvent, StandardModelError, TransactionsError, WorkspaceError,
+    WorkspacePk,
+};
+
+pub mod sink;
+
+pub use sink::{AuditLogSink, AuditLogSinkConfig};
+
+const LIST_HISTORY_EVENTS: &str = include_str!("queries/audit_log/list_history_events.sql");
+const LIST_SECRET_USAGES: &str = include_str!("queries/audit_log/list_secret_usages.sql");
+const LIST_HISTORY_EVENTS_PAGE: &str =
+    include_str!("queries/audit_log/list_history_events_page.sql");
+const LIST_SECRET_USAGES_PAGE: &str = include_str!("queries/audit_log/list_secret_usages_page.sql");
+
+/// The label of the [`AuditLogEntries`](AuditLogEntry) made from [`SecretUsages`](SecretUsage).
+const SECRET_ACCESS_LABEL: &str = "secret.access";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum AuditLogError {
+    #[error("audit log sink responded with status {0}")]
+    HttpStatus(reqwest::StatusCode),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("no tenancy set in context")]
+    NoWorkspace,
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("audit log sink address is not publicly routable: {0}")]
+    SinkAddressNotPublic(String),
+    #[error("audit log sink did not respond in time")]
+    SinkTimeout,
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Subscriber(#[from] SubscriberError),
+    #[error(transparent)]
+    Transactions(#[from] TransactionsError),
+    #[error("could not resolve audit log sink address: {0}")]
+    UnresolvedAddress(String),
+    #[error("unsupported audit log sink url scheme: {0}")]
+    UnsupportedSinkScheme(String),
+    #[error(transparent)]
+    UrlParse(#[from] url::ParseError),
+    #[error(transparent)]
+    Workspace(#[from] Box<WorkspaceError>),
+}
+
+pub type AuditLogResult<T> = Result<T, AuditLogError>;
+
+/// What an [`AuditLogEntry`] records.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum AuditLogKind {
+    /// A change set was applied to head
+    ChangeSetApply,
+    /// A fix batch or fix was created, confirmed, started or finished
+    FixRun,
+    /// Any other [`HistoryEvent`]
+    HistoryEvent,
+    /// A member was added to or removed from the workspace, or had their role changed
+    MembershipChange,
+    /// A member voted on merging a change set
+    MergeVote,
+    /// A module was installed
+    ModuleInstall,
+    /// A secret was decrypted
+    SecretAccess,
+}
+
+impl AuditLogKind {
+    /// Classifies a [`HistoryEvent`] by its label.
+    pub fn for_history_event_label(label: &str) -> Self {
+        match label.split_once('.') {
+            Some(("change_set", "apply")) => Self::ChangeSetApply,
+            Some(("change_set", "merge_vote")) => Self::MergeVote,
+            Some(("fix" | "fix_batch", _)) => Self::FixRun,
+            Some(("installed_pkg", _)) => Self::ModuleInstall,
+            Some(("workspace", action)) if action.starts_with("member_") => Self::MembershipChange,
+            _ if label == SECRET_ACCESS_LABEL => Self::SecretAccess,
+            _ => Self::HistoryEvent,
+        }
+    }
+}
+
+/// A single entry of a workspace's audit log.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub kind: AuditLogKind,
+    pub label: String,
+    pub message: String,
+    pub actor: HistoryActor,
+    pub data: Value,
+    pub workspace_pk: Option<WorkspacePk>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl AuditLogEntry {
+    /// Lists the audit log of the [`DalContext`]'s workspace, oldest first, optionally limited to
+    /// the entries recorded at or after `since` and before `until`.
+    pub async fn list(
+        ctx: &DalContext,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> AuditLogResult<Vec<Self>> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(AuditLogError::NoWorkspace)?;
+        let txns = ctx.txns().await?;
+
+        let history_events: Vec<HistoryEvent> = objects_from_rows(
+            txns.pg()
+                .query(LIST_HISTORY_EVENTS, &[&workspace_pk, &since, &until])
+                .await?,
+        )?;
+        let secret_usages: Vec<SecretUsage> = objects_from_rows(
+            txns.pg()
+                .query(LIST_SECRET_USAGES, &[&workspace_pk, &since, &until])
+                .await?,
+        )?;
+
+        let mut entries: Vec<Self> = history_events
+            .into_iter()
+            .map(Self::from)
+            .chain(secret_usages.into_iter().map(Self::from))
+            .collect();
+        // Both lists are already ordered, and the sort is stable, so entries recorded at the same
+        // time keep their order.
+        entries.sort_by_key(|entry| entry.occurred_at);
+
+        Ok(entries)
+    }
+
+    /// Like [`list`](Self::list), but lists at most `limit` entries, starting after the entry
+    /// `after` points at. Pass the returned page's [`next`](AuditLogPage::next) as `after` to
+    /// list the following page.
+    pub async fn list_page(
+        ctx: &DalContext,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        after: Option<&AuditLogCursor>,
+        limit: i64,
+    ) -> AuditLogResult<AuditLogPage> {
+        let workspace_pk = ctx
+            .tenancy()
+            .workspace_pk()
+            .ok_or(AuditLogError::NoWorkspace)?;
+        let txns = ctx.txns().await?;
+
+        // Entries recorded at the same time are ordered history events first, each source by pk,
+        // so a source's entries recorded at the cursor's time only follow it if the source's
+        // entries come later (or it is the cursor's own source, and their pk is greater).
+        let after_created_at = after.map(|after| after.occurred_at);
+        let (history_events_at_cursor, after_history_event_pk) = match after.map(|after| after.last)
+        {
+            Some(CursorEntry::HistoryEvent(pk)) => (true, Some(pk)),
+            Some(CursorEntry::SecretUsage(_)) => (false, None),
+            None => (true, None),
+        };
+        let after_secret_usage_pk = match after.map(|after| after.last) {
+            Some(CursorEntry::SecretUsage(pk)) => Some(pk),
+            Some(CursorEntry::HistoryEvent(_)) | None => None,
+        };
+
+        let history_events: Vec<HistoryEvent> = objects_from_rows(
+            txns.pg()
+                .query(
+                    LIST_HISTORY_EVENTS_PAGE,
+                    &[
+                        &workspace_pk,
+                        &since,
+                        &until,
+                        &after_created_at,
+                        &history_events_at_cursor,
+                        &after_history_event_pk,
+                        &limit,
+                    ],
+                )
+                .await?,
+        )?;
+        let secret_usages: Vec<SecretUsage> = objects_from_rows(
+            txns.pg()
+                .query(
+                    LIST_SECRET_USAGES_PAGE,
+                    &[
+                        &workspace_pk,
+                        &since,
+                        &until,
+                        &after_created_at,
+                        &true,
+                        &after_secret_usage_pk,
+                        &limit,
+                    ],
+                )
+                .await?,
+        )?;
+
+        let mut entries: Vec<(CursorEntry, Self)> = history_events
+            .into_iter()
+            .map(|event| (CursorEntry::HistoryEvent(event.pk), Self::from(event)))
+            .chain(
+                secret_usages
+                    .into_iter()
+                    .map(|usage| (CursorEntry::SecretUsage(usage.pk()), Self::from(usage))),
+            )
+            .collect();
+        // As with `list`, the sort is stable, so history events stay ahead of secret usages
+        // recorded at the same time, and each source stays ordered by pk.
+        entries.sort_by_key(|(_, entry)| entry.occurred_at);
+        entries.truncate(limit.max(0) as usize);
+
+        let next = match entries.last() {
+            Some((last, entry)) if entries.len() as i64 == limit => Some(AuditLogCursor {
+                occurred_at: entry.occurred_at,
+                last: *last,
+            }),
+            _ => None,
+        };
+
+        Ok(AuditLogPage {
+            entries: entries.into_iter().map(|(_, entry)| entry).collect(),
+            next,
+        })
+    }
+
+    /// Serializes the entry as a single line of [NDJSON](http://ndjson.org), including the
+    /// trailing newline.
+    pub fn to_ndjson_line(&self) -> AuditLogResult<String> {
+        let mut line = serde_json::to_string(self)?;
+        line.push('\n');
+        Ok(line)
+    }
+}
+
+/// A page of a workspace's audit log, see [`AuditLogEntry::list_page`].
+#[derive(Clone, Debug)]
+pub struct AuditLogPage {
+    pub entries: Vec<AuditLogEntry>,
+    /// Where the following page starts, unless this is the last page.
+    pub next: Option<AuditLogCursor>,
+}
+
+/// Points at the last [`AuditLogEntry`] of an [`AuditLogPage`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AuditLogCursor {
+    occurred_at: DateTime<Utc>,
+    last: CursorEntry,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum CursorEntry {
+    HistoryEvent(HistoryEventPk),
+    SecretUsage(SecretUsagePk),
+}
+
+impl From<HistoryEvent> for AuditLogEntry {
+    fn from(event: HistoryEvent) -> Self {
+        Self {
+            kind: AuditLogKind::for_history_event_label(&event.label),
+            label: event.label,
+            message: event.message,
+            actor: event.actor,
+            data: event.data,
+            workspace_pk: event.tenancy.workspace_pk(),
+            occurred_at: event.timestamp.created_at,
+        }
+    }
+}
+
+impl From<SecretUsage> for AuditLogEntry {
+    fn from(usage: SecretUsage) -> Self {
+        let source = usage.source();
+        Self {
+            kind: AuditLogKind::SecretAccess,
+            label: SECRET_ACCESS_LABEL.to_owned(),
+            message: "Secret decrypted".to_owned(),
+            actor: usage.actor(),
+            data: serde_json::json!({
+                "pk": usage.pk(),
+                "secretId": usage.secret_id(),
+                "funcId": source.func_id(),
+                "componentId": source.component_id(),
+                "fixId": source.fix_id(),
+                "fixBatchId": source.fix_batch_id(),
+            }),
+            workspace_pk: usage.tenancy().workspace_pk(),
+            occurred_at: usage.created_at(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_history_event_labels() {
+        for (label, kind) in [
+            ("change_set.apply", AuditLogKind::ChangeSetApply),
+            ("change_set.create", AuditLogKind::HistoryEvent),
+            ("change_set.merge_vote", AuditLogKind::MergeVote),
+            ("fix.updated", AuditLogKind::FixRun),
+            ("fix_batch.confirmed", AuditLogKind::FixRun),
+            ("installed_pkg.create", AuditLogKind::ModuleInstall),
+            ("workspace.create", AuditLogKind::HistoryEvent),
+            ("workspace.member_removed", AuditLogKind::MembershipChange),
+            ("secret.access", AuditLogKind::SecretAccess),
+            ("secret.updated", AuditLogKind::HistoryEvent),
+            ("unlabeled", AuditLogKind::HistoryEvent),
+        ] {
+            assert_eq!(
+                kind,
+                AuditLogKind::for_history_event_label(label),
+                "{label}"
+            );
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! This module contains [`AuditLogSink`], an external endpoint that
+//! [`AuditLogEntries`](AuditLogEntry) are forwarded to as they are recorded.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use tokio::{
+    net::{lookup_host, UdpSocket},
+    time::timeout,
+};
+use url::{Host, Url};
+
+use super::{AuditLogEntry, AuditLogError, AuditLogResult};
+
+/// The syslog priority of every forwarded entry: facility 13 ("log audit") at severity 6
+/// ("informational").
+const SYSLOG_PRIORITY: u8 = 13 * 8 + 6;
+const SYSLOG_APP_NAME: &str = "sdf";
+/// How long resolving a sink's address, connecting to it or sending it an entry may take, so that
+/// a sink which never answers can't hold up forwarding indefinitely.
+const SINK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where to forward audit log entries to.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AuditLogSinkConfig {
+    /// POST each entry, as a line of NDJSON, to an HTTP endpoint
+    Http { url: String },
+    /// Send each entry as an RFC 5424 syslog message over UDP to `address` (`host:port`)
+    Syslog { address: String },
+}
+
+impl AuditLogSinkConfig {
+    /// Resolves the addresses of the sink, failing unless its url has an `http` or `https` scheme
+    /// and every address is publicly routable, so that a workspace can't have sdf send requests
+    /// into the internal network.
+    pub async fn validate(&self) -> AuditLogResult<Vec<SocketAddr>> {
+        let (target, addrs) = match self {
+            Self::Http { url } => {
+                let url = Url::parse(url)?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err(AuditLogError::UnsupportedSinkScheme(
+                        url.scheme().to_owned(),
+                    ));
+                }
+                let host = match url.host() {
+                    Some(Host::Domain(domain)) => domain.to_owned(),
+                    Some(Host::Ipv4(ip)) => ip.to_string(),
+                    Some(Host::Ipv6(ip)) => ip.to_string(),
+                    None => return Err(AuditLogError::UnresolvedAddress(url.to_string())),
+                };
+                let port = url.port_or_known_default().unwrap_or(80);
+                let addrs = timeout(SINK_TIMEOUT, lookup_host((host, port)))
+                    .await
+                    .map_err(|_| AuditLogError::SinkTimeout)??;
+                (url.to_string(), addrs.collect::<Vec<_>>())
+            }
+            Self::Syslog { address } => {
+                let addrs = timeout(SINK_TIMEOUT, lookup_host(address))
+                    .await
+                    .map_err(|_| AuditLogError::SinkTimeout)??;
+                (address.clone(), addrs.collect())
+            }
+        };
+
+        if addrs.is_empty() {
+            return Err(AuditLogError::UnresolvedAddress(target));
+        }
+        if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+            return Err(AuditLogError::SinkAddressNotPublic(addr.to_string()));
+        }
+
+        Ok(addrs)
+    }
+}
+
+/// A connected [`AuditLogSinkConfig`].
+#[derive(Clone, Debug)]
+pub enum AuditLogSink {
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+    Syslog {
+        socket: Arc<UdpSocket>,
+    },
+}
+
+impl AuditLogSink {
+    /// Connects to the sink once it has been [validated](AuditLogSinkConfig::validate). HTTP
+    /// requests are only ever made to the validated addresses, and redirects are not followed, so
+    /// the sink can't later point sdf elsewhere.
+    pub async fn connect(config: &AuditLogSinkConfig) -> AuditLogResult<Self> {
+        let addrs = config.validate().await?;
+        Self::connect_to(config, &addrs).await
+    }
+
+    async fn connect_to(config: &AuditLogSinkConfig, addrs: &[SocketAddr]) -> AuditLogResult<Self> {
+        match config {
+            AuditLogSinkConfig::Http { url } => {
+                let mut client = reqwest::Client::builder()
+                    .connect_timeout(SINK_TIMEOUT)
+                    .timeout(SINK_TIMEOUT)
+                    .redirect(reqwest::redirect::Policy::none());
+                if let Some(Host::Domain(domain)) = Url::parse(url)?.host() {
+                    client = client.resolve_to_addrs(domain, addrs);
+                }
+                Ok(Self::Http {
+                    client: client.build()?,
+                    url: url.clone(),
+                })
+            }
+            AuditLogSinkConfig::Syslog { address } => {
+                let target = addrs
+                    .first()
+                    .ok_or_else(|| AuditLogError::UnresolvedAddress(address.clone()))?;
+                let socket = if target.is_ipv4() {
+                    UdpSocket::bind("0.0.0.0:0").await?
+                } else {
+                    UdpSocket::bind("[::]:0").await?
+                };
+                socket.connect(target).await?;
+                Ok(Self::Syslog {
+                    socket: Arc::new(socket),
+                })
+            }
+        }
+    }
+
+    /// Forwards a single entry.
+    #[instrument(name = "audit_log_sink.send", skip_all, level = "debug")]
+    pub async fn send(&self, entry: &AuditLogEntry) -> AuditLogResult<()> {
+        match self {
+            Self::Http { client, url } => {
+                let response = client
+                    .post(url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+                    .body(entry.to_ndjson_line()?)
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    return Err(AuditLogError::HttpStatus(response.status()));
+                }
+            }
+            Self::Syslog { socket } => {
+                timeout(SINK_TIMEOUT, socket.send(syslog_message(entry)?.as_bytes()))
+                    .await
+                    .map_err(|_| AuditLogError::SinkTimeout)??;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether an address is reachable on the public internet, rather than being loopback, private,
+/// link-local, shared, reserved or otherwise special-purpose.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_multicast()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        // "This network", 0.0.0.0/8
+        || a == 0
+        // Shared address space, 100.64.0.0/10
+        || (a == 100 && (b & 0b1100_0000) == 64)
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b & 0b1111_1110) == 18)
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+        // Documentation, 2001:db8::/32
+        || (first == 0x2001 && second == 0x0db8)
+        // IPv4-compatible and NAT64 addresses embed IPv4 addresses which aren't checked here
+        || first == 0
+        || (first == 0x0064 && second == 0xff9b))
+}
+
+/// Formats an entry as an RFC 5424 syslog message, with the entry's kind as the message id and
+/// the entry itself, as JSON, as the message.
+fn syslog_message(entry: &AuditLogEntry) -> AuditLogResult<String> {
+    Ok(format!(
+        "<{SYSLOG_PRIORITY}>1 {} - {SYSLOG_APP_NAME} - {} - {}",
+        entry.occurred_at.to_rfc3339(),
+        entry.kind,
+        serde_json::to_string(entry)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+    use crate::{audit_log::AuditLogKind, HistoryActor, WorkspacePk};
+
+    fn entry() -> AuditLogEntry {
+        AuditLogEntry {
+            kind: AuditLogKind::ChangeSetApply,
+            label: "change_set.apply".to_owned(),
+            message: "Change Set applied".to_owned(),
+            actor: HistoryActor::SystemInit,
+            data: serde_json::json!({ "pk": "1" }),
+            workspace_pk: Some(WorkspacePk::NONE),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_syslog_messages_to_a_local_listener() {
+        let listener = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .expect("cannot bind listener");
+        let addr = listener.local_addr().expect("listener has no address");
+        let sink = AuditLogSink::connect_to(
+            &AuditLogSinkConfig::Syslog {
+                address: addr.to_string(),
+            },
+            &[addr],
+        )
+        .await
+        .expect("cannot connect sink");
+
+        let entry = entry();
+        sink.send(&entry).await.expect("cannot send entry");
+
+        let mut buf = vec![0; 64 * 1024];
+        let len = listener.recv(&mut buf).await.expect("cannot receive");
+        let message = String::from_utf8_lossy(&buf[..len]);
+        assert!(message.starts_with("<110>1 "), "{message}");
+        assert!(message.contains(" sdf - changeSetApply - {"), "{message}");
+
+        let json = &message[message.find('{').expect("no json in message")..];
+        assert_eq!(
+            entry,
+            serde_json::from_str(json).expect("cannot deserialize entry")
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_ndjson_to_a_local_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("cannot bind listener");
+        let addr = listener.local_addr().expect("listener has no address");
+        let url = format!("http://{addr}/audit");
+
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("cannot accept");
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            loop {
+                let len = stream.read(&mut buf).await.expect("cannot read request");
+                assert_ne!(0, len, "connection closed before the request was complete");
+                request.extend_from_slice(&buf[..len]);
+
+                let text = String::from_utf8_lossy(&request).to_lowercase();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let content_length: usize = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .expect("request has no content length")
+                        .trim()
+                        .parse()
+                        .expect("content length is not a number");
+                    if body.len() >= content_length {
+                        break;
+                    }
+                }
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .expect("cannot write response");
+            String::from_utf8(request).expect("request is not utf-8")
+        });
+
+        let sink = AuditLogSink::connect_to(&AuditLogSinkConfig::Http { url }, &[addr])
+            .await
+            .expect("cannot connect sink");
+        let entry = entry();
+        sink.send(&entry).await.expect("cannot send entry");
+
+        let request = received.await.expect("listener task failed");
+        assert!(request.starts_with("POST /audit "), "{request}");
+        assert!(
+            request
+                .to_lowercase()
+                .contains("content-type: application/x-ndjson"),
+            "{request}"
+        );
+        let (_, body) = request.split_once("\r\n\r\n").expect("request has no body");
+        assert_eq!(entry.to_ndjson_line().expect("cannot serialize"), body);
+    }
+
+    #[tokio::test]
+    async fn validate_rejects_non_public_sinks() {
+        for config in [
+            AuditLogSinkConfig::Http {
+                url: "http://127.0.0.1:8080/audit".to_owned(),
+            },
+            AuditLogSinkConfig::Http {
+                url: "http://169.254.169.254/latest/meta-data".to_owned(),
+            },
+            AuditLogSinkConfig::Http {
+                url: "https://[::1]/audit".to_owned(),
+            },
+            AuditLogSinkConfig::Http {
+                url: "http://[::ffff:10.0.0.1]/audit".to_owned(),
+            },
+            AuditLogSinkConfig::Syslog {
+                address: "10.1.2.3:514".to_owned(),
+            },
+            AuditLogSinkConfig::Syslog {
+                address: "[fd00::1]:514".to_owned(),
+            },
+        ] {
+            assert!(
+                matches!(
+                    config.validate().await,
+                    Err(AuditLogError::SinkAddressNotPublic(_))
+                ),
+                "{config:?}"
+            );
+        }
+
+        assert!(matches!(
+            AuditLogSinkConfig::Http {
+                url: "file:///etc/passwd".to_owned(),
+            }
+            .validate()
+            .await,
+            Err(AuditLogError::UnsupportedSinkScheme(_))
+        ));
+
+        for config in [
+            AuditLogSinkConfig::Http {
+                url: "https://1.1.1.1/audit".to_owned(),
+            },
+            AuditLogSinkConfig::Syslog {
+                address: "[2606:4700:4700::1111]:514".to_owned(),
+            },
+        ] {
+            assert!(config.validate().await.is_ok(), "{config:?}");
+        }
+    }
+}
@@ -151,6 +151,8 @@ impl ChangeSet {
 
     #[instrument(skip(ctx))]
     pub async fn apply(&mut self, ctx: &mut DalContext) -> ChangeSetResult<()> {
+        ctx.allow_head_writes_to_apply(self.pk)?;
+
         let actor = serde_json::to_value(ctx.history_actor())?;
         let row = ctx
             .txns()
@@ -173,7 +175,7 @@ impl ChangeSet {
         .await?;
 
         let user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => {
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
                 let user = User::get_by_pk(ctx, *user_pk)
                     .await?
                     .ok_or(ChangeSetError::InvalidActor(*user_pk))?;
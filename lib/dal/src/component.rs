@@ -31,7 +31,7 @@ use crate::{
     AttributePrototypeArgumentError, AttributePrototypeError, AttributePrototypeId,
     AttributeReadContext, ComponentType, DalContext, EdgeError, ExternalProviderError, FixError,
     FixId, Func, FuncBackendKind, FuncError, HistoryActor, HistoryEventError, Node, NodeError,
-    PropError, RootPropChild, Schema, SchemaError, SchemaId, Socket, StandardModel,
+    PropError, RootPropChild, Schema, SchemaError, SchemaId, SecretError, Socket, StandardModel,
     StandardModelError, Tenancy, Timestamp, TransactionsError, UserPk, ValidationPrototypeError,
     ValidationResolverError, Visibility, WorkspaceError, WsEvent, WsEventResult, WsPayload,
 };
@@ -160,6 +160,8 @@ pub enum ComponentError {
     SchemaVariant(#[from] SchemaVariantError),
     #[error("schema variant has not been finalized at least once: {0}")]
     SchemaVariantNotFinalized(SchemaVariantId),
+    #[error("secret error: {0}")]
+    Secret(#[from] SecretError),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("socket error: {0}")]
@@ -282,7 +284,7 @@ impl Component {
             .await?
             .ok_or(SchemaVariantError::MissingSchema(schema_variant_id))?;
         let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         };
 
@@ -836,7 +838,7 @@ impl Component {
         }
 
         let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         };
 
@@ -9,9 +9,12 @@ use crate::qualification::{
     QualificationResult, QualificationSubCheck, QualificationSubCheckStatus, QualificationView,
 };
 use crate::schema::SchemaVariant;
+use crate::secret::SecretExpiryStatus;
 use crate::validation::ValidationError;
 use crate::ws_event::WsEvent;
-use crate::{AttributeReadContext, DalContext, RootPropChild, StandardModel, ValidationResolver};
+use crate::{
+    AttributeReadContext, DalContext, RootPropChild, Secret, StandardModel, ValidationResolver,
+};
 use crate::{Component, ComponentError, ComponentId};
 
 // FIXME(nick): use the formal types from the new version of function authoring instead of this
@@ -41,6 +44,11 @@ impl Component {
         let all_fields_valid_qualification_view =
             Self::all_fields_valid_qualification(ctx, component_id).await?;
         let mut results: Vec<QualificationView> = vec![all_fields_valid_qualification_view];
+        if let Some(secrets_not_expired_qualification_view) =
+            Self::secrets_not_expired_qualification(ctx, component_id).await?
+        {
+            results.push(secrets_not_expired_qualification_view);
+        }
         let mut qualification_views = vec![];
 
         // Prepare to assemble qualification views and access the "/root/qualification" prop tree.
@@ -130,7 +138,7 @@ impl Component {
         }
 
         qualification_views.sort();
-        // We want the "all fields valid" to always be first
+        // We want the "all fields valid" (and "secrets are not expired") to always be first
         results.extend(qualification_views);
 
         WsEvent::checked_qualifications(ctx, component_id)
@@ -203,4 +211,67 @@ impl Component {
             qualification_name: name.to_string(),
         })
     }
+
+    /// An ephemeral qualification (not present in the
+    /// [`prop tree`](crate::schema::variant::leaves)) that fails if any [`Secret`] the component
+    /// uses has expired, and warns if any is about to expire or is due to be rotated. Components
+    /// which use no secrets do not get this qualification.
+    #[instrument(skip_all)]
+    pub async fn secrets_not_expired_qualification(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Option<QualificationView>> {
+        let secrets = Secret::list_for_component(ctx, component_id).await?;
+        if secrets.is_empty() {
+            return Ok(None);
+        }
+
+        let mut status = QualificationSubCheckStatus::Success;
+        let mut sub_checks = Vec::new();
+        for secret in secrets {
+            let (sub_check_status, description) = match secret.expiry_status() {
+                SecretExpiryStatus::Expired => (
+                    QualificationSubCheckStatus::Failure,
+                    format!("secret \"{}\" has expired", secret.name()),
+                ),
+                SecretExpiryStatus::Expiring => (
+                    QualificationSubCheckStatus::Warning,
+                    format!(
+                        "secret \"{}\" expires or is due to be rotated soon",
+                        secret.name()
+                    ),
+                ),
+                SecretExpiryStatus::RotationOverdue => (
+                    QualificationSubCheckStatus::Warning,
+                    format!("secret \"{}\" is overdue for rotation", secret.name()),
+                ),
+                SecretExpiryStatus::Valid => continue,
+            };
+
+            if sub_check_status == QualificationSubCheckStatus::Failure
+                || status == QualificationSubCheckStatus::Success
+            {
+                status = sub_check_status;
+            }
+            sub_checks.push(QualificationSubCheck {
+                description,
+                status: sub_check_status,
+            });
+        }
+
+        let name = "Secrets are not expired";
+        Ok(Some(QualificationView {
+            title: name.to_string(),
+            output: vec![],
+            description: None,
+            link: None,
+            result: Some(QualificationResult {
+                status,
+                title: None,
+                link: None,
+                sub_checks,
+            }),
+            qualification_name: name.to_string(),
+        }))
+    }
 }
@@ -10,6 +10,7 @@ use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
 use crate::component::ComponentResult;
 use crate::func::binding_return_value::FuncBindingReturnValue;
+use crate::secret::SecretUsageSource;
 use crate::ws_event::WsEvent;
 use crate::{
     func::backend::js_action::ActionRunResult, ActionKind, ActionPrototype, ActionPrototypeContext,
@@ -150,7 +151,9 @@ impl Component {
             None => return Ok(()),
         };
 
-        action.run(ctx, *self.id()).await?;
+        action
+            .run(ctx, *self.id(), SecretUsageSource::default())
+            .await?;
 
         Ok(())
     }
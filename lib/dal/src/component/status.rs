@@ -139,7 +139,7 @@ impl ComponentStatus {
 
     fn user_pk(history_actor: &HistoryActor) -> Option<UserPk> {
         match history_actor {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         }
     }
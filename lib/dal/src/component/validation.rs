@@ -13,6 +13,7 @@ use crate::func::backend::{
 use crate::func::before::before_funcs_for_component;
 use crate::func::binding::FuncBinding;
 use crate::func::binding_return_value::FuncBindingReturnValue;
+use crate::secret::SecretUsageSource;
 use crate::ComponentError;
 use crate::{
     AttributeReadContext, Component, DalContext, ExternalProviderId, Func, FuncBackendKind,
@@ -83,7 +84,12 @@ impl Component {
             }
         };
 
-        let before = before_funcs_for_component(ctx, self.id()).await?;
+        let before = before_funcs_for_component(
+            ctx,
+            self.id(),
+            SecretUsageSource::default().with_func_id(*func.id()),
+        )
+        .await?;
 
         // Now, we can load in the mutated args!
         let (func_binding, _) =
@@ -1,5 +1,6 @@
 use std::{mem, path::PathBuf, sync::Arc};
 
+use council_server::LocalCouncil;
 use futures::Future;
 use serde::{Deserialize, Serialize};
 use si_crypto::SymmetricCryptoService;
@@ -15,7 +16,7 @@ use crate::{
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
     },
-    HistoryActor, StandardModel, Tenancy, TenancyError, Visibility,
+    ChangeSetPk, HistoryActor, SecretProviders, StandardModel, Tenancy, TenancyError, Visibility,
 };
 
 /// A context type which contains handles to common core service dependencies.
@@ -40,6 +41,11 @@ pub struct ServicesContext {
     module_index_url: Option<String>,
     /// A service that can encrypt and decrypt values with a set of symmetric keys
     symmetric_crypto_service: SymmetricCryptoService,
+    /// The providers which resolve secrets that are references to values held elsewhere
+    secret_providers: SecretProviders,
+    /// When set, dependent values updates are coordinated by this in-process council rather
+    /// than by council over NATS
+    local_council: Option<LocalCouncil>,
 }
 
 impl ServicesContext {
@@ -54,6 +60,7 @@ impl ServicesContext {
         pkgs_path: Option<PathBuf>,
         module_index_url: Option<String>,
         symmetric_crypto_service: SymmetricCryptoService,
+        secret_providers: SecretProviders,
     ) -> Self {
         Self {
             pg_pool,
@@ -64,15 +71,25 @@ impl ServicesContext {
             pkgs_path,
             module_index_url,
             symmetric_crypto_service,
+            secret_providers,
+            local_council: None,
         }
     }
 
+    /// Coordinates dependent values updates with an in-process council rather than with council
+    /// over NATS.
+    pub fn with_local_council(mut self, local_council: LocalCouncil) -> Self {
+        self.local_council = Some(local_council);
+        self
+    }
+
     /// Consumes and returns [`DalContextBuilder`].
     pub fn into_builder(self, blocking: bool) -> DalContextBuilder {
         DalContextBuilder {
             services_context: self,
             blocking,
             no_dependent_values: false,
+            change_set_scope: None,
         }
     }
 
@@ -110,6 +127,17 @@ impl ServicesContext {
         &self.symmetric_crypto_service
     }
 
+    /// Get a reference to the secret providers
+    pub fn secret_providers(&self) -> &SecretProviders {
+        &self.secret_providers
+    }
+
+    /// Get a reference to the in-process council, if dependent values updates don't use council
+    /// over NATS
+    pub fn local_council(&self) -> Option<&LocalCouncil> {
+        self.local_council.as_ref()
+    }
+
     /// Builds and returns a new [`Connections`].
     pub async fn connections(&self) -> PgPoolResult<Connections> {
         let pg_conn = self.pg_pool.get().await?;
@@ -222,6 +250,32 @@ pub struct DalContext {
     /// Determines if we should not enqueue dependent value update jobs for attribute updates in
     /// this context
     no_dependent_values: bool,
+    /// When set, changes can only be committed in one of these change sets: head can be read, but
+    /// not written to.
+    change_set_scope: Option<Arc<[ChangeSetPk]>>,
+    /// Tracks writes made while the [`Visibility`] was outside of the change set scope. It is
+    /// shared with clones, as they share (and commit) the same transactions.
+    scope_writes: Arc<Mutex<ScopeWrites>>,
+}
+
+/// Writes made by a [`DalContext`] with a change set scope (or by its clones) while their
+/// [`Visibility`] was outside of the scope.
+#[derive(Debug, Default)]
+struct ScopeWrites {
+    /// The out of scope change set the transactions were last handed out for, and how many rows
+    /// had been written in them by then.
+    pending: Option<(ChangeSetPk, i64)>,
+    /// The first out of scope change set which was written to.
+    written: Option<ChangeSetPk>,
+}
+
+/// The error for writing to `change_set_pk` when it is outside of a context's change set scope.
+fn scope_error(change_set_pk: ChangeSetPk) -> TransactionsError {
+    if change_set_pk == ChangeSetPk::NONE {
+        TransactionsError::HeadNotInScope
+    } else {
+        TransactionsError::ChangeSetNotInScope(change_set_pk)
+    }
 }
 
 impl DalContext {
@@ -232,15 +286,18 @@ impl DalContext {
             services_context,
             blocking,
             no_dependent_values: false,
+            change_set_scope: None,
         }
     }
 
     /// Consumes all inner transactions and committing all changes made within them.
     pub async fn commit(&self) -> Result<(), TransactionsError> {
+        self.ensure_visibility_in_scope()?;
         if self.blocking {
             self.blocking_commit().await?;
         } else {
             let mut guard = self.conns_state.lock().await;
+            self.ensure_writes_in_scope(&guard).await?;
             *guard = guard.take().commit().await?;
         }
 
@@ -263,16 +320,105 @@ impl DalContext {
         self.services_context.symmetric_crypto_service()
     }
 
+    pub fn secret_providers(&self) -> &SecretProviders {
+        self.services_context.secret_providers()
+    }
+
     /// Consumes all inner transactions, committing all changes made within them, and
     /// blocks until all queued jobs have reported as finishing.
     pub async fn blocking_commit(&self) -> Result<(), TransactionsError> {
+        self.ensure_visibility_in_scope()?;
         let mut guard = self.conns_state.lock().await;
+        self.ensure_writes_in_scope(&guard).await?;
 
         *guard = guard.take().blocking_commit().await?;
 
         Ok(())
     }
 
+    /// Returns an error if the context was built with a change set scope which does not include
+    /// its current [`Visibility`]. Head is never in scope for writes.
+    fn ensure_visibility_in_scope(&self) -> Result<(), TransactionsError> {
+        if !self.visibility_in_scope() {
+            return Err(scope_error(self.visibility.change_set_pk));
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the context, or one of its clones, wrote to the transactions while its
+    /// [`Visibility`] was outside of the change set scope--for example by writing to head and then
+    /// switching to a change set in scope before committing.
+    async fn ensure_writes_in_scope(
+        &self,
+        state: &ConnectionState,
+    ) -> Result<(), TransactionsError> {
+        if self.change_set_scope.is_none() {
+            return Ok(());
+        }
+        if let ConnectionState::Transactions(txns) = state {
+            self.track_scope_writes(txns, false).await?;
+        }
+        match self.scope_writes.lock().await.written {
+            Some(change_set_pk) => Err(scope_error(change_set_pk)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the context may write under its current [`Visibility`].
+    fn visibility_in_scope(&self) -> bool {
+        match &self.change_set_scope {
+            Some(change_set_scope) => {
+                let change_set_pk = self.visibility.change_set_pk;
+                change_set_pk != ChangeSetPk::NONE && change_set_scope.contains(&change_set_pk)
+            }
+            None => true,
+        }
+    }
+
+    /// Records whether rows were written since the transactions were last handed out under an out
+    /// of scope [`Visibility`]. If `handing_out` is set and the current visibility is out of
+    /// scope, starts tracking the writes made under it.
+    async fn track_scope_writes(
+        &self,
+        txns: &Transactions,
+        handing_out: bool,
+    ) -> Result<(), TransactionsError> {
+        let mut scope_writes = self.scope_writes.lock().await;
+        let mut written_rows = None;
+        if let Some((change_set_pk, written_before)) = scope_writes.pending.take() {
+            let written_after = txns.written_row_count().await?;
+            if written_after > written_before {
+                scope_writes.written.get_or_insert(change_set_pk);
+            }
+            written_rows = Some(written_after);
+        }
+        if handing_out && !self.visibility_in_scope() {
+            let written_before = match written_rows {
+                Some(written_rows) => written_rows,
+                None => txns.written_row_count().await?,
+            };
+            scope_writes.pending = Some((self.visibility.change_set_pk, written_before));
+        }
+        Ok(())
+    }
+
+    /// Lets a context with a change set scope write to head, as applying `change_set_pk` has to,
+    /// if `change_set_pk` is in scope. The context can write to head for the rest of its life, so
+    /// it should only be used to apply the change set and for what follows from it (like the fix
+    /// batch running the change set's actions).
+    pub fn allow_head_writes_to_apply(
+        &mut self,
+        change_set_pk: ChangeSetPk,
+    ) -> Result<(), TransactionsError> {
+        if let Some(change_set_scope) = &self.change_set_scope {
+            if !change_set_scope.contains(&change_set_pk) {
+                return Err(TransactionsError::ChangeSetNotInScope(change_set_pk));
+            }
+            self.change_set_scope = None;
+        }
+        Ok(())
+    }
+
     /// Rolls all inner transactions back, discarding all changes made within them.
     ///
     /// This is equivalent to the transaction's `Drop` implementations, but provides any error
@@ -435,6 +581,10 @@ impl DalContext {
             *guard = conns_state;
         }
 
+        if self.change_set_scope.is_some() {
+            self.track_scope_writes(guard.txns(), true).await?;
+        }
+
         Ok(MutexGuard::map(guard, |cs| cs.txns()))
     }
 
@@ -598,6 +748,9 @@ pub struct DalContextBuilder {
     /// Determines if we should not enqueue dependent value update jobs for attribute value
     /// changes.
     no_dependent_values: bool,
+    /// When set, only [`DalContexts`](DalContext) for head or for one of these change sets can be
+    /// built, and only those for one of these change sets can commit.
+    change_set_scope: Option<Arc<[ChangeSetPk]>>,
 }
 
 impl DalContextBuilder {
@@ -612,6 +765,8 @@ impl DalContextBuilder {
             visibility: Visibility::new_head(false),
             history_actor: HistoryActor::SystemInit,
             no_dependent_values: self.no_dependent_values,
+            change_set_scope: self.change_set_scope.clone(),
+            scope_writes: Default::default(),
         })
     }
 
@@ -629,6 +784,8 @@ impl DalContextBuilder {
             history_actor: access_builder.history_actor,
             visibility: Visibility::new_head(false),
             no_dependent_values: self.no_dependent_values,
+            change_set_scope: self.change_set_scope.clone(),
+            scope_writes: Default::default(),
         })
     }
 
@@ -637,6 +794,13 @@ impl DalContextBuilder {
         &self,
         request_context: RequestContext,
     ) -> Result<DalContext, TransactionsError> {
+        let change_set_pk = request_context.visibility.change_set_pk;
+        if let Some(change_set_scope) = &self.change_set_scope {
+            if change_set_pk != ChangeSetPk::NONE && !change_set_scope.contains(&change_set_pk) {
+                return Err(TransactionsError::ChangeSetNotInScope(change_set_pk));
+            }
+        }
+
         let conns = self.connections().await?;
         Ok(DalContext {
             services_context: self.services_context.clone(),
@@ -646,6 +810,8 @@ impl DalContextBuilder {
             visibility: request_context.visibility,
             history_actor: request_context.history_actor,
             no_dependent_values: self.no_dependent_values,
+            change_set_scope: self.change_set_scope.clone(),
+            scope_writes: Default::default(),
         })
     }
 
@@ -686,11 +852,21 @@ impl DalContextBuilder {
     pub fn set_no_dependent_values(&mut self) {
         self.no_dependent_values = true;
     }
+
+    /// Restricts the change sets this builder can build [`DalContexts`](DalContext) for to head
+    /// and `change_set_pks`.
+    pub fn set_change_set_scope(&mut self, change_set_pks: impl Into<Arc<[ChangeSetPk]>>) {
+        self.change_set_scope = Some(change_set_pks.into());
+    }
 }
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum TransactionsError {
+    #[error("change set {0} is outside of the change sets this context may access")]
+    ChangeSetNotInScope(ChangeSetPk),
+    #[error("head is outside of the change sets this context may write to")]
+    HeadNotInScope,
     #[error(transparent)]
     JobQueueProcessor(#[from] JobQueueProcessorError),
     #[error(transparent)]
@@ -791,6 +967,19 @@ impl Transactions {
         &self.nats_txn
     }
 
+    /// Counts the rows inserted, updated or deleted so far in the PostgreSQL transaction.
+    async fn written_row_count(&self) -> Result<i64, TransactionsError> {
+        let row = self
+            .pg_txn
+            .query_one(
+                "SELECT COALESCE(SUM(n_tup_ins + n_tup_upd + n_tup_del), 0)::bigint AS count
+                 FROM pg_stat_xact_user_tables",
+                &[],
+            )
+            .await?;
+        Ok(row.try_get("count")?)
+    }
+
     /// Consumes all inner transactions, committing all changes made within them, and returns
     /// underlying connections.
     pub async fn commit_into_conns(self) -> Result<Connections, TransactionsError> {
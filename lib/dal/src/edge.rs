@@ -184,7 +184,7 @@ impl Edge {
         tail_socket_id: SocketId,
     ) -> EdgeResult<Self> {
         let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         };
 
@@ -434,7 +434,7 @@ impl Edge {
         edge_argument.delete_by_id(ctx).await?;
 
         let actor_user_pk = match ctx.history_actor() {
-            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(*user_pk),
             _ => None,
         };
         let _rows = ctx
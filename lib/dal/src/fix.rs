@@ -11,17 +11,20 @@ use thiserror::Error;
 use crate::fix::batch::FixBatchId;
 use crate::func::binding_return_value::FuncBindingReturnValueError;
 use crate::schema::SchemaUiMenu;
+use crate::secret::SecretUsageSource;
 use crate::{
     func::backend::js_action::ActionRunResult, impl_standard_model, pk, standard_model,
     standard_model_accessor, standard_model_accessor_ro, standard_model_belongs_to, ActionKind,
     ActionPrototype, ActionPrototypeError, ActionPrototypeId, Component, ComponentError,
     ComponentId, DalContext, FixBatch, FixResolverError, Func, FuncError, HistoryEventError,
     ResourceView, SchemaError, StandardModel, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, Visibility, WsEvent, WsEventError, WsEventResult, WsPayload,
+    TransactionsError, UserError, UserPk, Visibility, WorkspaceError, WsEvent, WsEventError,
+    WsEventResult, WsPayload,
 };
 use veritech_client::ResourceStatus;
 
 pub mod batch;
+pub mod confirmation;
 pub mod resolver;
 
 /// The completion status of a [`Fix`] or [`FixBatch`](crate::FixBatch).
@@ -65,6 +68,8 @@ type JsonValue = serde_json::Value;
 pub enum FixError {
     #[error(transparent)]
     ActionPrototype(#[from] ActionPrototypeError),
+    #[error("fix batch {0} has already been confirmed")]
+    AlreadyConfirmed(FixBatchId),
     #[error("cannot stamp batch or fix as started since it already finished")]
     AlreadyFinished,
     #[error("cannot stamp batch or fix as started since it already started")]
@@ -75,6 +80,14 @@ pub enum FixError {
     BatchAlreadyStarted(FixId, FixBatchId),
     #[error(transparent)]
     Component(#[from] ComponentError),
+    #[error("fix batch {0} can only be confirmed by a signed in user")]
+    ConfirmationNotByUser(FixBatchId),
+    #[error("fix batch {0} does not require confirmation")]
+    ConfirmationNotRequired(FixBatchId),
+    #[error("fix batch {0} requires a second approver and can not be confirmed by its author")]
+    ConfirmedByAuthor(FixBatchId),
+    #[error("confirming user not found: {0}")]
+    ConfirmingUserNotFound(UserPk),
     #[error("completion status is empty")]
     EmptyCompletionStatus,
     #[error(transparent)]
@@ -99,6 +112,8 @@ pub enum FixError {
     NoFixesInBatch(FixBatchId),
     #[error("cannot stamp batch or fix as finished since it has not yet been started")]
     NotYetStarted,
+    #[error("fix batch {0} is pending confirmation")]
+    PendingConfirmation(FixBatchId),
     #[error(transparent)]
     Pg(#[from] PgError),
     #[error(transparent)]
@@ -110,6 +125,10 @@ pub enum FixError {
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
+    User(#[from] UserError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
@@ -243,7 +262,11 @@ impl Fix {
         // Stamp started and run the workflow.
         self.stamp_started(ctx).await?;
 
-        Ok(match action_prototype.run(ctx, self.component_id).await {
+        let fix_batch_id = self.fix_batch(ctx).await?.map(|batch| *batch.id());
+        let source = SecretUsageSource::default().with_fix(self.id, fix_batch_id);
+        let run_result = action_prototype.run(ctx, self.component_id, source).await;
+
+        Ok(match run_result {
             Ok(Some(run_result)) => {
                 let completion_status = match run_result.status {
                     ResourceStatus::Ok | ResourceStatus::Warning => FixCompletionStatus::Success,
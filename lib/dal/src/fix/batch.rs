@@ -6,9 +6,9 @@ use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
 use crate::{
-    fix::{FixCompletionStatus, FixError, FixResult},
+    fix::{confirmation::FixConfirmation, FixCompletionStatus, FixError, FixResult},
     impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_has_many,
-    DalContext, Fix, StandardModel, Tenancy, Timestamp, Visibility, WsEvent, WsEventResult,
+    DalContext, Fix, StandardModel, Tenancy, Timestamp, UserPk, Visibility, WsEvent, WsEventResult,
     WsPayload,
 };
 
@@ -44,6 +44,10 @@ pub struct FixBatch {
     finished_at: Option<String>,
     /// Indicates the state of the [`FixBatch`] when finished.
     completion_status: Option<FixCompletionStatus>,
+    /// The confirmation the [`FixBatch`] requires before it can start, if any.
+    required_confirmation: Option<FixConfirmation>,
+    /// The [`User`](crate::User) who gave the required confirmation, once given.
+    confirmed_by: Option<UserPk>,
 }
 
 impl_standard_model! {
@@ -79,6 +83,12 @@ impl FixBatch {
         Option<Enum(FixCompletionStatus)>,
         FixResult
     );
+    standard_model_accessor!(
+        required_confirmation,
+        Option<Enum(FixConfirmation)>,
+        FixResult
+    );
+    standard_model_accessor!(confirmed_by, Option<Pk(UserPk)>, FixResult);
 
     // TODO(nick): store the order (and what's sequential, conditional, parallel, etc.) someday.
     standard_model_has_many!(
@@ -125,7 +135,9 @@ impl FixBatch {
 
     /// A safe wrapper around setting the started column.
     pub async fn stamp_started(&mut self, ctx: &DalContext) -> FixResult<()> {
-        if self.started_at.is_some() {
+        if self.is_pending_confirmation() {
+            Err(FixError::PendingConfirmation(self.id))
+        } else if self.started_at.is_some() {
             Err(FixError::AlreadyStarted)
         } else if self.finished_at.is_some() {
             Err(FixError::AlreadyFinished)
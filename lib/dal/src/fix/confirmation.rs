@@ -0,0 +1,145 @@
+//! This module contains [`FixConfirmation`], the extra step a [`Workspace`] can require before a
+//! [`FixBatch`] containing [`Delete`](ActionKind::Delete) actions runs. Such a batch is created
+//! pending confirmation and will not start until it is [`confirmed`](FixBatch::confirm).
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+use telemetry::prelude::*;
+
+use crate::{
+    fix::{FixError, FixResult},
+    ActionKind, DalContext, FixBatch, FixBatchId, HistoryActor, HistoryEvent, StandardModel, User,
+    Workspace,
+};
+
+const FIX_BATCH_LOCK_BY_ID: &str = include_str!("../queries/fix_batch/lock_by_id.sql");
+
+/// How a [`FixBatch`] containing [`Delete`](ActionKind::Delete) actions must be confirmed before
+/// it runs.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum FixConfirmation {
+    /// The batch must be confirmed by a user who has recently signed in again. The freshness of
+    /// the sign in is checked by whoever calls [`FixBatch::confirm`].
+    Reauthenticate,
+    /// The batch must be confirmed by a user other than its author.
+    SecondApprover,
+}
+
+impl FixBatch {
+    /// Marks the batch as pending confirmation if its [`Workspace`] requires a
+    /// [`FixConfirmation`] for destructive fixes and any of its [`Fixes`](crate::Fix) deletes a
+    /// resource. Call this once all fixes have been added to the batch, and do not run the batch
+    /// while it [`is pending confirmation`](Self::is_pending_confirmation).
+    pub async fn require_confirmation_if_destructive(
+        &mut self,
+        ctx: &DalContext,
+    ) -> FixResult<Option<FixConfirmation>> {
+        let workspace_pk = match ctx.tenancy().workspace_pk() {
+            Some(workspace_pk) => workspace_pk,
+            None => return Ok(None),
+        };
+        let confirmation = match Workspace::get_by_pk(ctx, &workspace_pk)
+            .await?
+            .and_then(|workspace| workspace.destructive_fix_confirmation())
+        {
+            Some(confirmation) => confirmation,
+            None => return Ok(None),
+        };
+
+        let is_destructive = self
+            .fixes(ctx)
+            .await?
+            .iter()
+            .any(|fix| *fix.action_kind() == ActionKind::Delete);
+        if !is_destructive {
+            return Ok(None);
+        }
+
+        self.set_required_confirmation(ctx, Some(confirmation))
+            .await?;
+        Ok(Some(confirmation))
+    }
+
+    /// Gets the batch, locking it until the [`DalContext`]'s transaction ends. Concurrent attempts
+    /// to [`confirm`](Self::confirm) the same batch wait for each other this way, so only the first
+    /// confirms it and the rest see it already confirmed.
+    pub async fn get_by_id_for_update(
+        ctx: &DalContext,
+        id: &FixBatchId,
+    ) -> FixResult<Option<Self>> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query(FIX_BATCH_LOCK_BY_ID, &[ctx.tenancy(), id])
+            .await?;
+
+        Ok(Self::get_by_id(ctx, id).await?)
+    }
+
+    /// Whether the batch requires a confirmation which has not been given yet.
+    pub fn is_pending_confirmation(&self) -> bool {
+        self.required_confirmation.is_some() && self.confirmed_by.is_none()
+    }
+
+    /// Confirms a batch which [`is pending confirmation`](Self::is_pending_confirmation) on behalf
+    /// of the [`User`] in the [`DalContext`], and records the confirmation in the batch's history.
+    /// Confirming on behalf of an api token is not allowed, and a batch requiring a
+    /// [`SecondApprover`](FixConfirmation::SecondApprover) can not be confirmed by its author.
+    #[instrument(skip_all)]
+    pub async fn confirm(&mut self, ctx: &DalContext) -> FixResult<()> {
+        let confirmation = self
+            .required_confirmation
+            .ok_or(FixError::ConfirmationNotRequired(self.id))?;
+        if self.confirmed_by.is_some() {
+            return Err(FixError::AlreadyConfirmed(self.id));
+        }
+        if self.started_at.is_some() {
+            return Err(FixError::AlreadyStarted);
+        }
+
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => *user_pk,
+            HistoryActor::ApiToken { .. } | HistoryActor::SystemInit => {
+                return Err(FixError::ConfirmationNotByUser(self.id));
+            }
+        };
+        let user = User::get_by_pk(ctx, user_pk)
+            .await?
+            .ok_or(FixError::ConfirmingUserNotFound(user_pk))?;
+        if confirmation == FixConfirmation::SecondApprover && user.email() == &self.author {
+            return Err(FixError::ConfirmedByAuthor(self.id));
+        }
+
+        self.set_confirmed_by(ctx, Some(user_pk)).await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "fix_batch.confirmed",
+            "Fix Batch confirmed",
+            &serde_json::json!({
+                "pk": self.pk,
+                "id": self.id,
+                "confirmation": confirmation,
+                "confirmedBy": user_pk,
+                "visibility": self.visibility,
+            }),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
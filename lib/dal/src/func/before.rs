@@ -2,7 +2,8 @@ use serde::Deserialize;
 use veritech_client::{encrypt_value_tree, BeforeFunction};
 
 use crate::{
-    standard_model, ComponentId, DalContext, EncryptedSecret, Func, FuncError, FuncResult,
+    secret::SecretUsageSource, standard_model, ComponentId, DalContext, EncryptedSecret, Func,
+    FuncError, FuncResult,
 };
 
 const AUTH_FUNCS_FOR_COMPONENT: &str =
@@ -14,10 +15,16 @@ struct EncryptedSecretAndFunc {
     func: Func,
 }
 
+/// Builds the [`BeforeFunction`]s that authenticate a function run for the given component,
+/// decrypting each secret it uses. The decrypts are recorded against `source`, along with the
+/// component.
 pub async fn before_funcs_for_component(
     ctx: &DalContext,
     component_id: &ComponentId,
+    source: SecretUsageSource,
 ) -> FuncResult<Vec<BeforeFunction>> {
+    let source = source.with_component_id(*component_id);
+
     let rows = ctx
         .txns()
         .await?
@@ -36,7 +43,11 @@ pub async fn before_funcs_for_component(
     } in standard_model::objects_from_rows(rows)?
     {
         // Decrypt message from EncryptedSecret
-        let mut arg = encrypted_secret.decrypt(ctx).await?.message().into_inner();
+        let mut arg = encrypted_secret
+            .decrypt(ctx, source)
+            .await?
+            .message()
+            .into_inner();
         // Re-encrypt raw Value for transmission to Cyclone via Veritech
         encrypt_value_tree(&mut arg, ctx.encryption_key())?;
 
@@ -7,7 +7,7 @@ use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 
-use crate::{pk, DalContext, Timestamp, UserPk};
+use crate::{pk, ApiTokenPk, DalContext, Timestamp, UserPk};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -27,6 +27,11 @@ pub type HistoryEventResult<T> = Result<T, HistoryEventError>;
 #[remain::sorted]
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, StrumDisplay, Clone, Copy)]
 pub enum HistoryActor {
+    /// An [`ApiToken`](crate::ApiToken), acting on behalf of the user who created it
+    ApiToken {
+        api_token_pk: ApiTokenPk,
+        user_pk: UserPk,
+    },
     SystemInit,
     User(UserPk),
 }
@@ -34,7 +39,7 @@ pub enum HistoryActor {
 impl HistoryActor {
     pub fn distinct_id(&self) -> String {
         match self {
-            HistoryActor::User(pk) => pk.to_string(),
+            HistoryActor::User(pk) | HistoryActor::ApiToken { user_pk: pk, .. } => pk.to_string(),
             HistoryActor::SystemInit => "unknown-backend".to_string(),
         }
     }
@@ -46,6 +51,9 @@ impl From<UserPk> for HistoryActor {
     }
 }
 
+/// The [NATS](https://nats.io) subject every [`HistoryEvent`] is published on once created.
+pub const HISTORY_EVENT_SUBJECT: &str = "historyEvent";
+
 pk!(HistoryEventPk);
 
 /// HistoryEvents are the audit trail for things in SI. They track
@@ -85,7 +93,7 @@ impl HistoryEvent {
             .await?;
         let json: serde_json::Value = row.try_get("object")?;
         // TODO(fnichol): determine subject(s) for publishing
-        txns.nats().publish("historyEvent", &json).await?;
+        txns.nats().publish(HISTORY_EVENT_SUBJECT, &json).await?;
         let object: HistoryEvent = serde_json::from_value(json)?;
         Ok(object)
     }
@@ -53,6 +53,8 @@ pub struct InstalledPkg {
     pk: InstalledPkgPk,
     id: InstalledPkgId,
     name: String,
+    /// Absent for packages installed before versions were recorded.
+    version: Option<String>,
     root_hash: String,
     #[serde(flatten)]
     tenancy: Tenancy,
@@ -76,17 +78,19 @@ impl InstalledPkg {
     pub async fn new(
         ctx: &DalContext,
         name: impl AsRef<str>,
+        version: impl AsRef<str>,
         root_hash: impl AsRef<str>,
     ) -> InstalledPkgResult<Self> {
         let name = name.as_ref();
+        let version = version.as_ref();
         let root_hash = root_hash.as_ref();
         let row = ctx
             .txns()
             .await?
             .pg()
             .query_one(
-                "SELECT object FROM installed_pkg_create_v1($1, $2, $3, $4)",
-                &[ctx.tenancy(), ctx.visibility(), &name, &root_hash],
+                "SELECT object FROM installed_pkg_create_v1($1, $2, $3, $4, $5)",
+                &[ctx.tenancy(), ctx.visibility(), &name, &version, &root_hash],
             )
             .await?;
         let object = standard_model::finish_create_from_row(ctx, row).await?;
@@ -94,9 +98,14 @@ impl InstalledPkg {
     }
 
     standard_model_accessor!(name, String, InstalledPkgResult);
+    standard_model_accessor!(version, Option<String>, InstalledPkgResult);
     standard_model_accessor!(root_hash, String, InstalledPkgResult);
 
     pub async fn find_by_hash(ctx: &DalContext, hash: &str) -> InstalledPkgResult<Option<Self>> {
         Ok(Self::find_by_attr(ctx, "root_hash", &hash).await?.pop())
     }
+
+    pub async fn find_by_name(ctx: &DalContext, name: &str) -> InstalledPkgResult<Vec<Self>> {
+        Ok(Self::find_by_attr(ctx, "name", &name).await?)
+    }
 }
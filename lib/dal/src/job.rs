@@ -1,5 +1,7 @@
 pub mod consumer;
+pub mod dead_letter;
 pub mod definition;
 pub mod processor;
 pub mod producer;
 pub mod queue;
+pub mod schedule;
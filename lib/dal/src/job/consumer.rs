@@ -3,7 +3,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use si_data_nats::NatsError;
-use si_data_pg::PgPoolError;
+use si_data_pg::{PgError, PgPoolError};
 use thiserror::Error;
 use tokio::task::JoinError;
 
@@ -11,8 +11,8 @@ use crate::{
     fix::FixError, func::binding_return_value::FuncBindingReturnValueError,
     job::producer::BlockingJobError, job::producer::JobProducerError, status::StatusUpdaterError,
     AccessBuilder, ActionPrototypeError, ActionPrototypeId, AttributeValueError, ComponentError,
-    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, StandardModelError,
-    TransactionsError, Visibility, WsEventError,
+    ComponentId, DalContext, DalContextBuilder, FixBatchId, FixResolverError, KeyPairError,
+    SecretError, StandardModelError, TransactionsError, Visibility, WsEventError,
 };
 
 #[remain::sorted]
@@ -52,6 +52,8 @@ pub enum JobConsumerError {
     Io(#[from] ::std::io::Error),
     #[error(transparent)]
     JobProducer(#[from] JobProducerError),
+    #[error(transparent)]
+    KeyPair(#[from] KeyPairError),
     #[error("missing fix execution batch for id: {0}")]
     MissingFixBatch(FixBatchId),
     #[error(transparent)]
@@ -63,7 +65,11 @@ pub enum JobConsumerError {
     #[error("no schema variant found for component {0}")]
     NoSchemaVariantFound(ComponentId),
     #[error(transparent)]
-    PgPool(#[from] PgPoolError),
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    PgPool(#[from] Box<PgPoolError>),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -76,10 +82,18 @@ pub enum JobConsumerError {
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
+    #[error("unknown job kind {0}")]
+    UnknownJobKind(String),
     #[error(transparent)]
     WsEvent(#[from] WsEventError),
 }
 
+impl From<PgPoolError> for JobConsumerError {
+    fn from(value: PgPoolError) -> Self {
+        Self::PgPool(Box::new(value))
+    }
+}
+
 impl From<JobConsumerError> for std::io::Error {
     fn from(jce: JobConsumerError) -> Self {
         Self::new(std::io::ErrorKind::InvalidData, jce)
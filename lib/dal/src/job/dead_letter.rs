@@ -0,0 +1,194 @@
+//! This module contains [`JobDeadLetter`], the record `pinga` keeps of a job that failed, along
+//! with everything needed to run it again.
+//!
+//! Unlike a [`JobFailure`](crate::JobFailure), which only stores a message for the user, a dead
+//! letter keeps the full [`JobInfo`] of the failed job. It can then be inspected and either
+//! replayed (through the [`JobQueueProcessor`](crate::JobQueueProcessor)) or discarded.
+
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    job::consumer::JobInfo, pk, standard_model::objects_from_rows, AccessBuilder, DalContext,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility,
+};
+
+const LIST: &str = include_str!("../queries/job_dead_letter/list.sql");
+const GET_BY_PK: &str = include_str!("../queries/job_dead_letter/get_by_pk.sql");
+const DELETE: &str = include_str!("../queries/job_dead_letter/delete.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum JobDeadLetterError {
+    #[error("job dead letter not found: {0}")]
+    NotFound(JobDeadLetterPk),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Transactions(#[from] Box<TransactionsError>),
+}
+
+impl From<TransactionsError> for JobDeadLetterError {
+    fn from(value: TransactionsError) -> Self {
+        Self::Transactions(Box::new(value))
+    }
+}
+
+pub type JobDeadLetterResult<T> = Result<T, JobDeadLetterError>;
+
+pk!(JobDeadLetterPk);
+
+/// A failed job, kept around so that it can be replayed or discarded.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct JobDeadLetter {
+    pk: JobDeadLetterPk,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    job_id: String,
+    kind: String,
+    arg: Value,
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    blocking: bool,
+    error_chain: Vec<String>,
+}
+
+impl JobDeadLetter {
+    /// Records `job` as dead, along with the chain of errors (outermost first) that made it fail.
+    /// The dead letter belongs to the job's own tenancy, not the [`DalContext`]'s.
+    #[instrument(skip_all, fields(job.id = %job.id, job.kind = %job.kind))]
+    pub async fn new(
+        ctx: &DalContext,
+        job: &JobInfo,
+        error: &(dyn Error + Send + Sync + 'static),
+    ) -> JobDeadLetterResult<Self> {
+        let error_chain = Self::collect_error_chain(error);
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM job_dead_letter_create_v1($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    job.access_builder.tenancy(),
+                    &job.id,
+                    &job.kind,
+                    &job.arg,
+                    &serde_json::to_value(job.access_builder)?,
+                    &job.visibility,
+                    &job.blocking,
+                    &serde_json::to_value(error_chain)?,
+                ],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    fn collect_error_chain(error: &(dyn Error + 'static)) -> Vec<String> {
+        let mut chain = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(error) = source {
+            chain.push(error.to_string());
+            source = error.source();
+        }
+        chain
+    }
+
+    pub async fn get_by_pk(ctx: &DalContext, pk: JobDeadLetterPk) -> JobDeadLetterResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, ctx.tenancy()])
+            .await?
+            .ok_or(JobDeadLetterError::NotFound(pk))?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Lists the dead letters visible to the [`DalContext`]'s tenancy, newest first.
+    pub async fn list(ctx: &DalContext) -> JobDeadLetterResult<Vec<Self>> {
+        let rows = ctx.txns().await?.pg().query(LIST, &[ctx.tenancy()]).await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Enqueues the failed job again and removes the dead letter. Both only happen once the
+    /// [`DalContext`] is committed, and the job is sent with a new id but otherwise unchanged.
+    #[instrument(skip_all, fields(job_dead_letter.pk = %self.pk))]
+    pub async fn replay(self, ctx: &DalContext) -> JobDeadLetterResult<()> {
+        let job = self.job_info();
+        ctx.enqueue_job(Box::new(job)).await?;
+        self.discard(ctx).await
+    }
+
+    /// Removes the dead letter without running the job again.
+    pub async fn discard(self, ctx: &DalContext) -> JobDeadLetterResult<()> {
+        ctx.txns().await?.pg().execute(DELETE, &[&self.pk]).await?;
+        Ok(())
+    }
+
+    /// Builds a fresh [`JobInfo`] to replay the failed job with. Nothing is waiting on a replayed
+    /// job, so it is never blocking.
+    pub fn job_info(&self) -> JobInfo {
+        JobInfo {
+            id: Ulid::new().to_string(),
+            kind: self.kind.clone(),
+            created_at: Utc::now(),
+            arg: self.arg.clone(),
+            access_builder: self.access_builder,
+            visibility: self.visibility,
+            blocking: false,
+        }
+    }
+
+    pub fn pk(&self) -> JobDeadLetterPk {
+        self.pk
+    }
+
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn arg(&self) -> &Value {
+        &self.arg
+    }
+
+    pub fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn blocking(&self) -> bool {
+        self.blocking
+    }
+
+    pub fn error_chain(&self) -> &[String] {
+        &self.error_chain
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.timestamp.created_at
+    }
+}
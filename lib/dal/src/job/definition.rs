@@ -1,7 +1,31 @@
+use crate::job::consumer::{JobConsumer, JobConsumerError, JobConsumerResult, JobInfo};
+
 mod dependent_values_update;
 mod fix;
+mod key_pair_rotation;
 mod refresh;
+mod scheduled_refresh;
+mod secret_expiry_check;
 
 pub use dependent_values_update::DependentValuesUpdate;
 pub use fix::{FixItem, FixesJob};
+pub use key_pair_rotation::KeyPairRotationJob;
 pub use refresh::RefreshJob;
+pub use scheduled_refresh::{ScheduledRefreshJob, SCHEDULED_REFRESH_SCHEDULE_NAME};
+pub use secret_expiry_check::{SecretExpiryCheckJob, SECRET_EXPIRY_CHECK_SCHEDULE_NAME};
+
+/// Turns a [`JobInfo`] back into the [`JobConsumer`] for its kind, so that it can be run.
+pub fn job_consumer_from_job_info(
+    job_info: JobInfo,
+) -> JobConsumerResult<Box<dyn JobConsumer + Send + Sync>> {
+    Ok(match job_info.kind.as_str() {
+        stringify!(DependentValuesUpdate) => Box::new(DependentValuesUpdate::try_from(job_info)?)
+            as Box<dyn JobConsumer + Send + Sync>,
+        stringify!(FixesJob) => Box::new(FixesJob::try_from(job_info)?),
+        stringify!(KeyPairRotationJob) => Box::new(KeyPairRotationJob::try_from(job_info)?),
+        stringify!(RefreshJob) => Box::new(RefreshJob::try_from(job_info)?),
+        stringify!(ScheduledRefreshJob) => Box::new(ScheduledRefreshJob::try_from(job_info)?),
+        stringify!(SecretExpiryCheckJob) => Box::new(SecretExpiryCheckJob::try_from(job_info)?),
+        kind => return Err(JobConsumerError::UnknownJobKind(kind.to_owned())),
+    })
+}
@@ -92,20 +92,28 @@ impl JobConsumer for DependentValuesUpdate {
         )
     )]
     async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
-        let council_subject =
-            if let Some(subject_prefix) = ctx.nats_conn().metadata().subject_prefix() {
-                format!("{subject_prefix}.council")
-            } else {
-                "council".to_string()
-            };
         let jid = council_server::Id::from_string(&self.job_id().unwrap())?;
-        let mut council = council_server::Client::new(
-            ctx.nats_conn().clone(),
-            &council_subject,
-            jid,
-            self.visibility().change_set_pk.into(),
-        )
-        .await?;
+        let change_set_id = self.visibility().change_set_pk.into();
+        let mut council = match ctx.services_context().local_council() {
+            Some(local_council) => {
+                council_server::Client::new_local(local_council.clone(), jid, change_set_id)
+            }
+            None => {
+                let council_subject =
+                    if let Some(subject_prefix) = ctx.nats_conn().metadata().subject_prefix() {
+                        format!("{subject_prefix}.council")
+                    } else {
+                        "council".to_string()
+                    };
+                council_server::Client::new(
+                    ctx.nats_conn().clone(),
+                    &council_subject,
+                    jid,
+                    change_set_id,
+                )
+                .await?
+            }
+        };
         let pub_council = council.clone_into_pub();
 
         match self.inner_run(ctx, &mut council, pub_council).await {
@@ -0,0 +1,98 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    AccessBuilder, DalContext, KeyPair, StatusUpdater, Visibility, WsEvent,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct KeyPairRotationJobArgs {}
+
+/// Rotates the [`KeyPair`] of the workspace in the job's tenancy, re-sealing all of its secrets
+/// to the new key and reporting progress through a [`StatusUpdate`](crate::StatusUpdate).
+#[derive(Clone, Debug, Serialize)]
+pub struct KeyPairRotationJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl KeyPairRotationJob {
+    pub fn new(access_builder: AccessBuilder) -> Box<Self> {
+        Box::new(Self {
+            access_builder,
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for KeyPairRotationJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(KeyPairRotationJobArgs {})?)
+    }
+}
+
+impl JobConsumerMetadata for KeyPairRotationJob {
+    fn type_name(&self) -> String {
+        "KeyPairRotationJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for KeyPairRotationJob {
+    #[instrument(
+        name = "key_pair_rotation_job.run",
+        skip_all,
+        level = "info",
+        fields(
+            workspace_pk = ?self.access_builder.tenancy().workspace_pk(),
+        )
+    )]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        let mut status_updater = StatusUpdater::initialize(ctx).await;
+
+        let key_pair = KeyPair::rotate(ctx, &mut status_updater).await?;
+        info!(key_pair_pk = %key_pair.pk(), "rotated workspace key pair");
+
+        status_updater.finish(ctx).await;
+
+        WsEvent::change_set_written(ctx)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for KeyPairRotationJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        let _args = KeyPairRotationJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        definition::RefreshJob,
+        producer::{JobProducer, JobProducerResult},
+    },
+    standard_model, AccessBuilder, Component, ComponentId, DalContext, HistoryActor, StandardModel,
+    Tenancy, Visibility, WorkspacePk,
+};
+
+/// The name of the [`JobSchedule`](crate::JobSchedule) that periodically fires a
+/// [`ScheduledRefreshJob`].
+pub const SCHEDULED_REFRESH_SCHEDULE_NAME: &str = "resource-refresh";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ScheduledRefreshJobArgs {}
+
+/// Looks up every component on head, across all workspaces, and enqueues a [`RefreshJob`] per
+/// workspace for them. This is meant to be fired periodically by a named
+/// [`JobSchedule`](crate::JobSchedule) rather than enqueued directly.
+#[derive(Clone, Debug, Serialize)]
+pub struct ScheduledRefreshJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl ScheduledRefreshJob {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            access_builder: AccessBuilder::new(Tenancy::new_empty(), HistoryActor::SystemInit),
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+
+    /// Gets all the components on head in the database, bypassing tenancy checks.
+    async fn components(ctx: &DalContext) -> JobConsumerResult<Vec<Component>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT DISTINCT ON (id) id, row_to_json(components.*) as object
+                 FROM components
+                 WHERE is_visible_v1($1, visibility_change_set_pk, visibility_deleted_at)
+                       AND (visibility_deleted_at IS NULL OR needs_destroy)
+                 ORDER BY id",
+                &[ctx.visibility()],
+            )
+            .await?;
+        Ok(standard_model::objects_from_rows(rows)?)
+    }
+}
+
+impl JobProducer for ScheduledRefreshJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(ScheduledRefreshJobArgs {})?)
+    }
+}
+
+impl JobConsumerMetadata for ScheduledRefreshJob {
+    fn type_name(&self) -> String {
+        "ScheduledRefreshJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for ScheduledRefreshJob {
+    #[instrument(name = "scheduled_refresh_job.run", skip_all, level = "info")]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        ctx.update_with_deleted_visibility();
+
+        let mut components_by_workspace: BTreeMap<WorkspacePk, Vec<ComponentId>> = BTreeMap::new();
+        for component in Self::components(ctx).await? {
+            if let Some(workspace_pk) = component.tenancy().workspace_pk() {
+                components_by_workspace
+                    .entry(workspace_pk)
+                    .or_default()
+                    .push(*component.id());
+            }
+        }
+
+        debug!(
+            workspaces = components_by_workspace.len(),
+            "enqueueing resource refreshes"
+        );
+
+        for (workspace_pk, component_ids) in components_by_workspace {
+            ctx.enqueue_job(RefreshJob::new(
+                AccessBuilder::new(Tenancy::new(workspace_pk), HistoryActor::SystemInit),
+                Visibility::new_head(false),
+                component_ids,
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for ScheduledRefreshJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        let _args = ScheduledRefreshJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
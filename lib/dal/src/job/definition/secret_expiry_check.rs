@@ -0,0 +1,113 @@
+use std::{collections::BTreeMap, convert::TryFrom};
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobConsumer, JobConsumerError, JobConsumerMetadata, JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    secret::{SecretExpiryWarning, SECRET_EXPIRY_WARNING_DAYS},
+    AccessBuilder, DalContext, HistoryActor, Secret, StandardModel, Tenancy, Visibility,
+    WorkspacePk, WsEvent,
+};
+
+/// The name of the [`JobSchedule`](crate::JobSchedule) that periodically fires a
+/// [`SecretExpiryCheckJob`].
+pub const SECRET_EXPIRY_CHECK_SCHEDULE_NAME: &str = "secret-expiry-check";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct SecretExpiryCheckJobArgs {}
+
+/// Looks up every secret on head, across all workspaces, which has expired or will expire (or is
+/// due to be rotated) within the warning window, and warns each workspace about its secrets with
+/// a [`WsEvent`]. This is meant to be fired periodically by a named
+/// [`JobSchedule`](crate::JobSchedule) rather than enqueued directly.
+#[derive(Clone, Debug, Serialize)]
+pub struct SecretExpiryCheckJob {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl SecretExpiryCheckJob {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            access_builder: AccessBuilder::new(Tenancy::new_empty(), HistoryActor::SystemInit),
+            visibility: Visibility::new_head(false),
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for SecretExpiryCheckJob {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(SecretExpiryCheckJobArgs {})?)
+    }
+}
+
+impl JobConsumerMetadata for SecretExpiryCheckJob {
+    fn type_name(&self) -> String {
+        "SecretExpiryCheckJob".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for SecretExpiryCheckJob {
+    #[instrument(name = "secret_expiry_check_job.run", skip_all, level = "info")]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<()> {
+        let cutoff = Utc::now() + Duration::days(SECRET_EXPIRY_WARNING_DAYS);
+
+        let mut warnings_by_workspace: BTreeMap<WorkspacePk, Vec<SecretExpiryWarning>> =
+            BTreeMap::new();
+        for secret in Secret::list_expiring_in_all_workspaces(ctx, cutoff).await? {
+            if let Some(workspace_pk) = secret.tenancy().workspace_pk() {
+                warnings_by_workspace
+                    .entry(workspace_pk)
+                    .or_default()
+                    .push(SecretExpiryWarning::from(&secret));
+            }
+        }
+
+        debug!(
+            workspaces = warnings_by_workspace.len(),
+            "warning about expiring secrets"
+        );
+
+        for (workspace_pk, warnings) in warnings_by_workspace {
+            WsEvent::secrets_expiring(workspace_pk, warnings)
+                .await?
+                .publish_on_commit(ctx)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TryFrom<JobInfo> for SecretExpiryCheckJob {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        let _args = SecretExpiryCheckJobArgs::deserialize(&job.arg)?;
+
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
@@ -1,5 +1,8 @@
 use async_trait::async_trait;
 use dyn_clone::DynClone;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use si_data_nats::NatsClient;
+use strum::{Display, EnumString, EnumVariantNames};
 use thiserror::Error;
 
 use crate::{
@@ -7,9 +10,15 @@ use crate::{
     DalContext,
 };
 
+mod jetstream_processor;
+mod local_processor;
 mod nats_processor;
+pub use jetstream_processor::{JetStreamProcessor, JETSTREAM_REPLY_MAILBOX_HEADER};
+pub use local_processor::LocalProcessor;
 pub use nats_processor::NatsProcessor;
 
+const NATS_JOB_QUEUE: &str = "pinga-jobs";
+
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum JobQueueProcessorError {
@@ -17,6 +26,8 @@ pub enum JobQueueProcessorError {
     BlockingJob(#[from] BlockingJobError),
     #[error(transparent)]
     JobProducer(#[from] JobProducerError),
+    #[error("local job processor was already started")]
+    LocalProcessorAlreadyStarted,
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
     #[error(transparent)]
@@ -38,3 +49,62 @@ pub trait JobQueueProcessor: std::fmt::Debug + DynClone {
 }
 
 dyn_clone::clone_trait_object!(JobQueueProcessor);
+
+/// Which transport jobs are sent to `pinga` over.
+#[remain::sorted]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    DeserializeFromStr,
+    Display,
+    EnumString,
+    EnumVariantNames,
+    Eq,
+    PartialEq,
+    SerializeDisplay,
+)]
+#[strum(serialize_all = "camelCase")]
+pub enum JobQueueTransport {
+    /// Durable delivery through a JetStream stream, see [`JetStreamProcessor`].
+    JetStream,
+    /// Fire-and-forget delivery through core NATS, see [`NatsProcessor`].
+    Nats,
+}
+
+impl Default for JobQueueTransport {
+    fn default() -> Self {
+        Self::Nats
+    }
+}
+
+impl JobQueueTransport {
+    #[must_use]
+    pub const fn variants() -> &'static [&'static str] {
+        <JobQueueTransport as strum::VariantNames>::VARIANTS
+    }
+
+    /// Builds the [`JobQueueProcessor`] for this transport, creating the JetStream stream first
+    /// if it doesn't exist yet.
+    pub async fn processor(
+        &self,
+        client: NatsClient,
+    ) -> JobQueueProcessorResult<Box<dyn JobQueueProcessor + Send + Sync>> {
+        Ok(match self {
+            Self::JetStream => {
+                let processor = JetStreamProcessor::new(client);
+                processor.get_or_create_stream().await?;
+                Box::new(processor)
+            }
+            Self::Nats => Box::new(NatsProcessor::new(client)),
+        })
+    }
+}
+
+/// The subject `pinga` consumes jobs from, taking the client's subject prefix into account.
+fn pinga_jobs_subject(client: &NatsClient) -> String {
+    match client.metadata().subject_prefix() {
+        Some(prefix) => format!("{prefix}.{NATS_JOB_QUEUE}"),
+        None => NATS_JOB_QUEUE.to_owned(),
+    }
+}
@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use futures::StreamExt;
+use si_data_nats::{
+    jetstream::{self, context::Publish, stream::RetentionPolicy},
+    propagation, NatsClient,
+};
+use telemetry::prelude::*;
+use tokio::task::JoinSet;
+
+use crate::{
+    job::{
+        consumer::JobInfo,
+        producer::{BlockingJobError, BlockingJobResult, JobProducer, JobProducerError},
+        queue::JobQueue,
+    },
+    DalContext,
+};
+
+use super::{
+    pinga_jobs_subject, JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult,
+};
+
+/// The header carrying the mailbox a blocking job's result should be published to. JetStream uses
+/// a message's reply subject for acknowledgements, so it can't carry the mailbox like core NATS.
+pub const JETSTREAM_REPLY_MAILBOX_HEADER: &str = "Si-Reply-Mailbox";
+
+const JETSTREAM_JOBS_STREAM: &str = "PINGA_JOBS";
+
+/// A [`JobQueueProcessor`] which publishes jobs to a NATS JetStream stream, so that they are
+/// persisted until a `pinga` instance acknowledges them rather than lost if nobody is listening.
+#[derive(Clone, Debug)]
+pub struct JetStreamProcessor {
+    client: NatsClient,
+    context: jetstream::Context,
+    queue: JobQueue,
+    pinga_subject: String,
+    stream_name: String,
+}
+
+impl JetStreamProcessor {
+    pub fn new(client: NatsClient) -> Self {
+        let pinga_subject = pinga_jobs_subject(&client);
+        // Stream names can't contain `.`, so the subject prefix (if any) is folded in with `_`
+        let stream_name = match client.metadata().subject_prefix() {
+            Some(prefix) => format!("{}_{JETSTREAM_JOBS_STREAM}", prefix.replace('.', "_")),
+            None => JETSTREAM_JOBS_STREAM.to_owned(),
+        };
+
+        Self {
+            context: client.jetstream(),
+            client,
+            queue: JobQueue::new(),
+            pinga_subject,
+            stream_name,
+        }
+    }
+
+    /// The subject jobs are published to, which is captured by the [stream](Self::stream_name).
+    pub fn subject(&self) -> &str {
+        &self.pinga_subject
+    }
+
+    pub fn stream_name(&self) -> &str {
+        &self.stream_name
+    }
+
+    /// Gets the jobs stream, creating it if it doesn't exist yet. The stream uses work queue
+    /// retention, so a job is removed as soon as a consumer acknowledges it.
+    pub async fn get_or_create_stream(&self) -> JobQueueProcessorResult<jetstream::stream::Stream> {
+        self.context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: self.stream_name.clone(),
+                subjects: vec![self.pinga_subject.clone()],
+                retention: RetentionPolicy::WorkQueue,
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))
+    }
+
+    /// Publishes a job and waits for JetStream to acknowledge that it has been persisted. The job
+    /// id doubles as the message id, so a retried publish is deduplicated by the server.
+    async fn publish(
+        &self,
+        job_info: &JobInfo,
+        reply_mailbox: Option<&str>,
+    ) -> JobQueueProcessorResult<()> {
+        let mut headers = propagation::trace_context_headers();
+        if let Some(reply_mailbox) = reply_mailbox {
+            headers.insert(JETSTREAM_REPLY_MAILBOX_HEADER, reply_mailbox);
+        }
+        let publish = Publish::build()
+            .headers(headers)
+            .payload(serde_json::to_vec(job_info)?.into())
+            .message_id(&job_info.id);
+
+        self.context
+            .send_publish(self.pinga_subject.clone(), publish)
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?
+            .await
+            .map_err(|err| JobQueueProcessorError::Transport(Box::new(err)))?;
+
+        Ok(())
+    }
+
+    async fn push_all_jobs(&self) -> JobQueueProcessorResult<()> {
+        while let Some(element) = self.queue.fetch_job().await {
+            let job_info = JobInfo::new(element)?;
+
+            if let Err(err) = self.publish(&job_info, None).await {
+                error!("JetStream job push failed, some jobs will be dropped");
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobQueueProcessor for JetStreamProcessor {
+    async fn enqueue_job(&self, job: Box<dyn JobProducer + Send + Sync>, _ctx: &DalContext) {
+        self.queue.enqueue_job(job).await
+    }
+
+    async fn block_on_job(&self, job: Box<dyn JobProducer + Send + Sync>) -> BlockingJobResult {
+        let job_info = JobInfo::new_blocking(job)
+            .map_err(|e: JobProducerError| BlockingJobError::JobProducer(e.to_string()))?;
+
+        let job_reply_inbox = self.client.new_inbox();
+        let mut reply_subscriber = self
+            .client
+            .subscribe(&job_reply_inbox)
+            .await
+            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+        self.publish(&job_info, Some(job_reply_inbox.as_str()))
+            .await
+            .map_err(|e| BlockingJobError::Nats(e.to_string()))?;
+
+        match reply_subscriber.next().await {
+            Some(message) => serde_json::from_slice::<BlockingJobResult>(message.payload())
+                .map_err(|e| BlockingJobError::Serde(e.to_string()))?,
+            None => Err(BlockingJobError::Nats(
+                "Subscriber or connection no longer valid".to_string(),
+            )),
+        }
+    }
+
+    async fn block_on_jobs(
+        &self,
+        jobs: Vec<Box<dyn JobProducer + Send + Sync>>,
+    ) -> BlockingJobResult {
+        let mut dispatched_jobs = JoinSet::new();
+
+        // Fan out, dispatching all queued jobs to pinga over JetStream.
+        for job in jobs {
+            let job_processor = Self::new(self.client.clone());
+            dispatched_jobs.spawn(async move { job_processor.block_on_job(job).await });
+        }
+
+        let mut results = Vec::new();
+        // Wait for all queued jobs to finish (regardless of success), before exiting.
+        loop {
+            match dispatched_jobs.join_next().await {
+                // All jobs done.
+                None => break,
+                Some(Ok(Ok(_))) => { /* Nothing to do. Job succeeded. */ }
+                Some(Ok(Err(job_error))) => {
+                    results.push(job_error);
+                }
+                Some(Err(join_err)) => {
+                    results.push(BlockingJobError::JobExecution(join_err.to_string()));
+                }
+            }
+        }
+
+        if !results.is_empty() {
+            Err(BlockingJobError::JobExecution(
+                results
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn process_queue(&self) -> JobQueueProcessorResult<()> {
+        let processor = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = processor.push_all_jobs().await {
+                error!("Unable to push jobs to JetStream: {err}");
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn blocking_process_queue(&self) -> JobQueueProcessorResult<()> {
+        self.block_on_jobs(self.queue.drain().await).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use once_cell::sync::OnceCell;
+use telemetry::prelude::*;
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    job::{
+        consumer::{JobConsumerError, JobInfo},
+        definition::job_consumer_from_job_info,
+        producer::{BlockingJobError, BlockingJobResult, JobProducer, JobProducerError},
+        queue::JobQueue,
+    },
+    DalContext, DalContextBuilder, JobDeadLetter, JobFailure, ServicesContext,
+};
+
+use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
+
+/// A [`JobQueueProcessor`] which runs jobs on tasks in the current tokio runtime rather than
+/// sending them to `pinga`, for single-binary deployments and tests.
+///
+/// The processor must be [started](Self::start) with the [`ServicesContext`] that jobs are run
+/// with before any job is processed. That context usually holds this processor too, so that jobs
+/// can enqueue (and block on) further jobs.
+#[derive(Clone, Debug)]
+pub struct LocalProcessor {
+    queue: JobQueue,
+    services_context: Arc<OnceCell<ServicesContext>>,
+    concurrency: Arc<Semaphore>,
+}
+
+impl LocalProcessor {
+    /// Creates a processor which runs at most `concurrency_limit` queued jobs at once. Blocking
+    /// jobs don't count towards the limit, as their caller is already waiting on them.
+    pub fn new(concurrency_limit: usize) -> Self {
+        Self {
+            queue: JobQueue::new(),
+            services_context: Default::default(),
+            concurrency: Arc::new(Semaphore::new(concurrency_limit)),
+        }
+    }
+
+    pub fn start(&self, services_context: ServicesContext) -> JobQueueProcessorResult<()> {
+        self.services_context
+            .set(services_context)
+            .map_err(|_| JobQueueProcessorError::LocalProcessorAlreadyStarted)
+    }
+
+    /// Runs a job to completion, recording a [`JobFailure`] and a [`JobDeadLetter`] if it fails,
+    /// like `pinga` would.
+    #[instrument(
+        name = "local_processor.run",
+        skip_all,
+        level = "info",
+        fields(job_info.id = %job_info.id, job_info.kind = %job_info.kind)
+    )]
+    async fn run(&self, job_info: JobInfo) -> BlockingJobResult {
+        let ctx_builder = self
+            .services_context
+            .get()
+            .ok_or_else(|| {
+                BlockingJobError::JobExecution("local job processor was not started".to_owned())
+            })?
+            .clone()
+            .into_builder(job_info.blocking);
+
+        let result = match job_consumer_from_job_info(job_info.clone()) {
+            Ok(job) => job.run_job(ctx_builder.clone()).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = result {
+            error!(error = ?err, "job execution failed");
+            if let Err(record_err) = record_job_failure(ctx_builder, &job_info, &err).await {
+                error!(error = ?record_err, "unable to record job failure");
+            }
+            return Err(BlockingJobError::JobExecution(err.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+async fn record_job_failure(
+    ctx_builder: DalContextBuilder,
+    job_info: &JobInfo,
+    err: &JobConsumerError,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let ctx = ctx_builder
+        .build(job_info.access_builder.build(job_info.visibility))
+        .await?;
+
+    JobFailure::new(&ctx, &job_info.kind, err.to_string()).await?;
+    JobDeadLetter::new(&ctx, job_info, err).await?;
+
+    ctx.commit().await?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl JobQueueProcessor for LocalProcessor {
+    async fn enqueue_job(&self, job: Box<dyn JobProducer + Send + Sync>, _ctx: &DalContext) {
+        self.queue.enqueue_job(job).await
+    }
+
+    async fn block_on_job(&self, job: Box<dyn JobProducer + Send + Sync>) -> BlockingJobResult {
+        let job_info = JobInfo::new_blocking(job)
+            .map_err(|e: JobProducerError| BlockingJobError::JobProducer(e.to_string()))?;
+
+        self.run(job_info).await
+    }
+
+    async fn block_on_jobs(
+        &self,
+        jobs: Vec<Box<dyn JobProducer + Send + Sync>>,
+    ) -> BlockingJobResult {
+        let mut dispatched_jobs = JoinSet::new();
+
+        // Fan out, running all queued jobs concurrently.
+        for job in jobs {
+            let job_processor = self.clone();
+            dispatched_jobs.spawn(async move { job_processor.block_on_job(job).await });
+        }
+
+        let mut results = Vec::new();
+        // Wait for all queued jobs to finish (regardless of success), before exiting.
+        loop {
+            match dispatched_jobs.join_next().await {
+                // All jobs done.
+                None => break,
+                Some(Ok(Ok(_))) => { /* Nothing to do. Job succeeded. */ }
+                Some(Ok(Err(job_error))) => {
+                    results.push(job_error);
+                }
+                Some(Err(join_err)) => {
+                    results.push(BlockingJobError::JobExecution(join_err.to_string()));
+                }
+            }
+        }
+
+        if !results.is_empty() {
+            Err(BlockingJobError::JobExecution(
+                results
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn process_queue(&self) -> JobQueueProcessorResult<()> {
+        let jobs = self
+            .queue
+            .drain()
+            .await
+            .into_iter()
+            .map(JobInfo::new)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for job_info in jobs {
+            let processor = self.clone();
+            tokio::spawn(async move {
+                // The semaphore is never closed, so acquiring a permit can't fail
+                let _permit = processor.concurrency.acquire().await;
+                // Failures are logged and recorded by `run`, nobody is waiting on the result
+                let _ = processor.run(job_info).await;
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn blocking_process_queue(&self) -> JobQueueProcessorResult<()> {
+        self.block_on_jobs(self.queue.drain().await).await?;
+
+        Ok(())
+    }
+}
@@ -13,9 +13,9 @@ use crate::{
     DalContext,
 };
 
-use super::{JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult};
-
-const NATS_JOB_QUEUE: &str = "pinga-jobs";
+use super::{
+    pinga_jobs_subject, JobQueueProcessor, JobQueueProcessorError, JobQueueProcessorResult,
+};
 
 #[derive(Clone, Debug)]
 pub struct NatsProcessor {
@@ -26,11 +26,7 @@ pub struct NatsProcessor {
 
 impl NatsProcessor {
     pub fn new(client: NatsClient) -> Self {
-        let pinga_subject = if let Some(prefix) = client.metadata().subject_prefix() {
-            format!("{prefix}.{NATS_JOB_QUEUE}")
-        } else {
-            NATS_JOB_QUEUE.to_owned()
-        };
+        let pinga_subject = pinga_jobs_subject(&client);
 
         Self {
             client,
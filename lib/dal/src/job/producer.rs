@@ -4,6 +4,7 @@ use thiserror::Error;
 use ulid::Ulid;
 
 use super::consumer::{JobConsumerMetadata, JobInfo};
+use crate::{AccessBuilder, Visibility};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -66,3 +67,26 @@ impl JobInfo {
         })
     }
 }
+
+/// A [`JobInfo`] can be re-enqueued as-is, which is how jobs that were persisted (for example by
+/// a [`JobSchedule`](crate::job::schedule::JobSchedule)) make their way back to the
+/// [`JobQueueProcessor`](crate::JobQueueProcessor).
+impl JobConsumerMetadata for JobInfo {
+    fn type_name(&self) -> String {
+        self.kind.clone()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+impl JobProducer for JobInfo {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(self.arg.clone())
+    }
+}
@@ -0,0 +1,342 @@
+//! This module contains [`JobSchedule`], a persisted request to run a job at some point in the
+//! future, either once (a "delayed" job) or repeatedly on a [`CronSchedule`].
+//!
+//! Schedules are stored in Postgres and fired by `pinga` (or by `sdf` when it runs jobs itself,
+//! see [`LocalProcessor`](crate::job::processor::LocalProcessor)). Every replica polls for due
+//! schedules with [`JobSchedule::claim_due`], which locks the due rows, advances (or removes)
+//! them and commits _before_ the jobs are dispatched. A schedule firing is therefore delivered
+//! at most once, no matter how many replicas are polling.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use si_data_pg::{PgError, PgPool, PgPoolError};
+use telemetry::prelude::*;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    job::{
+        consumer::JobInfo,
+        producer::{JobProducer, JobProducerError},
+    },
+    pk,
+    standard_model::objects_from_rows,
+    AccessBuilder, DalContext, ServicesContext, StandardModelError, Tenancy, Timestamp,
+    TransactionsError, Visibility, WorkspacePk,
+};
+
+pub mod cron;
+
+pub use cron::{CronSchedule, CronScheduleError};
+
+const LIST: &str = include_str!("../queries/job_schedule/list.sql");
+const GET_BY_PK: &str = include_str!("../queries/job_schedule/get_by_pk.sql");
+const CLAIM_DUE: &str = include_str!("../queries/job_schedule/claim_due.sql");
+const RESCHEDULE: &str = include_str!("../queries/job_schedule/reschedule.sql");
+const DELETE: &str = include_str!("../queries/job_schedule/delete.sql");
+const DELETE_BY_NAME: &str = include_str!("../queries/job_schedule/delete_by_name.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum JobScheduleError {
+    #[error(transparent)]
+    CronSchedule(#[from] CronScheduleError),
+    #[error("cron expression {0} never matches")]
+    CronScheduleNeverMatches(String),
+    #[error(transparent)]
+    JobProducer(#[from] JobProducerError),
+    #[error("job schedule not found: {0}")]
+    NotFound(JobSchedulePk),
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    PgPool(#[from] Box<PgPoolError>),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error(transparent)]
+    StandardModel(#[from] StandardModelError),
+    #[error(transparent)]
+    Transactions(#[from] Box<TransactionsError>),
+}
+
+impl From<PgPoolError> for JobScheduleError {
+    fn from(value: PgPoolError) -> Self {
+        Self::PgPool(Box::new(value))
+    }
+}
+
+impl From<TransactionsError> for JobScheduleError {
+    fn from(value: TransactionsError) -> Self {
+        Self::Transactions(Box::new(value))
+    }
+}
+
+pub type JobScheduleResult<T> = Result<T, JobScheduleError>;
+
+pk!(JobSchedulePk);
+
+/// A job that will be enqueued by `pinga` at [`next_run_at`](Self::next_run_at). Schedules
+/// without a [`CronSchedule`] fire once and are then removed.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct JobSchedule {
+    pk: JobSchedulePk,
+    name: Option<String>,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+    kind: String,
+    arg: Value,
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    cron: Option<CronSchedule>,
+    next_run_at: DateTime<Utc>,
+    last_run_at: Option<DateTime<Utc>>,
+    last_claimed_by: Option<String>,
+}
+
+impl JobSchedule {
+    /// Schedules `job` to be run once, at `run_at`. The schedule is created inside the
+    /// [`DalContext`]'s transaction, so it only takes effect once the context is committed.
+    #[instrument(skip_all)]
+    pub async fn new_delayed(
+        ctx: &DalContext,
+        job: Box<dyn JobProducer + Send + Sync>,
+        run_at: DateTime<Utc>,
+    ) -> JobScheduleResult<Self> {
+        Self::create(ctx, None, job, None, run_at).await
+    }
+
+    /// Schedules `job` to be run every time `cron` matches, starting with the next match from
+    /// now.
+    #[instrument(skip_all)]
+    pub async fn new_cron(
+        ctx: &DalContext,
+        job: Box<dyn JobProducer + Send + Sync>,
+        cron: CronSchedule,
+    ) -> JobScheduleResult<Self> {
+        let next_run_at = Self::first_run_for(&cron)?;
+        Self::create(ctx, None, job, Some(cron), next_run_at).await
+    }
+
+    /// Creates or updates the cron schedule with the given unique `name`. This is meant for
+    /// system-level schedules that every replica of a service "ensures" on startup: calling it
+    /// repeatedly with the same expression will not move the next run.
+    #[instrument(skip_all, fields(job_schedule.name = name.as_ref()))]
+    pub async fn upsert_named_cron(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        job: Box<dyn JobProducer + Send + Sync>,
+        cron: CronSchedule,
+    ) -> JobScheduleResult<Self> {
+        let next_run_at = Self::first_run_for(&cron)?;
+        Self::create(ctx, Some(name.as_ref()), job, Some(cron), next_run_at).await
+    }
+
+    async fn create(
+        ctx: &DalContext,
+        name: Option<&str>,
+        job: Box<dyn JobProducer + Send + Sync>,
+        cron: Option<CronSchedule>,
+        next_run_at: DateTime<Utc>,
+    ) -> JobScheduleResult<Self> {
+        let access_builder = job.access_builder();
+        let cron = cron.map(|cron| cron.to_string());
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM job_schedule_create_v1($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &name,
+                    access_builder.tenancy(),
+                    &job.type_name(),
+                    &job.arg()?,
+                    &serde_json::to_value(access_builder)?,
+                    &job.visibility(),
+                    &cron,
+                    &next_run_at,
+                ],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    fn first_run_for(cron: &CronSchedule) -> JobScheduleResult<DateTime<Utc>> {
+        cron.next_after(Utc::now())
+            .ok_or_else(|| JobScheduleError::CronScheduleNeverMatches(cron.to_string()))
+    }
+
+    /// Gets the schedule, if it is visible to the [`DalContext`]'s tenancy.
+    pub async fn get_by_pk(ctx: &DalContext, pk: JobSchedulePk) -> JobScheduleResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(GET_BY_PK, &[&pk, ctx.tenancy()])
+            .await?
+            .ok_or(JobScheduleError::NotFound(pk))?;
+        let json: Value = row.try_get("object")?;
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Lists the schedules visible to the [`DalContext`]'s tenancy, soonest first.
+    pub async fn list(ctx: &DalContext) -> JobScheduleResult<Vec<Self>> {
+        let rows = ctx.txns().await?.pg().query(LIST, &[ctx.tenancy()]).await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Removes the schedule so that it never fires again.
+    pub async fn delete(self, ctx: &DalContext) -> JobScheduleResult<()> {
+        ctx.txns().await?.pg().execute(DELETE, &[&self.pk]).await?;
+        Ok(())
+    }
+
+    /// Removes the schedule with the given unique `name`, if there is one. This is the
+    /// counterpart of [`upsert_named_cron`](Self::upsert_named_cron) for system-level schedules
+    /// which have been turned off.
+    #[instrument(skip_all, fields(job_schedule.name = name.as_ref()))]
+    pub async fn delete_named(ctx: &DalContext, name: impl AsRef<str>) -> JobScheduleResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(DELETE_BY_NAME, &[&name.as_ref()])
+            .await?;
+        Ok(())
+    }
+
+    /// Claims up to `limit` schedules that are due now, returning a [`JobInfo`] for each firing.
+    /// Repeating schedules are moved to their next run and one-off schedules are removed.
+    ///
+    /// This uses its own connection and transaction (rows are locked with `SKIP LOCKED`), which
+    /// is committed before returning. The caller is responsible for dispatching the returned
+    /// jobs; if it fails to do so, those firings are lost rather than duplicated.
+    pub async fn claim_due(
+        pg_pool: &PgPool,
+        claimed_by: &str,
+        limit: i64,
+    ) -> JobScheduleResult<Vec<JobInfo>> {
+        Self::claim_due_at(pg_pool, claimed_by, Utc::now(), None, limit).await
+    }
+
+    /// Like [`claim_due`](Self::claim_due), but treats `now` as the current time and, given a
+    /// `workspace_pk`, only claims that workspace's schedules.
+    #[instrument(skip_all, level = "debug", fields(job_schedule.claimed_by = claimed_by))]
+    pub async fn claim_due_at(
+        pg_pool: &PgPool,
+        claimed_by: &str,
+        now: DateTime<Utc>,
+        workspace_pk: Option<WorkspacePk>,
+        limit: i64,
+    ) -> JobScheduleResult<Vec<JobInfo>> {
+        let mut conn = pg_pool.get().await?;
+        let txn = conn.transaction().await?;
+
+        let rows = txn.query(CLAIM_DUE, &[&now, &limit, &workspace_pk]).await?;
+        let schedules: Vec<Self> = objects_from_rows(rows)?;
+
+        let mut jobs = Vec::with_capacity(schedules.len());
+        for schedule in schedules {
+            match schedule.cron.as_ref() {
+                Some(cron) => match cron.next_after(now) {
+                    Some(next_run_at) => {
+                        txn.execute(RESCHEDULE, &[&schedule.pk, &next_run_at, &now, &claimed_by])
+                            .await?;
+                    }
+                    None => {
+                        warn!(
+                            job_schedule.pk = %schedule.pk,
+                            cron = %cron,
+                            "cron schedule will never match again, removing it",
+                        );
+                        txn.execute(DELETE, &[&schedule.pk]).await?;
+                    }
+                },
+                None => {
+                    txn.execute(DELETE, &[&schedule.pk]).await?;
+                }
+            }
+            jobs.push(schedule.job_info());
+        }
+
+        txn.commit().await?;
+
+        Ok(jobs)
+    }
+
+    /// Claims every due schedule, `batch_size` at a time, and enqueues its job. Claiming commits
+    /// before the jobs are enqueued, so if the caller fails between the two steps the firing is
+    /// skipped rather than being run twice by another poller.
+    #[instrument(skip_all, level = "debug", fields(job_schedule.claimed_by = claimed_by))]
+    pub async fn enqueue_due(
+        services_context: &ServicesContext,
+        claimed_by: &str,
+        batch_size: i64,
+    ) -> JobScheduleResult<()> {
+        loop {
+            let jobs = Self::claim_due(services_context.pg_pool(), claimed_by, batch_size).await?;
+            if jobs.is_empty() {
+                return Ok(());
+            }
+            let claimed = jobs.len();
+
+            let ctx = DalContext::builder(services_context.clone(), false)
+                .build_default()
+                .await?;
+            for job in jobs {
+                debug!(job.id = %job.id, job.kind = %job.kind, "enqueueing scheduled job");
+                ctx.enqueue_job(Box::new(job)).await?;
+            }
+            ctx.commit().await?;
+
+            if (claimed as i64) < batch_size {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Builds a fresh [`JobInfo`] for a single firing of this schedule.
+    pub fn job_info(&self) -> JobInfo {
+        JobInfo {
+            id: Ulid::new().to_string(),
+            kind: self.kind.clone(),
+            created_at: Utc::now(),
+            arg: self.arg.clone(),
+            access_builder: self.access_builder,
+            visibility: self.visibility,
+            blocking: false,
+        }
+    }
+
+    pub fn pk(&self) -> JobSchedulePk {
+        self.pk
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn cron(&self) -> Option<&CronSchedule> {
+        self.cron.as_ref()
+    }
+
+    pub fn next_run_at(&self) -> DateTime<Utc> {
+        self.next_run_at
+    }
+
+    pub fn last_run_at(&self) -> Option<DateTime<Utc>> {
+        self.last_run_at
+    }
+
+    pub fn last_claimed_by(&self) -> Option<&str> {
+        self.last_claimed_by.as_deref()
+    }
+}
@@ -0,0 +1,300 @@
+//! A small parser and evaluator for the classic five-field cron syntax used by
+//! [`JobSchedules`](super::JobSchedule).
+//!
+//! Supported syntax per field is `*`, single values, ranges (`a-b`), steps (`*/n` and `a-b/n`)
+//! and comma separated lists of any of those. The `@yearly`, `@annually`, `@monthly`,
+//! `@weekly`, `@daily`, `@midnight` and `@hourly` shorthands are also understood. All times are
+//! evaluated in UTC.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use thiserror::Error;
+
+/// How far into the future [`CronSchedule::next_after`] will look before giving up. This covers
+/// every leap year cycle, so an expression that has no match within this window never will.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 8;
+
+#[remain::sorted]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CronScheduleError {
+    #[error("invalid {0} field in cron expression: {1}")]
+    InvalidField(&'static str, String),
+    #[error("cron expression must have 5 fields, found {0}")]
+    WrongFieldCount(usize),
+}
+
+pub type CronScheduleResult<T> = Result<T, CronScheduleError>;
+
+/// A parsed cron expression, such as `*/5 * * * *`.
+#[derive(Clone, Debug, PartialEq, Eq, DeserializeFromStr, SerializeDisplay)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Returns the original expression this schedule was parsed from.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns the first time strictly after `after` (at minute granularity) that matches this
+    /// schedule, or `None` if the expression can never match (e.g. `0 0 30 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut candidate = start;
+
+        while candidate < limit {
+            if !bit_set(self.months, candidate.month()) {
+                let (year, month) = if candidate.month() == 12 {
+                    (candidate.year() + 1, 1)
+                } else {
+                    (candidate.year(), candidate.month() + 1)
+                };
+                candidate = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+                continue;
+            }
+            if !self.day_matches(&candidate) {
+                candidate = Utc
+                    .with_ymd_and_hms(
+                        candidate.year(),
+                        candidate.month(),
+                        candidate.day(),
+                        0,
+                        0,
+                        0,
+                    )
+                    .single()?
+                    + Duration::days(1);
+                continue;
+            }
+            if !bit_set(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !bit_set(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            return Some(candidate);
+        }
+
+        None
+    }
+
+    /// Day of month and day of week combine the way they do in Vixie cron: if both are
+    /// restricted, a day matching _either_ of them is a match.
+    fn day_matches(&self, candidate: &DateTime<Utc>) -> bool {
+        let dom = bit_set(self.days_of_month, candidate.day());
+        let dow = bit_set(
+            self.days_of_week,
+            candidate.weekday().num_days_from_sunday(),
+        );
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = s.trim();
+        let expanded = match expression {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(CronScheduleError::WrongFieldCount(fields.len()));
+        }
+
+        let minutes = parse_field("minute", fields[0], 0, 59)?;
+        let hours = parse_field("hour", fields[1], 0, 23)?;
+        let days_of_month = parse_field("day of month", fields[2], 1, 31)?;
+        let months = parse_field("month", fields[3], 1, 12)?;
+        let mut days_of_week = parse_field("day of week", fields[4], 0, 7)?;
+        // Both 0 and 7 mean Sunday
+        if bit_set(days_of_week, 7) {
+            days_of_week |= 1;
+            days_of_week &= !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.to_owned(),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            day_of_month_restricted: !fields[2].starts_with('*'),
+            day_of_week_restricted: !fields[4].starts_with('*'),
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn bit_set(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn parse_field(name: &'static str, field: &str, min: u32, max: u32) -> CronScheduleResult<u64> {
+    let invalid = || CronScheduleError::InvalidField(name, field.to_owned());
+    let parse = |value: &str| -> CronScheduleResult<u32> {
+        let value: u32 = value.parse().map_err(|_| invalid())?;
+        if value < min || value > max {
+            return Err(invalid());
+        }
+        Ok(value)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse(start)?, parse(end)?)
+        } else {
+            let value = parse(range)?;
+            // `5/15` means "starting at 5, every 15"
+            if step > 1 {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if start > end {
+            return Err(invalid());
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0)
+            .single()
+            .expect("valid date")
+    }
+
+    fn cron(expression: &str) -> CronSchedule {
+        expression.parse().expect("failed to parse cron expression")
+    }
+
+    #[test]
+    fn every_five_minutes() {
+        let schedule = cron("*/5 * * * *");
+        assert_eq!(
+            Some(at(2023, 8, 1, 10, 5)),
+            schedule.next_after(at(2023, 8, 1, 10, 0))
+        );
+        assert_eq!(
+            Some(at(2023, 8, 1, 11, 0)),
+            schedule.next_after(at(2023, 8, 1, 10, 57))
+        );
+    }
+
+    #[test]
+    fn daily_rolls_over_month_and_year() {
+        let schedule = cron("@daily");
+        assert_eq!(
+            Some(at(2024, 1, 1, 0, 0)),
+            schedule.next_after(at(2023, 12, 31, 0, 0))
+        );
+    }
+
+    #[test]
+    fn lists_and_ranges() {
+        let schedule = cron("15,45 9-17 * * 1-5");
+        // Friday evening rolls over to Monday morning
+        assert_eq!(
+            Some(at(2023, 8, 7, 9, 15)),
+            schedule.next_after(at(2023, 8, 4, 17, 45))
+        );
+    }
+
+    #[test]
+    fn sunday_can_be_seven() {
+        assert_eq!(
+            cron("0 0 * * 0").next_after(at(2023, 8, 1, 0, 0)),
+            cron("0 0 * * 7").next_after(at(2023, 8, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // The 1st of the month, or any Sunday
+        let schedule = cron("0 0 1 * 0");
+        assert_eq!(
+            Some(at(2023, 8, 6, 0, 0)),
+            schedule.next_after(at(2023, 8, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn impossible_date() {
+        assert_eq!(None, cron("0 0 30 2 *").next_after(at(2023, 1, 1, 0, 0)));
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        assert_eq!(
+            Err(CronScheduleError::WrongFieldCount(4)),
+            "* * * *".parse::<CronSchedule>()
+        );
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("5-1 * * * *".parse::<CronSchedule>().is_err());
+    }
+
+    #[test]
+    fn round_trips_through_serde() {
+        let schedule = cron("@hourly");
+        let json = serde_json::to_string(&schedule).expect("failed to serialize");
+        assert_eq!(r#""@hourly""#, json);
+        let parsed: CronSchedule = serde_json::from_str(&json).expect("failed to deserialize");
+        assert_eq!(schedule, parsed);
+    }
+}
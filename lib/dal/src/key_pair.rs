@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use si_crypto::{SymmetricCryptoError, SymmetricCryptoService, SymmetricNonce};
 use si_data_nats::NatsError;
@@ -11,14 +14,23 @@ use thiserror::Error;
 use crate::{
     pk,
     serde_impls::{base64_bytes_serde, nonce_serde},
-    standard_model_accessor_ro, DalContext, HistoryEvent, HistoryEventError, Timestamp,
-    TransactionsError, Workspace, WorkspaceError, WorkspacePk,
+    standard_model_accessor_ro, DalContext, EncryptedSecret, HistoryEvent, HistoryEventError,
+    SecretError, StatusUpdater, Timestamp, TransactionsError, Workspace, WorkspaceError,
+    WorkspacePk,
 };
 
 mod key_pair_box_public_key_serde;
 
 const PUBLIC_KEY_GET_CURRENT: &str = include_str!("./queries/public_key_get_current.sql");
 const KEY_PAIR_GET_BY_PK: &str = include_str!("queries/key_pair_get_by_pk.sql");
+const KEY_PAIR_GET_BY_PK_FOR_SHARE: &str = include_str!("queries/key_pair_get_by_pk_for_share.sql");
+const KEY_PAIR_LOCK_FOR_WORKSPACE: &str = include_str!("queries/key_pair_lock_for_workspace.sql");
+const KEY_PAIR_RETIRE_FOR_WORKSPACE: &str =
+    include_str!("queries/key_pair_retire_for_workspace.sql");
+const KEY_PAIR_DELETE_RETIRED_FOR_WORKSPACE: &str =
+    include_str!("queries/key_pair_delete_retired_for_workspace.sql");
+const KEY_PAIR_LIST_TO_REENCRYPT: &str = include_str!("queries/key_pair_list_to_reencrypt.sql");
+const KEY_PAIR_REENCRYPT: &str = include_str!("queries/key_pair_reencrypt.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -35,6 +47,8 @@ pub enum KeyPairError {
     NoCurrentKeyPair,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("secret error: {0}")]
+    Secret(#[from] Box<SecretError>),
     #[error("error serializing/deserializing json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("symmetric crypto error: {0}")]
@@ -57,6 +71,7 @@ pub struct KeyPair {
     public_key: BoxPublicKey,
     secret_key: BoxSecretKey,
     created_lamport_clock: u64,
+    retired_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -119,6 +134,25 @@ impl KeyPair {
         Ok(key_pair)
     }
 
+    /// Gets the key pair, even if it has since been deleted, holding a share lock on it until the
+    /// `ctx`'s transaction ends. [`rotate`](Self::rotate) can't retire a key pair while a secret
+    /// is being sealed to it, and a secret sealed after a rotation sees the key pair as retired.
+    pub(crate) async fn get_by_pk_for_share(
+        ctx: &DalContext,
+        pk: KeyPairPk,
+    ) -> KeyPairResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(KEY_PAIR_GET_BY_PK_FOR_SHARE, &[&pk])
+            .await?;
+        let json: serde_json::Value = row.try_get("object")?;
+        let key_pair_row: KeyPairRow = serde_json::from_value(json)?;
+        let key_pair = key_pair_row.decrypt_into(ctx.symmetric_crypto_service())?;
+        Ok(key_pair)
+    }
+
     pub async fn get_current(ctx: &DalContext) -> KeyPairResult<Self> {
         let row = ctx
             .txns()
@@ -138,6 +172,139 @@ impl KeyPair {
     standard_model_accessor_ro!(public_key, BoxPublicKey);
     standard_model_accessor_ro!(secret_key, BoxSecretKey);
     standard_model_accessor_ro!(created_lamport_clock, u64);
+    standard_model_accessor_ro!(retired_at, Option<DateTime<Utc>>);
+
+    /// A retired key pair can still decrypt the secrets sealed to it, but no new secrets may be
+    /// sealed to it.
+    pub fn is_retired(&self) -> bool {
+        self.retired_at.is_some()
+    }
+
+    /// Replaces the workspace's key pair with a newly generated one, re-sealing every
+    /// [`EncryptedSecret`] in the workspace (across all change sets) to the new key.
+    ///
+    /// The previous key pairs are retired before the new one is created, so they remain able to
+    /// decrypt the secrets which have yet to be re-sealed, and are deleted once every secret has
+    /// been. All of this happens within the `ctx`'s transaction, so a failed rotation leaves the
+    /// workspace with the key pair it had before. The workspace's key pairs are locked for the
+    /// whole rotation, so secrets created concurrently either finish sealing to the old key pair
+    /// before it is retired (and are re-sealed) or wait and are refused for it being retired.
+    pub async fn rotate(
+        ctx: &DalContext,
+        status_updater: &mut StatusUpdater,
+    ) -> KeyPairResult<Self> {
+        let workspace_pk = ctx.tenancy().workspace_pk();
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query(KEY_PAIR_LOCK_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+
+        let retired_pks: Vec<KeyPairPk> = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(KEY_PAIR_RETIRE_FOR_WORKSPACE, &[&workspace_pk])
+            .await?
+            .into_iter()
+            .map(|row| row.try_get("pk"))
+            .collect::<Result<_, _>>()?;
+        if retired_pks.is_empty() {
+            return Err(KeyPairError::NoCurrentKeyPair);
+        }
+
+        let key_pair = Self::new(ctx, "default").await?;
+
+        let encrypted_secrets = EncryptedSecret::list_to_reseal(ctx, key_pair.pk)
+            .await
+            .map_err(Box::new)?;
+        let total = encrypted_secrets.len();
+        status_updater
+            .secrets_resealed(ctx, key_pair.pk, 0, total)
+            .await;
+
+        let mut retired_key_pairs = HashMap::new();
+        for (index, mut encrypted_secret) in encrypted_secrets.into_iter().enumerate() {
+            let retired_key_pair_pk = encrypted_secret.key_pair_pk();
+            if !retired_key_pairs.contains_key(&retired_key_pair_pk) {
+                let retired_key_pair = Self::get_by_pk(ctx, retired_key_pair_pk).await?;
+                retired_key_pairs.insert(retired_key_pair_pk, retired_key_pair);
+            }
+            let retired_key_pair = &retired_key_pairs[&retired_key_pair_pk];
+
+            encrypted_secret
+                .reseal(ctx, retired_key_pair, &key_pair)
+                .await
+                .map_err(Box::new)?;
+            status_updater
+                .secrets_resealed(ctx, key_pair.pk, index + 1, total)
+                .await;
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(KEY_PAIR_DELETE_RETIRED_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            "key_pair.rotate".to_owned(),
+            "Key Pair rotated".to_owned(),
+            &serde_json::json![{
+                "pk": key_pair.pk,
+                "retired_pks": retired_pks,
+                "resealed_secrets": total,
+            }],
+        )
+        .await?;
+
+        Ok(key_pair)
+    }
+
+    /// Re-encrypts the secret key of every key pair, in every workspace, which is not encrypted
+    /// with the active key of the [`SymmetricCryptoService`], and returns how many were
+    /// re-encrypted.
+    ///
+    /// Retired and deleted key pairs are included, so that no row is left referencing a key
+    /// which is about to be removed.
+    pub async fn reencrypt_all(ctx: &DalContext) -> KeyPairResult<usize> {
+        let symmetric_crypto_service = ctx.symmetric_crypto_service();
+        let txns = ctx.txns().await?;
+
+        let rows = txns
+            .pg()
+            .query(
+                KEY_PAIR_LIST_TO_REENCRYPT,
+                &[&symmetric_crypto_service.active_key_hash().to_string()],
+            )
+            .await?;
+
+        let mut reencrypted = 0;
+        for row in rows {
+            let json: serde_json::Value = row.try_get("object")?;
+            let key_pair_row: KeyPairRow = serde_json::from_value(json)?;
+            let key_pair = key_pair_row.decrypt_into(symmetric_crypto_service)?;
+
+            let (secret_key_crypted, secret_key_nonce, secret_key_key_hash) =
+                symmetric_crypto_service.encrypt(key_pair.secret_key.as_ref());
+            txns.pg()
+                .execute(
+                    KEY_PAIR_REENCRYPT,
+                    &[
+                        &key_pair.pk,
+                        &base64_encode_bytes(secret_key_crypted.as_slice()),
+                        &base64_encode_bytes(secret_key_nonce.as_ref()),
+                        &secret_key_key_hash.to_string(),
+                    ],
+                )
+                .await?;
+            reencrypted += 1;
+        }
+
+        Ok(reencrypted)
+    }
 
     pub async fn workspace(&self, ctx: &DalContext) -> KeyPairResult<Workspace> {
         Workspace::get_by_pk(ctx, &self.workspace_pk)
@@ -221,6 +388,7 @@ struct KeyPairRow {
     #[serde(with = "base64_bytes_serde")]
     secret_key_crypted: Vec<u8>,
     created_lamport_clock: u64,
+    retired_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -245,6 +413,7 @@ impl KeyPairRow {
             public_key: self.public_key,
             secret_key,
             created_lamport_clock: self.created_lamport_clock,
+            retired_at: self.retired_at,
             timestamp: self.timestamp,
         })
     }
@@ -273,6 +442,7 @@ mod tests {
             secret_key_key_hash: *secret_key_key_hash,
             secret_key_crypted,
             created_lamport_clock: 0,
+            retired_at: None,
             timestamp: Timestamp::now(),
         }
     }
@@ -18,6 +18,7 @@ pub use action_prototype::{
     ActionPrototypeView,
 };
 pub use actor_view::ActorView;
+pub use api_token::{ApiToken, ApiTokenError, ApiTokenPk, ApiTokenResult};
 pub use attribute::value::view::AttributeView;
 pub use attribute::{
     context::{
@@ -36,6 +37,10 @@ pub use attribute::{
         AttributeValueResult,
     },
 };
+pub use audit_log::{
+    AuditLogCursor, AuditLogEntry, AuditLogError, AuditLogKind, AuditLogPage, AuditLogResult,
+    AuditLogSink, AuditLogSinkConfig,
+};
 pub use builtins::{BuiltinsError, BuiltinsResult};
 pub use change_set::{ChangeSet, ChangeSetError, ChangeSetPk, ChangeSetStatus};
 pub use code_view::{CodeLanguage, CodeView};
@@ -52,6 +57,7 @@ pub use diagram::{
 };
 pub use edge::{Edge, EdgeError, EdgeResult};
 pub use fix::batch::{FixBatch, FixBatchId};
+pub use fix::confirmation::FixConfirmation;
 pub use fix::resolver::{FixResolver, FixResolverError, FixResolverId};
 pub use fix::{Fix, FixCompletionStatus, FixError, FixId};
 pub use func::argument::FuncArgument;
@@ -63,8 +69,13 @@ pub use func::{
 };
 pub use history_event::{HistoryActor, HistoryEvent, HistoryEventError};
 pub use index_map::IndexMap;
+pub use job::dead_letter::{JobDeadLetter, JobDeadLetterError, JobDeadLetterPk};
 pub use job::definition::DependentValuesUpdate;
-pub use job::processor::{JobQueueProcessor, NatsProcessor};
+pub use job::processor::{
+    JetStreamProcessor, JobQueueProcessor, JobQueueProcessorError, JobQueueTransport,
+    LocalProcessor, NatsProcessor,
+};
+pub use job::schedule::{CronSchedule, JobSchedule, JobScheduleError, JobSchedulePk};
 pub use job_failure::{JobFailure, JobFailureError, JobFailureResult};
 pub use jwt_key::JwtPublicSigningKey;
 pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, PublicKey};
@@ -93,8 +104,9 @@ pub use schema::variant::root_prop::RootPropChild;
 pub use schema::variant::SchemaVariantError;
 pub use schema::{Schema, SchemaError, SchemaId, SchemaPk, SchemaVariant, SchemaVariantId};
 pub use secret::{
-    DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretId, SecretPk,
-    SecretResult, SecretVersion,
+    DecryptedSecret, EncryptedSecret, Secret, SecretAlgorithm, SecretError, SecretExpiryStatus,
+    SecretId, SecretKind, SecretPk, SecretProviders, SecretProvidersConfig, SecretResult,
+    SecretVersion,
 };
 use si_data_nats::{NatsClient, NatsError};
 use si_data_pg::{PgError, PgPool, PgPoolError};
@@ -103,10 +115,16 @@ pub use standard_model::{StandardModel, StandardModelError, StandardModelResult}
 pub use status::{
     StatusUpdate, StatusUpdateError, StatusUpdateResult, StatusUpdater, StatusUpdaterError,
 };
+pub use symmetric_reencryption::{
+    reencrypt_symmetric_data, SymmetricReencryptionError, SymmetricReencryptionReport,
+    SymmetricReencryptionResult,
+};
 use telemetry::prelude::*;
 pub use tenancy::{Tenancy, TenancyError};
 pub use timestamp::{Timestamp, TimestampError};
-pub use user::{User, UserClaim, UserError, UserPk, UserResult};
+pub use user::{
+    User, UserClaim, UserError, UserPk, UserResult, WorkspacePermission, WorkspaceRole,
+};
 pub use validation::prototype::{
     context::ValidationPrototypeContext, ValidationPrototype, ValidationPrototypeError,
     ValidationPrototypeId,
@@ -124,7 +142,9 @@ use crate::builtins::SelectedTestBuiltinSchemas;
 pub mod action;
 pub mod action_prototype;
 pub mod actor_view;
+pub mod api_token;
 pub mod attribute;
+pub mod audit_log;
 pub mod authentication_prototype;
 pub mod builtins;
 pub mod change_set;
@@ -163,6 +183,7 @@ pub mod standard_accessors;
 pub mod standard_model;
 pub mod standard_pk;
 pub mod status;
+pub mod symmetric_reencryption;
 pub mod tasks;
 pub mod tenancy;
 pub mod timestamp;
@@ -272,6 +293,7 @@ pub async fn migrate_builtins(
         Some(pkgs_path),
         Some(module_index_url),
         symmetric_crypto_service.clone(),
+        SecretProviders::default(),
     );
     let dal_context = services_context.into_builder(true);
     let mut ctx = dal_context.build_default().await?;
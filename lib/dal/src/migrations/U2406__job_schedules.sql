@@ -0,0 +1,59 @@
+CREATE TABLE job_schedules
+(
+    pk                          ident primary key default ident_create_v1(),
+    name                        text UNIQUE,
+    tenancy_workspace_pk        ident,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    kind                        text                     NOT NULL,
+    arg                         jsonb                    NOT NULL,
+    access_builder              jsonb                    NOT NULL,
+    visibility                  jsonb                    NOT NULL,
+    cron                        text,
+    next_run_at                 timestamp with time zone NOT NULL,
+    last_run_at                 timestamp with time zone,
+    last_claimed_by             text
+);
+CREATE INDEX ON job_schedules (next_run_at);
+CREATE INDEX ON job_schedules (tenancy_workspace_pk);
+
+CREATE OR REPLACE FUNCTION job_schedule_create_v1(
+    this_name text,
+    this_tenancy jsonb,
+    this_kind text,
+    this_arg jsonb,
+    this_access_builder jsonb,
+    this_visibility jsonb,
+    this_cron text,
+    this_next_run_at timestamp with time zone,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_new_row           job_schedules%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    -- Named schedules are upserted so that every replica of a service can safely ensure that its
+    -- schedule exists. The next run is only moved if the cron expression changed.
+    INSERT INTO job_schedules (name, tenancy_workspace_pk, kind, arg, access_builder, visibility,
+                               cron, next_run_at)
+    VALUES (this_name, this_tenancy_record.tenancy_workspace_pk, this_kind, this_arg,
+            this_access_builder, this_visibility, this_cron, this_next_run_at)
+    ON CONFLICT (name) DO UPDATE
+        SET kind           = EXCLUDED.kind,
+            arg            = EXCLUDED.arg,
+            access_builder = EXCLUDED.access_builder,
+            visibility     = EXCLUDED.visibility,
+            cron           = EXCLUDED.cron,
+            next_run_at    = CASE
+                                 WHEN job_schedules.cron IS DISTINCT FROM EXCLUDED.cron
+                                     THEN EXCLUDED.next_run_at
+                                 ELSE job_schedules.next_run_at
+                             END,
+            updated_at     = CLOCK_TIMESTAMP()
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,42 @@
+CREATE TABLE job_dead_letters
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    job_id                      text                     NOT NULL,
+    kind                        text                     NOT NULL,
+    arg                         jsonb                    NOT NULL,
+    access_builder              jsonb                    NOT NULL,
+    visibility                  jsonb                    NOT NULL,
+    blocking                    bool                     NOT NULL DEFAULT FALSE,
+    error_chain                 jsonb                    NOT NULL
+);
+CREATE INDEX ON job_dead_letters (tenancy_workspace_pk);
+
+CREATE OR REPLACE FUNCTION job_dead_letter_create_v1(
+    this_tenancy jsonb,
+    this_job_id text,
+    this_kind text,
+    this_arg jsonb,
+    this_access_builder jsonb,
+    this_visibility jsonb,
+    this_blocking bool,
+    this_error_chain jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_new_row           job_dead_letters%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO job_dead_letters (tenancy_workspace_pk, job_id, kind, arg, access_builder,
+                                  visibility, blocking, error_chain)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_job_id, this_kind, this_arg,
+            this_access_builder, this_visibility, this_blocking, this_error_chain)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,23 @@
+ALTER TABLE workspaces
+    ADD COLUMN require_signed_modules boolean NOT NULL DEFAULT false,
+    ADD COLUMN trusted_publisher_keys text[]  NOT NULL DEFAULT '{}';
+
+CREATE OR REPLACE FUNCTION workspace_update_module_trust_v1(
+    this_pk ident,
+    this_require_signed_modules boolean,
+    this_trusted_publisher_keys text[],
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET require_signed_modules = this_require_signed_modules,
+        trusted_publisher_keys = this_trusted_publisher_keys,
+        updated_at             = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO STRICT this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,85 @@
+ALTER TABLE encrypted_secrets
+    ADD COLUMN kind text NOT NULL DEFAULT 'stored';
+
+CREATE OR REPLACE VIEW secrets AS
+SELECT pk,
+       id,
+       tenancy_workspace_pk,
+       visibility_change_set_pk,
+       visibility_deleted_at,
+       key_pair_pk,
+       created_at,
+       created_by,
+       updated_at,
+       updated_by,
+       name,
+       definition,
+       description,
+       kind
+FROM encrypted_secrets;
+
+CREATE OR REPLACE FUNCTION encrypted_secret_create_v2(
+    this_tenancy jsonb,
+    this_visibility jsonb,
+    this_name text,
+    this_definition text,
+    this_description text,
+    this_kind text,
+    this_crypted text,
+    this_version text,
+    this_algorithm text,
+    this_key_pair_pk ident,
+    this_nonce text,
+    this_key_hash text,
+    this_created_by ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_visibility_record visibility_record_v1;
+    this_new_row           encrypted_secrets%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+    this_visibility_record := visibility_json_to_columns_v1(this_visibility);
+
+    INSERT INTO encrypted_secrets (tenancy_workspace_pk,
+                                   visibility_change_set_pk,
+                                   name,
+                                   definition,
+                                   description,
+                                   kind,
+                                   crypted,
+                                   version,
+                                   algorithm,
+                                   key_pair_pk,
+                                   nonce,
+                                   key_hash,
+                                   created_by,
+                                   updated_by)
+    VALUES (this_tenancy_record.tenancy_workspace_pk,
+            this_visibility_record.visibility_change_set_pk,
+            this_name,
+            this_definition,
+            this_description,
+            this_kind,
+            this_crypted,
+            this_version,
+            this_algorithm,
+            this_key_pair_pk,
+            this_nonce,
+            this_key_hash,
+            this_created_by,
+            this_created_by)
+    RETURNING * INTO this_new_row;
+
+    -- Purge the returning record of sensitive data to avoid accidentally
+    -- deserializing these fields in application code
+    this_new_row.nonce = null;
+    this_new_row.key_hash = null;
+    this_new_row.crypted = null;
+    this_new_row.version = null;
+    this_new_row.algorithm = null;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,40 @@
+CREATE TABLE secret_usages
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    secret_id                   ident                    NOT NULL,
+    actor                       jsonb                    NOT NULL,
+    func_id                     ident,
+    component_id                ident,
+    fix_id                      ident,
+    fix_batch_id                ident
+);
+CREATE INDEX ON secret_usages (tenancy_workspace_pk, secret_id, created_at);
+
+CREATE OR REPLACE FUNCTION secret_usage_create_v1(
+    this_tenancy jsonb,
+    this_secret_id ident,
+    this_actor jsonb,
+    this_func_id ident,
+    this_component_id ident,
+    this_fix_id ident,
+    this_fix_batch_id ident,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_new_row           secret_usages%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    INSERT INTO secret_usages (tenancy_workspace_pk, secret_id, actor, func_id, component_id,
+                               fix_id, fix_batch_id)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_secret_id, this_actor, this_func_id,
+            this_component_id, this_fix_id, this_fix_batch_id)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,19 @@
+ALTER TABLE user_belongs_to_workspaces
+    ADD COLUMN role text NOT NULL DEFAULT 'editor'
+        CHECK (role IN ('viewer', 'editor', 'approver', 'admin'));
+
+-- Every member could do everything before roles existed, so existing members keep that access.
+UPDATE user_belongs_to_workspaces SET role = 'admin';
+
+CREATE OR REPLACE FUNCTION user_associate_workspace_v2(
+    this_user_pk ident,
+    this_workspace_pk ident,
+    this_role text
+    ) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO user_belongs_to_workspaces (user_pk, workspace_pk, role)
+        VALUES (this_user_pk, this_workspace_pk, this_role)
+        ON CONFLICT DO NOTHING;
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,46 @@
+CREATE TABLE api_tokens
+(
+    pk                          ident primary key default ident_create_v1(),
+    tenancy_workspace_pk        ident                    NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    name                        text                     NOT NULL,
+    token_hash                  text                     NOT NULL,
+    created_by_user_pk          ident                    NOT NULL,
+    permissions                 jsonb                    NOT NULL DEFAULT '["read"]'::jsonb,
+    change_set_pks              jsonb,
+    last_used_at                timestamp with time zone,
+    revoked_at                  timestamp with time zone
+);
+CREATE UNIQUE INDEX ON api_tokens (token_hash);
+CREATE INDEX ON api_tokens (tenancy_workspace_pk);
+
+CREATE OR REPLACE FUNCTION api_token_create_v1(
+    this_tenancy jsonb,
+    this_name text,
+    this_token_hash text,
+    this_created_by_user_pk ident,
+    this_permissions jsonb,
+    this_change_set_pks jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_tenancy_record    tenancy_record_v1;
+    this_new_row           api_tokens%ROWTYPE;
+BEGIN
+    this_tenancy_record := tenancy_json_to_columns_v1(this_tenancy);
+
+    -- A token can't be used at all without the read permission, so every token gets it
+    IF NOT this_permissions ? 'read' THEN
+        this_permissions := this_permissions || '["read"]'::jsonb;
+    END IF;
+
+    INSERT INTO api_tokens (tenancy_workspace_pk, name, token_hash, created_by_user_pk,
+                            permissions, change_set_pks)
+    VALUES (this_tenancy_record.tenancy_workspace_pk, this_name, this_token_hash,
+            this_created_by_user_pk, this_permissions, this_change_set_pks)
+    RETURNING * INTO this_new_row;
+
+    object := row_to_json(this_new_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -0,0 +1,25 @@
+ALTER TABLE encrypted_secrets
+    ADD COLUMN expires_at timestamp with time zone,
+    ADD COLUMN rotate_by  timestamp with time zone;
+
+CREATE INDEX ON encrypted_secrets (expires_at) WHERE expires_at IS NOT NULL;
+CREATE INDEX ON encrypted_secrets (rotate_by) WHERE rotate_by IS NOT NULL;
+
+CREATE OR REPLACE VIEW secrets AS
+SELECT pk,
+       id,
+       tenancy_workspace_pk,
+       visibility_change_set_pk,
+       visibility_deleted_at,
+       key_pair_pk,
+       created_at,
+       created_by,
+       updated_at,
+       updated_by,
+       name,
+       definition,
+       description,
+       kind,
+       expires_at,
+       rotate_by
+FROM encrypted_secrets;
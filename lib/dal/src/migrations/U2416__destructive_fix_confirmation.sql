@@ -0,0 +1,24 @@
+ALTER TABLE workspaces
+    ADD COLUMN destructive_fix_confirmation text;
+
+ALTER TABLE fix_batches
+    ADD COLUMN required_confirmation text,
+    ADD COLUMN confirmed_by          ident;
+
+CREATE OR REPLACE FUNCTION workspace_update_destructive_fix_confirmation_v1(
+    this_pk ident,
+    this_destructive_fix_confirmation text,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET destructive_fix_confirmation = this_destructive_fix_confirmation,
+        updated_at                   = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO STRICT this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
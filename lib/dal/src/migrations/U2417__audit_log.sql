@@ -0,0 +1,2 @@
+CREATE INDEX ON history_events (tenancy_workspace_pk, created_at);
+CREATE INDEX ON secret_usages (tenancy_workspace_pk, created_at);
@@ -0,0 +1,20 @@
+ALTER TABLE workspaces
+    ADD COLUMN audit_log_sink jsonb;
+
+CREATE OR REPLACE FUNCTION workspace_update_audit_log_sink_v1(
+    this_pk ident,
+    this_audit_log_sink jsonb,
+    OUT object json) AS
+$$
+DECLARE
+    this_updated_row workspaces%ROWTYPE;
+BEGIN
+    UPDATE workspaces
+    SET audit_log_sink = this_audit_log_sink,
+        updated_at     = CLOCK_TIMESTAMP()
+    WHERE pk = this_pk
+    RETURNING * INTO STRICT this_updated_row;
+
+    object := row_to_json(this_updated_row);
+END;
+$$ LANGUAGE PLPGSQL VOLATILE;
@@ -1,16 +1,25 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
+use module_index_client::IndexClientError;
+use semver::VersionReq;
 use serde::{Deserialize, Serialize};
+use si_crypto::SigningError;
 use thiserror::Error;
 use url::ParseError;
 
-pub use export::{get_component_type, PkgExporter};
+pub use export::{get_component_type, PkgExporter, TEMPLATE_CHANGE_SET_NAME};
 pub use import::{
-    attach_resource_payload_to_value, import_pkg, import_pkg_from_pkg, ImportAttributeSkip,
-    ImportEdgeSkip, ImportOptions, ImportSkips,
+    attach_resource_payload_to_value, fetch_module_signature, import_pkg, import_pkg_from_pkg,
+    preview_import, ImportAction, ImportAttributeSkip, ImportEdgeSkip, ImportItemKey,
+    ImportItemKind, ImportOptions, ImportPreview, ImportPreviewItem, ImportPreviewNote,
+    ImportSkips,
 };
 use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
+pub use upgrade::{
+    fetch_installed_pkg, plan_upgrade, upgrade_pkg, FuncChange, MigratedComponent, PropChange,
+    PropRename, SocketChange, UpgradePlan, VariantUpgradePlan,
+};
 
 use crate::authentication_prototype::AuthenticationPrototypeError;
 use crate::{
@@ -22,7 +31,7 @@ use crate::{
     installed_pkg::InstalledPkgError,
     prop_tree::PropTreeError,
     schema::variant::definition::{SchemaVariantDefinitionError, SchemaVariantDefinitionId},
-    socket::{SocketEdgeKind, SocketError},
+    socket::{SocketEdgeKind, SocketError, SocketId},
     ActionPrototypeError, AttributeContextBuilderError, AttributePrototypeArgumentError,
     AttributePrototypeArgumentId, AttributePrototypeError, AttributePrototypeId,
     AttributeReadContext, AttributeValueError, ChangeSetError, ChangeSetPk, ComponentError,
@@ -33,8 +42,10 @@ use crate::{
     WorkspaceError, WorkspacePk, WsEvent, WsEventResult, WsPayload,
 };
 
+mod dependency;
 mod export;
 mod import;
+mod upgrade;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -99,10 +110,22 @@ pub enum PkgError {
     ConflictingMapKeyPrototypes(PropId),
     #[error("expected data on an SiPkg node, but none found: {0}")]
     DataNotFound(String),
+    #[error("module dependencies form a cycle: {0}")]
+    DependencyCycle(String),
+    #[error(
+        "{0} depends on {1} {2}, but the module index has no version of {1} which satisfies it"
+    )]
+    DependencyNotInModuleIndex(String, String, VersionReq),
+    #[error("{0} depends on {1} {2}, which is not installed and no module index is configured to fetch it from")]
+    DependencyNotInstalled(String, String, VersionReq),
+    #[error("{0} depends on {1} {2}, but the installed versions of {1} ({3}) do not satisfy it")]
+    DependencyUnsatisfiable(String, String, VersionReq, String),
     #[error(transparent)]
     Edge(#[from] EdgeError),
     #[error("edge refers to component not in export: {0}")]
     EdgeRefersToMissingComponent(ComponentId),
+    #[error("func {0} was excluded, but the schema variants being imported use it and there is no func with the same name to use in its place")]
+    ExcludedFuncInUse(String),
     #[error("Cannot find Socket for explicit InternalProvider {0}")]
     ExplicitInternalProviderMissingSocket(InternalProviderId),
     #[error(transparent)]
@@ -171,11 +194,15 @@ pub enum PkgError {
     MissingRootProp(SchemaVariantId),
     #[error("Cannot find schema_variant_definition {0}")]
     MissingSchemaVariantDefinition(SchemaVariantId),
+    #[error("Cannot find socket {0}")]
+    MissingSocket(SocketId),
     #[error("Cannot find socket with name {0} for edge kind {1}")]
     MissingSocketName(String, SocketEdgeKind),
     #[error("Unique id missing for node in workspace backup: {0}")]
     MissingUniqueIdForNode(String),
     #[error(transparent)]
+    ModuleIndexClient(#[from] IndexClientError),
+    #[error(transparent)]
     Node(#[from] NodeError),
     #[error("Package with that hash already installed: {0}")]
     PackageAlreadyInstalled(String),
@@ -199,9 +226,13 @@ pub enum PkgError {
     SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error("schema {0} variant {1} was not installed by the upgraded module")]
+    SchemaVariantNotFoundForUpgrade(String, String),
     #[error("json serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    Signing(#[from] SigningError),
+    #[error(transparent)]
     Socket(#[from] SocketError),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
@@ -209,8 +240,26 @@ pub enum PkgError {
     StandardModelMissingBelongsTo(&'static str, &'static str, String),
     #[error("standard model relationship {0} found multiple belongs_to for {1} with id {2}")]
     StandardModelMultipleBelongsTo(&'static str, &'static str, String),
+    #[error("template {0} has no change set named {1}")]
+    TemplateChangeSetNotFound(String, String),
+    #[error("templates must be imported into a change set")]
+    TemplateImportInHead,
     #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
+    #[error("module {0} is not signed, and this workspace only installs signed modules")]
+    UnsignedModule(String),
+    #[error("module {0} is not signed by any of this workspace's trusted publisher keys")]
+    UntrustedModule(String),
+    #[error("module {0} is not installed, so it cannot be upgraded")]
+    UpgradeFromModuleNotInstalled(String),
+    #[error("module upgrades must happen in a change set")]
+    UpgradeInHead,
+    #[error("module {0} with hash {1} is installed, but the module index does not have it")]
+    UpgradeInstalledModuleNotInModuleIndex(String, String),
+    #[error("cannot upgrade module {0} to a different module {1}")]
+    UpgradeModuleNameMismatch(String, String),
+    #[error("prop {0} was not possibly renamed to {1}, so the rename cannot be confirmed")]
+    UpgradeRenameNotPossible(String, String),
     #[error(transparent)]
     UrlParse(#[from] ParseError),
     #[error("Validation creation error: {0}")]
@@ -0,0 +1,197 @@
+use async_recursion::async_recursion;
+use semver::{Version, VersionReq};
+use si_pkg::{SiPkg, SiPkgKind};
+use telemetry::prelude::*;
+use ulid::Ulid;
+
+use crate::{installed_pkg::InstalledPkg, DalContext};
+
+use super::{
+    import::{fetch_module_signature, import_resolved_pkg},
+    ImportOptions, PkgError, PkgResult,
+};
+
+/// Installs the modules `pkg` depends on which aren't installed yet, depth first, so that every
+/// module is installed after its own dependencies.
+///
+/// A dependency is satisfied by an installed module with the same name whose version matches the
+/// dependency's requirement. Otherwise the newest matching version which hasn't been yanked is
+/// fetched from the module index, if one is given in the [`ImportOptions`]. A module which is
+/// installed at a non-matching version is not replaced: the import fails instead.
+///
+/// Versions are compared as described in [`parse_version`].
+pub(super) async fn install_dependencies(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    options: &ImportOptions,
+) -> PkgResult<()> {
+    // Only modules declare dependencies; workspace backups carry everything they need
+    if pkg.metadata()?.kind() != SiPkgKind::Module {
+        return Ok(());
+    }
+
+    install_dependencies_of(ctx, pkg, options, &mut vec![]).await
+}
+
+#[async_recursion]
+async fn install_dependencies_of(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    options: &ImportOptions,
+    dependents: &mut Vec<String>,
+) -> PkgResult<()> {
+    let metadata = pkg.metadata()?;
+    dependents.push(metadata.name().to_owned());
+
+    for dependency in pkg.dependencies()? {
+        let name = dependency.name();
+        let version_req = dependency.version_req();
+
+        if dependents.iter().any(|dependent| dependent == name) {
+            return Err(PkgError::DependencyCycle(format!(
+                "{} -> {name}",
+                dependents.join(" -> ")
+            )));
+        }
+
+        let installed = InstalledPkg::find_by_name(ctx, name).await?;
+        if !installed.is_empty() {
+            if installed
+                .iter()
+                .any(|installed_pkg| satisfies(installed_pkg.version(), version_req))
+            {
+                continue;
+            }
+
+            let installed_versions = installed
+                .iter()
+                .map(|installed_pkg| installed_pkg.version().unwrap_or("unknown"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(PkgError::DependencyUnsatisfiable(
+                metadata.name().to_owned(),
+                name.to_owned(),
+                version_req.clone(),
+                installed_versions,
+            ));
+        }
+
+        let module_index = options.module_index.as_ref().ok_or_else(|| {
+            PkgError::DependencyNotInstalled(
+                metadata.name().to_owned(),
+                name.to_owned(),
+                version_req.clone(),
+            )
+        })?;
+
+        let (module_id, version) = module_index
+            .list_published_versions(name)
+            .await?
+            .into_iter()
+            .filter(|published| published.yanked_at.is_none())
+            .filter_map(|published| {
+                let version = parse_version(&published.version)?;
+                version_req
+                    .matches(&version)
+                    .then_some((published.module_id, version))
+            })
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .ok_or_else(|| {
+                PkgError::DependencyNotInModuleIndex(
+                    metadata.name().to_owned(),
+                    name.to_owned(),
+                    version_req.clone(),
+                )
+            })?;
+
+        info!(%name, %version, "installing dependency from module index");
+        let module_id = Ulid::from_string(&module_id)?;
+        let dependency_pkg =
+            SiPkg::load_from_bytes(module_index.download_module(module_id).await?)?;
+        let signature = fetch_module_signature(module_index, module_id).await?;
+
+        install_dependencies_of(ctx, &dependency_pkg, options, dependents).await?;
+
+        // Options such as the schemas to import are meant for the package being imported,
+        // not for the modules it depends on
+        import_resolved_pkg(
+            ctx,
+            &dependency_pkg,
+            ImportOptions {
+                module_index: options.module_index.clone(),
+                signature,
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+
+    dependents.pop();
+
+    Ok(())
+}
+
+/// Whether an installed module's version satisfies a dependency's requirement. Modules installed
+/// before their versions were recorded satisfy any requirement, as there is nothing to compare.
+fn satisfies(version: Option<&str>, version_req: &VersionReq) -> bool {
+    match version {
+        Some(version) => {
+            parse_version(version).is_some_and(|version| version_req.matches(&version))
+        }
+        None => {
+            warn!(%version_req, "assuming a module installed without a version satisfies a dependency");
+            true
+        }
+    }
+}
+
+/// Parses a module version for comparison with a dependency's requirement. Versions which aren't
+/// semver, like the dated versions of builtins and intrinsics, are normalized: `major.minor` and
+/// `major` are padded with zeros, and `2023-05-24` becomes `2023.5.24`, so that it satisfies a
+/// requirement like `>=2023.5.1`.
+pub(crate) fn parse_version(version: &str) -> Option<Version> {
+    if let Ok(version) = Version::parse(version) {
+        return Some(version);
+    }
+
+    let separator = if version.contains('-') { '-' } else { '.' };
+    let parts = version.split(separator).collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let mut numbers = [0; 3];
+    for (number, part) in numbers.iter_mut().zip(parts) {
+        if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        *number = part.parse().ok()?;
+    }
+
+    Some(Version::new(numbers[0], numbers[1], numbers[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_non_semver_module_versions() {
+        assert_eq!(Some(Version::new(1, 4, 0)), parse_version("1.4.0"));
+        assert_eq!(Some(Version::new(0, 1, 0)), parse_version("0.1"));
+        assert_eq!(Some(Version::new(2, 0, 0)), parse_version("2"));
+        assert_eq!(Some(Version::new(2023, 5, 24)), parse_version("2023-05-24"));
+        assert_eq!(None, parse_version("latest"));
+        assert_eq!(None, parse_version("1..2"));
+        assert_eq!(None, parse_version("2023-05-24-01"));
+    }
+
+    #[test]
+    fn dated_versions_satisfy_requirements() {
+        let version_req = VersionReq::parse(">=2023.5.1").expect("able to parse version req");
+        assert!(satisfies(Some("2023-05-24"), &version_req));
+        assert!(!satisfies(Some("2023-04-30"), &version_req));
+        assert!(satisfies(None, &version_req));
+        assert!(!satisfies(Some("latest"), &version_req));
+    }
+}
@@ -54,8 +54,12 @@ pub struct PkgExporter {
     component_map: ComponentMap,
     is_workspace_export: bool,
     include_components: bool,
+    component_ids: Option<Vec<ComponentId>>,
 }
 
+/// The name of the only change set in a template.
+pub const TEMPLATE_CHANGE_SET_NAME: &str = "template";
+
 fn std_model_change_set_matches<StdModel: StandardModel>(
     change_set_pk: Option<ChangeSetPk>,
     standard_model_thing: &StdModel,
@@ -96,6 +100,7 @@ impl PkgExporter {
             component_map: ComponentMap::new(),
             is_workspace_export: false,
             include_components: false,
+            component_ids: None,
         }
     }
 
@@ -117,12 +122,40 @@ impl PkgExporter {
             component_map: ComponentMap::new(),
             is_workspace_export: true,
             include_components: true,
+            component_ids: None,
+        }
+    }
+
+    /// Exports the given components, and the edges between them, as a template which can be
+    /// imported into any workspace to create fresh copies of them. Components refer to their
+    /// schema variants by name, so the workspace importing the template must already have them.
+    pub fn new_template_exporter(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        description: Option<impl Into<String>>,
+        created_by: impl Into<String>,
+        component_ids: Vec<ComponentId>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            description: description.map(Into::into),
+            kind: SiPkgKind::Template,
+            created_by: created_by.into(),
+            schema_ids: Some(vec![]),
+            func_map: FuncSpecMap::new(),
+            variant_map: VariantSpecMap::new(),
+            component_map: ComponentMap::new(),
+            is_workspace_export: false,
+            include_components: true,
+            component_ids: Some(component_ids),
         }
     }
 
     pub async fn export_as_bytes(&mut self, ctx: &DalContext) -> PkgResult<Vec<u8>> {
         match self.kind {
             SiPkgKind::Module => info!("Building module package"),
+            SiPkgKind::Template => info!("Building template package"),
             SiPkgKind::WorkspaceBackup => info!("Building workspace backup package"),
         }
 
@@ -1331,8 +1364,12 @@ impl PkgExporter {
             schema_specs.push(schema_spec);
         }
 
-        if self.is_workspace_export && self.include_components {
+        if self.include_components {
             for component in Component::list(ctx).await? {
+                if !self.includes_component(*component.id()) {
+                    continue;
+                }
+
                 if let Some((component_spec, component_funcs, component_head_funcs)) = self
                     .export_component(ctx, change_set_pk, &component)
                     .await?
@@ -1350,6 +1387,12 @@ impl PkgExporter {
             }
 
             for edge in Edge::list(ctx).await? {
+                if !self.includes_component(edge.head_object_id().into())
+                    || !self.includes_component(edge.tail_object_id().into())
+                {
+                    continue;
+                }
+
                 edge_specs.push(self.export_edge(ctx, change_set_pk, &edge).await?);
             }
         }
@@ -1363,6 +1406,13 @@ impl PkgExporter {
         ))
     }
 
+    fn includes_component(&self, component_id: ComponentId) -> bool {
+        match &self.component_ids {
+            None => true,
+            Some(component_ids) => component_ids.contains(&component_id),
+        }
+    }
+
     pub async fn export_edge(
         &mut self,
         ctx: &DalContext,
@@ -1685,6 +1735,21 @@ impl PkgExporter {
                 pkg_spec_builder.funcs(funcs);
                 pkg_spec_builder.schemas(schemas);
             }
+            SiPkgKind::Template => {
+                let (mut funcs, head_funcs, _, components, edges) =
+                    self.export_change_set(ctx, None).await?;
+                funcs.extend_from_slice(&head_funcs);
+
+                pkg_spec_builder.default_change_set(TEMPLATE_CHANGE_SET_NAME);
+                pkg_spec_builder.change_set(
+                    ChangeSetSpec::builder()
+                        .name(TEMPLATE_CHANGE_SET_NAME)
+                        .funcs(remove_duplicate_func_specs(&funcs))
+                        .components(prepare_template_components(components))
+                        .edges(edges)
+                        .build()?,
+                );
+            }
             SiPkgKind::WorkspaceBackup => {
                 let (mut head_funcs, funcs, schemas, components, edges) =
                     self.export_change_set(ctx, Some(ChangeSetPk::NONE)).await?;
@@ -1732,6 +1797,70 @@ impl PkgExporter {
     }
 }
 
+/// Prepares exported components to be instantiated afresh by a template: positions are moved so
+/// that the top left corner of the area the components cover is at the origin, and anything
+/// describing the real resources behind the components, or the secrets used to reach them, is
+/// dropped.
+fn prepare_template_components(mut components: Vec<ComponentSpec>) -> Vec<ComponentSpec> {
+    let coordinate = |value: &str| value.parse::<f64>().ok();
+    let origin_x = components
+        .iter()
+        .filter_map(|component| coordinate(&component.position.x))
+        .reduce(f64::min);
+    let origin_y = components
+        .iter()
+        .filter_map(|component| coordinate(&component.position.y))
+        .reduce(f64::min);
+
+    let root_path = PropPath::new(["root"]);
+    let dropped_paths = [
+        PropPath::new(["root", "resource"]),
+        PropPath::new(["root", "secrets"]),
+    ];
+    for component in &mut components {
+        let position = &mut component.position;
+        if let (Some(origin_x), Some(x)) = (origin_x, coordinate(&position.x)) {
+            position.x = (x - origin_x).to_string();
+        }
+        if let (Some(origin_y), Some(y)) = (origin_y, coordinate(&position.y)) {
+            position.y = (y - origin_y).to_string();
+        }
+
+        component.needs_destroy = false;
+        component.deletion_user_pk = None;
+        component
+            .attributes
+            .retain(|attribute| match &attribute.path {
+                AttributeValuePath::Prop { path, .. } => {
+                    let path = PropPath::from(path);
+                    !dropped_paths
+                        .iter()
+                        .any(|dropped_path| path.is_descendant_of(dropped_path))
+                }
+                _ => true,
+            });
+        // The root value holds the values of every prop beneath it, including the dropped ones
+        for attribute in &mut component.attributes {
+            match &attribute.path {
+                AttributeValuePath::Prop { path, .. } if PropPath::from(path) == root_path => {}
+                _ => continue,
+            }
+            for value in [
+                &mut attribute.value,
+                &mut attribute.unprocessed_value,
+                &mut attribute.implicit_value,
+            ] {
+                if let Some(serde_json::Value::Object(object)) = value {
+                    object.remove("resource");
+                    object.remove("secrets");
+                }
+            }
+        }
+    }
+
+    components
+}
+
 fn remove_duplicate_func_specs(func_specs: &[FuncSpec]) -> Vec<FuncSpec> {
     let mut unique_id_set = HashSet::new();
 
@@ -1770,3 +1899,99 @@ pub async fn get_component_type(
         None => SchemaVariantSpecComponentType::default(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType};
+
+    use super::*;
+
+    fn attribute(path: &str, value: serde_json::Value) -> AttributeValueSpec {
+        AttributeValueSpec {
+            parent_path: None,
+            path: AttributeValuePath::Prop {
+                path: path.to_owned(),
+                key: None,
+                index: None,
+            },
+            func_unique_id: "si:identity".to_owned(),
+            func_binding_args: serde_json::json!({}),
+            handler: None,
+            backend_kind: FuncSpecBackendKind::Identity,
+            response_type: FuncSpecBackendResponseType::Identity,
+            code_base64: None,
+            unprocessed_value: Some(value.clone()),
+            value: Some(value.clone()),
+            output_stream: None,
+            is_proxy: false,
+            sealed_proxy: false,
+            component_specific: false,
+            inputs: vec![],
+            implicit_value: Some(value),
+        }
+    }
+
+    #[test]
+    fn template_components_drop_resources_and_secrets() {
+        let component = ComponentSpec {
+            name: "rocket".to_owned(),
+            position: PositionSpec {
+                x: "100".to_owned(),
+                y: "50".to_owned(),
+                width: None,
+                height: None,
+            },
+            variant: ComponentSpecVariant::WorkspaceVariant {
+                variant_unique_id: "rocket".to_owned(),
+            },
+            needs_destroy: true,
+            deletion_user_pk: None,
+            unique_id: "rocket".to_owned(),
+            deleted: false,
+            attributes: vec![
+                attribute(
+                    "root",
+                    serde_json::json!({
+                        "domain": { "name": "rocket" },
+                        "resource": { "payload": "launched" },
+                        "secrets": { "fuel": "secret-id" },
+                    }),
+                ),
+                attribute("root\u{b}domain\u{b}name", serde_json::json!("rocket")),
+                attribute(
+                    "root\u{b}resource\u{b}payload",
+                    serde_json::json!("launched"),
+                ),
+                attribute("root\u{b}secrets\u{b}fuel", serde_json::json!("secret-id")),
+            ],
+            input_sockets: vec![],
+            output_sockets: vec![],
+        };
+
+        let components = prepare_template_components(vec![component]);
+        let component = components.get(0).expect("component should be kept");
+
+        assert_eq!(
+            ("0", "0"),
+            (component.position.x.as_str(), component.position.y.as_str())
+        );
+        assert!(!component.needs_destroy);
+        let paths: Vec<&str> = component
+            .attributes
+            .iter()
+            .filter_map(|attribute| match &attribute.path {
+                AttributeValuePath::Prop { path, .. } => Some(path.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec!["root", "root\u{b}domain\u{b}name"], paths);
+
+        let root = component.attributes.get(0).expect("root should be kept");
+        for value in [&root.value, &root.unprocessed_value, &root.implicit_value] {
+            assert_eq!(
+                Some(serde_json::json!({ "domain": { "name": "rocket" } })),
+                *value
+            );
+        }
+    }
+}
@@ -5,8 +5,11 @@ use std::{
 };
 
 use chrono::Utc;
+use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
+use si_crypto::{DetachedSignature, VerifyingKey};
 use tokio::sync::Mutex;
+use ulid::Ulid;
 
 use si_pkg::{
     AttributeValuePath, ComponentSpecVariant, EdgeSpecKind, SchemaVariantSpecPropRoot, SiPkg,
@@ -48,12 +51,19 @@ use crate::{
     AttributeValue, AttributeValueError, ChangeSet, ChangeSetPk, Component, ComponentId,
     DalContext, Edge, ExternalProvider, ExternalProviderId, Func, FuncArgument, FuncError, FuncId,
     InternalProvider, InternalProviderError, InternalProviderId, LeafKind, Node, Prop, PropId,
-    PropKind, Schema, SchemaId, SchemaVariant, SchemaVariantError, SchemaVariantId, Socket,
-    StandardModel, Tenancy, UserPk, ValidationPrototype, ValidationPrototypeContext, Workspace,
-    WorkspacePk,
+    PropKind, Schema, SchemaError, SchemaId, SchemaVariant, SchemaVariantError, SchemaVariantId,
+    Socket, StandardModel, Tenancy, UserPk, ValidationPrototype, ValidationPrototypeContext,
+    Workspace, WorkspacePk,
 };
 
-use super::{PkgError, PkgResult};
+use super::{dependency::install_dependencies, PkgError, PkgResult, TEMPLATE_CHANGE_SET_NAME};
+
+mod preview;
+
+pub use preview::{
+    preview_import, ImportAction, ImportItemKey, ImportItemKind, ImportPreview, ImportPreviewItem,
+    ImportPreviewNote,
+};
 
 #[derive(Clone, Debug)]
 enum Thing {
@@ -82,6 +92,37 @@ pub struct ImportOptions {
     /// If set to `true` then we will set the functions to a builtin
     /// in the UI. They will be marked as such.
     pub is_builtin: bool,
+    /// The module index to fetch dependencies from when they aren't already
+    /// installed. Without one, every dependency must already be installed.
+    pub module_index: Option<IndexClient>,
+    /// A detached signature over the package's root hash. Workspaces which require signed
+    /// modules only install modules signed by one of their trusted publisher keys.
+    pub signature: Option<DetachedSignature>,
+    /// Items from an [`ImportPreview`] which should not be imported. An excluded func is
+    /// replaced by an existing func with the same name. If there is none, excluding a func which
+    /// an imported schema variant uses fails the import.
+    pub excluded_items: HashSet<ImportItemKey>,
+}
+
+impl ImportOptions {
+    fn is_excluded(&self, key: &ImportItemKey) -> bool {
+        self.excluded_items.contains(key)
+    }
+}
+
+/// Funcs which are shared between modules rather than installed from each one. Every func in a
+/// template is shared, since it only carries the funcs bound to its components, which the
+/// workspace normally already has from installing their schemas.
+fn is_shared_func(kind: SiPkgKind, func_spec: &SiPkgFunc<'_>) -> bool {
+    // This is a hack because the hash of the intrinsics has changed from the version in the
+    // packages. We also apply this to si:resourcePayloadToValue since it should be an
+    // intrinsic but is only in our packages
+    let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
+
+    kind == SiPkgKind::Template
+        || func::is_intrinsic(func_spec.name())
+        || special_case_funcs.contains(&func_spec.name())
+        || func_spec.is_from_builtin().unwrap_or(false)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -101,17 +142,17 @@ async fn import_change_set(
     Vec<(String, Vec<ImportAttributeSkip>)>,
     Vec<ImportEdgeSkip>,
 )> {
+    // Only needed to tell whether an excluded func can be left out
+    let funcs_in_use = if options.excluded_items.is_empty() {
+        HashSet::new()
+    } else {
+        preview::func_unique_ids_in_use(schemas, options).await?
+    };
+
     for func_spec in funcs {
         let unique_id = func_spec.unique_id().to_string();
 
-        // This is a hack because the hash of the intrinsics has changed from the version in the
-        // packages. We also apply this to si:resourcePayloadToValue since it should be an
-        // intrinsic but is only in our packages
-        let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
-        if func::is_intrinsic(func_spec.name())
-            || special_case_funcs.contains(&func_spec.name())
-            || func_spec.is_from_builtin().unwrap_or(false)
-        {
+        if is_shared_func(metadata.kind(), func_spec) {
             if let Some(func) = Func::find_by_name(ctx, func_spec.name()).await? {
                 thing_map.insert(
                     change_set_pk,
@@ -134,6 +175,17 @@ async fn import_change_set(
                     import_func_arguments(ctx, None, *func.id(), &args, thing_map).await?;
                 }
             }
+        } else if options.is_excluded(&ImportItemKey::for_func(func_spec)) {
+            // Anything which uses the excluded func uses the existing one in its place
+            match Func::find_by_name(ctx, func_spec.name()).await? {
+                Some(func) => {
+                    thing_map.insert(change_set_pk, unique_id.to_owned(), Thing::Func(func));
+                }
+                None if funcs_in_use.contains(&unique_id) => {
+                    return Err(PkgError::ExcludedFuncInUse(func_spec.name().to_owned()));
+                }
+                None => {}
+            }
         } else {
             let func = if let Some(Some(func)) = options
                 .skip_import_funcs
@@ -199,6 +251,9 @@ async fn import_change_set(
                 }
             }
         }
+        if options.is_excluded(&ImportItemKey::for_schema(schema_spec)) {
+            continue;
+        }
 
         info!(
             "installing schema '{}' from {}",
@@ -206,8 +261,15 @@ async fn import_change_set(
             metadata.name(),
         );
 
-        let (_, schema_variant_ids) =
-            import_schema(ctx, change_set_pk, schema_spec, installed_pkg_id, thing_map).await?;
+        let (_, schema_variant_ids) = import_schema(
+            ctx,
+            change_set_pk,
+            schema_spec,
+            installed_pkg_id,
+            thing_map,
+            options,
+        )
+        .await?;
 
         installed_schema_variant_ids.extend(schema_variant_ids);
     }
@@ -215,8 +277,20 @@ async fn import_change_set(
     println!("Finished Imports: {}", Utc::now());
 
     let mut component_attribute_skips = vec![];
+    let mut excluded_component_unique_ids = HashSet::new();
     for component_spec in components {
-        let skips = import_component(ctx, change_set_pk, component_spec, thing_map).await?;
+        if options.is_excluded(&ImportItemKey::for_component(component_spec)) {
+            excluded_component_unique_ids.insert(component_spec.unique_id().to_owned());
+            continue;
+        }
+        let skips = import_component(
+            ctx,
+            change_set_pk,
+            metadata.kind(),
+            component_spec,
+            thing_map,
+        )
+        .await?;
         if !skips.is_empty() {
             component_attribute_skips.push((component_spec.name().to_owned(), skips));
         }
@@ -224,6 +298,13 @@ async fn import_change_set(
 
     let mut edge_skips = vec![];
     for edge_spec in edges {
+        // Edges to excluded components have nothing to connect to
+        if options.is_excluded(&ImportItemKey::for_edge(edge_spec))
+            || excluded_component_unique_ids.contains(edge_spec.from_component_unique_id())
+            || excluded_component_unique_ids.contains(edge_spec.to_component_unique_id())
+        {
+            continue;
+        }
         if let Some(skip) = import_edge(ctx, change_set_pk, edge_spec, thing_map).await? {
             edge_skips.push(skip);
         }
@@ -382,6 +463,7 @@ async fn import_edge(
 async fn import_component(
     ctx: &DalContext,
     change_set_pk: Option<ChangeSetPk>,
+    kind: SiPkgKind,
     component_spec: &SiPkgComponent<'_>,
     thing_map: &mut ThingMap,
 ) -> PkgResult<Vec<ImportAttributeSkip>> {
@@ -392,12 +474,16 @@ async fn import_component(
             schema_name,
             variant_name,
         } => {
-            let schema = Schema::find_by_name_builtin(ctx, schema_name.as_str())
-                .await?
-                .ok_or(PkgError::ComponentMissingBuiltinSchema(
-                    schema_name.to_owned(),
-                    component_spec.name().into(),
-                ))?;
+            let schema = match kind {
+                SiPkgKind::Template => find_template_schema(ctx, schema_name).await?,
+                SiPkgKind::Module | SiPkgKind::WorkspaceBackup => {
+                    Schema::find_by_name_builtin(ctx, schema_name.as_str()).await?
+                }
+            }
+            .ok_or(PkgError::ComponentMissingBuiltinSchema(
+                schema_name.to_owned(),
+                component_spec.name().into(),
+            ))?;
 
             schema
                 .find_variant_by_name(ctx, variant_name.as_str())
@@ -548,6 +634,20 @@ async fn import_component(
     Ok(skips)
 }
 
+/// Templates are instantiated in whichever workspace imports them, so their components use the
+/// schema with the right name, whether it is a builtin or was installed from a module.
+async fn find_template_schema(ctx: &DalContext, name: &str) -> PkgResult<Option<Schema>> {
+    if let Some(schema) = Schema::find_by_attr(ctx, "name", &name).await?.pop() {
+        return Ok(Some(schema));
+    }
+
+    match Schema::find_by_name_builtin(ctx, name).await {
+        Ok(schema) => Ok(schema),
+        Err(SchemaError::NotFoundByName(_)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn get_prop_kind_for_value(value: Option<&serde_json::Value>) -> Option<PropKind> {
     match value {
         Some(serde_json::Value::Array(_)) => Some(PropKind::Array),
@@ -927,6 +1027,52 @@ pub enum ImportEdgeSkip {
     MissingOutputSocket(String),
 }
 
+/// Ensures that a module or template may be installed into the context's workspace: if the
+/// workspace requires signed modules, `signature` must have been made over the package's root hash
+/// by one of the workspace's trusted publisher keys.
+async fn verify_module_trust(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    signature: Option<&DetachedSignature>,
+) -> PkgResult<()> {
+    let workspace = match ctx.tenancy().workspace_pk() {
+        Some(workspace_pk) => Workspace::get_by_pk(ctx, &workspace_pk)
+            .await?
+            .ok_or(PkgError::WorkspaceNotFound(workspace_pk))?,
+        None => return Ok(()),
+    };
+    if !workspace.require_signed_modules() {
+        return Ok(());
+    }
+
+    let name = pkg.metadata()?.name().to_owned();
+    let signature = signature.ok_or_else(|| PkgError::UnsignedModule(name.clone()))?;
+
+    for trusted_key in workspace.trusted_publisher_keys() {
+        let verifying_key = VerifyingKey::decode(trusted_key)?;
+        if pkg.verify_signature(signature, &verifying_key).is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err(PkgError::UntrustedModule(name))
+}
+
+/// Fetches a module's signature from the module index. Signatures made by a publisher key which
+/// has since been revoked are ignored, as though the module were unsigned.
+pub async fn fetch_module_signature(
+    module_index: &IndexClient,
+    module_id: Ulid,
+) -> PkgResult<Option<DetachedSignature>> {
+    Ok(match module_index.get_module_signature(module_id).await? {
+        Some(response) if response.publisher_key.revoked_at.is_none() => {
+            Some(DetachedSignature::decode(response.signature)?)
+        }
+        _ => None,
+    })
+}
+
+/// Installs a package, first installing any modules it depends on which aren't installed yet.
 pub async fn import_pkg_from_pkg(
     ctx: &DalContext,
     pkg: &SiPkg,
@@ -935,26 +1081,58 @@ pub async fn import_pkg_from_pkg(
     Option<InstalledPkgId>,
     Vec<SchemaVariantId>,
     Option<Vec<ImportSkips>>,
+)> {
+    let options = options.unwrap_or_default();
+
+    install_dependencies(ctx, pkg, &options).await?;
+
+    import_resolved_pkg(ctx, pkg, options).await
+}
+
+/// Installs a package whose dependencies are already installed.
+pub(super) async fn import_resolved_pkg(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    options: ImportOptions,
+) -> PkgResult<(
+    Option<InstalledPkgId>,
+    Vec<SchemaVariantId>,
+    Option<Vec<ImportSkips>>,
 )> {
     // We have to write the installed_pkg row first, so that we have an id, and rely on transaction
     // semantics to remove the row if anything in the installation process fails
     let root_hash = pkg.hash()?.to_string();
+    let metadata = pkg.metadata()?;
 
-    let options = options.unwrap_or_default();
+    // Templates are instantiated afresh each time they're imported, rather than installed
+    let is_template = metadata.kind() == SiPkgKind::Template;
 
-    if InstalledPkg::find_by_hash(ctx, &root_hash).await?.is_some() {
+    if !is_template && InstalledPkg::find_by_hash(ctx, &root_hash).await?.is_some() {
         return Err(PkgError::PackageAlreadyInstalled(root_hash));
     }
 
-    let metadata = pkg.metadata()?;
+    // Templates carry func code just like modules do. Builtins and packages built from a
+    // workspace's own assets (which are not recorded as installed) come from inside the system,
+    // so there is no publisher to trust
+    if matches!(metadata.kind(), SiPkgKind::Module | SiPkgKind::Template)
+        && !options.is_builtin
+        && !options.no_record
+    {
+        verify_module_trust(ctx, pkg, options.signature.as_ref()).await?;
+    }
 
-    let installed_pkg_id = if options.no_record {
+    let installed_pkg_id = if options.no_record || is_template {
         None
     } else {
         Some(
-            *InstalledPkg::new(ctx, metadata.name(), pkg.hash()?.to_string())
-                .await?
-                .id(),
+            *InstalledPkg::new(
+                ctx,
+                metadata.name(),
+                metadata.version(),
+                pkg.hash()?.to_string(),
+            )
+            .await?
+            .id(),
         )
     };
 
@@ -978,6 +1156,48 @@ pub async fn import_pkg_from_pkg(
 
             Ok((installed_pkg_id, installed_schema_variant_ids, None))
         }
+        SiPkgKind::Template => {
+            let change_set_pk = ctx.visibility().change_set_pk;
+            if change_set_pk == ChangeSetPk::NONE {
+                return Err(PkgError::TemplateImportInHead);
+            }
+
+            let change_set_name = metadata
+                .default_change_set()
+                .unwrap_or(TEMPLATE_CHANGE_SET_NAME);
+            let template = pkg
+                .change_sets()?
+                .into_iter()
+                .find(|change_set| change_set.name() == change_set_name)
+                .ok_or(PkgError::TemplateChangeSetNotFound(
+                    metadata.name().into(),
+                    change_set_name.into(),
+                ))?;
+
+            let (_, attribute_skips, edge_skips) = import_change_set(
+                ctx,
+                Some(change_set_pk),
+                &metadata,
+                &template.funcs()?,
+                &[],
+                &template.components()?,
+                &template.edges()?,
+                None,
+                &mut change_set_things,
+                &options,
+            )
+            .await?;
+
+            Ok((
+                None,
+                vec![],
+                Some(vec![ImportSkips {
+                    change_set_pk,
+                    attribute_skips,
+                    edge_skips,
+                }]),
+            ))
+        }
         SiPkgKind::WorkspaceBackup => {
             let mut ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
 
@@ -1370,6 +1590,7 @@ async fn import_schema(
     schema_spec: &SiPkgSchema<'_>,
     installed_pkg_id: Option<InstalledPkgId>,
     thing_map: &mut ThingMap,
+    options: &ImportOptions,
 ) -> PkgResult<(Option<SchemaId>, Vec<SchemaVariantId>)> {
     let schema = match change_set_pk {
         None => {
@@ -1470,6 +1691,10 @@ async fn import_schema(
 
         let mut installed_schema_variant_ids = vec![];
         for variant_spec in &schema_spec.variants()? {
+            if options.is_excluded(&ImportItemKey::for_variant(schema_spec, variant_spec)) {
+                continue;
+            }
+
             let variant = import_schema_variant(
                 ctx,
                 change_set_pk,
@@ -0,0 +1,499 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use si_pkg::{
+    SiPkg, SiPkgChangeSet, SiPkgComponent, SiPkgEdge, SiPkgFunc, SiPkgKind, SiPkgSchema,
+    SiPkgSchemaVariant,
+};
+
+use crate::{
+    installed_pkg::{InstalledPkgAsset, InstalledPkgAssetKind},
+    pkg::{PkgResult, TEMPLATE_CHANGE_SET_NAME},
+    DalContext, Func, Schema, StandardModel,
+};
+
+use super::{is_shared_func, ImportOptions};
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportItemKind {
+    Component,
+    Edge,
+    Func,
+    Schema,
+    SchemaVariant,
+}
+
+/// Identifies an item in a package across an import preview and the selective import which
+/// follows it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemKey {
+    pub kind: ImportItemKind,
+    pub id: String,
+}
+
+impl ImportItemKey {
+    pub fn new(kind: ImportItemKind, id: impl Into<String>) -> Self {
+        Self {
+            kind,
+            id: id.into(),
+        }
+    }
+
+    pub fn for_func(func_spec: &SiPkgFunc<'_>) -> Self {
+        Self::new(ImportItemKind::Func, func_spec.unique_id())
+    }
+
+    pub fn for_schema(schema_spec: &SiPkgSchema<'_>) -> Self {
+        Self::new(
+            ImportItemKind::Schema,
+            schema_spec.unique_id().unwrap_or(schema_spec.name()),
+        )
+    }
+
+    pub fn for_variant(
+        schema_spec: &SiPkgSchema<'_>,
+        variant_spec: &SiPkgSchemaVariant<'_>,
+    ) -> Self {
+        Self::new(
+            ImportItemKind::SchemaVariant,
+            match variant_spec.unique_id() {
+                Some(unique_id) => unique_id.to_owned(),
+                None => format!("{}/{}", schema_spec.name(), variant_spec.name()),
+            },
+        )
+    }
+
+    pub fn for_component(component_spec: &SiPkgComponent<'_>) -> Self {
+        Self::new(ImportItemKind::Component, component_spec.unique_id())
+    }
+
+    pub fn for_edge(edge_spec: &SiPkgEdge<'_>) -> Self {
+        Self::new(ImportItemKind::Edge, edge_spec.unique_id())
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportAction {
+    Create,
+    Skip,
+    Update,
+}
+
+/// Why an item will be skipped, or what to look out for when it is imported.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportPreviewNote {
+    /// The identical item was installed by another module and will be shared.
+    AlreadyInstalled,
+    /// The item is deleted in this change set of the backup.
+    Deleted,
+    /// The edge connects a component which was excluded.
+    EndpointExcluded,
+    /// The item was excluded from the import.
+    Excluded,
+    /// The func was excluded, but schema variants being imported use it and there is no func
+    /// with the same name to use in its place, so the import will fail.
+    ExcludedFuncInUse,
+    /// An item with the same name already exists and will be kept alongside the imported one.
+    NameConflict,
+    /// The schema isn't one of the schemas chosen for import.
+    NotSelected,
+    /// An existing func will be used in place of the one in the package. An excluded func which
+    /// schema variants being imported use is replaced by the existing func with the same name.
+    ReplacedByExistingFunc,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreviewItem {
+    pub key: ImportItemKey,
+    pub name: String,
+    /// The change set of a workspace backup which the item belongs to.
+    pub change_set: Option<String>,
+    pub action: ImportAction,
+    pub note: Option<ImportPreviewNote>,
+}
+
+/// What importing a package with a set of [`ImportOptions`] will do, item by item. Items which
+/// shouldn't be imported can be added to [`ImportOptions::excluded_items`] for a selective
+/// import.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    pub items: Vec<ImportPreviewItem>,
+}
+
+impl ImportPreview {
+    pub fn items_with_action(
+        &self,
+        action: ImportAction,
+    ) -> impl Iterator<Item = &ImportPreviewItem> {
+        self.items.iter().filter(move |item| item.action == action)
+    }
+
+    fn push(
+        &mut self,
+        key: ImportItemKey,
+        name: impl Into<String>,
+        change_set: Option<&str>,
+        (action, note): (ImportAction, Option<ImportPreviewNote>),
+    ) {
+        self.items.push(ImportPreviewItem {
+            key,
+            name: name.into(),
+            change_set: change_set.map(ToOwned::to_owned),
+            action,
+            note,
+        });
+    }
+}
+
+/// Works out what [`import_pkg_from_pkg`](super::import_pkg_from_pkg) would do with `pkg` in the
+/// context's change set, without changing anything. Dependencies which aren't installed yet are
+/// not included.
+pub async fn preview_import(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    options: &ImportOptions,
+) -> PkgResult<ImportPreview> {
+    let metadata = pkg.metadata()?;
+    let mut preview = ImportPreview::default();
+
+    match metadata.kind() {
+        SiPkgKind::Module => {
+            let schemas = pkg.schemas()?;
+            let funcs_in_use = func_unique_ids_in_use(&schemas, options).await?;
+            preview_module_funcs(ctx, &pkg.funcs()?, options, &funcs_in_use, &mut preview).await?;
+            preview_module_schemas(ctx, &schemas, options, &mut preview).await?;
+        }
+        SiPkgKind::Template => {
+            // Every component and edge in a template is created afresh
+            let change_set_name = metadata
+                .default_change_set()
+                .unwrap_or(TEMPLATE_CHANGE_SET_NAME);
+            for change_set in pkg.change_sets()? {
+                if change_set.name() == change_set_name {
+                    preview_change_set(
+                        ctx,
+                        metadata.kind(),
+                        &change_set,
+                        options,
+                        &mut HashSet::new(),
+                        &mut preview,
+                    )
+                    .await?;
+                }
+            }
+        }
+        SiPkgKind::WorkspaceBackup => {
+            // Restoring a backup replaces the workspace, so its change sets are compared with
+            // each other rather than with what is in the workspace now. The default change set
+            // is restored first and the others are built on top of it.
+            let default_change_set_name = metadata.default_change_set().unwrap_or("head");
+            let mut change_sets = pkg.change_sets()?;
+            change_sets.sort_by_key(|change_set| change_set.name() != default_change_set_name);
+
+            let mut seen = HashSet::new();
+            for change_set in &change_sets {
+                preview_change_set(
+                    ctx,
+                    metadata.kind(),
+                    change_set,
+                    options,
+                    &mut seen,
+                    &mut preview,
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(preview)
+}
+
+async fn preview_module_funcs(
+    ctx: &DalContext,
+    funcs: &[SiPkgFunc<'_>],
+    options: &ImportOptions,
+    funcs_in_use: &HashSet<String>,
+    preview: &mut ImportPreview,
+) -> PkgResult<()> {
+    for func_spec in funcs {
+        let key = ImportItemKey::for_func(func_spec);
+        let name_taken = Func::find_by_name(ctx, func_spec.name()).await?.is_some();
+
+        let outcome = if is_shared_func(SiPkgKind::Module, func_spec) {
+            if name_taken {
+                (
+                    ImportAction::Skip,
+                    Some(ImportPreviewNote::AlreadyInstalled),
+                )
+            } else {
+                (ImportAction::Create, None)
+            }
+        } else if options.is_excluded(&key) {
+            excluded_func_outcome(funcs_in_use.contains(func_spec.unique_id()), name_taken)
+        } else if options
+            .skip_import_funcs
+            .as_ref()
+            .is_some_and(|skip_funcs| skip_funcs.contains_key(func_spec.unique_id()))
+        {
+            (
+                ImportAction::Skip,
+                Some(ImportPreviewNote::ReplacedByExistingFunc),
+            )
+        } else if is_installed(ctx, InstalledPkgAssetKind::Func, func_spec.hash()).await? {
+            (
+                ImportAction::Skip,
+                Some(ImportPreviewNote::AlreadyInstalled),
+            )
+        } else if name_taken {
+            (ImportAction::Create, Some(ImportPreviewNote::NameConflict))
+        } else {
+            (ImportAction::Create, None)
+        };
+
+        preview.push(key, func_spec.name(), None, outcome);
+    }
+
+    Ok(())
+}
+
+async fn preview_module_schemas(
+    ctx: &DalContext,
+    schemas: &[SiPkgSchema<'_>],
+    options: &ImportOptions,
+    preview: &mut ImportPreview,
+) -> PkgResult<()> {
+    for schema_spec in schemas {
+        let key = ImportItemKey::for_schema(schema_spec);
+        let selected = options.schemas.as_ref().map_or(true, |schemas| {
+            schemas.contains(&schema_spec.name().to_lowercase())
+        });
+
+        let outcome = if !selected {
+            (ImportAction::Skip, Some(ImportPreviewNote::NotSelected))
+        } else if options.is_excluded(&key) {
+            (ImportAction::Skip, Some(ImportPreviewNote::Excluded))
+        } else if is_installed(ctx, InstalledPkgAssetKind::Schema, schema_spec.hash()).await? {
+            (
+                ImportAction::Skip,
+                Some(ImportPreviewNote::AlreadyInstalled),
+            )
+        } else if !Schema::find_by_attr(ctx, "name", &schema_spec.name())
+            .await?
+            .is_empty()
+        {
+            (ImportAction::Create, Some(ImportPreviewNote::NameConflict))
+        } else {
+            (ImportAction::Create, None)
+        };
+        preview.push(key, schema_spec.name(), None, outcome);
+
+        for variant_spec in schema_spec.variants()? {
+            let variant_key = ImportItemKey::for_variant(schema_spec, &variant_spec);
+
+            // Variants are skipped along with their schema, for the same reason
+            let variant_outcome = if outcome.0 == ImportAction::Skip {
+                outcome
+            } else if options.is_excluded(&variant_key) {
+                (ImportAction::Skip, Some(ImportPreviewNote::Excluded))
+            } else if is_installed(
+                ctx,
+                InstalledPkgAssetKind::SchemaVariant,
+                variant_spec.hash(),
+            )
+            .await?
+            {
+                (
+                    ImportAction::Skip,
+                    Some(ImportPreviewNote::AlreadyInstalled),
+                )
+            } else {
+                (ImportAction::Create, None)
+            };
+            preview.push(variant_key, variant_spec.name(), None, variant_outcome);
+        }
+    }
+
+    Ok(())
+}
+
+async fn preview_change_set(
+    ctx: &DalContext,
+    kind: SiPkgKind,
+    change_set: &SiPkgChangeSet<'_>,
+    options: &ImportOptions,
+    seen: &mut HashSet<ImportItemKey>,
+    preview: &mut ImportPreview,
+) -> PkgResult<()> {
+    let change_set_name = Some(change_set.name());
+    let funcs_in_use = func_unique_ids_in_use(&change_set.schemas()?, options).await?;
+
+    for func_spec in change_set.funcs()? {
+        let key = ImportItemKey::for_func(&func_spec);
+        let outcome = if is_shared_func(kind, &func_spec)
+            && Func::find_by_name(ctx, func_spec.name()).await?.is_some()
+        {
+            (
+                ImportAction::Skip,
+                Some(ImportPreviewNote::AlreadyInstalled),
+            )
+        } else if options.is_excluded(&key) && funcs_in_use.contains(func_spec.unique_id()) {
+            excluded_func_outcome(
+                true,
+                Func::find_by_name(ctx, func_spec.name()).await?.is_some(),
+            )
+        } else {
+            change_set_outcome(&key, func_spec.deleted(), options, seen)
+        };
+        preview.push(key, func_spec.name(), change_set_name, outcome);
+    }
+
+    for schema_spec in change_set.schemas()? {
+        let key = ImportItemKey::for_schema(&schema_spec);
+        let outcome = change_set_outcome(&key, schema_spec.deleted(), options, seen);
+        preview.push(key, schema_spec.name(), change_set_name, outcome);
+
+        for variant_spec in schema_spec.variants()? {
+            let variant_key = ImportItemKey::for_variant(&schema_spec, &variant_spec);
+            let variant_outcome = if outcome.0 == ImportAction::Skip {
+                outcome
+            } else {
+                change_set_outcome(&variant_key, variant_spec.deleted(), options, seen)
+            };
+            preview.push(
+                variant_key,
+                variant_spec.name(),
+                change_set_name,
+                variant_outcome,
+            );
+        }
+    }
+
+    let mut excluded_component_unique_ids = HashSet::new();
+    for component_spec in change_set.components()? {
+        let key = ImportItemKey::for_component(&component_spec);
+        if options.is_excluded(&key) {
+            excluded_component_unique_ids.insert(component_spec.unique_id().to_owned());
+        }
+        let outcome = change_set_outcome(&key, component_spec.deleted(), options, seen);
+        preview.push(key, component_spec.name(), change_set_name, outcome);
+    }
+
+    for edge_spec in change_set.edges()? {
+        let key = ImportItemKey::for_edge(&edge_spec);
+        let outcome = if !options.is_excluded(&key)
+            && (excluded_component_unique_ids.contains(edge_spec.from_component_unique_id())
+                || excluded_component_unique_ids.contains(edge_spec.to_component_unique_id()))
+        {
+            (
+                ImportAction::Skip,
+                Some(ImportPreviewNote::EndpointExcluded),
+            )
+        } else {
+            change_set_outcome(&key, edge_spec.deleted(), options, seen)
+        };
+        preview.push(
+            key,
+            format!(
+                "{} -> {}",
+                edge_spec.from_socket_name(),
+                edge_spec.to_socket_name()
+            ),
+            change_set_name,
+            outcome,
+        );
+    }
+
+    Ok(())
+}
+
+/// The unique ids of the funcs used by the schema variants which will be imported from
+/// `schemas`, whether or not those variants are already installed.
+pub(super) async fn func_unique_ids_in_use(
+    schemas: &[SiPkgSchema<'_>],
+    options: &ImportOptions,
+) -> PkgResult<HashSet<String>> {
+    let mut func_unique_ids = HashSet::new();
+    for schema_spec in schemas {
+        let selected = options.schemas.as_ref().map_or(true, |schemas| {
+            schemas.contains(&schema_spec.name().to_lowercase())
+        });
+        if !selected || options.is_excluded(&ImportItemKey::for_schema(schema_spec)) {
+            continue;
+        }
+
+        for variant_spec in schema_spec.variants()? {
+            if variant_spec.deleted()
+                || options.is_excluded(&ImportItemKey::for_variant(schema_spec, &variant_spec))
+            {
+                continue;
+            }
+            let variant_spec = variant_spec.to_spec().await?;
+            func_unique_ids.extend(
+                variant_spec
+                    .func_unique_ids()
+                    .into_iter()
+                    .map(ToOwned::to_owned),
+            );
+        }
+    }
+
+    Ok(func_unique_ids)
+}
+
+/// An excluded func is left out of the import, unless schema variants being imported use it.
+/// Then the existing func with the same name is used in its place, and without one the import
+/// fails.
+fn excluded_func_outcome(
+    in_use: bool,
+    name_taken: bool,
+) -> (ImportAction, Option<ImportPreviewNote>) {
+    let note = match (in_use, name_taken) {
+        (false, _) => ImportPreviewNote::Excluded,
+        (true, true) => ImportPreviewNote::ReplacedByExistingFunc,
+        (true, false) => ImportPreviewNote::ExcludedFuncInUse,
+    };
+    (ImportAction::Skip, Some(note))
+}
+
+/// Items of a backup's change sets are updated if an earlier change set already restored them.
+fn change_set_outcome(
+    key: &ImportItemKey,
+    deleted: bool,
+    options: &ImportOptions,
+    seen: &mut HashSet<ImportItemKey>,
+) -> (ImportAction, Option<ImportPreviewNote>) {
+    if options.is_excluded(key) {
+        (ImportAction::Skip, Some(ImportPreviewNote::Excluded))
+    } else if seen.contains(key) {
+        (
+            ImportAction::Update,
+            deleted.then_some(ImportPreviewNote::Deleted),
+        )
+    } else if deleted {
+        (ImportAction::Skip, Some(ImportPreviewNote::Deleted))
+    } else {
+        seen.insert(key.clone());
+        (ImportAction::Create, None)
+    }
+}
+
+async fn is_installed(
+    ctx: &DalContext,
+    kind: InstalledPkgAssetKind,
+    hash: object_tree::Hash,
+) -> PkgResult<bool> {
+    Ok(
+        !InstalledPkgAsset::list_for_kind_and_hash(ctx, kind, &hash.to_string())
+            .await?
+            .is_empty(),
+    )
+}
@@ -0,0 +1,936 @@
+use std::collections::HashMap;
+
+use module_index_client::IndexClient;
+use serde::{Deserialize, Serialize};
+use si_pkg::{PropSpec, SiPkg, SocketSpec, SocketSpecArity, SocketSpecKind};
+use telemetry::prelude::*;
+use ulid::Ulid;
+
+use crate::{
+    func::intrinsics::IntrinsicFunc,
+    installed_pkg::{InstalledPkg, InstalledPkgAsset, InstalledPkgAssetTyped},
+    AttributeContextBuilder, AttributeReadContext, AttributeValue, AttributeValueError,
+    AttributeView, Component, ComponentError, ComponentId, DalContext, Edge, Func, FuncError,
+    NodeId, Prop, PropKind, PropPath, SchemaVariantId, Socket, SocketId, StandardModel,
+};
+
+use super::{import::import_pkg_from_pkg, ImportOptions, PkgError, PkgResult};
+
+/// A change to a prop under a schema variant's `/root/domain` between two versions of a module.
+/// Paths are `/` separated and start at the root prop, e.g. `/root/domain/region`.
+///
+/// Only the topmost prop of an added or removed subtree is listed.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PropChange {
+    Added {
+        path: String,
+        prop_kind: PropKind,
+    },
+    KindChanged {
+        path: String,
+        from: PropKind,
+        to: PropKind,
+    },
+    /// A prop which was removed while a prop of the same kind was added under the same parent,
+    /// with no other prop of that kind added or removed there. It may have been renamed, or
+    /// replaced by an unrelated prop, so its values are only carried over to `to` if the caller
+    /// confirms the rename with a [`PropRename`]. Otherwise it is treated as removed.
+    PossiblyRenamed {
+        from: String,
+        to: String,
+        prop_kind: PropKind,
+    },
+    Removed {
+        path: String,
+        prop_kind: PropKind,
+    },
+}
+
+/// Confirms that a [`PropChange::PossiblyRenamed`] of a variant's plan is a rename, so that
+/// [`upgrade_pkg`] carries the values of the prop over to its new name.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PropRename {
+    pub schema_name: String,
+    pub variant_name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl PropRename {
+    fn confirms(&self, variant_plan: &VariantUpgradePlan, from: &str, to: &str) -> bool {
+        self.schema_name == variant_plan.schema_name
+            && self.variant_name == variant_plan.variant_name
+            && self.from == from
+            && self.to == to
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SocketChange {
+    Added {
+        name: String,
+        socket_kind: SocketSpecKind,
+    },
+    ArityChanged {
+        name: String,
+        socket_kind: SocketSpecKind,
+        from: SocketSpecArity,
+        to: SocketSpecArity,
+    },
+    Removed {
+        name: String,
+        socket_kind: SocketSpecKind,
+    },
+}
+
+/// A change to a func between two versions of a module. Funcs are matched by name.
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FuncChange {
+    Added { name: String },
+    Changed { name: String },
+    Removed { name: String },
+}
+
+/// How an installed schema variant changes in the new version of its module, and which
+/// components will be moved onto the new version of the variant.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantUpgradePlan {
+    pub schema_name: String,
+    pub variant_name: String,
+    /// The installed variant the components are moved off of.
+    pub schema_variant_id: SchemaVariantId,
+    pub prop_changes: Vec<PropChange>,
+    pub socket_changes: Vec<SocketChange>,
+    pub components: Vec<ComponentId>,
+    /// Components which are left on the installed variant: a resource can't be moved inside a
+    /// change set, and protected components can't be replaced.
+    pub blocked_components: Vec<ComponentId>,
+}
+
+/// The changes between an installed module and a newer version of it, and what upgrading to the
+/// newer version will do to the workspace.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradePlan {
+    pub name: String,
+    pub from_version: String,
+    pub to_version: String,
+    pub func_changes: Vec<FuncChange>,
+    /// Variants which differ between the two versions. Variants which are identical are reused
+    /// by the new version as they are, so their components need no migration.
+    pub variants: Vec<VariantUpgradePlan>,
+    /// Installed variants which the new version no longer contains. Their components stay on
+    /// them.
+    pub removed_variants: Vec<SchemaVariantId>,
+}
+
+/// A component which was replaced by a component of the new version of its variant.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MigratedComponent {
+    pub from: ComponentId,
+    pub to: ComponentId,
+}
+
+/// Compares `installed_pkg`, which must be installed in the workspace, to `new_pkg`, a newer
+/// version of the same module, without changing anything.
+pub async fn plan_upgrade(
+    ctx: &DalContext,
+    installed_pkg: &SiPkg,
+    new_pkg: &SiPkg,
+) -> PkgResult<UpgradePlan> {
+    let installed_metadata = installed_pkg.metadata()?;
+    let new_metadata = new_pkg.metadata()?;
+    if installed_metadata.name() != new_metadata.name() {
+        return Err(PkgError::UpgradeModuleNameMismatch(
+            installed_metadata.name().to_owned(),
+            new_metadata.name().to_owned(),
+        ));
+    }
+
+    let installed_hash = installed_pkg.hash()?.to_string();
+    let installed_record = InstalledPkg::find_by_hash(ctx, &installed_hash)
+        .await?
+        .ok_or(PkgError::UpgradeFromModuleNotInstalled(installed_hash))?;
+
+    let new_hash = new_pkg.hash()?.to_string();
+    if InstalledPkg::find_by_hash(ctx, &new_hash).await?.is_some() {
+        return Err(PkgError::PackageAlreadyInstalled(new_hash));
+    }
+
+    let installed_variant_ids: HashMap<String, SchemaVariantId> =
+        InstalledPkgAsset::list_for_installed_pkg_id(ctx, *installed_record.id())
+            .await?
+            .iter()
+            .filter_map(|asset| match asset.as_installed_schema_variant().ok()? {
+                InstalledPkgAssetTyped::SchemaVariant { id, hash, .. } => Some((hash, id)),
+                _ => None,
+            })
+            .collect();
+
+    let mut new_variants = HashMap::new();
+    for schema in new_pkg.schemas()? {
+        for variant in schema.variants()? {
+            new_variants.insert(
+                (schema.name().to_owned(), variant.name().to_owned()),
+                variant,
+            );
+        }
+    }
+
+    let mut variants = vec![];
+    let mut removed_variants = vec![];
+
+    for schema in installed_pkg.schemas()? {
+        for variant in schema.variants()? {
+            let schema_variant_id = match installed_variant_ids.get(&variant.hash().to_string()) {
+                Some(schema_variant_id) => *schema_variant_id,
+                None => continue,
+            };
+
+            let new_variant =
+                match new_variants.get(&(schema.name().to_owned(), variant.name().to_owned())) {
+                    Some(new_variant) => new_variant,
+                    None => {
+                        removed_variants.push(schema_variant_id);
+                        continue;
+                    }
+                };
+            if new_variant.hash() == variant.hash() {
+                continue;
+            }
+
+            let installed_spec = variant.to_spec().await?;
+            let new_spec = new_variant.to_spec().await?;
+
+            let mut prop_changes = vec![];
+            diff_props(
+                &["root".to_owned()],
+                &[&installed_spec.domain],
+                &[&new_spec.domain],
+                &mut prop_changes,
+            );
+
+            let mut components = vec![];
+            let mut blocked_components = vec![];
+            for component in Component::list_for_schema_variant(ctx, schema_variant_id).await? {
+                if component.resource(ctx).await?.payload.is_some()
+                    || component.get_protected(ctx).await?
+                {
+                    blocked_components.push(*component.id());
+                } else {
+                    components.push(*component.id());
+                }
+            }
+
+            variants.push(VariantUpgradePlan {
+                schema_name: schema.name().to_owned(),
+                variant_name: variant.name().to_owned(),
+                schema_variant_id,
+                prop_changes,
+                socket_changes: diff_sockets(&installed_spec.sockets, &new_spec.sockets),
+                components,
+                blocked_components,
+            });
+        }
+    }
+
+    Ok(UpgradePlan {
+        name: installed_metadata.name().to_owned(),
+        from_version: installed_metadata.version().to_owned(),
+        to_version: new_metadata.version().to_owned(),
+        func_changes: diff_funcs(installed_pkg, new_pkg)?,
+        variants,
+        removed_variants,
+    })
+}
+
+/// Installs `new_pkg` and moves the components of the installed variants which changed onto the
+/// new versions of those variants, carrying over the values which were set on them by hand (see
+/// [`plan_upgrade`]). Values of removed props, and of props whose kind changed, are dropped, as
+/// are those of possibly renamed props unless the rename is one of `confirmed_renames`.
+///
+/// Each migrated component is replaced by a new component, which takes over its edges wherever
+/// the new variant still has a socket of the same name. This must happen in a change set.
+///
+/// Returns the plan which was carried out, the schema variants installed by `new_pkg`, and the
+/// components which were migrated.
+pub async fn upgrade_pkg(
+    ctx: &DalContext,
+    installed_pkg: &SiPkg,
+    new_pkg: &SiPkg,
+    options: Option<ImportOptions>,
+    confirmed_renames: &[PropRename],
+) -> PkgResult<(UpgradePlan, Vec<SchemaVariantId>, Vec<MigratedComponent>)> {
+    if ctx.visibility().is_head() {
+        return Err(PkgError::UpgradeInHead);
+    }
+
+    let plan = plan_upgrade(ctx, installed_pkg, new_pkg).await?;
+
+    for rename in confirmed_renames {
+        let possible = plan.variants.iter().any(|variant_plan| {
+            variant_plan.prop_changes.iter().any(|change| match change {
+                PropChange::PossiblyRenamed { from, to, .. } => {
+                    rename.confirms(variant_plan, from, to)
+                }
+                _ => false,
+            })
+        });
+        if !possible {
+            return Err(PkgError::UpgradeRenameNotPossible(
+                rename.from.to_owned(),
+                rename.to.to_owned(),
+            ));
+        }
+    }
+
+    // The installed record of the new version is how its variants are found below
+    let options = ImportOptions {
+        no_record: false,
+        ..options.unwrap_or_default()
+    };
+    let (_, schema_variant_ids, _) = import_pkg_from_pkg(ctx, new_pkg, Some(options)).await?;
+
+    let new_hash = new_pkg.hash()?.to_string();
+    let new_record = InstalledPkg::find_by_hash(ctx, &new_hash)
+        .await?
+        .ok_or(PkgError::UpgradeFromModuleNotInstalled(new_hash))?;
+    let new_variant_ids: HashMap<String, SchemaVariantId> =
+        InstalledPkgAsset::list_for_installed_pkg_id(ctx, *new_record.id())
+            .await?
+            .iter()
+            .filter_map(|asset| match asset.as_installed_schema_variant().ok()? {
+                InstalledPkgAssetTyped::SchemaVariant { id, hash, .. } => Some((hash, id)),
+                _ => None,
+            })
+            .collect();
+
+    let mut installed_specs = HashMap::new();
+    for schema in installed_pkg.schemas()? {
+        for variant in schema.variants()? {
+            installed_specs.insert(
+                (schema.name().to_owned(), variant.name().to_owned()),
+                variant.to_spec().await?,
+            );
+        }
+    }
+
+    let mut migrated = vec![];
+    for schema in new_pkg.schemas()? {
+        for variant in schema.variants()? {
+            let variant_plan = match plan.variants.iter().find(|variant_plan| {
+                variant_plan.schema_name == schema.name()
+                    && variant_plan.variant_name == variant.name()
+            }) {
+                Some(variant_plan) => variant_plan,
+                None => continue,
+            };
+            let new_variant_id = *new_variant_ids.get(&variant.hash().to_string()).ok_or(
+                PkgError::SchemaVariantNotFoundForUpgrade(
+                    schema.name().to_owned(),
+                    variant.name().to_owned(),
+                ),
+            )?;
+            let installed_domain = &installed_specs
+                .get(&(schema.name().to_owned(), variant.name().to_owned()))
+                .ok_or(PkgError::SchemaVariantNotFoundForUpgrade(
+                    schema.name().to_owned(),
+                    variant.name().to_owned(),
+                ))?
+                .domain;
+
+            let variant_renames: Vec<&PropRename> = confirmed_renames
+                .iter()
+                .filter(|rename| {
+                    rename.schema_name == variant_plan.schema_name
+                        && rename.variant_name == variant_plan.variant_name
+                })
+                .collect();
+
+            for component_id in &variant_plan.components {
+                let to = migrate_component(
+                    ctx,
+                    *component_id,
+                    variant_plan,
+                    &variant_renames,
+                    installed_domain,
+                    new_variant_id,
+                )
+                .await?;
+
+                migrated.push(MigratedComponent {
+                    from: *component_id,
+                    to,
+                });
+            }
+        }
+    }
+
+    Ok((plan, schema_variant_ids, migrated))
+}
+
+/// Finds the installed version of the module named `name` in the module index, so that it can be
+/// compared to a newer version. If several versions are installed, the most recently installed
+/// one is used.
+pub async fn fetch_installed_pkg(
+    ctx: &DalContext,
+    module_index: &IndexClient,
+    name: &str,
+) -> PkgResult<SiPkg> {
+    let installed_record = InstalledPkg::find_by_name(ctx, name)
+        .await?
+        .into_iter()
+        .max_by_key(|installed_pkg| installed_pkg.timestamp().created_at)
+        .ok_or_else(|| PkgError::UpgradeFromModuleNotInstalled(name.to_owned()))?;
+
+    let published = module_index
+        .list_published_versions(name)
+        .await?
+        .into_iter()
+        .find(|published| published.hash == installed_record.root_hash())
+        .ok_or_else(|| {
+            PkgError::UpgradeInstalledModuleNotInModuleIndex(
+                name.to_owned(),
+                installed_record.root_hash().to_owned(),
+            )
+        })?;
+
+    Ok(SiPkg::load_from_bytes(
+        module_index
+            .download_module(Ulid::from_string(&published.module_id)?)
+            .await?,
+    )?)
+}
+
+/// Replaces a component by a component of the new variant, returning the id of the new
+/// component.
+async fn migrate_component(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    variant_plan: &VariantUpgradePlan,
+    confirmed_renames: &[&PropRename],
+    installed_domain: &PropSpec,
+    new_variant_id: SchemaVariantId,
+) -> PkgResult<ComponentId> {
+    let mut component = Component::get_by_id(ctx, &component_id)
+        .await?
+        .ok_or(ComponentError::NotFound(component_id))?;
+    let node = component
+        .node(ctx)
+        .await?
+        .pop()
+        .ok_or(PkgError::ComponentMissingNode(component_id))?;
+
+    let (new_component, mut new_node) =
+        Component::new(ctx, component.name(ctx).await?, new_variant_id).await?;
+    new_node
+        .set_geometry(ctx, node.x(), node.y(), node.width(), node.height())
+        .await?;
+    new_component
+        .set_type(ctx, component.get_type(ctx).await?)
+        .await?;
+
+    let mut leaves = vec![];
+    collect_migratable_props(&["root".to_owned()], installed_domain, &mut leaves);
+
+    for path in leaves {
+        let new_path = match migrated_path(&path, &variant_plan.prop_changes, confirmed_renames) {
+            Some(new_path) => new_path,
+            None => continue,
+        };
+
+        if let Some(value) =
+            value_set_on_component(ctx, component_id, variant_plan.schema_variant_id, &path).await?
+        {
+            set_value_on_component(ctx, *new_component.id(), new_variant_id, &new_path, value)
+                .await?;
+        }
+    }
+
+    for mut edge in Edge::list_for_component(ctx, component_id).await? {
+        let (head_node_id, head_socket_id) = if edge.head_node_id() == *node.id() {
+            match migrated_socket_id(ctx, edge.head_socket_id(), *new_node.id()).await? {
+                Some(socket_id) => (*new_node.id(), socket_id),
+                None => {
+                    edge.delete_and_propagate(ctx).await?;
+                    continue;
+                }
+            }
+        } else {
+            (edge.head_node_id(), edge.head_socket_id())
+        };
+        let (tail_node_id, tail_socket_id) = if edge.tail_node_id() == *node.id() {
+            match migrated_socket_id(ctx, edge.tail_socket_id(), *new_node.id()).await? {
+                Some(socket_id) => (*new_node.id(), socket_id),
+                None => {
+                    edge.delete_and_propagate(ctx).await?;
+                    continue;
+                }
+            }
+        } else {
+            (edge.tail_node_id(), edge.tail_socket_id())
+        };
+
+        // Removing the edge first releases frames, which can't be deleted with children attached
+        edge.delete_and_propagate(ctx).await?;
+        Edge::new_for_connection(
+            ctx,
+            head_node_id,
+            head_socket_id,
+            tail_node_id,
+            tail_socket_id,
+            edge.kind().to_owned(),
+        )
+        .await?;
+    }
+
+    component.delete_and_propagate(ctx).await?;
+
+    info!(from = %component_id, to = %new_component.id(), "migrated component to upgraded variant");
+
+    Ok(*new_component.id())
+}
+
+/// Finds the socket of the new node with the same name and edge kind as `socket_id`.
+async fn migrated_socket_id(
+    ctx: &DalContext,
+    socket_id: SocketId,
+    new_node_id: NodeId,
+) -> PkgResult<Option<SocketId>> {
+    let socket = Socket::get_by_id(ctx, &socket_id)
+        .await?
+        .ok_or(PkgError::MissingSocket(socket_id))?;
+
+    Ok(Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        socket.name(),
+        *socket.edge_kind(),
+        new_node_id,
+    )
+    .await?
+    .map(|socket| *socket.id()))
+}
+
+/// Returns the value of the prop at `path` if it was set on the component by hand, rather than
+/// inherited from the variant or computed by a function.
+async fn value_set_on_component(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    schema_variant_id: SchemaVariantId,
+    path: &[String],
+) -> PkgResult<Option<serde_json::Value>> {
+    let prop =
+        match Prop::find_prop_by_path_opt(ctx, schema_variant_id, &PropPath::new(path)).await? {
+            Some(prop) => prop,
+            None => return Ok(None),
+        };
+
+    let read_context = AttributeReadContext {
+        prop_id: Some(*prop.id()),
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = match AttributeValue::find_for_context(ctx, read_context).await? {
+        Some(attribute_value) if !attribute_value.context.is_component_unset() => attribute_value,
+        _ => return Ok(None),
+    };
+
+    let prototype = attribute_value
+        .attribute_prototype(ctx)
+        .await?
+        .ok_or(AttributeValueError::MissingAttributePrototype)?;
+    let func = Func::get_by_id(ctx, &prototype.func_id())
+        .await?
+        .ok_or(FuncError::NotFound(prototype.func_id()))?;
+    match IntrinsicFunc::maybe_from_str(func.name()) {
+        Some(
+            IntrinsicFunc::SetArray
+            | IntrinsicFunc::SetBoolean
+            | IntrinsicFunc::SetInteger
+            | IntrinsicFunc::SetMap
+            | IntrinsicFunc::SetString,
+        ) => {}
+        _ => return Ok(None),
+    }
+
+    let view = AttributeView::new(ctx, read_context, Some(*attribute_value.id())).await?;
+
+    Ok(match view.value() {
+        serde_json::Value::Null => None,
+        value => Some(value.to_owned()),
+    })
+}
+
+async fn set_value_on_component(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    schema_variant_id: SchemaVariantId,
+    path: &[String],
+    value: serde_json::Value,
+) -> PkgResult<()> {
+    let prop =
+        match Prop::find_prop_by_path_opt(ctx, schema_variant_id, &PropPath::new(path)).await? {
+            Some(prop) => prop,
+            None => return Ok(()),
+        };
+
+    let read_context = AttributeReadContext {
+        prop_id: Some(*prop.id()),
+        component_id: Some(component_id),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await?
+        .ok_or(AttributeValueError::Missing)?;
+    let parent_attribute_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await?
+        .ok_or_else(|| AttributeValueError::ParentNotFound(*attribute_value.id()))?;
+
+    let context = AttributeContextBuilder::from(attribute_value.context)
+        .set_component_id(component_id)
+        .to_context()?;
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_attribute_value.id()),
+        context,
+        Some(value),
+        None,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Collects the paths of the props under `prop` which can be carried over to a new variant:
+/// every prop reachable through objects, other than the objects themselves. Maps and arrays are
+/// carried over whole.
+fn collect_migratable_props(parent_path: &[String], prop: &PropSpec, paths: &mut Vec<Vec<String>>) {
+    let mut path = parent_path.to_vec();
+    path.push(prop_spec_name(prop).to_owned());
+
+    match prop {
+        PropSpec::Object { entries, .. } => {
+            for entry in entries {
+                collect_migratable_props(&path, entry, paths);
+            }
+        }
+        _ => paths.push(path),
+    }
+}
+
+/// Where the value of the prop at `path` goes in the new variant, or `None` if it is dropped.
+fn migrated_path(
+    path: &[String],
+    prop_changes: &[PropChange],
+    confirmed_renames: &[&PropRename],
+) -> Option<Vec<String>> {
+    let mut path = path.to_vec();
+
+    for change in prop_changes {
+        match change {
+            PropChange::Removed { path: removed, .. }
+            | PropChange::KindChanged { path: removed, .. } => {
+                if path.starts_with(&split_path(removed)) {
+                    return None;
+                }
+            }
+            PropChange::PossiblyRenamed { from, to, .. } => {
+                let confirmed = confirmed_renames
+                    .iter()
+                    .any(|rename| rename.from == *from && rename.to == *to);
+                let from = split_path(from);
+                if path.starts_with(&from) {
+                    if !confirmed {
+                        return None;
+                    }
+                    let mut renamed = split_path(to);
+                    renamed.extend_from_slice(&path[from.len()..]);
+                    path = renamed;
+                }
+            }
+            PropChange::Added { .. } => {}
+        }
+    }
+
+    Some(path)
+}
+
+fn join_path(path: &[String]) -> String {
+    format!("/{}", path.join("/"))
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.trim_start_matches('/')
+        .split('/')
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Compares the children of a prop which exists in both versions, matching them by name. Props
+/// which only exist on one side are paired up as possible renames where that is unambiguous.
+fn diff_props(
+    parent_path: &[String],
+    installed: &[&PropSpec],
+    new: &[&PropSpec],
+    changes: &mut Vec<PropChange>,
+) {
+    let child_path = |prop: &PropSpec| {
+        let mut path = parent_path.to_vec();
+        path.push(prop_spec_name(prop).to_owned());
+        path
+    };
+
+    let mut removed = vec![];
+    let mut matched = vec![];
+    for installed_prop in installed {
+        match new
+            .iter()
+            .find(|new_prop| prop_spec_name(new_prop) == prop_spec_name(installed_prop))
+        {
+            Some(new_prop) => matched.push((*installed_prop, *new_prop)),
+            None => removed.push(*installed_prop),
+        }
+    }
+    let mut added: Vec<&PropSpec> = new
+        .iter()
+        .filter(|new_prop| {
+            !installed
+                .iter()
+                .any(|installed_prop| prop_spec_name(installed_prop) == prop_spec_name(new_prop))
+        })
+        .copied()
+        .collect();
+
+    removed.retain(|removed_prop| {
+        let kind = prop_spec_kind(removed_prop);
+        let same_kind_removed = installed
+            .iter()
+            .filter(|prop| prop_spec_kind(prop) == kind)
+            .filter(|prop| {
+                !new.iter()
+                    .any(|new_prop| prop_spec_name(new_prop) == prop_spec_name(prop))
+            })
+            .count();
+        let same_kind_added: Vec<usize> = added
+            .iter()
+            .enumerate()
+            .filter(|(_, prop)| prop_spec_kind(prop) == kind)
+            .map(|(index, _)| index)
+            .collect();
+
+        if same_kind_removed == 1 && same_kind_added.len() == 1 {
+            let renamed_to = added.remove(same_kind_added[0]);
+            changes.push(PropChange::PossiblyRenamed {
+                from: join_path(&child_path(removed_prop)),
+                to: join_path(&child_path(renamed_to)),
+                prop_kind: kind,
+            });
+            matched.push((*removed_prop, renamed_to));
+            false
+        } else {
+            true
+        }
+    });
+
+    for prop in removed {
+        changes.push(PropChange::Removed {
+            path: join_path(&child_path(prop)),
+            prop_kind: prop_spec_kind(prop),
+        });
+    }
+    for prop in added {
+        changes.push(PropChange::Added {
+            path: join_path(&child_path(prop)),
+            prop_kind: prop_spec_kind(prop),
+        });
+    }
+
+    for (installed_prop, new_prop) in matched {
+        let (from, to) = (prop_spec_kind(installed_prop), prop_spec_kind(new_prop));
+        if from != to {
+            changes.push(PropChange::KindChanged {
+                path: join_path(&child_path(new_prop)),
+                from,
+                to,
+            });
+            continue;
+        }
+
+        diff_props(
+            &child_path(new_prop),
+            &prop_spec_children(installed_prop),
+            &prop_spec_children(new_prop),
+            changes,
+        );
+    }
+}
+
+fn diff_sockets(installed: &[SocketSpec], new: &[SocketSpec]) -> Vec<SocketChange> {
+    let kind_and_arity = |socket: &SocketSpec| {
+        socket
+            .data
+            .as_ref()
+            .map(|data| (data.kind, data.arity))
+            .unwrap_or((SocketSpecKind::Input, SocketSpecArity::Many))
+    };
+
+    let mut changes = vec![];
+
+    for installed_socket in installed {
+        let (socket_kind, from) = kind_and_arity(installed_socket);
+        match new.iter().find(|new_socket| {
+            new_socket.name == installed_socket.name && kind_and_arity(new_socket).0 == socket_kind
+        }) {
+            Some(new_socket) => {
+                let (_, to) = kind_and_arity(new_socket);
+                if from != to {
+                    changes.push(SocketChange::ArityChanged {
+                        name: installed_socket.name.to_owned(),
+                        socket_kind,
+                        from,
+                        to,
+                    });
+                }
+            }
+            None => changes.push(SocketChange::Removed {
+                name: installed_socket.name.to_owned(),
+                socket_kind,
+            }),
+        }
+    }
+
+    for new_socket in new {
+        let (socket_kind, _) = kind_and_arity(new_socket);
+        if !installed.iter().any(|installed_socket| {
+            installed_socket.name == new_socket.name
+                && kind_and_arity(installed_socket).0 == socket_kind
+        }) {
+            changes.push(SocketChange::Added {
+                name: new_socket.name.to_owned(),
+                socket_kind,
+            });
+        }
+    }
+
+    changes
+}
+
+fn diff_funcs(installed_pkg: &SiPkg, new_pkg: &SiPkg) -> PkgResult<Vec<FuncChange>> {
+    let installed_funcs: HashMap<String, String> = installed_pkg
+        .funcs()?
+        .iter()
+        .map(|func| (func.name().to_owned(), func.hash().to_string()))
+        .collect();
+    let new_funcs: HashMap<String, String> = new_pkg
+        .funcs()?
+        .iter()
+        .map(|func| (func.name().to_owned(), func.hash().to_string()))
+        .collect();
+
+    let mut changes = vec![];
+    for (name, hash) in &installed_funcs {
+        match new_funcs.get(name) {
+            Some(new_hash) if new_hash == hash => {}
+            Some(_) => changes.push(FuncChange::Changed {
+                name: name.to_owned(),
+            }),
+            None => changes.push(FuncChange::Removed {
+                name: name.to_owned(),
+            }),
+        }
+    }
+    for name in new_funcs.keys() {
+        if !installed_funcs.contains_key(name) {
+            changes.push(FuncChange::Added {
+                name: name.to_owned(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| func_change_name(a).cmp(func_change_name(b)));
+
+    Ok(changes)
+}
+
+fn func_change_name(change: &FuncChange) -> &str {
+    match change {
+        FuncChange::Added { name }
+        | FuncChange::Changed { name }
+        | FuncChange::Removed { name } => name,
+    }
+}
+
+fn prop_spec_name(prop: &PropSpec) -> &str {
+    match prop {
+        PropSpec::Array { name, .. }
+        | PropSpec::Boolean { name, .. }
+        | PropSpec::Map { name, .. }
+        | PropSpec::Number { name, .. }
+        | PropSpec::Object { name, .. }
+        | PropSpec::String { name, .. } => name,
+    }
+}
+
+fn prop_spec_kind(prop: &PropSpec) -> PropKind {
+    match prop {
+        PropSpec::Array { .. } => PropKind::Array,
+        PropSpec::Boolean { .. } => PropKind::Boolean,
+        PropSpec::Map { .. } => PropKind::Map,
+        PropSpec::Number { .. } => PropKind::Integer,
+        PropSpec::Object { .. } => PropKind::Object,
+        PropSpec::String { .. } => PropKind::String,
+    }
+}
+
+fn prop_spec_children(prop: &PropSpec) -> Vec<&PropSpec> {
+    match prop {
+        PropSpec::Object { entries, .. } => entries.iter().collect(),
+        PropSpec::Array { type_prop, .. } | PropSpec::Map { type_prop, .. } => {
+            vec![type_prop.as_ref()]
+        }
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn possible_renames_are_only_followed_once_confirmed() {
+        let prop_changes = vec![PropChange::PossiblyRenamed {
+            from: "/root/domain/serial_number".to_owned(),
+            to: "/root/domain/serial".to_owned(),
+            prop_kind: PropKind::String,
+        }];
+        let rename = PropRename {
+            schema_name: "Rocket".to_owned(),
+            variant_name: "v0".to_owned(),
+            from: "/root/domain/serial_number".to_owned(),
+            to: "/root/domain/serial".to_owned(),
+        };
+
+        let renamed_path = split_path("/root/domain/serial_number");
+        assert_eq!(None, migrated_path(&renamed_path, &prop_changes, &[]));
+        assert_eq!(
+            Some(split_path("/root/domain/serial")),
+            migrated_path(&renamed_path, &prop_changes, &[&rename])
+        );
+
+        let unchanged_path = split_path("/root/domain/payload");
+        assert_eq!(
+            Some(unchanged_path.clone()),
+            migrated_path(&unchanged_path, &prop_changes, &[])
+        );
+    }
+}
@@ -0,0 +1,5 @@
+UPDATE api_tokens
+SET last_used_at = CLOCK_TIMESTAMP()
+WHERE token_hash = $1
+  AND revoked_at IS NULL
+RETURNING row_to_json(api_tokens.*) AS object;
@@ -0,0 +1,4 @@
+SELECT row_to_json(api_tokens.*) AS object
+FROM api_tokens
+WHERE in_tenancy_v1($1, api_tokens.tenancy_workspace_pk)
+ORDER BY created_at DESC;
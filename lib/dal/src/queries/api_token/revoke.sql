@@ -0,0 +1,7 @@
+UPDATE api_tokens
+SET revoked_at = CLOCK_TIMESTAMP(),
+    updated_at = CLOCK_TIMESTAMP()
+WHERE in_tenancy_v1($1, api_tokens.tenancy_workspace_pk)
+  AND pk = $2
+  AND revoked_at IS NULL
+RETURNING row_to_json(api_tokens.*) AS object;
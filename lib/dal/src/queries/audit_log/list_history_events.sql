@@ -0,0 +1,6 @@
+SELECT row_to_json(history_events.*) AS object
+FROM history_events
+WHERE history_events.tenancy_workspace_pk = $1
+  AND ($2::timestamptz IS NULL OR history_events.created_at >= $2)
+  AND ($3::timestamptz IS NULL OR history_events.created_at < $3)
+ORDER BY created_at, pk;
@@ -0,0 +1,10 @@
+SELECT row_to_json(secret_usages.*) AS object
+FROM secret_usages
+WHERE secret_usages.tenancy_workspace_pk = $1
+  AND ($2::timestamptz IS NULL OR secret_usages.created_at >= $2)
+  AND ($3::timestamptz IS NULL OR secret_usages.created_at < $3)
+  AND ($4::timestamptz IS NULL
+    OR secret_usages.created_at > $4
+    OR (secret_usages.created_at = $4 AND $5::bool AND ($6::ident IS NULL OR secret_usages.pk > $6)))
+ORDER BY created_at, pk
+LIMIT $7;
@@ -1,7 +1,6 @@
 select distinct u.email
 FROM history_events ha
-    INNER JOIN users u ON u.pk = (ha.actor->>'User')::ident
+    INNER JOIN users u
+        ON u.pk = COALESCE(ha.actor->>'User', ha.actor->'ApiToken'->>'user_pk')::ident
 WHERE ha.tenancy_workspace_pk = $1
     AND ha.data::jsonb -> 'visibility' ->> 'visibility_change_set_pk' = $2
-
-
@@ -0,0 +1,5 @@
+SELECT fix_batches.pk
+FROM fix_batches
+WHERE fix_batches.id = $2
+  AND in_tenancy_v1($1, fix_batches.tenancy_workspace_pk)
+FOR UPDATE
@@ -0,0 +1,2 @@
+DELETE FROM job_dead_letters
+WHERE pk = $1;
@@ -0,0 +1,4 @@
+SELECT row_to_json(job_dead_letters.*) AS object
+FROM job_dead_letters
+WHERE pk = $1
+  AND in_tenancy_v1($2, job_dead_letters.tenancy_workspace_pk);
@@ -0,0 +1,4 @@
+SELECT row_to_json(job_dead_letters.*) AS object
+FROM job_dead_letters
+WHERE in_tenancy_v1($1, job_dead_letters.tenancy_workspace_pk)
+ORDER BY created_at DESC;
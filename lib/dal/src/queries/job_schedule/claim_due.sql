@@ -0,0 +1,7 @@
+SELECT row_to_json(job_schedules.*) AS object
+FROM job_schedules
+WHERE next_run_at <= $1
+  AND ($3::ident IS NULL OR job_schedules.tenancy_workspace_pk = $3)
+ORDER BY next_run_at
+LIMIT $2
+FOR UPDATE SKIP LOCKED;
@@ -0,0 +1,2 @@
+DELETE FROM job_schedules
+WHERE pk = $1;
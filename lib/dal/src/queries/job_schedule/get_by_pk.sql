@@ -0,0 +1,4 @@
+SELECT row_to_json(job_schedules.*) AS object
+FROM job_schedules
+WHERE pk = $1
+  AND in_tenancy_v1($2, job_schedules.tenancy_workspace_pk);
@@ -0,0 +1,4 @@
+SELECT row_to_json(job_schedules.*) AS object
+FROM job_schedules
+WHERE in_tenancy_v1($1, job_schedules.tenancy_workspace_pk)
+ORDER BY next_run_at;
@@ -0,0 +1,3 @@
+UPDATE job_schedules
+SET next_run_at = $2, last_run_at = $3, last_claimed_by = $4, updated_at = CLOCK_TIMESTAMP()
+WHERE pk = $1;
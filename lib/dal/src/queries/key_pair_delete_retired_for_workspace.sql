@@ -0,0 +1,6 @@
+UPDATE key_pairs
+SET visibility_deleted_at = CLOCK_TIMESTAMP(),
+    updated_at            = CLOCK_TIMESTAMP()
+WHERE key_pairs.workspace_pk = $1
+  AND key_pairs.retired_at IS NOT NULL
+  AND key_pairs.visibility_deleted_at IS NULL
@@ -0,0 +1,4 @@
+SELECT row_to_json(key_pairs.*) AS object
+FROM key_pairs
+WHERE key_pairs.pk = $1
+FOR SHARE
@@ -0,0 +1,5 @@
+SELECT key_pairs.pk
+FROM key_pairs
+WHERE key_pairs.workspace_pk = $1
+  AND key_pairs.visibility_deleted_at IS NULL
+FOR UPDATE
@@ -0,0 +1,6 @@
+UPDATE key_pairs
+SET secret_key_crypted  = $2,
+    secret_key_nonce    = $3,
+    secret_key_key_hash = $4,
+    updated_at          = CLOCK_TIMESTAMP()
+WHERE key_pairs.pk = $1
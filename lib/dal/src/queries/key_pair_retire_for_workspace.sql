@@ -0,0 +1,7 @@
+UPDATE key_pairs
+SET retired_at = CLOCK_TIMESTAMP(),
+    updated_at = CLOCK_TIMESTAMP()
+WHERE key_pairs.workspace_pk = $1
+  AND key_pairs.retired_at IS NULL
+  AND key_pairs.visibility_deleted_at IS NULL
+RETURNING key_pairs.pk
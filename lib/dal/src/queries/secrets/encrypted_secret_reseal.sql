@@ -0,0 +1,7 @@
+UPDATE encrypted_secrets
+SET crypted     = $2,
+    key_pair_pk = $3,
+    nonce       = $4,
+    key_hash    = $5,
+    updated_at  = CLOCK_TIMESTAMP()
+WHERE encrypted_secrets.pk = $1
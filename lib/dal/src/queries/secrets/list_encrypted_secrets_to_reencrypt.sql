@@ -0,0 +1,4 @@
+SELECT row_to_json(encrypted_secrets.*) AS object
+FROM encrypted_secrets
+WHERE encrypted_secrets.key_hash != $1
+ORDER BY encrypted_secrets.pk
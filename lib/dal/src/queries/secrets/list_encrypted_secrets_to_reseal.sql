@@ -0,0 +1,5 @@
+SELECT row_to_json(encrypted_secrets.*) AS object
+FROM encrypted_secrets
+WHERE encrypted_secrets.tenancy_workspace_pk = $1
+  AND encrypted_secrets.key_pair_pk != $2
+ORDER BY encrypted_secrets.pk
@@ -0,0 +1,8 @@
+SELECT row_to_json(secrets.*) AS object
+FROM (SELECT DISTINCT ON (id) secrets.*
+      FROM secrets
+      WHERE is_visible_v1($1, visibility_change_set_pk, visibility_deleted_at)
+      ORDER BY id, visibility_change_set_pk DESC, visibility_deleted_at DESC NULLS FIRST) AS secrets
+WHERE secrets.expires_at <= $2
+   OR secrets.rotate_by <= $2
+ORDER BY secrets.tenancy_workspace_pk, secrets.id;
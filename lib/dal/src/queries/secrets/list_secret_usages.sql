@@ -0,0 +1,7 @@
+SELECT row_to_json(secret_usages.*) AS object
+FROM secret_usages
+WHERE in_tenancy_v1($1, secret_usages.tenancy_workspace_pk)
+  AND secret_usages.secret_id = $2
+  AND ($3::timestamptz IS NULL OR secret_usages.created_at >= $3)
+  AND ($4::timestamptz IS NULL OR secret_usages.created_at < $4)
+ORDER BY created_at DESC;
@@ -0,0 +1,11 @@
+SELECT DISTINCT ON (secret.id) row_to_json(secret.*) AS object
+FROM props_v1($1, $2) secret_prop
+         JOIN attribute_values_v1($1, $2) av
+              ON av.attribute_context_prop_id = secret_prop.id
+                  AND av.attribute_context_component_id = $3
+         JOIN func_binding_return_values_v1($1, $2) fbrv
+              ON av.func_binding_return_value_id = fbrv.id AND fbrv.value IS NOT NULL
+    -- The value of a secret prop is the id of the secret, as a json string
+         JOIN secrets_v1($1, $2) secret ON secret.id = (fbrv.value #>> '{}')::ident
+WHERE secret_prop.path LIKE 'rootsecrets%'
+ORDER BY secret.id;
@@ -0,0 +1,8 @@
+SELECT key_hash, count(*) AS references
+FROM (SELECT key_pairs.secret_key_key_hash AS key_hash
+      FROM key_pairs
+      UNION ALL
+      SELECT encrypted_secrets.key_hash AS key_hash
+      FROM encrypted_secrets) AS symmetric_key_hashes
+GROUP BY key_hash
+ORDER BY key_hash
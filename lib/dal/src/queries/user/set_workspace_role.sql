@@ -0,0 +1,5 @@
+UPDATE user_belongs_to_workspaces
+SET role       = $3,
+    updated_at = CLOCK_TIMESTAMP()
+WHERE user_pk = $1
+  AND workspace_pk = $2
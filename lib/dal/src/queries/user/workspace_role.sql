@@ -0,0 +1,4 @@
+SELECT bt.role AS role
+FROM user_belongs_to_workspaces AS bt
+WHERE bt.user_pk = $1
+  AND bt.workspace_pk = $2
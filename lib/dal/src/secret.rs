@@ -1,6 +1,7 @@
 use std::fmt;
 
 use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use si_crypto::{SymmetricCryptoError, SymmetricCryptoService, SymmetricNonce};
@@ -12,7 +13,7 @@ use sodiumoxide::crypto::{
 use strum::{AsRefStr, Display, EnumString};
 use thiserror::Error;
 
-use si_data_pg::PgError;
+use si_data_pg::{PgError, PgPoolError};
 use telemetry::prelude::*;
 use veritech_client::SensitiveContainer;
 
@@ -29,7 +30,22 @@ use crate::{
     Tenancy, Timestamp, TransactionsError, UserPk, Visibility,
 };
 
+pub mod expiry;
+pub mod provider;
+pub mod usage;
+
+pub use expiry::{
+    SecretExpiryStatus, SecretExpiryWarning, SecretsExpiringPayload, SECRET_EXPIRY_WARNING_DAYS,
+};
+pub use provider::{SecretProviderError, SecretProviders, SecretProvidersConfig, SecretReference};
+pub use usage::{SecretUsage, SecretUsagePk, SecretUsageSource};
+
 const LIST_SECRET_DEFINITIONS: &str = include_str!("queries/secrets/list_secret_definitions.sql");
+const LIST_ENCRYPTED_SECRETS_TO_RESEAL: &str =
+    include_str!("queries/secrets/list_encrypted_secrets_to_reseal.sql");
+const ENCRYPTED_SECRET_RESEAL: &str = include_str!("queries/secrets/encrypted_secret_reseal.sql");
+const LIST_ENCRYPTED_SECRETS_TO_REENCRYPT: &str =
+    include_str!("queries/secrets/list_encrypted_secrets_to_reencrypt.sql");
 
 /// Error type for Secrets.
 #[remain::sorted]
@@ -41,14 +57,26 @@ pub enum SecretError {
     DeserializeMessage(#[source] serde_json::Error),
     #[error("history event error: {0}")]
     HistoryEvent(#[from] HistoryEventError),
+    #[error("error deserializing secret reference: {0}")]
+    InvalidReference(#[source] serde_json::Error),
     #[error("key pair error: {0}")]
     KeyPair(#[from] KeyPairError),
     #[error("key pair not found for secret")]
     KeyPairNotFound,
+    #[error("key pair {0} is retired and can no longer have secrets sealed to it")]
+    KeyPairRetired(KeyPairPk),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("pg pool error: {0}")]
+    PgPool(#[source] Box<PgPoolError>),
+    #[error("secret provider error: {0}")]
+    Provider(#[from] SecretProviderError),
+    #[error("reference secret {0} has no workspace to resolve it in")]
+    ReferenceWithoutWorkspace(SecretId),
     #[error("secret not found: {0}")]
     SecretNotFound(SecretId),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModelError(#[from] StandardModelError),
     #[error("symmetric crypto error: {0}")]
@@ -57,6 +85,12 @@ pub enum SecretError {
     Transactions(#[from] TransactionsError),
 }
 
+impl From<PgPoolError> for SecretError {
+    fn from(value: PgPoolError) -> Self {
+        Self::PgPool(Box::new(value))
+    }
+}
+
 /// Result type for Secrets.
 pub type SecretResult<T> = Result<T, SecretError>;
 
@@ -75,6 +109,9 @@ pub struct Secret {
     key_pair_pk: KeyPairPk,
     definition: String,
     description: Option<String>,
+    kind: SecretKind,
+    expires_at: Option<DateTime<Utc>>,
+    rotate_by: Option<DateTime<Utc>>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -130,6 +167,17 @@ impl Secret {
     // Once created, these object fields are to be considered immutable
     standard_model_accessor_ro!(definition, String);
     standard_model_accessor_ro!(description, Option<String>);
+    standard_model_accessor_ro!(kind, SecretKind);
+
+    /// When the secret's value stops working, if known.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    /// When the secret's value should be replaced by, if ever.
+    pub fn rotate_by(&self) -> Option<DateTime<Utc>> {
+        self.rotate_by
+    }
 
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
@@ -143,6 +191,10 @@ pub struct SecretView {
     pub name: String,
     pub definition: String,
     pub description: Option<String>,
+    pub kind: SecretKind,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub rotate_by: Option<DateTime<Utc>>,
+    pub expiry_status: SecretExpiryStatus,
     pub created_info: HistoryEventMetadata,
     pub updated_info: Option<HistoryEventMetadata>,
 }
@@ -181,11 +233,17 @@ impl SecretView {
             }
         };
 
+        let expiry_status = secret.expiry_status();
+
         Ok(Self {
             id: secret.id,
             name: secret.name,
             definition: secret.definition,
             description: secret.description,
+            kind: secret.kind,
+            expires_at: secret.expires_at,
+            rotate_by: secret.rotate_by,
+            expiry_status,
             created_info,
             updated_info,
         })
@@ -201,6 +259,9 @@ impl From<EncryptedSecret> for Secret {
             key_pair_pk: value.key_pair_pk,
             definition: value.definition,
             description: value.description,
+            kind: value.kind,
+            expires_at: value.expires_at,
+            rotate_by: value.rotate_by,
             tenancy: value.tenancy,
             timestamp: value.timestamp,
             created_by: value.created_by,
@@ -224,6 +285,9 @@ pub struct EncryptedSecret {
     name: String,
     definition: String,
     description: Option<String>,
+    kind: SecretKind,
+    expires_at: Option<DateTime<Utc>>,
+    rotate_by: Option<DateTime<Utc>>,
     key_pair_pk: KeyPairPk,
     #[serde(with = "nonce_serde")]
     nonce: SymmetricNonce,
@@ -250,6 +314,9 @@ impl fmt::Debug for EncryptedSecret {
             .field("name", &self.name)
             .field("definition", &self.definition)
             .field("description", &self.description)
+            .field("kind", &self.kind)
+            .field("expires_at", &self.expires_at)
+            .field("rotate_by", &self.rotate_by)
             .field("version", &self.version)
             .field("algorithm", &self.algorithm)
             .field("key_hash", &self.key_hash)
@@ -282,11 +349,73 @@ impl EncryptedSecret {
         version: SecretVersion,
         algorithm: SecretAlgorithm,
     ) -> SecretResult<Secret> {
-        let name = name.as_ref();
+        Self::create(
+            ctx,
+            name.as_ref(),
+            definition,
+            description,
+            SecretKind::Stored,
+            crypted,
+            key_pair_pk,
+            version,
+            algorithm,
+        )
+        .await
+    }
+
+    /// Creates a new secret whose value lives outside of our database and returns a
+    /// corresponding [`Secret`] representation. The `crypted` payload is a [`SecretReference`],
+    /// encrypted like the value of any other secret, which is resolved by the
+    /// [`SecretProviders`] when the secret is decrypted.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_reference(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        definition: String,
+        description: Option<String>,
+        crypted: &[u8],
+        key_pair_pk: KeyPairPk,
+        version: SecretVersion,
+        algorithm: SecretAlgorithm,
+    ) -> SecretResult<Secret> {
+        Self::create(
+            ctx,
+            name.as_ref(),
+            definition,
+            description,
+            SecretKind::Reference,
+            crypted,
+            key_pair_pk,
+            version,
+            algorithm,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create(
+        ctx: &DalContext,
+        name: &str,
+        definition: String,
+        description: Option<String>,
+        kind: SecretKind,
+        crypted: &[u8],
+        key_pair_pk: KeyPairPk,
+        version: SecretVersion,
+        algorithm: SecretAlgorithm,
+    ) -> SecretResult<Secret> {
+        // Holds a share lock on the key pair until the transaction ends, so a concurrent rotation
+        // can't retire it (and miss re-sealing this secret) between this check and the insert.
+        if KeyPair::get_by_pk_for_share(ctx, key_pair_pk)
+            .await?
+            .is_retired()
+        {
+            return Err(SecretError::KeyPairRetired(key_pair_pk));
+        }
 
         let maybe_actor = match ctx.history_actor() {
             HistoryActor::SystemInit => None,
-            HistoryActor::User(user_pk) => Some(user_pk),
+            HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => Some(user_pk),
         };
 
         let (double_crypted, nonce, key_hash) = ctx.symmetric_crypto_service().encrypt(crypted);
@@ -296,13 +425,14 @@ impl EncryptedSecret {
             .await?
             .pg()
             .query_one(
-                "SELECT object FROM encrypted_secret_create_v1($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                "SELECT object FROM encrypted_secret_create_v2($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)",
                 &[
                     ctx.tenancy(),
                     ctx.visibility(),
                     &name,
                     &definition,
                     &description,
+                    &kind.as_ref(),
                     &base64_encode_bytes(double_crypted.as_slice()),
                     &version.as_ref(),
                     &algorithm.as_ref(),
@@ -325,8 +455,61 @@ impl EncryptedSecret {
     standard_model_accessor!(updated_by, Option<Pk(UserPk)>, SecretResult);
     standard_model_accessor!(key_pair_pk, Pk(KeyPairPk), SecretResult);
 
-    // Once created, this object field is immutable
+    // Once created, these object fields are immutable
     standard_model_accessor_ro!(definition, String);
+    standard_model_accessor_ro!(kind, SecretKind);
+
+    /// Sets (or clears) when the secret expires and when it should be rotated by.
+    pub async fn set_expiry(
+        &mut self,
+        ctx: &DalContext,
+        expires_at: Option<DateTime<Utc>>,
+        rotate_by: Option<DateTime<Utc>>,
+    ) -> SecretResult<()> {
+        standard_model::update(
+            ctx,
+            "encrypted_secrets",
+            "expires_at",
+            self.id(),
+            &expires_at,
+            TypeHint::TimestampWithTimeZone,
+        )
+        .await?;
+        let updated_at = standard_model::update(
+            ctx,
+            "encrypted_secrets",
+            "rotate_by",
+            self.id(),
+            &rotate_by,
+            TypeHint::TimestampWithTimeZone,
+        )
+        .await?;
+
+        let _history_event = HistoryEvent::new(
+            ctx,
+            Self::history_event_label(vec!["updated"]),
+            Self::history_event_message("updated"),
+            &serde_json::json!({
+                "pk": self.pk,
+                "field": "expiry",
+                "value": {"expiresAt": &expires_at, "rotateBy": &rotate_by},
+            }),
+        )
+        .await?;
+        self.timestamp.updated_at = updated_at;
+        self.expires_at = expires_at;
+        self.rotate_by = rotate_by;
+
+        Ok(())
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn rotate_by(&self) -> Option<DateTime<Utc>> {
+        self.rotate_by
+    }
 
     pub async fn set_crypted(&mut self, ctx: &DalContext, value: Vec<u8>) -> SecretResult<()> {
         let (double_crypted, nonce, key_hash) = ctx.symmetric_crypto_service().encrypt(&value);
@@ -372,15 +555,50 @@ impl EncryptedSecret {
     }
 
     /// Decrypts the encrypted secret with its associated [`KeyPair`] and returns a
-    /// [`DecryptedSecret`].
-    pub async fn decrypt(self, ctx: &DalContext) -> SecretResult<DecryptedSecret> {
+    /// [`DecryptedSecret`]. For a [`SecretKind::Reference`] secret, the decrypted reference is
+    /// resolved to the secret's value with the `ctx`'s [`SecretProviders`], scoped to the
+    /// secret's own workspace.
+    ///
+    /// Every successful decrypt is recorded as a [`SecretUsage`] for `source`.
+    pub async fn decrypt(
+        self,
+        ctx: &DalContext,
+        source: SecretUsageSource,
+    ) -> SecretResult<DecryptedSecret> {
         let key_pair = self.key_pair(ctx).await?;
+        let id = self.id;
+        let kind = self.kind;
+        let workspace_pk = self.tenancy.workspace_pk();
 
-        self.into_decrypted(
+        let decrypted = self.into_decrypted(
             key_pair.public_key(),
             key_pair.secret_key(),
             ctx.symmetric_crypto_service(),
-        )
+        )?;
+
+        let decrypted = match kind {
+            SecretKind::Reference => {
+                let reference: SecretReference = serde_json::from_value(decrypted.message)
+                    .map_err(SecretError::InvalidReference)?;
+                let workspace_pk =
+                    workspace_pk.ok_or(SecretError::ReferenceWithoutWorkspace(id))?;
+                let message = ctx
+                    .secret_providers()
+                    .resolve(workspace_pk, &reference)
+                    .await?;
+
+                DecryptedSecret {
+                    name: decrypted.name,
+                    definition: decrypted.definition,
+                    message: message.into_inner(),
+                }
+            }
+            SecretKind::Stored => decrypted,
+        };
+
+        SecretUsage::new(ctx, id, source).await?;
+
+        Ok(decrypted)
     }
 
     fn into_decrypted(
@@ -414,6 +632,123 @@ impl EncryptedSecret {
     pub async fn key_pair(&self, ctx: &DalContext) -> SecretResult<KeyPair> {
         Ok(KeyPair::get_by_pk(ctx, self.key_pair_pk).await?)
     }
+
+    /// Lists every encrypted secret in the workspace which is not sealed to the given
+    /// [`KeyPair`], across all change sets and including deleted secrets.
+    pub(crate) async fn list_to_reseal(
+        ctx: &DalContext,
+        key_pair_pk: KeyPairPk,
+    ) -> SecretResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_ENCRYPTED_SECRETS_TO_RESEAL,
+                &[&ctx.tenancy().workspace_pk(), &key_pair_pk],
+            )
+            .await?;
+
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Opens the secret with the [`KeyPair`] it is currently sealed to and seals it to another,
+    /// re-encrypting it with the current symmetric key along the way.
+    ///
+    /// This updates the row for this exact object (by pk), rather than going through the
+    /// visibility of the `ctx`, as every copy of a secret has to be re-sealed when its key pair
+    /// is rotated.
+    pub(crate) async fn reseal(
+        &mut self,
+        ctx: &DalContext,
+        from: &KeyPair,
+        to: &KeyPair,
+    ) -> SecretResult<()> {
+        if from.pk() != self.key_pair_pk {
+            return Err(SecretError::KeyPairNotFound);
+        }
+
+        // Explicitly match on (version, algorithm) tuple to ensure that any new
+        // versions/algorithms will trigger a compilation failure
+        let crypted = match (self.version, self.algorithm) {
+            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => {
+                let symmetric_decrypted = ctx.symmetric_crypto_service().decrypt(
+                    &self.crypted,
+                    &self.nonce,
+                    &self.key_hash,
+                )?;
+                let message =
+                    sealedbox::open(&symmetric_decrypted, from.public_key(), from.secret_key())
+                        .map_err(|_| SecretError::DecryptionFailed)?;
+
+                sealedbox::seal(&message, to.public_key())
+            }
+        };
+
+        let (double_crypted, nonce, key_hash) = ctx.symmetric_crypto_service().encrypt(&crypted);
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                ENCRYPTED_SECRET_RESEAL,
+                &[
+                    &self.pk,
+                    &base64_encode_bytes(double_crypted.as_slice()),
+                    &to.pk(),
+                    &base64_encode_bytes(nonce.as_ref()),
+                    &key_hash.to_string(),
+                ],
+            )
+            .await?;
+
+        self.crypted = double_crypted;
+        self.nonce = nonce;
+        self.key_hash = *key_hash;
+        self.key_pair_pk = to.pk();
+
+        Ok(())
+    }
+
+    /// Re-encrypts every encrypted secret, in every workspace and visibility, which is not
+    /// encrypted with the active key of the [`SymmetricCryptoService`], and returns how many were
+    /// re-encrypted. The sealed payload, and so the [`KeyPair`] it is sealed to, is unchanged.
+    pub async fn reencrypt_all(ctx: &DalContext) -> SecretResult<usize> {
+        let symmetric_crypto_service = ctx.symmetric_crypto_service();
+        let txns = ctx.txns().await?;
+
+        let rows = txns
+            .pg()
+            .query(
+                LIST_ENCRYPTED_SECRETS_TO_REENCRYPT,
+                &[&symmetric_crypto_service.active_key_hash().to_string()],
+            )
+            .await?;
+        let encrypted_secrets: Vec<Self> = objects_from_rows(rows)?;
+
+        for encrypted_secret in &encrypted_secrets {
+            let crypted = symmetric_crypto_service.decrypt(
+                &encrypted_secret.crypted,
+                &encrypted_secret.nonce,
+                &encrypted_secret.key_hash,
+            )?;
+
+            let (double_crypted, nonce, key_hash) = symmetric_crypto_service.encrypt(&crypted);
+            txns.pg()
+                .execute(
+                    ENCRYPTED_SECRET_RESEAL,
+                    &[
+                        &encrypted_secret.pk,
+                        &base64_encode_bytes(double_crypted.as_slice()),
+                        &encrypted_secret.key_pair_pk,
+                        &base64_encode_bytes(nonce.as_ref()),
+                        &key_hash.to_string(),
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(encrypted_secrets.len())
+    }
 }
 
 /// A secret that has been decrypted.
@@ -474,6 +809,27 @@ impl Default for SecretVersion {
     }
 }
 
+/// Where the value of a secret lives.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SecretKind {
+    /// The encrypted payload is a [`SecretReference`] to a value held by one of the
+    /// [`SecretProviders`], so the value itself is never stored
+    Reference,
+    /// The encrypted payload is the value itself
+    Stored,
+}
+
+impl Default for SecretKind {
+    fn default() -> Self {
+        Self::Stored
+    }
+}
+
 /// The algorithm used to encrypt a secret.
 #[remain::sorted]
 #[derive(
@@ -555,6 +911,9 @@ mod tests {
                 name,
                 definition,
                 description,
+                kind: Default::default(),
+                expires_at: None,
+                rotate_by: None,
                 key_pair_pk: KeyPairPk::NONE,
                 nonce,
                 key_hash: *key_hash,
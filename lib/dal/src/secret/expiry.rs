@@ -0,0 +1,205 @@
+//! This module contains the lifecycle metadata of a [`Secret`]: the optional date it expires at
+//! and the optional date it should be rotated by. Components using an expired secret fail their
+//! qualifications, and workspaces are warned through a [`WsEvent`] as these dates approach.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumString};
+
+use crate::{
+    standard_model::objects_from_rows, ChangeSetPk, ComponentId, DalContext, Secret, SecretId,
+    SecretResult, Visibility, WorkspacePk, WsEvent, WsEventResult, WsPayload,
+};
+
+const LIST_SECRETS_FOR_COMPONENT: &str =
+    include_str!("../queries/secrets/list_secrets_for_component.sql");
+const LIST_EXPIRING_SECRETS: &str = include_str!("../queries/secrets/list_expiring_secrets.sql");
+
+/// How many days before its expiry (or rotate-by) date a [`Secret`] is considered to be
+/// [`Expiring`](SecretExpiryStatus::Expiring).
+pub const SECRET_EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// Where a [`Secret`] is in its lifecycle, as of a given time.
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum SecretExpiryStatus {
+    /// The expiry date has passed
+    Expired,
+    /// The expiry or rotate-by date is within the warning window
+    Expiring,
+    /// The rotate-by date has passed, but the secret has not expired yet
+    RotationOverdue,
+    /// Neither date has passed nor is within the warning window (or neither is set)
+    Valid,
+}
+
+impl SecretExpiryStatus {
+    /// Returns the status of a secret with the given dates at `now`, warning `warning_window`
+    /// ahead of either date.
+    pub fn at(
+        expires_at: Option<DateTime<Utc>>,
+        rotate_by: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        warning_window: Duration,
+    ) -> Self {
+        let warn_from = now + warning_window;
+
+        if expires_at.map_or(false, |expires_at| expires_at <= now) {
+            Self::Expired
+        } else if rotate_by.map_or(false, |rotate_by| rotate_by <= now) {
+            Self::RotationOverdue
+        } else if expires_at
+            .into_iter()
+            .chain(rotate_by)
+            .any(|date| date <= warn_from)
+        {
+            Self::Expiring
+        } else {
+            Self::Valid
+        }
+    }
+}
+
+impl Secret {
+    /// Returns the [`SecretExpiryStatus`] of this secret right now.
+    pub fn expiry_status(&self) -> SecretExpiryStatus {
+        SecretExpiryStatus::at(
+            self.expires_at(),
+            self.rotate_by(),
+            Utc::now(),
+            Duration::days(SECRET_EXPIRY_WARNING_DAYS),
+        )
+    }
+
+    /// Lists the secrets set on any of the secret props of a [`Component`](crate::Component).
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> SecretResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_SECRETS_FOR_COMPONENT,
+                &[ctx.tenancy(), ctx.visibility(), &component_id],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    /// Lists the secrets on head, across all workspaces, which expire or are to be rotated at or
+    /// before `cutoff`, ordered by workspace. This bypasses tenancy checks and is meant for
+    /// system jobs only.
+    pub async fn list_expiring_in_all_workspaces(
+        ctx: &DalContext,
+        cutoff: DateTime<Utc>,
+    ) -> SecretResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_EXPIRING_SECRETS,
+                &[&Visibility::new_head(false), &cutoff],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretExpiryWarning {
+    pub secret_id: SecretId,
+    pub name: String,
+    pub definition: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub rotate_by: Option<DateTime<Utc>>,
+    pub status: SecretExpiryStatus,
+}
+
+impl From<&Secret> for SecretExpiryWarning {
+    fn from(secret: &Secret) -> Self {
+        Self {
+            secret_id: *secret.id(),
+            name: secret.name().to_owned(),
+            definition: secret.definition().to_owned(),
+            expires_at: secret.expires_at(),
+            rotate_by: secret.rotate_by(),
+            status: secret.expiry_status(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsExpiringPayload {
+    secrets: Vec<SecretExpiryWarning>,
+}
+
+impl WsEvent {
+    /// Warns a workspace about its secrets which have expired, are about to expire, or are due
+    /// to be rotated. The event is sent for head, regardless of the [`DalContext`]'s tenancy and
+    /// visibility.
+    pub async fn secrets_expiring(
+        workspace_pk: WorkspacePk,
+        secrets: Vec<SecretExpiryWarning>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new_raw(
+            workspace_pk,
+            ChangeSetPk::NONE,
+            WsPayload::SecretsExpiring(SecretsExpiringPayload { secrets }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(expires_in_days: Option<i64>, rotate_in_days: Option<i64>) -> SecretExpiryStatus {
+        let now = Utc::now();
+        SecretExpiryStatus::at(
+            expires_in_days.map(|days| now + Duration::days(days)),
+            rotate_in_days.map(|days| now + Duration::days(days)),
+            now,
+            Duration::days(SECRET_EXPIRY_WARNING_DAYS),
+        )
+    }
+
+    #[test]
+    fn without_dates_is_valid() {
+        assert_eq!(SecretExpiryStatus::Valid, status(None, None));
+    }
+
+    #[test]
+    fn far_dates_are_valid() {
+        assert_eq!(SecretExpiryStatus::Valid, status(Some(90), Some(60)));
+    }
+
+    #[test]
+    fn near_dates_are_expiring() {
+        assert_eq!(SecretExpiryStatus::Expiring, status(Some(3), None));
+        assert_eq!(SecretExpiryStatus::Expiring, status(Some(90), Some(3)));
+    }
+
+    #[test]
+    fn passed_rotate_by_is_overdue() {
+        assert_eq!(
+            SecretExpiryStatus::RotationOverdue,
+            status(Some(3), Some(-1))
+        );
+    }
+
+    #[test]
+    fn passed_expiry_is_expired() {
+        assert_eq!(SecretExpiryStatus::Expired, status(Some(-1), Some(-30)));
+        assert_eq!(SecretExpiryStatus::Expired, status(Some(0), None));
+    }
+}
@@ -0,0 +1,702 @@
+//! Providers which resolve [`SecretKind::Reference`](super::SecretKind::Reference) secrets to
+//! the values they point to, which live outside of our database.
+//!
+//! References are resolved when a function that needs the secret is dispatched, and the resolved
+//! value is handled exactly like the decrypted value of a stored secret: it is kept in a
+//! [`SensitiveContainer`] and re-encrypted for Cyclone, which redacts it from function output.
+//!
+//! Providers are shared by every workspace, so each one scopes the paths it resolves to the
+//! [`WorkspacePk`] of the secret: a reference can only ever point at values which were placed
+//! under its own workspace.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use reqwest::{header::HeaderValue, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use url::Url;
+use veritech_client::SensitiveContainer;
+
+use crate::WorkspacePk;
+
+/// The name the [`LocalSecretProvider`] is registered under.
+pub const LOCAL_SECRET_PROVIDER_NAME: &str = "local";
+/// The name the [`VaultSecretProvider`] is registered under.
+pub const VAULT_SECRET_PROVIDER_NAME: &str = "vault";
+
+const ENV_PATH_PREFIX: &str = "env:";
+const FILE_PATH_PREFIX: &str = "file:";
+const VAULT_TOKEN_HEADER: &str = "X-Vault-Token";
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SecretProviderError {
+    #[error("invalid secret reference path for provider {0}: {1}")]
+    InvalidPath(&'static str, String),
+    #[error("invalid vault token")]
+    InvalidVaultToken(#[from] reqwest::header::InvalidHeaderValue),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("secret provider {0} is not configured to resolve {1}")]
+    NotConfigured(&'static str, String),
+    #[error("secret not found by provider {0}: {1}")]
+    NotFound(&'static str, String),
+    #[error("secret provider not found: {0}")]
+    ProviderNotFound(String),
+    #[error("request error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("unexpected response from secret provider {0}: {1}")]
+    UnexpectedResponse(&'static str, StatusCode),
+    #[error("url parse error: {0}")]
+    UrlParse(#[from] url::ParseError),
+}
+
+pub type SecretProviderResult<T> = Result<T, SecretProviderError>;
+
+/// What a [`SecretKind::Reference`](super::SecretKind::Reference) secret holds in place of a
+/// value: the name of the [`SecretProvider`] to resolve it with, and where that provider can find
+/// it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretReference {
+    pub provider: String,
+    pub path: String,
+}
+
+/// Resolves the path of a [`SecretReference`] to the secret's value. The path is always relative
+/// to the location the provider keeps the given workspace's secrets in.
+#[async_trait]
+pub trait SecretProvider: fmt::Debug + Send + Sync {
+    async fn resolve(
+        &self,
+        workspace_pk: WorkspacePk,
+        path: &str,
+    ) -> SecretProviderResult<SensitiveContainer<Value>>;
+}
+
+/// The [`SecretProviders`](SecretProvider) available to resolve references, keyed by name.
+#[derive(Clone, Debug, Default)]
+pub struct SecretProviders {
+    providers: HashMap<String, Arc<dyn SecretProvider>>,
+}
+
+impl SecretProviders {
+    /// Registers the providers which are enabled in the config.
+    pub fn from_config(config: &SecretProvidersConfig) -> SecretProviderResult<Self> {
+        let mut providers = Self::default();
+        if let Some(local) = &config.local {
+            providers.register(
+                LOCAL_SECRET_PROVIDER_NAME,
+                LocalSecretProvider::new(
+                    local.root.clone(),
+                    local.env_prefix.clone(),
+                    local.env.clone(),
+                ),
+            );
+        }
+        if let Some(vault) = &config.vault {
+            providers.register(
+                VAULT_SECRET_PROVIDER_NAME,
+                VaultSecretProvider::new(&vault.address, vault.token.as_str(), &vault.mount)?,
+            );
+        }
+
+        Ok(providers)
+    }
+
+    /// Registers a provider under the given name, replacing any provider already registered
+    /// under it.
+    pub fn register(&mut self, name: impl Into<String>, provider: impl SecretProvider + 'static) {
+        self.providers.insert(name.into(), Arc::new(provider));
+    }
+
+    pub async fn resolve(
+        &self,
+        workspace_pk: WorkspacePk,
+        reference: &SecretReference,
+    ) -> SecretProviderResult<SensitiveContainer<Value>> {
+        self.providers
+            .get(&reference.provider)
+            .ok_or_else(|| SecretProviderError::ProviderNotFound(reference.provider.clone()))?
+            .resolve(workspace_pk, &reference.path)
+            .await
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SecretProvidersConfig {
+    #[serde(default)]
+    pub local: Option<LocalSecretProviderConfig>,
+    #[serde(default)]
+    pub vault: Option<VaultSecretProviderConfig>,
+}
+
+impl SecretProvidersConfig {
+    /// Keeps the variables of `vars` which `env:` paths of the local provider can resolve to,
+    /// i.e. those starting with its `env_prefix`. Servers pass their environment when they load
+    /// their config, so that the environment is never read after startup.
+    pub fn snapshot_env(&mut self, vars: impl IntoIterator<Item = (String, String)>) {
+        if let Some(LocalSecretProviderConfig {
+            env_prefix: Some(env_prefix),
+            env,
+            ..
+        }) = &mut self.local
+        {
+            env.extend(
+                vars.into_iter()
+                    .filter(|(name, _)| name.starts_with(env_prefix.as_str()))
+                    .map(|(name, value)| (name, value.into())),
+            );
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LocalSecretProviderConfig {
+    /// The directory holding a subdirectory per workspace, named by its pk, which `file:` paths
+    /// are resolved relative to. Files can't be resolved if unset.
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    /// The prefix prepended to the workspace pk and name in `env:` paths. Environment variables
+    /// can't be resolved if unset, so that a reference can never read the server's own
+    /// configuration.
+    #[serde(default)]
+    pub env_prefix: Option<String>,
+    /// The environment variables starting with `env_prefix`, as
+    /// [snapshotted](SecretProvidersConfig::snapshot_env) by the server.
+    #[serde(skip)]
+    pub env: HashMap<String, SensitiveContainer<String>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct VaultSecretProviderConfig {
+    pub address: String,
+    pub token: SensitiveContainer<String>,
+    #[serde(default = "default_vault_mount")]
+    pub mount: String,
+}
+
+fn default_vault_mount() -> String {
+    "secret".to_string()
+}
+
+/// Resolves secrets from the environment of the server, with paths like `env:AWS_PROD`, or from
+/// files in a directory, with paths like `file:aws/prod.json`.
+///
+/// `env:AWS_PROD` reads `<env_prefix><workspace_pk>_AWS_PROD` from the variables the provider was
+/// created with, and `file:aws/prod.json` reads
+/// `<root>/<workspace_pk>/aws/prod.json`, which must not leave the workspace's directory through
+/// a symlink.
+///
+/// Values are parsed as JSON, falling back to a JSON string for values which aren't.
+#[derive(Clone, Debug)]
+pub struct LocalSecretProvider {
+    root: Option<PathBuf>,
+    env_prefix: Option<String>,
+    env: Arc<HashMap<String, SensitiveContainer<String>>>,
+}
+
+impl LocalSecretProvider {
+    pub fn new(
+        root: Option<PathBuf>,
+        env_prefix: Option<String>,
+        env: HashMap<String, SensitiveContainer<String>>,
+    ) -> Self {
+        Self {
+            root,
+            env_prefix,
+            env: Arc::new(env),
+        }
+    }
+
+    fn parse_value(raw: String) -> SensitiveContainer<Value> {
+        serde_json::from_str(&raw)
+            .unwrap_or(Value::String(raw))
+            .into()
+    }
+
+    fn resolve_env(
+        &self,
+        workspace_pk: WorkspacePk,
+        name: &str,
+    ) -> SecretProviderResult<SensitiveContainer<Value>> {
+        let prefix = self.env_prefix.as_deref().ok_or_else(|| {
+            SecretProviderError::NotConfigured(LOCAL_SECRET_PROVIDER_NAME, "env".to_string())
+        })?;
+        if name.is_empty() || name.contains(['=', '\0']) {
+            return Err(SecretProviderError::InvalidPath(
+                LOCAL_SECRET_PROVIDER_NAME,
+                format!("{ENV_PATH_PREFIX}{name}"),
+            ));
+        }
+
+        let var = format!("{prefix}{workspace_pk}_{name}");
+        let raw = self.env.get(&var).ok_or(SecretProviderError::NotFound(
+            LOCAL_SECRET_PROVIDER_NAME,
+            var,
+        ))?;
+
+        Ok(Self::parse_value(String::clone(raw)))
+    }
+
+    async fn resolve_file(
+        &self,
+        workspace_pk: WorkspacePk,
+        path: &str,
+    ) -> SecretProviderResult<SensitiveContainer<Value>> {
+        let root = self.root.as_deref().ok_or_else(|| {
+            SecretProviderError::NotConfigured(LOCAL_SECRET_PROVIDER_NAME, "file".to_string())
+        })?;
+
+        let invalid_path = || {
+            SecretProviderError::InvalidPath(
+                LOCAL_SECRET_PROVIDER_NAME,
+                format!("{FILE_PATH_PREFIX}{path}"),
+            )
+        };
+        let not_found_or = |err: std::io::Error| {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                SecretProviderError::NotFound(
+                    LOCAL_SECRET_PROVIDER_NAME,
+                    format!("{FILE_PATH_PREFIX}{path}"),
+                )
+            } else {
+                err.into()
+            }
+        };
+
+        // Only plain relative paths are allowed, so a reference can't escape the workspace's
+        // directory by name
+        let relative = Path::new(path);
+        if path.is_empty()
+            || !relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(invalid_path());
+        }
+
+        // Symlinks are resolved before reading, so that one can't point outside of the
+        // workspace's directory either
+        let workspace_root = tokio::fs::canonicalize(root.join(workspace_pk.to_string()))
+            .await
+            .map_err(not_found_or)?;
+        let file = tokio::fs::canonicalize(workspace_root.join(relative))
+            .await
+            .map_err(not_found_or)?;
+        if !file.starts_with(&workspace_root) {
+            return Err(invalid_path());
+        }
+
+        let raw = tokio::fs::read_to_string(file)
+            .await
+            .map_err(not_found_or)?;
+
+        Ok(Self::parse_value(raw))
+    }
+}
+
+#[async_trait]
+impl SecretProvider for LocalSecretProvider {
+    async fn resolve(
+        &self,
+        workspace_pk: WorkspacePk,
+        path: &str,
+    ) -> SecretProviderResult<SensitiveContainer<Value>> {
+        if let Some(name) = path.strip_prefix(ENV_PATH_PREFIX) {
+            self.resolve_env(workspace_pk, name)
+        } else if let Some(path) = path.strip_prefix(FILE_PATH_PREFIX) {
+            self.resolve_file(workspace_pk, path).await
+        } else {
+            Err(SecretProviderError::InvalidPath(
+                LOCAL_SECRET_PROVIDER_NAME,
+                path.to_string(),
+            ))
+        }
+    }
+}
+
+/// Resolves secrets from a Vault-compatible KV (version 2) secrets engine over HTTP. The path is
+/// the path of the secret below the workspace's pk within the engine's mount, so `aws/prod` reads
+/// `<mount>/data/<workspace_pk>/aws/prod`, and the value is the secret's data.
+#[derive(Clone, Debug)]
+pub struct VaultSecretProvider {
+    client: reqwest::Client,
+    base_url: Url,
+    /// Marked as sensitive, so that it is redacted in `Debug` output
+    token: HeaderValue,
+}
+
+#[derive(Deserialize)]
+struct VaultKvResponse {
+    data: VaultKvData,
+}
+
+#[derive(Deserialize)]
+struct VaultKvData {
+    data: Value,
+}
+
+impl VaultSecretProvider {
+    pub fn new(address: &str, token: &str, mount: &str) -> SecretProviderResult<Self> {
+        let mut token = HeaderValue::from_str(token)?;
+        token.set_sensitive(true);
+
+        // The trailing slash makes the data path relative to the mount, rather than replacing it
+        let base_url =
+            Url::parse(address)?.join(&format!("v1/{}/data/", mount.trim_matches('/')))?;
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            base_url,
+            token,
+        })
+    }
+}
+
+#[async_trait]
+impl SecretProvider for VaultSecretProvider {
+    async fn resolve(
+        &self,
+        workspace_pk: WorkspacePk,
+        path: &str,
+    ) -> SecretProviderResult<SensitiveContainer<Value>> {
+        let path = path.trim_start_matches('/');
+        let invalid_path =
+            || SecretProviderError::InvalidPath(VAULT_SECRET_PROVIDER_NAME, path.to_string());
+        if path.contains(['?', '#']) {
+            return Err(invalid_path());
+        }
+        let segments = path
+            .split('/')
+            .map(|segment| match percent_decode(segment) {
+                Some(segment)
+                    if !matches!(segment.as_str(), "" | "." | "..")
+                        && !segment.contains(['/', '?', '#']) =>
+                {
+                    Ok(segment)
+                }
+                _ => Err(invalid_path()),
+            })
+            .collect::<SecretProviderResult<Vec<_>>>()?;
+
+        // Every segment is percent-encoded again as it is appended, so none of them can leave the
+        // workspace's data path
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| invalid_path())?
+            .pop_if_empty()
+            .push(&workspace_pk.to_string())
+            .extend(&segments);
+
+        let response = self
+            .client
+            .get(url)
+            .header(VAULT_TOKEN_HEADER, self.token.clone())
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let response: VaultKvResponse = response.json().await?;
+                Ok(response.data.data.into())
+            }
+            StatusCode::NOT_FOUND => Err(SecretProviderError::NotFound(
+                VAULT_SECRET_PROVIDER_NAME,
+                path.to_string(),
+            )),
+            status => Err(SecretProviderError::UnexpectedResponse(
+                VAULT_SECRET_PROVIDER_NAME,
+                status,
+            )),
+        }
+    }
+}
+
+/// Decodes the percent-encoded bytes of a path segment, returning `None` if an escape is invalid
+/// or the decoded segment is not UTF-8.
+fn percent_decode(segment: &str) -> Option<String> {
+    let mut bytes = segment.bytes();
+    let mut decoded = Vec::with_capacity(segment.len());
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let high = char::from(bytes.next()?).to_digit(16)?;
+            let low = char::from(bytes.next()?).to_digit(16)?;
+            decoded.push((high * 16 + low) as u8);
+        } else {
+            decoded.push(byte);
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::Path as AxumPath, http::HeaderMap, routing::get, Json, Router};
+
+    use super::*;
+
+    const TOKEN: &str = "hvs.stub-token";
+    const WORKSPACE: &str = "01H9ZQD3BC8VCWAT8SG6RPXNWF";
+    const OTHER_WORKSPACE: &str = "01H9ZQF0N5CG5WY2MZMCKTV4TK";
+
+    fn workspace_pk(pk: &str) -> WorkspacePk {
+        ulid::Ulid::from_string(pk)
+            .expect("invalid workspace pk")
+            .into()
+    }
+
+    async fn stub_vault_read(
+        AxumPath(path): AxumPath<String>,
+        headers: HeaderMap,
+    ) -> Result<Json<Value>, StatusCode> {
+        if headers
+            .get(VAULT_TOKEN_HEADER)
+            .and_then(|v| v.to_str().ok())
+            != Some(TOKEN)
+        {
+            return Err(StatusCode::FORBIDDEN);
+        }
+
+        match path.split_once('/') {
+            Some((WORKSPACE, "aws/prod")) => Ok(Json(serde_json::json!({
+                "data": {
+                    "data": {"accessKeyId": "AKIA-STUB", "secretAccessKey": "shhh"},
+                    "metadata": {"version": 3},
+                },
+            }))),
+            _ => Err(StatusCode::NOT_FOUND),
+        }
+    }
+
+    async fn stub_vault() -> String {
+        let app = Router::new().route("/v1/kv/data/*path", get(stub_vault_read));
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().expect("invalid address"))
+            .serve(app.into_make_service());
+        let address = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        address
+    }
+
+    #[tokio::test]
+    async fn vault_resolves_kv_data() {
+        let address = stub_vault().await;
+        let provider =
+            VaultSecretProvider::new(&address, TOKEN, "kv").expect("unable to create provider");
+
+        let value = provider
+            .resolve(workspace_pk(WORKSPACE), "aws/prod")
+            .await
+            .expect("unable to resolve secret");
+        assert_eq!(
+            serde_json::json!({"accessKeyId": "AKIA-STUB", "secretAccessKey": "shhh"}),
+            value.into_inner()
+        );
+
+        assert!(matches!(
+            provider
+                .resolve(workspace_pk(WORKSPACE), "aws/staging")
+                .await,
+            Err(SecretProviderError::NotFound(VAULT_SECRET_PROVIDER_NAME, _))
+        ));
+        assert!(matches!(
+            provider
+                .resolve(workspace_pk(OTHER_WORKSPACE), "aws/prod")
+                .await,
+            Err(SecretProviderError::NotFound(VAULT_SECRET_PROVIDER_NAME, _))
+        ));
+        assert!(matches!(
+            provider
+                .resolve(
+                    workspace_pk(OTHER_WORKSPACE),
+                    &format!("..%2f{WORKSPACE}%2faws%2fprod")
+                )
+                .await,
+            Err(SecretProviderError::InvalidPath(
+                VAULT_SECRET_PROVIDER_NAME,
+                _
+            ))
+        ));
+        for path in [
+            "../sys/seal",
+            "%2e%2e/sys/seal",
+            ".%2e/sys/seal",
+            "%2E%2E/sys/seal",
+            "aws/%2e",
+            "aws/./prod",
+            "aws//prod",
+            "aws%2f..%2f..%2fsys%2fseal",
+            "aws/prod?version=1",
+            "aws/prod#x",
+            "aws/prod%3fversion=1",
+            "aws/%zz",
+        ] {
+            assert!(
+                matches!(
+                    provider.resolve(workspace_pk(WORKSPACE), path).await,
+                    Err(SecretProviderError::InvalidPath(
+                        VAULT_SECRET_PROVIDER_NAME,
+                        _
+                    ))
+                ),
+                "{path}"
+            );
+        }
+        assert_eq!(
+            serde_json::json!({"accessKeyId": "AKIA-STUB", "secretAccessKey": "shhh"}),
+            provider
+                .resolve(workspace_pk(WORKSPACE), "aws/%70rod")
+                .await
+                .expect("unable to resolve encoded secret path")
+                .into_inner()
+        );
+
+        let unauthorized =
+            VaultSecretProvider::new(&address, "nope", "kv").expect("unable to create provider");
+        assert!(matches!(
+            unauthorized
+                .resolve(workspace_pk(WORKSPACE), "aws/prod")
+                .await,
+            Err(SecretProviderError::UnexpectedResponse(
+                VAULT_SECRET_PROVIDER_NAME,
+                StatusCode::FORBIDDEN
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn local_resolves_files_within_workspace_root() {
+        let root = tempfile::tempdir().expect("unable to create tempdir");
+        let workspace_root = root.path().join(WORKSPACE);
+        let other_workspace_root = root.path().join(OTHER_WORKSPACE);
+        tokio::fs::create_dir_all(workspace_root.join("aws"))
+            .await
+            .expect("unable to create dir");
+        tokio::fs::create_dir(&other_workspace_root)
+            .await
+            .expect("unable to create dir");
+        tokio::fs::write(
+            workspace_root.join("aws/prod.json"),
+            r#"{"accessKeyId": "AKIA"}"#,
+        )
+        .await
+        .expect("unable to write secret");
+        tokio::fs::write(workspace_root.join("token"), "plain-token")
+            .await
+            .expect("unable to write secret");
+        tokio::fs::write(root.path().join("shared"), "shared-token")
+            .await
+            .expect("unable to write secret");
+        tokio::fs::symlink(
+            workspace_root.join("token"),
+            other_workspace_root.join("token"),
+        )
+        .await
+        .expect("unable to create symlink");
+        tokio::fs::symlink(root.path(), other_workspace_root.join("root"))
+            .await
+            .expect("unable to create symlink");
+
+        let provider =
+            LocalSecretProvider::new(Some(root.path().to_path_buf()), None, HashMap::new());
+
+        assert_eq!(
+            serde_json::json!({"accessKeyId": "AKIA"}),
+            provider
+                .resolve(workspace_pk(WORKSPACE), "file:aws/prod.json")
+                .await
+                .expect("unable to resolve secret")
+                .into_inner()
+        );
+        assert_eq!(
+            serde_json::json!("plain-token"),
+            provider
+                .resolve(workspace_pk(WORKSPACE), "file:token")
+                .await
+                .expect("unable to resolve secret")
+                .into_inner()
+        );
+        assert!(matches!(
+            provider
+                .resolve(workspace_pk(OTHER_WORKSPACE), "file:aws/prod.json")
+                .await,
+            Err(SecretProviderError::NotFound(..))
+        ));
+        for path in [
+            "file:token",
+            "file:root/shared",
+            "file:../shared",
+            "file:/etc/passwd",
+        ] {
+            assert!(
+                matches!(
+                    provider.resolve(workspace_pk(OTHER_WORKSPACE), path).await,
+                    Err(SecretProviderError::InvalidPath(..))
+                ),
+                "{path}"
+            );
+        }
+        assert!(matches!(
+            provider.resolve(workspace_pk(WORKSPACE), "env:HOME").await,
+            Err(SecretProviderError::NotConfigured(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn local_resolves_env_within_workspace_prefix() {
+        let mut config = SecretProvidersConfig {
+            local: Some(LocalSecretProviderConfig {
+                env_prefix: Some("SI_PROVIDER_TEST_".to_string()),
+                ..Default::default()
+            }),
+            vault: None,
+        };
+        config.snapshot_env([
+            (
+                format!("SI_PROVIDER_TEST_{WORKSPACE}_TOKEN"),
+                "env-token".to_string(),
+            ),
+            ("HOME".to_string(), "/root".to_string()),
+        ]);
+        let providers = SecretProviders::from_config(&config).expect("unable to create providers");
+        let resolve = |workspace: &str, path: &str| {
+            providers.resolve(
+                workspace_pk(workspace),
+                &SecretReference {
+                    provider: LOCAL_SECRET_PROVIDER_NAME.to_string(),
+                    path: path.to_string(),
+                },
+            )
+        };
+
+        assert_eq!(
+            serde_json::json!("env-token"),
+            resolve(WORKSPACE, "env:TOKEN")
+                .await
+                .expect("unable to resolve secret")
+                .into_inner()
+        );
+        assert!(matches!(
+            resolve(OTHER_WORKSPACE, "env:TOKEN").await,
+            Err(SecretProviderError::NotFound(..))
+        ));
+        for path in ["env:", "env:TOKEN=x", "env:TOKEN\0"] {
+            assert!(
+                matches!(
+                    resolve(WORKSPACE, path).await,
+                    Err(SecretProviderError::InvalidPath(..))
+                ),
+                "{path}"
+            );
+        }
+    }
+}
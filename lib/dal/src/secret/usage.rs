@@ -0,0 +1,174 @@
+//! This module contains [`SecretUsage`], the audit record written every time an
+//! [`EncryptedSecret`](crate::EncryptedSecret) is decrypted.
+//!
+//! Each record says who decrypted the secret and, when known, which [`Func`](crate::Func) it
+//! was decrypted for, on behalf of which [`Component`](crate::Component), and as part of which
+//! [`Fix`](crate::Fix) and [`FixBatch`](crate::FixBatch).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use telemetry::prelude::*;
+
+use crate::{
+    pk, standard_model::objects_from_rows, ComponentId, DalContext, FixBatchId, FixId, FuncId,
+    HistoryActor, SecretId, Tenancy, Timestamp,
+};
+
+use super::SecretResult;
+
+const LIST_SECRET_USAGES: &str = include_str!("../queries/secrets/list_secret_usages.sql");
+
+/// The [NATS](https://nats.io) subject every [`SecretUsage`] is published on once its
+/// [`DalContext`] commits.
+pub const SECRET_USAGE_SUBJECT: &str = "secretUsage";
+
+pk!(SecretUsagePk);
+
+/// What a secret was decrypted for. Every field is optional, since not every decrypt happens on
+/// behalf of a function, a component or a fix.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretUsageSource {
+    func_id: Option<FuncId>,
+    component_id: Option<ComponentId>,
+    fix_id: Option<FixId>,
+    fix_batch_id: Option<FixBatchId>,
+}
+
+impl SecretUsageSource {
+    pub fn with_func_id(mut self, func_id: FuncId) -> Self {
+        self.func_id = Some(func_id);
+        self
+    }
+
+    pub fn with_component_id(mut self, component_id: ComponentId) -> Self {
+        self.component_id = Some(component_id);
+        self
+    }
+
+    pub fn with_fix(mut self, fix_id: FixId, fix_batch_id: Option<FixBatchId>) -> Self {
+        self.fix_id = Some(fix_id);
+        self.fix_batch_id = fix_batch_id;
+        self
+    }
+
+    pub fn func_id(&self) -> Option<FuncId> {
+        self.func_id
+    }
+
+    pub fn component_id(&self) -> Option<ComponentId> {
+        self.component_id
+    }
+
+    pub fn fix_id(&self) -> Option<FixId> {
+        self.fix_id
+    }
+
+    pub fn fix_batch_id(&self) -> Option<FixBatchId> {
+        self.fix_batch_id
+    }
+}
+
+/// A record of a single decrypt of a secret.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SecretUsage {
+    pk: SecretUsagePk,
+    secret_id: SecretId,
+    actor: HistoryActor,
+    #[serde(flatten)]
+    source: SecretUsageSource,
+    #[serde(flatten)]
+    tenancy: Tenancy,
+    #[serde(flatten)]
+    timestamp: Timestamp,
+}
+
+impl SecretUsage {
+    /// Records that the [`DalContext`]'s actor decrypted the secret for `source`.
+    #[instrument(skip(ctx))]
+    pub(crate) async fn new(
+        ctx: &DalContext,
+        secret_id: SecretId,
+        source: SecretUsageSource,
+    ) -> SecretResult<Self> {
+        // This query explicitly uses its own connection to bypass/avoid a ctx's database
+        // transaction--a decrypt happened whether or not the transaction is later committed!
+        let row = ctx
+            .pg_pool()
+            .get()
+            .await?
+            .query_one(
+                "SELECT object FROM secret_usage_create_v1($1, $2, $3, $4, $5, $6, $7)",
+                &[
+                    ctx.tenancy(),
+                    &secret_id,
+                    &serde_json::to_value(ctx.history_actor())?,
+                    &source.func_id,
+                    &source.component_id,
+                    &source.fix_id,
+                    &source.fix_batch_id,
+                ],
+            )
+            .await?;
+        let json: Value = row.try_get("object")?;
+
+        // Unlike the record, this is only published when the ctx commits, along with the work the
+        // secret was decrypted for. Failing to publish it is not a reason to fail the decrypt, the
+        // record is in the database.
+        if let Err(err) = ctx
+            .txns()
+            .await?
+            .nats()
+            .publish(SECRET_USAGE_SUBJECT, &json)
+            .await
+        {
+            warn!(error = ?err, "failed to publish secret usage");
+        }
+
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Lists the usages of a secret visible to the [`DalContext`]'s tenancy, newest first,
+    /// optionally limited to those recorded at or after `since` and before `until`.
+    pub async fn list_for_secret(
+        ctx: &DalContext,
+        secret_id: SecretId,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> SecretResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                LIST_SECRET_USAGES,
+                &[ctx.tenancy(), &secret_id, &since, &until],
+            )
+            .await?;
+        Ok(objects_from_rows(rows)?)
+    }
+
+    pub fn pk(&self) -> SecretUsagePk {
+        self.pk
+    }
+
+    pub fn secret_id(&self) -> SecretId {
+        self.secret_id
+    }
+
+    pub fn actor(&self) -> HistoryActor {
+        self.actor
+    }
+
+    pub fn source(&self) -> SecretUsageSource {
+        self.source
+    }
+
+    pub fn tenancy(&self) -> Tenancy {
+        self.tenancy
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.timestamp.created_at
+    }
+}
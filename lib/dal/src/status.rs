@@ -15,12 +15,12 @@ use thiserror::Error;
 use tokio::sync::Mutex;
 
 use crate::{
-    pk, schema::variant::leaves::LeafKind, standard_model::objects_from_rows, ActorView,
-    AttributeValue, AttributeValueError, AttributeValueId, ChangeSetPk, Component, ComponentError,
-    ComponentId, ComponentStatus, DalContext, ExternalProvider, ExternalProviderError,
-    InternalProvider, InternalProviderError, Prop, PropError, PropId, SchemaVariant, SocketId,
-    StandardModel, StandardModelError, Tenancy, Timestamp, UserPk, WsEvent, WsEventError,
-    WsEventResult, WsPayload,
+    key_pair::KeyPairPk, pk, schema::variant::leaves::LeafKind, standard_model::objects_from_rows,
+    ActorView, AttributeValue, AttributeValueError, AttributeValueId, ChangeSetPk, Component,
+    ComponentError, ComponentId, ComponentStatus, DalContext, ExternalProvider,
+    ExternalProviderError, InternalProvider, InternalProviderError, Prop, PropError, PropId,
+    SchemaVariant, SocketId, StandardModel, StandardModelError, Tenancy, Timestamp, UserPk,
+    WsEvent, WsEventError, WsEventResult, WsPayload,
 };
 
 const MODEL_TABLE: &str = "status_updates";
@@ -75,6 +75,21 @@ pub struct StatusUpdateData {
     queued_dependent_value_ids: HashSet<AttributeValueId>,
     running_dependent_value_ids: HashSet<AttributeValueId>,
     completed_dependent_value_ids: HashSet<AttributeValueId>,
+    #[serde(default)]
+    secret_reseal_progress: Option<SecretResealProgress>,
+}
+
+/// Progress through re-sealing a workspace's secrets to a newly rotated
+/// [`KeyPair`](crate::KeyPair).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretResealProgress {
+    /// The key pair which secrets are being re-sealed to
+    pub key_pair_pk: KeyPairPk,
+    /// The number of secrets re-sealed so far
+    pub resealed: usize,
+    /// The total number of secrets to re-seal
+    pub total: usize,
 }
 
 impl postgres_types::ToSql for StatusUpdateData {
@@ -290,6 +305,27 @@ impl StatusUpdate {
         self.metadata_from_value_ids(value_ids)
     }
 
+    /// Returns the progress through re-sealing secrets to a rotated key pair, if this status
+    /// update tracks a key pair rotation.
+    pub fn secret_reseal_progress(&self) -> Option<&SecretResealProgress> {
+        self.data.secret_reseal_progress.as_ref()
+    }
+
+    /// Sets the progress through re-sealing secrets to a rotated key pair and persists the
+    /// update.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if there is a connection issue or if the update fails.
+    pub async fn set_secret_reseal_progress(
+        &mut self,
+        ctx: &DalContext,
+        progress: SecretResealProgress,
+    ) -> StatusUpdateResult<()> {
+        self.data.secret_reseal_progress = Some(progress);
+        self.persist_data_to_db(ctx).await
+    }
+
     /// Marks the status update as finished and persists the update.
     ///
     /// # Errors
@@ -371,6 +407,8 @@ pub struct StatusMessage {
     pk: StatusUpdatePk,
     status: StatusMessageState,
     values: Vec<AttributeValueMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_reseal_progress: Option<SecretResealProgress>,
 }
 
 /// A representation of the kind of attribute value that is being processed.
@@ -523,6 +561,32 @@ impl StatusUpdater {
         }
     }
 
+    /// Updates the [`StatusUpdate`] with how many of a workspace's secrets have been re-sealed to
+    /// a newly rotated [`KeyPair`](crate::KeyPair).
+    pub async fn secrets_resealed(
+        &mut self,
+        ctx: &DalContext,
+        key_pair_pk: KeyPairPk,
+        resealed: usize,
+        total: usize,
+    ) {
+        match self.inner.lock().await.as_mut() {
+            Some(inner) => {
+                let progress = SecretResealProgress {
+                    key_pair_pk,
+                    resealed,
+                    total,
+                };
+                if let Err(err) = inner.secrets_resealed(ctx, progress).await {
+                    error!(error = ?err, "failed to update resealed secrets");
+                }
+            }
+            None => {
+                trace!("unable to call secrets_resealed; inner is not initialized");
+            }
+        }
+    }
+
     /// Marks the [`StatusUpdate`] as finished, and ensures that there are no unprocessed values.
     pub async fn finish(self, ctx: &DalContext) {
         match self.inner.lock().await.as_mut() {
@@ -818,6 +882,30 @@ impl StatusUpdaterInner {
         Ok(())
     }
 
+    async fn secrets_resealed(
+        &mut self,
+        ctx: &DalContext,
+        progress: SecretResealProgress,
+    ) -> Result<(), StatusUpdaterError> {
+        self.model.set_secret_reseal_progress(ctx, progress).await?;
+
+        let status = if progress.resealed >= progress.total {
+            StatusMessageState::Completed
+        } else if progress.resealed == 0 {
+            StatusMessageState::Queued
+        } else {
+            StatusMessageState::Running
+        };
+
+        Self::publish_immediately(
+            ctx,
+            WsEvent::secret_reseal_status_update(ctx, self.model.pk, status, progress).await?,
+        )
+        .await?;
+
+        Ok(())
+    }
+
     async fn finish(&mut self, ctx: &DalContext) -> Result<(), StatusUpdaterError> {
         self.model.finish(ctx).await?;
 
@@ -915,7 +1003,37 @@ impl WsEvent {
     ) -> WsEventResult<Self> {
         WsEvent::new(
             ctx,
-            WsPayload::StatusUpdate(StatusMessage { pk, status, values }),
+            WsPayload::StatusUpdate(StatusMessage {
+                pk,
+                status,
+                values,
+                secret_reseal_progress: None,
+            }),
+        )
+        .await
+    }
+
+    /// Creates a new `WsEvent` for a [`StatusUpdate`] tracking the re-sealing of a workspace's
+    /// secrets to a newly rotated [`KeyPair`](crate::KeyPair).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Err`] if no user exists for a user pk or if there is a connection issue with the
+    /// database.
+    pub async fn secret_reseal_status_update(
+        ctx: &DalContext,
+        pk: StatusUpdatePk,
+        status: StatusMessageState,
+        progress: SecretResealProgress,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::StatusUpdate(StatusMessage {
+                pk,
+                status,
+                values: vec![],
+                secret_reseal_progress: Some(progress),
+            }),
         )
         .await
     }
@@ -0,0 +1,93 @@
+//! This module re-encrypts every value encrypted with the [`SymmetricCryptoService`] under its
+//! active key, so that the other keys it was loaded with can be retired.
+//!
+//! Retiring a key works in three steps: make a new key the active one (keeping the old key as an
+//! extra key), run [`reencrypt_symmetric_data`], and once the [`SymmetricReencryptionReport`] no
+//! longer lists the old key's hash, stop loading the old key.
+//!
+//! [`SymmetricCryptoService`]: si_crypto::SymmetricCryptoService
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use si_data_pg::PgError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, EncryptedSecret, KeyPair, KeyPairError, SecretError, TransactionsError};
+
+const SYMMETRIC_KEY_HASH_REFERENCES: &str =
+    include_str!("queries/symmetric_key_hash_references.sql");
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum SymmetricReencryptionError {
+    #[error("key pair error: {0}")]
+    KeyPair(#[from] KeyPairError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("secret error: {0}")]
+    Secret(#[from] SecretError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type SymmetricReencryptionResult<T> = Result<T, SymmetricReencryptionError>;
+
+/// What a run of [`reencrypt_symmetric_data`] did, and which keys are still needed afterwards.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymmetricReencryptionReport {
+    /// The hash of the key everything was re-encrypted under.
+    pub active_key_hash: String,
+    pub key_pairs_reencrypted: usize,
+    pub encrypted_secrets_reencrypted: usize,
+    /// How many values are encrypted under each key hash. Any hash other than the active one
+    /// belongs to a key which can not be retired yet.
+    pub key_hash_references: BTreeMap<String, i64>,
+}
+
+impl SymmetricReencryptionReport {
+    /// Returns the hashes of the keys, other than the active one, which values are still
+    /// encrypted under.
+    pub fn retained_key_hashes(&self) -> Vec<&str> {
+        self.key_hash_references
+            .keys()
+            .filter(|key_hash| **key_hash != self.active_key_hash)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Re-encrypts the secret keys of all [`KeyPairs`](KeyPair) and all
+/// [`EncryptedSecrets`](EncryptedSecret) which are not yet encrypted under the active key of the
+/// [`DalContext`]'s [`SymmetricCryptoService`](si_crypto::SymmetricCryptoService).
+///
+/// This ignores the tenancy and visibility of the [`DalContext`]: every row is re-encrypted. The
+/// changes are only persisted once the [`DalContext`] is committed.
+#[instrument(skip_all)]
+pub async fn reencrypt_symmetric_data(
+    ctx: &DalContext,
+) -> SymmetricReencryptionResult<SymmetricReencryptionReport> {
+    let active_key_hash = ctx.symmetric_crypto_service().active_key_hash().to_string();
+
+    let key_pairs_reencrypted = KeyPair::reencrypt_all(ctx).await?;
+    let encrypted_secrets_reencrypted = EncryptedSecret::reencrypt_all(ctx).await?;
+
+    let key_hash_references = ctx
+        .txns()
+        .await?
+        .pg()
+        .query(SYMMETRIC_KEY_HASH_REFERENCES, &[])
+        .await?
+        .into_iter()
+        .map(|row| Ok((row.try_get("key_hash")?, row.try_get("references")?)))
+        .collect::<Result<_, PgError>>()?;
+
+    Ok(SymmetricReencryptionReport {
+        active_key_hash,
+        key_pairs_reencrypted,
+        encrypted_secrets_reencrypted,
+        key_hash_references,
+    })
+}
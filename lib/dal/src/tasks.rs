@@ -2,9 +2,9 @@
 //! SI binaries that are dependent on the [`dal`](crate).
 
 // This modules should remain private! Add "pub use" statements to use their contents.
-mod resource_scheduler;
+mod audit_log_forwarder;
 mod status_receiver;
 
-pub use resource_scheduler::{ResourceScheduler, ResourceSchedulerError};
+pub use audit_log_forwarder::AuditLogForwarder;
 pub use status_receiver::client::StatusReceiverClient;
 pub use status_receiver::{StatusReceiver, StatusReceiverError, StatusReceiverRequest};
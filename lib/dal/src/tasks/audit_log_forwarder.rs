@@ -0,0 +1,267 @@
+//! The [`AuditLogForwarder`] is a spawned, "long-running" [tokio](https://tokio.rs/) task that
+//! forwards [`HistoryEvents`](HistoryEvent) and [`SecretUsages`](SecretUsage), as they are
+//! published over [NATS](https://nats.io), to the [`AuditLogSink`] configured for their
+//! [`Workspace`].
+//!
+//! Each workspace's entries are forwarded by a task of its own, through a bounded queue, so that a
+//! slow or unresponsive sink only ever holds up the workspace which configured it.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use nats_subscriber::{Request, Subscriber};
+use telemetry::prelude::*;
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{self, error::TrySendError},
+    },
+    time::timeout,
+};
+
+use crate::{
+    audit_log::{AuditLogEntry, AuditLogResult, AuditLogSink, AuditLogSinkConfig},
+    history_event::HISTORY_EVENT_SUBJECT,
+    secret::{usage::SECRET_USAGE_SUBJECT, SecretUsage},
+    HistoryEvent, ServicesContext, Workspace, WorkspacePk,
+};
+
+/// The queue name for [NATS](https://nats.io), so that every entry is forwarded once no matter
+/// how many forwarders are running.
+const AUDIT_LOG_FORWARDER_QUEUE_NAME: &str = "auditLogForwarder";
+/// How long a workspace's [`AuditLogSinkConfig`] is used before it is looked up again, so that
+/// changing it takes effect without looking it up for every entry.
+const WORKSPACE_SINK_TTL: Duration = Duration::from_secs(60);
+/// How many of a workspace's entries may wait to be forwarded. Entries beyond it are dropped
+/// rather than holding up the entries of every other workspace.
+const WORKSPACE_QUEUE_SIZE: usize = 1024;
+/// How long a workspace's task waits for another entry before it stops, so that there is no task
+/// left running for every workspace that ever recorded an entry.
+const WORKSPACE_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Forwards every [`AuditLogEntry`] recorded, in any workspace, to the [`AuditLogSink`] of its
+/// workspace. Entries of workspaces without one are not forwarded.
+#[derive(Debug)]
+pub struct AuditLogForwarder {
+    /// The [`ServicesContext`](crate::ServicesContext) needed to look up each workspace's
+    /// [`AuditLogSinkConfig`].
+    services_context: ServicesContext,
+    history_events: Subscriber<HistoryEvent>,
+    secret_usages: Subscriber<SecretUsage>,
+    /// The queue of each workspace's [`WorkspaceForwarder`] task.
+    workspaces: HashMap<WorkspacePk, mpsc::Sender<AuditLogEntry>>,
+}
+
+impl AuditLogForwarder {
+    /// Create a new [`AuditLogForwarder`].
+    pub async fn new(services_context: ServicesContext) -> AuditLogResult<Self> {
+        let nats = services_context.nats_conn();
+        let history_events = Subscriber::create(HISTORY_EVENT_SUBJECT)
+            .queue_name(AUDIT_LOG_FORWARDER_QUEUE_NAME)
+            .start(nats)
+            .await?;
+        let secret_usages = Subscriber::create(SECRET_USAGE_SUBJECT)
+            .queue_name(AUDIT_LOG_FORWARDER_QUEUE_NAME)
+            .start(nats)
+            .await?;
+        Ok(Self {
+            services_context,
+            history_events,
+            secret_usages,
+            workspaces: HashMap::new(),
+        })
+    }
+
+    /// A _synchronous_ function that starts the [`forwarder`](Self) in a new asynchronous task.
+    pub fn start(self, shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        info!("starting audit log forwarder");
+        tokio::spawn(self.start_task(shutdown_broadcast_rx));
+    }
+
+    /// The "inner" portion of [`Self::start()`] that contains the core listener loop.
+    #[instrument(name = "audit_log_forwarder.start_task", skip_all, level = "debug")]
+    async fn start_task(mut self, mut shutdown_broadcast_rx: broadcast::Receiver<()>) {
+        loop {
+            let entry = tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => {
+                    trace!("the audit log forwarder task received shutdown");
+                    break;
+                }
+                request = self.history_events.next() => match request {
+                    Some(request) => request.map(|request: Request<HistoryEvent>| {
+                        AuditLogEntry::from(request.payload)
+                    }),
+                    None => {
+                        trace!("audit log forwarder history event stream has closed");
+                        break;
+                    }
+                },
+                request = self.secret_usages.next() => match request {
+                    Some(request) => request.map(|request: Request<SecretUsage>| {
+                        AuditLogEntry::from(request.payload)
+                    }),
+                    None => {
+                        trace!("audit log forwarder secret usage stream has closed");
+                        break;
+                    }
+                },
+            };
+
+            match entry {
+                Ok(entry) => self.dispatch(entry),
+                Err(err) => warn!(error = ?err, "next audit log entry errored"),
+            }
+        }
+
+        // Dropping the queues lets each workspace's task forward what is left in its queue, and
+        // then stop
+        self.workspaces.clear();
+
+        // Unsubscribe without draining the channels
+        for result in [
+            self.history_events.unsubscribe_after(0).await,
+            self.secret_usages.unsubscribe_after(0).await,
+        ] {
+            if let Err(err) = result {
+                error!("could not unsubscribe from nats: {:?}", err);
+            }
+        }
+    }
+
+    /// Queues an entry for its workspace's task, starting the task if it isn't running. This
+    /// never waits, so no workspace can hold up the others.
+    fn dispatch(&mut self, entry: AuditLogEntry) {
+        let workspace_pk = match entry.workspace_pk {
+            Some(workspace_pk) => workspace_pk,
+            None => return,
+        };
+
+        let entry = match self.queue(workspace_pk).try_send(entry) {
+            Ok(()) => return,
+            Err(TrySendError::Full(entry)) => {
+                warn!(
+                    %workspace_pk,
+                    label = %entry.label,
+                    "audit log queue of workspace is full, dropping entry"
+                );
+                return;
+            }
+            // The workspace's task stopped after being idle, so start another one
+            Err(TrySendError::Closed(entry)) => entry,
+        };
+
+        self.workspaces.remove(&workspace_pk);
+        if self.queue(workspace_pk).try_send(entry).is_err() {
+            warn!(%workspace_pk, "could not queue audit log entry of workspace");
+        }
+    }
+
+    /// The queue of the workspace's task, starting the task if there is none.
+    fn queue(&mut self, workspace_pk: WorkspacePk) -> &mpsc::Sender<AuditLogEntry> {
+        let services_context = &self.services_context;
+        self.workspaces.entry(workspace_pk).or_insert_with(|| {
+            let (tx, rx) = mpsc::channel(WORKSPACE_QUEUE_SIZE);
+            tokio::spawn(WorkspaceForwarder::new(services_context.clone(), workspace_pk).run(rx));
+            tx
+        })
+    }
+}
+
+/// Forwards the entries of a single workspace, one after another, to its [`AuditLogSink`].
+#[derive(Debug)]
+struct WorkspaceForwarder {
+    services_context: ServicesContext,
+    workspace_pk: WorkspacePk,
+    /// The workspace's [`AuditLogSinkConfig`], and when it was looked up.
+    config: Option<(Instant, Option<AuditLogSinkConfig>)>,
+    /// The sink connected for the workspace's current [`AuditLogSinkConfig`].
+    sink: Option<(AuditLogSinkConfig, AuditLogSink)>,
+}
+
+impl WorkspaceForwarder {
+    fn new(services_context: ServicesContext, workspace_pk: WorkspacePk) -> Self {
+        Self {
+            services_context,
+            workspace_pk,
+            config: None,
+            sink: None,
+        }
+    }
+
+    #[instrument(
+        name = "audit_log_forwarder.workspace",
+        skip_all,
+        level = "debug",
+        fields(workspace_pk = %self.workspace_pk)
+    )]
+    async fn run(mut self, mut rx: mpsc::Receiver<AuditLogEntry>) {
+        loop {
+            match timeout(WORKSPACE_IDLE_TIMEOUT, rx.recv()).await {
+                Ok(Some(entry)) => self.forward_or_warn(&entry).await,
+                Ok(None) => break,
+                Err(_) => {
+                    // Refuse new entries, so the forwarder starts a new task for them, but still
+                    // forward those queued in the meantime
+                    rx.close();
+                    while let Some(entry) = rx.recv().await {
+                        self.forward_or_warn(&entry).await;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn forward_or_warn(&mut self, entry: &AuditLogEntry) {
+        if let Err(err) = self.forward(entry).await {
+            warn!(
+                error = ?err,
+                label = %entry.label,
+                "failed to forward audit log entry"
+            );
+        }
+    }
+
+    /// Sends an entry to the workspace's sink, if it has one.
+    async fn forward(&mut self, entry: &AuditLogEntry) -> AuditLogResult<()> {
+        let config = match self.workspace_sink().await? {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+
+        let sink = match &self.sink {
+            Some((connected_config, sink)) if *connected_config == config => sink.clone(),
+            _ => {
+                let sink = AuditLogSink::connect(&config).await?;
+                self.sink = Some((config, sink.clone()));
+                sink
+            }
+        };
+        sink.send(entry).await
+    }
+
+    /// The workspace's [`AuditLogSinkConfig`], looked up again once [`WORKSPACE_SINK_TTL`] has
+    /// passed since it last was.
+    async fn workspace_sink(&mut self) -> AuditLogResult<Option<AuditLogSinkConfig>> {
+        if let Some((looked_up_at, config)) = &self.config {
+            if looked_up_at.elapsed() < WORKSPACE_SINK_TTL {
+                return Ok(config.clone());
+            }
+        }
+
+        let ctx = self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?;
+        let config = Workspace::get_by_pk(&ctx, &self.workspace_pk)
+            .await
+            .map_err(Box::new)?
+            .and_then(|workspace| workspace.audit_log_sink().cloned());
+
+        self.config = Some((Instant::now(), config.clone()));
+        Ok(config)
+    }
+}
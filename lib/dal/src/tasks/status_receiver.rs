@@ -152,10 +152,17 @@ impl StatusReceiver {
     /// This function is considered the "critical section" of the [`receiver`](Self). It _CANNOT_
     /// mutate rows that [`DependentValuesUpdate`](crate::DependentValuesUpdate) is mutating,
     /// otherwise a database deadlock may occur.
+    #[instrument(
+        name = "status_receiver.process",
+        skip_all,
+        level = "info",
+        fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+    )]
     async fn process(
         ctx_builder: DalContextBuilder,
         request: Request<StatusReceiverRequest>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        request.continue_trace(&Span::current());
         debug!("processing request from dependent values update job");
         let mut ctx = ctx_builder.build_default().await?;
 
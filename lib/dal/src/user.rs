@@ -1,3 +1,4 @@
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -5,6 +6,10 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::task::JoinError;
 
+pub mod role;
+
+pub use role::{WorkspacePermission, WorkspaceRole};
+
 use crate::ws_event::{WsEvent, WsEventResult, WsPayload};
 use crate::{
     jwt_key::JwtKeyError, pk, standard_model_accessor_ro, ChangeSetPk, DalContext, HistoryEvent,
@@ -14,6 +19,10 @@ use crate::{
 const USER_GET_BY_PK: &str = include_str!("queries/user/get_by_pk.sql");
 const USER_GET_BY_EMAIL_RAW: &str = include_str!("queries/user/get_by_email_raw.sql");
 const USER_LIST_FOR_WORKSPACE: &str = include_str!("queries/user/list_members_for_workspace.sql");
+const USER_LIST_MEMBER_ROLES_FOR_WORKSPACE: &str =
+    include_str!("queries/user/list_member_roles_for_workspace.sql");
+const USER_SET_WORKSPACE_ROLE: &str = include_str!("queries/user/set_workspace_role.sql");
+const USER_WORKSPACE_ROLE: &str = include_str!("queries/user/workspace_role.sql");
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -26,6 +35,8 @@ pub enum UserError {
     JwtKey(#[from] JwtKeyError),
     #[error("nats txn error: {0}")]
     Nats(#[from] NatsError),
+    #[error("user {0} is not a member of workspace {1}")]
+    NotAMember(UserPk, WorkspacePk),
     #[error("user not found in tenancy: {0} {1:?}")]
     NotFoundInTenancy(UserPk, Tenancy),
     #[error("no workspace in tenancy")]
@@ -36,6 +47,8 @@ pub enum UserError {
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
     Transactions(#[from] TransactionsError),
+    #[error("unknown workspace role: {0}")]
+    UnknownWorkspaceRole(String),
 }
 
 pub type UserResult<T> = Result<T, UserError>;
@@ -140,26 +153,108 @@ impl User {
         user_pk: &UserPk,
         workspace_pk: &WorkspacePk,
     ) -> UserResult<bool> {
-        let workspace_members =
-            User::list_members_for_workspace(ctx, workspace_pk.to_string()).await?;
+        Ok(Self::role_in_workspace(ctx, *user_pk, *workspace_pk)
+            .await?
+            .is_some())
+    }
 
-        let is_member = workspace_members.into_iter().any(|m| m.pk == *user_pk);
-        Ok(is_member)
+    /// Returns whether the user is a member of the workspace with a role granting `permission`.
+    pub async fn authorize_permission(
+        ctx: &DalContext,
+        user_pk: &UserPk,
+        workspace_pk: &WorkspacePk,
+        permission: WorkspacePermission,
+    ) -> UserResult<bool> {
+        Ok(Self::role_in_workspace(ctx, *user_pk, *workspace_pk)
+            .await?
+            .map(|role| role.allows(permission))
+            .unwrap_or(false))
     }
 
+    /// Makes the user a member of the workspace with the given [`WorkspaceRole`]. If the user is
+    /// already a member, their existing role is kept.
     pub async fn associate_workspace(
         &self,
         ctx: &DalContext,
         workspace_pk: WorkspacePk,
+        role: WorkspaceRole,
     ) -> UserResult<()> {
+        let is_member = Self::role_in_workspace(ctx, self.pk, workspace_pk)
+            .await?
+            .is_some();
+
         ctx.txns()
             .await?
             .pg()
             .execute(
-                "SELECT user_associate_workspace_v1($1, $2)",
-                &[&self.pk, &workspace_pk],
+                "SELECT user_associate_workspace_v2($1, $2, $3)",
+                &[&self.pk, &workspace_pk, &role.as_ref()],
+            )
+            .await?;
+
+        if !is_member {
+            record_membership_change(
+                ctx,
+                workspace_pk,
+                "workspace.member_added",
+                "Workspace member added",
+                serde_json::json!({ "userPk": self.pk, "role": role }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the [`WorkspaceRole`] of the user in the workspace, or `None` if they are not a
+    /// member of it.
+    pub async fn role_in_workspace(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<Option<WorkspaceRole>> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(USER_WORKSPACE_ROLE, &[&user_pk, &workspace_pk])
+            .await?;
+        match row {
+            Some(row) => {
+                let role: String = row.try_get("role")?;
+                Ok(Some(role_from_str(role)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Changes the [`WorkspaceRole`] of a member of the workspace.
+    pub async fn set_role_in_workspace(
+        ctx: &DalContext,
+        user_pk: UserPk,
+        workspace_pk: WorkspacePk,
+        role: WorkspaceRole,
+    ) -> UserResult<()> {
+        let updated = ctx
+            .txns()
+            .await?
+            .pg()
+            .execute(
+                USER_SET_WORKSPACE_ROLE,
+                &[&user_pk, &workspace_pk, &role.as_ref()],
             )
             .await?;
+        if updated == 0 {
+            return Err(UserError::NotAMember(user_pk, workspace_pk));
+        }
+
+        record_membership_change(
+            ctx,
+            workspace_pk,
+            "workspace.member_role_changed",
+            "Workspace member role changed",
+            serde_json::json!({ "userPk": user_pk, "role": role }),
+        )
+        .await?;
         Ok(())
     }
 
@@ -168,7 +263,8 @@ impl User {
         user_pk: UserPk,
         workspace_pkg: String,
     ) -> UserResult<()> {
-        ctx.txns()
+        let deleted = ctx
+            .txns()
             .await?
             .pg()
             .execute(
@@ -176,6 +272,17 @@ impl User {
                 &[&user_pk, &workspace_pkg],
             )
             .await?;
+
+        if let (true, Ok(workspace_pk)) = (deleted > 0, workspace_pkg.parse()) {
+            record_membership_change(
+                ctx,
+                workspace_pk,
+                "workspace.member_removed",
+                "Workspace member removed",
+                serde_json::json!({ "userPk": user_pk }),
+            )
+            .await?;
+        }
         Ok(())
     }
 
@@ -199,6 +306,52 @@ impl User {
 
         Ok(users)
     }
+
+    /// Lists the members of the workspace together with their [`WorkspaceRole`].
+    pub async fn list_member_roles_for_workspace(
+        ctx: &DalContext,
+        workspace_pk: WorkspacePk,
+    ) -> UserResult<Vec<(Self, WorkspaceRole)>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(USER_LIST_MEMBER_ROLES_FOR_WORKSPACE, &[&workspace_pk])
+            .await?;
+
+        let mut members = Vec::with_capacity(rows.len());
+        for row in rows.into_iter() {
+            let json: serde_json::Value = row.try_get("object")?;
+            let role: String = row.try_get("role")?;
+            members.push((serde_json::from_value(json)?, role_from_str(role)?));
+        }
+
+        Ok(members)
+    }
+}
+
+/// Records a change to the members of a workspace as a [`HistoryEvent`] of that workspace, so
+/// that it shows up in its audit log whatever the tenancy of the [`DalContext`].
+async fn record_membership_change(
+    ctx: &DalContext,
+    workspace_pk: WorkspacePk,
+    label: &str,
+    message: &str,
+    data: serde_json::Value,
+) -> UserResult<()> {
+    let _history_event = HistoryEvent::new(
+        &ctx.clone_with_new_tenancy(Tenancy::new(workspace_pk)),
+        label,
+        message,
+        &data,
+    )
+    .await?;
+    Ok(())
+}
+
+fn role_from_str(role: String) -> UserResult<WorkspaceRole> {
+    role.parse()
+        .map_err(|_| UserError::UnknownWorkspaceRole(role))
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
@@ -222,6 +375,18 @@ impl UserClaim {
         let claims = crate::jwt_key::validate_bearer_token(public_key, &token).await?;
         Ok(claims.custom)
     }
+
+    /// Returns when the session behind a bearer token was signed in, if the token says so. Used
+    /// to check that a user has recently authenticated again before a sensitive operation.
+    pub async fn issued_at_from_bearer_token(
+        public_key: JwtPublicSigningKey,
+        token: impl AsRef<str>,
+    ) -> UserResult<Option<DateTime<Utc>>> {
+        let claims = crate::jwt_key::validate_bearer_token(public_key, &token).await?;
+        Ok(claims
+            .issued_at
+            .and_then(|issued_at| Utc.timestamp_opt(issued_at.as_secs() as i64, 0).single()))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq)]
@@ -0,0 +1,159 @@
+//! This module contains [`WorkspaceRole`], the role a [`User`](crate::User) holds in each
+//! [`Workspace`](crate::Workspace) they belong to, and the [`WorkspacePermissions`](WorkspacePermission)
+//! each role grants.
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+
+/// What a member of a workspace is allowed to do in it.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WorkspacePermission {
+    /// Apply a change set to head
+    ApplyChangeSet,
+    /// Open change sets and make changes in them
+    EditChangeSet,
+    /// Install, upgrade, export and approve modules
+    InstallModules,
+    /// Create and revoke api tokens
+    ManageApiTokens,
+    /// Inspect, replay and discard jobs which failed permanently
+    ManageJobs,
+    /// Change the roles of the other members of the workspace, and sync its member list
+    ManageMembers,
+    /// Create and update secrets
+    ManageSecrets,
+    /// Change the workspace's settings, such as which module publishers it trusts and how fixes
+    /// which delete resources are confirmed
+    ManageWorkspace,
+    /// Read the workspace, its change sets and its components
+    Read,
+    /// Run fixes against real resources
+    RunFix,
+    /// View and export the workspace's audit log
+    ViewAuditLog,
+}
+
+/// The role of a member of a workspace. Every member has exactly one role per workspace.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WorkspaceRole {
+    /// Can do everything, including managing secrets, modules, api tokens, failed jobs, the
+    /// workspace's settings and the roles of other members
+    Admin,
+    /// Can do everything an editor can, and apply change sets and run fixes
+    Approver,
+    /// Can open change sets and make changes in them
+    Editor,
+    /// Can look, but not change anything
+    Viewer,
+}
+
+impl WorkspaceRole {
+    /// Returns every [`WorkspacePermission`] this role grants.
+    pub fn permissions(&self) -> &'static [WorkspacePermission] {
+        match self {
+            Self::Admin => &[
+                WorkspacePermission::ApplyChangeSet,
+                WorkspacePermission::EditChangeSet,
+                WorkspacePermission::InstallModules,
+                WorkspacePermission::ManageApiTokens,
+                WorkspacePermission::ManageJobs,
+                WorkspacePermission::ManageMembers,
+                WorkspacePermission::ManageSecrets,
+                WorkspacePermission::ManageWorkspace,
+                WorkspacePermission::Read,
+                WorkspacePermission::RunFix,
+                WorkspacePermission::ViewAuditLog,
+            ],
+            Self::Approver => &[
+                WorkspacePermission::ApplyChangeSet,
+                WorkspacePermission::EditChangeSet,
+                WorkspacePermission::Read,
+                WorkspacePermission::RunFix,
+            ],
+            Self::Editor => &[
+                WorkspacePermission::EditChangeSet,
+                WorkspacePermission::Read,
+            ],
+            Self::Viewer => &[WorkspacePermission::Read],
+        }
+    }
+
+    /// Returns whether this role grants `permission`.
+    pub fn allows(&self, permission: WorkspacePermission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl Default for WorkspaceRole {
+    fn default() -> Self {
+        Self::Editor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn admin_is_allowed_everything() {
+        for permission in WorkspacePermission::iter() {
+            assert!(WorkspaceRole::Admin.allows(permission));
+        }
+    }
+
+    #[test]
+    fn viewer_is_allowed_to_read_only() {
+        for permission in WorkspacePermission::iter() {
+            assert_eq!(
+                permission == WorkspacePermission::Read,
+                WorkspaceRole::Viewer.allows(permission)
+            );
+        }
+    }
+
+    #[test]
+    fn role_round_trips_through_its_string_form() {
+        for role in WorkspaceRole::iter() {
+            assert_eq!(
+                role,
+                role.as_ref()
+                    .parse()
+                    .expect("unable to parse workspace role")
+            );
+        }
+    }
+}
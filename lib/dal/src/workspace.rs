@@ -1,13 +1,15 @@
 use serde::{Deserialize, Serialize};
+use si_crypto::VerifyingKey;
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
 use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    pk, standard_model, standard_model_accessor_ro, DalContext, HistoryActor, HistoryEvent,
-    HistoryEventError, KeyPair, KeyPairError, StandardModelError, Tenancy, Timestamp,
-    TransactionsError, User, UserError, UserPk,
+    pk, standard_model, standard_model_accessor_ro, AuditLogError, AuditLogSinkConfig, DalContext,
+    FixConfirmation, HistoryActor, HistoryEvent, HistoryEventError, KeyPair, KeyPairError,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, User, UserError, UserPk,
+    WorkspaceRole,
 };
 
 const WORKSPACE_GET_BY_PK: &str = include_str!("queries/workspace/get_by_pk.sql");
@@ -17,6 +19,8 @@ const WORKSPACE_LIST_FOR_USER: &str = include_str!("queries/workspace/list_for_u
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum WorkspaceError {
+    #[error(transparent)]
+    AuditLog(#[from] Box<AuditLogError>),
     #[error(transparent)]
     HistoryEvent(#[from] HistoryEventError),
     #[error(transparent)]
@@ -52,6 +56,14 @@ pub struct WorkspaceSignup {
 pub struct Workspace {
     pk: WorkspacePk,
     name: String,
+    /// If set, only modules signed by one of the trusted publisher keys can be installed.
+    require_signed_modules: bool,
+    /// The Base64 encoded publisher keys whose module signatures are trusted.
+    trusted_publisher_keys: Vec<String>,
+    /// If set, fix batches which delete resources must be confirmed this way before they run.
+    destructive_fix_confirmation: Option<FixConfirmation>,
+    /// If set, the workspace's audit log entries are forwarded here as they are recorded.
+    audit_log_sink: Option<AuditLogSinkConfig>,
     #[serde(flatten)]
     timestamp: Timestamp,
 }
@@ -181,7 +193,8 @@ impl Workspace {
             None::<&str>,
         )
         .await?;
-        user.associate_workspace(ctx, *workspace.pk()).await?;
+        user.associate_workspace(ctx, *workspace.pk(), WorkspaceRole::Admin)
+            .await?;
 
         ctx.update_history_actor(HistoryActor::User(user.pk()));
 
@@ -223,5 +236,144 @@ impl Workspace {
         }
     }
 
+    pub fn require_signed_modules(&self) -> bool {
+        self.require_signed_modules
+    }
+
+    pub fn trusted_publisher_keys(&self) -> &[String] {
+        &self.trusted_publisher_keys
+    }
+
+    pub async fn set_require_signed_modules(
+        &mut self,
+        ctx: &DalContext,
+        require_signed_modules: bool,
+    ) -> WorkspaceResult<()> {
+        self.update_module_trust(
+            ctx,
+            require_signed_modules,
+            self.trusted_publisher_keys.clone(),
+        )
+        .await
+    }
+
+    /// Trusts modules signed by `key`. Trusting an already trusted key does nothing.
+    pub async fn trust_publisher_key(
+        &mut self,
+        ctx: &DalContext,
+        key: &VerifyingKey,
+    ) -> WorkspaceResult<()> {
+        let key = key.encode();
+        if self.trusted_publisher_keys.contains(&key) {
+            return Ok(());
+        }
+
+        let mut trusted_publisher_keys = self.trusted_publisher_keys.clone();
+        trusted_publisher_keys.push(key);
+
+        self.update_module_trust(ctx, self.require_signed_modules, trusted_publisher_keys)
+            .await
+    }
+
+    pub async fn distrust_publisher_key(
+        &mut self,
+        ctx: &DalContext,
+        key: &VerifyingKey,
+    ) -> WorkspaceResult<()> {
+        let key = key.encode();
+        let trusted_publisher_keys = self
+            .trusted_publisher_keys
+            .iter()
+            .filter(|trusted_key| **trusted_key != key)
+            .cloned()
+            .collect();
+
+        self.update_module_trust(ctx, self.require_signed_modules, trusted_publisher_keys)
+            .await
+    }
+
+    async fn update_module_trust(
+        &mut self,
+        ctx: &DalContext,
+        require_signed_modules: bool,
+        trusted_publisher_keys: Vec<String>,
+    ) -> WorkspaceResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_update_module_trust_v1($1, $2, $3)",
+                &[&self.pk, &require_signed_modules, &trusted_publisher_keys],
+            )
+            .await?;
+
+        *self = standard_model::object_from_row(row)?;
+
+        Ok(())
+    }
+
+    pub fn destructive_fix_confirmation(&self) -> Option<FixConfirmation> {
+        self.destructive_fix_confirmation
+    }
+
+    /// Requires fix batches which delete resources to be confirmed before they run, or stops
+    /// requiring it with `None`.
+    pub async fn set_destructive_fix_confirmation(
+        &mut self,
+        ctx: &DalContext,
+        destructive_fix_confirmation: Option<FixConfirmation>,
+    ) -> WorkspaceResult<()> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_update_destructive_fix_confirmation_v1($1, $2)",
+                &[
+                    &self.pk,
+                    &destructive_fix_confirmation
+                        .as_ref()
+                        .map(AsRef::<str>::as_ref),
+                ],
+            )
+            .await?;
+
+        *self = standard_model::object_from_row(row)?;
+
+        Ok(())
+    }
+
+    pub fn audit_log_sink(&self) -> Option<&AuditLogSinkConfig> {
+        self.audit_log_sink.as_ref()
+    }
+
+    /// Forwards the workspace's audit log entries to `audit_log_sink` as they are recorded, or
+    /// stops forwarding them with `None`. The sink must pass
+    /// [validation](AuditLogSinkConfig::validate).
+    pub async fn set_audit_log_sink(
+        &mut self,
+        ctx: &DalContext,
+        audit_log_sink: Option<AuditLogSinkConfig>,
+    ) -> WorkspaceResult<()> {
+        if let Some(audit_log_sink) = &audit_log_sink {
+            audit_log_sink.validate().await.map_err(Box::new)?;
+        }
+        let audit_log_sink = audit_log_sink.map(serde_json::to_value).transpose()?;
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "SELECT object FROM workspace_update_audit_log_sink_v1($1, $2)",
+                &[&self.pk, &audit_log_sink],
+            )
+            .await?;
+
+        *self = standard_model::object_from_row(row)?;
+
+        Ok(())
+    }
+
     standard_model_accessor_ro!(name, String);
 }
@@ -14,6 +14,7 @@ use crate::{
     fix::{batch::FixBatchReturn, FixReturn},
     func::binding::LogLinePayload,
     qualification::QualificationCheckPayload,
+    secret::SecretsExpiringPayload,
     status::StatusMessage,
     user::{CursorPayload, OnlinePayload},
     AttributeValueId, ChangeSetPk, ComponentId, DalContext, PropId, SchemaPk, SocketId,
@@ -66,6 +67,7 @@ pub enum WsPayload {
     Online(OnlinePayload),
     ResourceRefreshed(ResourceRefreshedPayload),
     SchemaCreated(SchemaPk),
+    SecretsExpiring(SecretsExpiringPayload),
     StatusUpdate(StatusMessage),
     WorkspaceExported(WorkspaceExportPayload),
     WorkspaceImportBeginApprovalProcess(WorkspaceImportApprovalActorPayload),
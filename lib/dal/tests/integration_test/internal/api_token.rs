@@ -0,0 +1,253 @@
+use dal::{
+    component::ComponentKind, AccessBuilder, ApiToken, ApiTokenError, ChangeSet, ChangeSetError,
+    ChangeSetPk, DalContext, DalContextBuilder, HistoryActor, Schema, StandardModel, Tenancy,
+    TransactionsError, User, UserPk, Visibility, WorkspacePermission, WorkspaceRole,
+    WorkspaceSignup,
+};
+use dal_test::test;
+
+#[test]
+async fn create_find_and_revoke(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+
+    let change_set_pk = ChangeSetPk::generate();
+    let (api_token, raw_token) = ApiToken::new(
+        ctx,
+        "ci",
+        vec![WorkspacePermission::RunFix],
+        Some(vec![change_set_pk]),
+    )
+    .await
+    .expect("cannot create api token");
+    assert!(raw_token.starts_with(dal::api_token::API_TOKEN_PREFIX));
+    assert!(api_token.allows(WorkspacePermission::RunFix));
+    assert!(api_token.allows(WorkspacePermission::Read));
+    assert!(!api_token.allows(WorkspacePermission::ApplyChangeSet));
+    assert_eq!(Some(&[change_set_pk][..]), api_token.change_set_pks());
+    assert_eq!(
+        HistoryActor::ApiToken {
+            api_token_pk: api_token.pk(),
+            user_pk: nw.user.pk(),
+        },
+        api_token.history_actor()
+    );
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let found = ApiToken::find_by_token(ctx, &raw_token)
+        .await
+        .expect("cannot find api token")
+        .expect("api token not found");
+    assert_eq!(api_token.pk(), found.pk());
+    assert!(found.last_used_at().is_some());
+    assert!(ApiToken::find_by_token(ctx, format!("{raw_token}x"))
+        .await
+        .expect("cannot find api token")
+        .is_none());
+
+    let revoked = ApiToken::revoke(ctx, api_token.pk())
+        .await
+        .expect("cannot revoke api token");
+    assert!(revoked.revoked_at().is_some());
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    assert!(ApiToken::find_by_token(ctx, &raw_token)
+        .await
+        .expect("cannot find api token")
+        .is_none());
+    assert_eq!(
+        vec![api_token.pk()],
+        ApiToken::list(ctx)
+            .await
+            .expect("cannot list api tokens")
+            .iter()
+            .map(ApiToken::pk)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+async fn permissions_are_limited_by_role(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let user = User::new(
+        ctx,
+        UserPk::generate(),
+        "funky",
+        "bobotclown@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    user.associate_workspace(ctx, *nw.workspace.pk(), WorkspaceRole::Editor)
+        .await
+        .expect("cannot associate user with workspace");
+
+    ctx.update_history_actor(HistoryActor::User(user.pk()));
+    ApiToken::new(ctx, "edits", vec![WorkspacePermission::EditChangeSet], None)
+        .await
+        .expect("an editor can grant editing");
+    assert!(matches!(
+        ApiToken::new(ctx, "fixes", vec![WorkspacePermission::RunFix], None).await,
+        Err(ApiTokenError::PermissionNotGranted(
+            _,
+            WorkspacePermission::RunFix
+        ))
+    ));
+
+    ctx.update_history_actor(HistoryActor::SystemInit);
+    assert!(matches!(
+        ApiToken::new(ctx, "system", Vec::new(), None).await,
+        Err(ApiTokenError::NotCreatedByUser)
+    ));
+}
+
+#[test]
+async fn change_set_scope_forbids_writing_to_head(
+    mut builder: DalContextBuilder,
+    nw: &WorkspaceSignup,
+) {
+    let change_set_pk = ChangeSetPk::generate();
+    builder.set_change_set_scope(vec![change_set_pk]);
+    let access_builder = AccessBuilder::new(
+        Tenancy::new(*nw.workspace.pk()),
+        HistoryActor::User(nw.user.pk()),
+    );
+
+    let head_ctx = builder
+        .build_head(access_builder)
+        .await
+        .expect("cannot build head context");
+    assert!(matches!(
+        head_ctx.blocking_commit().await,
+        Err(TransactionsError::HeadNotInScope)
+    ));
+
+    assert!(matches!(
+        builder
+            .build(
+                access_builder
+                    .clone()
+                    .build(Visibility::new_change_set(ChangeSetPk::generate(), false))
+            )
+            .await,
+        Err(TransactionsError::ChangeSetNotInScope(_))
+    ));
+
+    let change_set_ctx = builder
+        .build(access_builder.build(Visibility::new_change_set(change_set_pk, false)))
+        .await
+        .expect("cannot build change set context");
+    change_set_ctx
+        .blocking_commit()
+        .await
+        .expect("cannot commit in scoped change set");
+}
+
+#[test]
+async fn change_set_scope_forbids_writing_to_head_before_switching_visibility(
+    mut builder: DalContextBuilder,
+    nw: &WorkspaceSignup,
+) {
+    let change_set_pk = ChangeSetPk::generate();
+    builder.set_change_set_scope(vec![change_set_pk]);
+    let access_builder = AccessBuilder::new(
+        Tenancy::new(*nw.workspace.pk()),
+        HistoryActor::User(nw.user.pk()),
+    );
+    let change_set_visibility = Visibility::new_change_set(change_set_pk, false);
+
+    // Reading head through a clone is fine
+    let ctx = builder
+        .build(access_builder.build(change_set_visibility))
+        .await
+        .expect("cannot build change set context");
+    Schema::list(&ctx.clone_with_head())
+        .await
+        .expect("cannot list schemas on head");
+    Schema::new(&ctx, "scoped", &ComponentKind::Standard)
+        .await
+        .expect("cannot create schema in scoped change set");
+    ctx.blocking_commit()
+        .await
+        .expect("cannot commit in scoped change set after reading head");
+
+    // Writing to head and then switching to a change set in scope is not
+    let mut ctx = builder
+        .build_head(access_builder)
+        .await
+        .expect("cannot build head context");
+    Schema::new(&ctx, "head", &ComponentKind::Standard)
+        .await
+        .expect("cannot create schema");
+    ctx.update_visibility(change_set_visibility);
+    assert!(matches!(
+        ctx.blocking_commit().await,
+        Err(TransactionsError::HeadNotInScope)
+    ));
+
+    // ...and neither is writing to head through a clone
+    let ctx = builder
+        .build(access_builder.build(change_set_visibility))
+        .await
+        .expect("cannot build change set context");
+    Schema::new(&ctx.clone_with_head(), "head", &ComponentKind::Standard)
+        .await
+        .expect("cannot create schema");
+    assert!(matches!(
+        ctx.blocking_commit().await,
+        Err(TransactionsError::HeadNotInScope)
+    ));
+}
+
+#[test]
+async fn change_set_scope_allows_applying_scoped_change_sets(
+    mut builder: DalContextBuilder,
+    nw: &WorkspaceSignup,
+) {
+    let access_builder = AccessBuilder::new(
+        Tenancy::new(*nw.workspace.pk()),
+        HistoryActor::User(nw.user.pk()),
+    );
+
+    let ctx = builder
+        .build_head(access_builder)
+        .await
+        .expect("cannot build head context");
+    let mut scoped_change_set = ChangeSet::new(&ctx, "scoped", None)
+        .await
+        .expect("cannot create change set");
+    let mut other_change_set = ChangeSet::new(&ctx, "other", None)
+        .await
+        .expect("cannot create change set");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    builder.set_change_set_scope(vec![scoped_change_set.pk]);
+
+    let mut ctx = builder
+        .build_head(access_builder)
+        .await
+        .expect("cannot build head context");
+    assert!(matches!(
+        other_change_set.apply(&mut ctx).await,
+        Err(ChangeSetError::Transactions(
+            TransactionsError::ChangeSetNotInScope(_)
+        ))
+    ));
+
+    let mut ctx = builder
+        .build_head(access_builder)
+        .await
+        .expect("cannot build head context");
+    scoped_change_set
+        .apply(&mut ctx)
+        .await
+        .expect("cannot apply scoped change set");
+    ctx.blocking_commit()
+        .await
+        .expect("cannot commit applying scoped change set");
+}
@@ -0,0 +1,131 @@
+use chrono::{Duration, Utc};
+use dal::{
+    AuditLogEntry, AuditLogKind, AuditLogSinkConfig, DalContext, HistoryEvent, User, UserPk,
+    WorkspaceRole, WorkspaceSignup,
+};
+use dal_test::test;
+
+#[test]
+async fn list(ctx: &DalContext, nw: &WorkspaceSignup) {
+    HistoryEvent::new(
+        ctx,
+        "change_set.apply",
+        "Change Set applied",
+        &serde_json::json!({}),
+    )
+    .await
+    .expect("cannot create a new history event");
+
+    let user = User::new(
+        ctx,
+        UserPk::generate(),
+        "member",
+        "member@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    user.associate_workspace(ctx, *nw.workspace.pk(), WorkspaceRole::Viewer)
+        .await
+        .expect("cannot associate user with workspace");
+    User::set_role_in_workspace(ctx, user.pk(), *nw.workspace.pk(), WorkspaceRole::Editor)
+        .await
+        .expect("cannot set role of user");
+
+    let entries = AuditLogEntry::list(ctx, Some(Utc::now() - Duration::hours(1)), None)
+        .await
+        .expect("cannot list audit log");
+    let labels: Vec<(AuditLogKind, &str)> = entries
+        .iter()
+        .filter(|entry| entry.kind != AuditLogKind::HistoryEvent)
+        .map(|entry| (entry.kind, entry.label.as_str()))
+        .collect();
+    for expected in [
+        (AuditLogKind::ChangeSetApply, "change_set.apply"),
+        (AuditLogKind::MembershipChange, "workspace.member_added"),
+        (
+            AuditLogKind::MembershipChange,
+            "workspace.member_role_changed",
+        ),
+    ] {
+        assert!(labels.contains(&expected), "{expected:?} not in {labels:?}");
+    }
+    assert!(entries
+        .iter()
+        .all(|entry| entry.workspace_pk == Some(*nw.workspace.pk())));
+    assert!(entries
+        .windows(2)
+        .all(|pair| pair[0].occurred_at <= pair[1].occurred_at));
+
+    let entries = AuditLogEntry::list(ctx, None, Some(Utc::now() - Duration::hours(1)))
+        .await
+        .expect("cannot list audit log");
+    assert!(entries.is_empty());
+}
+
+#[test]
+async fn list_page(ctx: &DalContext) {
+    for index in 0..5 {
+        HistoryEvent::new(
+            ctx,
+            "change_set.apply",
+            format!("Change Set {index} applied"),
+            &serde_json::json!({}),
+        )
+        .await
+        .expect("cannot create a new history event");
+    }
+
+    let entries = AuditLogEntry::list(ctx, None, None)
+        .await
+        .expect("cannot list audit log");
+    assert!(entries.len() > 5);
+
+    let mut paged_entries = Vec::new();
+    let mut after = None;
+    loop {
+        let page = AuditLogEntry::list_page(ctx, None, None, after.as_ref(), 2)
+            .await
+            .expect("cannot list audit log page");
+        assert!(page.entries.len() <= 2);
+        paged_entries.extend(page.entries);
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(entries, paged_entries);
+}
+
+#[test]
+async fn set_audit_log_sink(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let mut workspace = nw.workspace.clone();
+    assert_eq!(None, workspace.audit_log_sink());
+
+    // Sinks within the internal network are refused
+    assert!(workspace
+        .set_audit_log_sink(
+            ctx,
+            Some(AuditLogSinkConfig::Syslog {
+                address: "127.0.0.1:514".to_owned(),
+            }),
+        )
+        .await
+        .is_err());
+    assert_eq!(None, workspace.audit_log_sink());
+
+    let sink = AuditLogSinkConfig::Syslog {
+        address: "1.1.1.1:514".to_owned(),
+    };
+    workspace
+        .set_audit_log_sink(ctx, Some(sink.clone()))
+        .await
+        .expect("cannot set audit log sink");
+    assert_eq!(Some(&sink), workspace.audit_log_sink());
+
+    workspace
+        .set_audit_log_sink(ctx, None)
+        .await
+        .expect("cannot unset audit log sink");
+    assert_eq!(None, workspace.audit_log_sink());
+}
@@ -0,0 +1,110 @@
+use pretty_assertions_sorted::assert_eq;
+
+use dal::action_prototype::ActionKind;
+use dal::{
+    ActionPrototype, ActionPrototypeContext, ComponentId, DalContext, Fix, FixBatch,
+    FixConfirmation, FixError, FuncId, HistoryActor, StandardModel, User, UserPk, Workspace,
+    WorkspaceSignup,
+};
+use dal_test::test;
+
+async fn require_confirmation(
+    ctx: &DalContext,
+    nw: &WorkspaceSignup,
+    confirmation: FixConfirmation,
+) {
+    let mut workspace = Workspace::get_by_pk(ctx, nw.workspace.pk())
+        .await
+        .expect("cannot get workspace")
+        .expect("workspace not found");
+    workspace
+        .set_destructive_fix_confirmation(ctx, Some(confirmation))
+        .await
+        .expect("cannot set destructive fix confirmation");
+    assert_eq!(Some(confirmation), workspace.destructive_fix_confirmation());
+}
+
+async fn new_batch(ctx: &DalContext, nw: &WorkspaceSignup, kind: ActionKind) -> FixBatch {
+    let prototype =
+        ActionPrototype::new(ctx, FuncId::NONE, kind, ActionPrototypeContext::default())
+            .await
+            .expect("unable to create action prototype");
+    let batch = FixBatch::new(ctx, nw.user.email(), "")
+        .await
+        .expect("cannot create fix batch");
+    Fix::new(ctx, *batch.id(), ComponentId::NONE, *prototype.id())
+        .await
+        .expect("cannot create fix");
+    batch
+}
+
+#[test]
+async fn destructive_batch_requires_second_approver(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    require_confirmation(ctx, nw, FixConfirmation::SecondApprover).await;
+
+    let mut batch = new_batch(ctx, nw, ActionKind::Delete).await;
+    assert_eq!(
+        Some(FixConfirmation::SecondApprover),
+        batch
+            .require_confirmation_if_destructive(ctx)
+            .await
+            .expect("cannot check whether batch requires confirmation")
+    );
+    assert!(batch.is_pending_confirmation());
+    assert!(matches!(
+        batch.stamp_started(ctx).await,
+        Err(FixError::PendingConfirmation(_))
+    ));
+
+    assert!(matches!(
+        batch.confirm(ctx).await,
+        Err(FixError::ConfirmedByAuthor(_))
+    ));
+
+    let approver = User::new(
+        ctx,
+        UserPk::generate(),
+        "approver",
+        "approver@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    ctx.update_history_actor(HistoryActor::User(approver.pk()));
+    batch.confirm(ctx).await.expect("cannot confirm fix batch");
+    assert!(!batch.is_pending_confirmation());
+    assert_eq!(Some(&approver.pk()), batch.confirmed_by());
+    assert!(matches!(
+        batch.confirm(ctx).await,
+        Err(FixError::AlreadyConfirmed(_))
+    ));
+
+    batch
+        .stamp_started(ctx)
+        .await
+        .expect("cannot start confirmed fix batch");
+}
+
+#[test]
+async fn non_destructive_batch_does_not_require_confirmation(
+    ctx: &mut DalContext,
+    nw: &WorkspaceSignup,
+) {
+    ctx.update_history_actor(HistoryActor::User(nw.user.pk()));
+    require_confirmation(ctx, nw, FixConfirmation::Reauthenticate).await;
+
+    let mut batch = new_batch(ctx, nw, ActionKind::Create).await;
+    assert_eq!(
+        None,
+        batch
+            .require_confirmation_if_destructive(ctx)
+            .await
+            .expect("cannot check whether batch requires confirmation")
+    );
+    assert!(!batch.is_pending_confirmation());
+    assert!(matches!(
+        batch.confirm(ctx).await,
+        Err(FixError::ConfirmationNotRequired(_))
+    ));
+}
@@ -0,0 +1,264 @@
+use std::time::Duration;
+
+use dal::{
+    job::{
+        consumer::JobInfo,
+        definition::RefreshJob,
+        processor::JETSTREAM_REPLY_MAILBOX_HEADER,
+        producer::{BlockingJobResult, JobProducer},
+    },
+    DalContext, JetStreamProcessor, JobQueueProcessor,
+};
+use dal_test::test;
+use futures::StreamExt;
+use pinga_server::server::{JetStreamDelivery, JobDisposition};
+use si_data_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy},
+};
+
+const ACK_WAIT: Duration = Duration::from_secs(1);
+const FETCH_EXPIRES: Duration = Duration::from_secs(3);
+
+async fn consumer(
+    processor: &JetStreamProcessor,
+    max_deliver: i64,
+) -> jetstream::consumer::Consumer<pull::Config> {
+    processor
+        .get_or_create_stream()
+        .await
+        .expect("could not create stream")
+        .create_consumer(pull::Config {
+            durable_name: Some("test".to_owned()),
+            ack_policy: AckPolicy::Explicit,
+            ack_wait: ACK_WAIT,
+            max_deliver,
+            ..Default::default()
+        })
+        .await
+        .expect("could not create consumer")
+}
+
+async fn next_message(
+    consumer: &jetstream::consumer::Consumer<pull::Config>,
+) -> Option<jetstream::Message> {
+    consumer
+        .batch()
+        .max_messages(1)
+        .expires(FETCH_EXPIRES)
+        .messages()
+        .await
+        .expect("could not fetch messages")
+        .next()
+        .await
+        .map(|message| message.expect("could not receive message"))
+}
+
+async fn delete_stream(ctx: &DalContext, processor: &JetStreamProcessor) {
+    ctx.nats_conn()
+        .jetstream()
+        .delete_stream(processor.stream_name())
+        .await
+        .expect("could not delete stream");
+}
+
+#[test]
+async fn jobs_are_redelivered_until_acknowledged(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 2).await;
+
+    let job: Box<dyn JobProducer + Send + Sync> =
+        RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]);
+    let expected_arg = job.arg().expect("could not get job arg");
+    processor.enqueue_job(job, ctx).await;
+    processor
+        .process_queue()
+        .await
+        .expect("could not process queue");
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    let job_info: JobInfo =
+        serde_json::from_slice(&message.payload).expect("could not deserialize job");
+    assert_eq!("RefreshJob", job_info.kind);
+    assert_eq!(expected_arg, job_info.arg);
+    assert!(!job_info.blocking);
+    assert_eq!(1, message.info().expect("no message info").delivered);
+
+    // Not acknowledging the job in time gets it redelivered...
+    tokio::time::sleep(ACK_WAIT).await;
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not redelivered");
+    let redelivered: JobInfo =
+        serde_json::from_slice(&message.payload).expect("could not deserialize job");
+    assert_eq!(job_info.id, redelivered.id);
+    assert_eq!(2, message.info().expect("no message info").delivered);
+
+    // ...until max deliver is reached
+    tokio::time::sleep(ACK_WAIT).await;
+    assert!(next_message(&consumer).await.is_none());
+
+    delete_stream(ctx, &processor).await;
+}
+
+#[test]
+async fn acknowledged_jobs_are_removed(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 5).await;
+
+    processor
+        .enqueue_job(
+            RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+            ctx,
+        )
+        .await;
+    processor
+        .process_queue()
+        .await
+        .expect("could not process queue");
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    message.ack().await.expect("could not ack");
+
+    tokio::time::sleep(ACK_WAIT).await;
+    assert!(next_message(&consumer).await.is_none());
+    let mut stream = processor
+        .get_or_create_stream()
+        .await
+        .expect("could not get stream");
+    let info = stream.info().await.expect("could not get stream info");
+    assert_eq!(0, info.state.messages);
+
+    delete_stream(ctx, &processor).await;
+}
+
+#[test]
+async fn blocking_jobs_reply_through_mailbox_header(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 5).await;
+
+    let blocking = {
+        let processor = processor.clone();
+        let job = RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]);
+        tokio::spawn(async move { processor.block_on_job(job).await })
+    };
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    let job_info: JobInfo =
+        serde_json::from_slice(&message.payload).expect("could not deserialize job");
+    assert!(job_info.blocking);
+    let mailbox = message
+        .headers
+        .as_ref()
+        .and_then(|headers| headers.get(JETSTREAM_REPLY_MAILBOX_HEADER))
+        .expect("no reply mailbox header")
+        .as_str()
+        .to_owned();
+
+    let result: BlockingJobResult = Ok(());
+    ctx.nats_conn()
+        .publish(
+            mailbox,
+            serde_json::to_vec(&result).expect("could not serialize result"),
+        )
+        .await
+        .expect("could not reply");
+    message.ack().await.expect("could not ack");
+
+    blocking
+        .await
+        .expect("blocking task panicked")
+        .expect("blocking job failed");
+
+    delete_stream(ctx, &processor).await;
+}
+
+async fn enqueue_refresh_job(ctx: &DalContext, processor: &JetStreamProcessor) {
+    processor
+        .enqueue_job(
+            RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+            ctx,
+        )
+        .await;
+    processor
+        .process_queue()
+        .await
+        .expect("could not process queue");
+}
+
+#[test]
+async fn done_deliveries_are_acknowledged(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 5).await;
+    enqueue_refresh_job(ctx, &processor).await;
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    let delivery = JetStreamDelivery::new(message, ACK_WAIT / 2, 5);
+    assert!(!delivery.is_last_delivery());
+    delivery.settle(JobDisposition::Done).await;
+
+    tokio::time::sleep(ACK_WAIT).await;
+    assert!(next_message(&consumer).await.is_none());
+
+    delete_stream(ctx, &processor).await;
+}
+
+#[test]
+async fn redelivered_deliveries_are_retried_until_the_last(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 2).await;
+    enqueue_refresh_job(ctx, &processor).await;
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    let delivery = JetStreamDelivery::new(message, ACK_WAIT / 2, 2);
+    assert!(!delivery.is_last_delivery());
+    delivery.settle(JobDisposition::Redeliver).await;
+
+    // A nak gets the job redelivered without waiting for the ack wait to lapse
+    let message = tokio::time::timeout(ACK_WAIT / 2, next_message(&consumer))
+        .await
+        .expect("job was not redelivered right away")
+        .expect("job was not redelivered");
+    assert_eq!(2, message.info().expect("no message info").delivered);
+    let delivery = JetStreamDelivery::new(message, ACK_WAIT / 2, 2);
+    assert!(delivery.is_last_delivery());
+    delivery.settle(JobDisposition::Done).await;
+
+    delete_stream(ctx, &processor).await;
+}
+
+#[test]
+async fn kept_alive_deliveries_are_not_redelivered(ctx: &DalContext) {
+    let processor = JetStreamProcessor::new(ctx.nats_conn().clone());
+    let consumer = consumer(&processor, 5).await;
+    enqueue_refresh_job(ctx, &processor).await;
+
+    let message = next_message(&consumer)
+        .await
+        .expect("job was not published");
+    let delivery = JetStreamDelivery::new(message, ACK_WAIT / 4, 5);
+
+    // A job running for several times the ack wait isn't handed to another consumer meanwhile...
+    let (_, redelivered) = tokio::join!(
+        delivery.keep_alive(tokio::time::sleep(ACK_WAIT * 3)),
+        next_message(&consumer),
+    );
+    assert!(redelivered.is_none());
+    delivery.settle(JobDisposition::Done).await;
+
+    // ...nor once it is done with
+    tokio::time::sleep(ACK_WAIT).await;
+    assert!(next_message(&consumer).await.is_none());
+
+    delete_stream(ctx, &processor).await;
+}
@@ -0,0 +1,73 @@
+use std::io;
+
+use dal::{
+    job::{
+        consumer::{JobConsumerError, JobInfo},
+        definition::RefreshJob,
+        producer::JobProducer,
+    },
+    DalContext, JobDeadLetter,
+};
+use dal_test::test;
+
+fn failed_job(ctx: &DalContext) -> (JobInfo, JobConsumerError) {
+    let job: Box<dyn JobProducer + Send + Sync> =
+        RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]);
+    let job_info = JobInfo::new(job).expect("could not build job info");
+    let err = JobConsumerError::Io(io::Error::new(io::ErrorKind::Other, "disk on fire"));
+    (job_info, err)
+}
+
+#[test]
+async fn new_and_list(ctx: &DalContext) {
+    let (job_info, err) = failed_job(ctx);
+    let dead_letter = JobDeadLetter::new(ctx, &job_info, &err)
+        .await
+        .expect("could not create dead letter");
+
+    assert_eq!(job_info.id, dead_letter.job_id());
+    assert_eq!("RefreshJob", dead_letter.kind());
+    assert_eq!(&job_info.arg, dead_letter.arg());
+    assert_eq!(job_info.visibility, dead_letter.visibility());
+    assert_eq!(&["disk on fire".to_owned()], dead_letter.error_chain());
+
+    let replayed = dead_letter.job_info();
+    assert_ne!(job_info.id, replayed.id);
+    assert_eq!(job_info.kind, replayed.kind);
+    assert_eq!(job_info.arg, replayed.arg);
+
+    let found = JobDeadLetter::get_by_pk(ctx, dead_letter.pk())
+        .await
+        .expect("could not get dead letter");
+    assert_eq!(dead_letter, found);
+    assert_eq!(
+        vec![dead_letter],
+        JobDeadLetter::list(ctx).await.expect("could not list")
+    );
+}
+
+#[test]
+async fn replay_and_discard(ctx: &DalContext) {
+    let (job_info, err) = failed_job(ctx);
+    let replayed = JobDeadLetter::new(ctx, &job_info, &err)
+        .await
+        .expect("could not create dead letter");
+    let discarded = JobDeadLetter::new(ctx, &job_info, &err)
+        .await
+        .expect("could not create dead letter");
+    assert_eq!(
+        2,
+        JobDeadLetter::list(ctx)
+            .await
+            .expect("could not list")
+            .len()
+    );
+
+    replayed.replay(ctx).await.expect("could not replay");
+    discarded.discard(ctx).await.expect("could not discard");
+
+    assert!(JobDeadLetter::list(ctx)
+        .await
+        .expect("could not list")
+        .is_empty());
+}
@@ -0,0 +1,100 @@
+use chrono::{Duration, Timelike, Utc};
+use dal::{job::definition::RefreshJob, CronSchedule, DalContext, JobSchedule};
+use dal_test::test;
+
+#[test]
+async fn delayed_and_cron_schedules_are_claimed_at_most_once(ctx: &DalContext) {
+    // A daily schedule about half a day away, so that it never fires during the test, and so
+    // that its first run is never close to a new year or day like a fixed expression could be
+    let hour = (Utc::now().hour() + 12) % 24;
+    let cron: CronSchedule = format!("0 {hour} * * *")
+        .parse()
+        .expect("could not parse cron");
+    let repeating = JobSchedule::new_cron(
+        ctx,
+        RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+        cron.clone(),
+    )
+    .await
+    .expect("could not create cron schedule");
+    let first_run = repeating.next_run_at();
+    assert_eq!(cron.next_after(Utc::now()), Some(first_run));
+
+    // Due between the first and second run of the cron schedule
+    let run_at = first_run + Duration::hours(1);
+    let delayed = JobSchedule::new_delayed(
+        ctx,
+        RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+        run_at,
+    )
+    .await
+    .expect("could not create delayed schedule");
+    assert_eq!(run_at, delayed.next_run_at());
+    assert!(delayed.cron().is_none());
+
+    // Claiming happens outside of our transaction, so it only sees committed schedules
+    ctx.commit().await.expect("could not commit");
+
+    // Only this test's own schedules are claimed, leaving those of other tests and the system
+    // alone
+    let workspace_pk = ctx.tenancy().workspace_pk();
+    assert!(workspace_pk.is_some());
+    let claim = |now| async move {
+        JobSchedule::claim_due_at(ctx.pg_pool(), "test", now, workspace_pk, 100)
+            .await
+            .expect("could not claim")
+    };
+
+    assert!(claim(first_run - Duration::minutes(1)).await.is_empty());
+
+    // The cron schedule fires and moves on to its next run
+    assert_eq!(1, claim(first_run).await.len());
+    assert!(claim(first_run).await.is_empty());
+
+    // The delayed schedule fires once and is removed
+    let claimed = claim(run_at).await;
+    assert_eq!(1, claimed.len());
+    assert_eq!("RefreshJob", claimed[0].kind);
+    assert!(claim(run_at).await.is_empty());
+
+    let schedules = JobSchedule::list(ctx).await.expect("could not list");
+    assert_eq!(1, schedules.len());
+    assert_eq!(repeating.pk(), schedules[0].pk());
+    assert_eq!(cron.next_after(first_run), Some(schedules[0].next_run_at()));
+    assert_eq!(Some(first_run), schedules[0].last_run_at());
+    assert_eq!(Some("test"), schedules[0].last_claimed_by());
+    assert_eq!(
+        schedules[0],
+        JobSchedule::get_by_pk(ctx, repeating.pk())
+            .await
+            .expect("could not get schedule")
+    );
+}
+
+#[test]
+async fn delete(ctx: &DalContext) {
+    let schedule = JobSchedule::new_delayed(
+        ctx,
+        RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+        Utc::now() + Duration::days(1),
+    )
+    .await
+    .expect("could not create delayed schedule");
+    let pk = schedule.pk();
+
+    assert_eq!(
+        vec![pk],
+        JobSchedule::list(ctx)
+            .await
+            .expect("could not list")
+            .iter()
+            .map(JobSchedule::pk)
+            .collect::<Vec<_>>()
+    );
+
+    schedule.delete(ctx).await.expect("could not delete");
+    assert!(JobSchedule::list(ctx)
+        .await
+        .expect("could not list")
+        .is_empty());
+}
@@ -1,7 +1,12 @@
-use dal::{key_pair::PublicKey, DalContext, KeyPair, Tenancy};
+use dal::{
+    key_pair::PublicKey, DalContext, EncryptedSecret, KeyPair, StandardModel, StatusUpdater,
+    Tenancy, WorkspaceSignup,
+};
 use dal_test::{
     test,
-    test_harness::{create_key_pair, create_workspace},
+    test_harness::{
+        create_key_pair, create_secret_with_message, create_workspace, encrypt_message,
+    },
 };
 
 #[test]
@@ -44,3 +49,50 @@ async fn public_key_get_current(ctx: &mut DalContext) {
     assert_eq!(second_key_pair.pk(), *pk.pk());
     assert_eq!(second_key_pair.public_key(), pk.public_key());
 }
+
+#[test]
+async fn rotate(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let message = serde_json::json!({"song": "Hard to Handle"});
+    let secret = create_secret_with_message(ctx, nw.key_pair.pk(), &message).await;
+
+    let mut status_updater = StatusUpdater::initialize(ctx).await;
+    let key_pair = KeyPair::rotate(ctx, &mut status_updater)
+        .await
+        .expect("unable to rotate key pair");
+    status_updater.finish(ctx).await;
+    assert_ne!(nw.key_pair.pk(), key_pair.pk());
+
+    let current = PublicKey::get_current(ctx)
+        .await
+        .expect("cannot get public key");
+    assert_eq!(key_pair.pk(), *current.pk());
+
+    let encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility");
+    assert_eq!(key_pair.pk(), encrypted_secret.key_pair_pk());
+
+    let decrypted = encrypted_secret
+        .decrypt(ctx, Default::default())
+        .await
+        .expect("failed to decrypt re-sealed secret");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+
+    // The old key pair is gone once every secret has been re-sealed
+    assert!(KeyPair::get_by_pk(ctx, nw.key_pair.pk()).await.is_err());
+    assert!(EncryptedSecret::new(
+        ctx,
+        "stale",
+        "Mock".to_owned(),
+        None,
+        &encrypt_message(ctx, key_pair.pk(), &message).await,
+        nw.key_pair.pk(),
+        Default::default(),
+        Default::default(),
+    )
+    .await
+    .is_err());
+}
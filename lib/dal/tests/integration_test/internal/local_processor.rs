@@ -0,0 +1,154 @@
+use chrono::Utc;
+use council_server::LocalCouncil;
+use dal::{
+    job::{consumer::JobInfo, definition::RefreshJob},
+    DalContext, JobDeadLetter, JobQueueProcessor, JobQueueProcessorError, LocalProcessor,
+    ServicesContext,
+};
+use dal_test::{helpers::component_bag::ComponentBagger, test};
+use serde_json::json;
+use tokio::sync::watch;
+
+#[test]
+async fn jobs_run_in_process(ctx: &DalContext) {
+    let processor = LocalProcessor::new(1);
+
+    // Nothing can be run until the processor knows which services to run jobs with
+    processor
+        .block_on_job(RefreshJob::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            vec![],
+        ))
+        .await
+        .expect_err("job ran before the processor was started");
+
+    processor
+        .start(ctx.services_context())
+        .expect("could not start processor");
+    assert!(matches!(
+        processor.start(ctx.services_context()),
+        Err(JobQueueProcessorError::LocalProcessorAlreadyStarted)
+    ));
+
+    processor
+        .block_on_job(RefreshJob::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            vec![],
+        ))
+        .await
+        .expect("job failed");
+    processor
+        .block_on_jobs(vec![
+            RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+            RefreshJob::new(ctx.access_builder(), *ctx.visibility(), vec![]),
+        ])
+        .await
+        .expect("jobs failed");
+}
+
+#[test]
+async fn failed_jobs_are_dead_lettered(ctx: &DalContext) {
+    let processor = LocalProcessor::new(1);
+    processor
+        .start(ctx.services_context())
+        .expect("could not start processor");
+
+    let job_info = JobInfo {
+        id: "01H7ZKQX3WDQ0NSAB9W9V7B2N9".to_owned(),
+        kind: "NotARealJob".to_owned(),
+        created_at: Utc::now(),
+        arg: json!([]),
+        access_builder: ctx.access_builder(),
+        visibility: *ctx.visibility(),
+        blocking: true,
+    };
+    processor
+        .block_on_job(Box::new(job_info))
+        .await
+        .expect_err("unknown job kind ran");
+
+    let dead_letters = JobDeadLetter::list(ctx).await.expect("could not list");
+    assert_eq!(1, dead_letters.len());
+    assert_eq!("NotARealJob", dead_letters[0].kind());
+    assert_eq!(
+        &["unknown job kind NotARealJob".to_owned()],
+        dead_letters[0].error_chain()
+    );
+}
+
+#[test]
+async fn dependent_values_update_through_local_council(ctx: &DalContext) {
+    let council = LocalCouncil::new();
+    let (subscriber_started_tx, mut subscriber_started_rx) = watch::channel(());
+    let (shutdown_request_tx, shutdown_request_rx) = watch::channel(());
+    let council_task = tokio::spawn(
+        council_server::Server::from_local(council.clone())
+            .run(subscriber_started_tx, shutdown_request_rx),
+    );
+    subscriber_started_rx
+        .changed()
+        .await
+        .expect("council did not start");
+
+    // Jobs run in this process, and their dependent values updates are coordinated by the local
+    // council rather than by council over NATS
+    let processor = LocalProcessor::new(1);
+    let services_context = ctx.services_context();
+    let services_context = ServicesContext::new(
+        services_context.pg_pool().clone(),
+        services_context.nats_conn().clone(),
+        Box::new(processor.clone()),
+        services_context.veritech().clone(),
+        services_context.encryption_key(),
+        ctx.pkgs_path().cloned(),
+        services_context.module_index_url().clone(),
+        services_context.symmetric_crypto_service().clone(),
+        services_context.secret_providers().clone(),
+    )
+    .with_local_council(council);
+    processor
+        .start(services_context.clone())
+        .expect("could not start processor");
+    let local_ctx = services_context
+        .into_builder(true)
+        .build(ctx.access_builder().build(*ctx.visibility()))
+        .await
+        .expect("could not build context");
+
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger
+        .create_component(&local_ctx, "source", "fallout")
+        .await;
+    let name_prop = fallout_bag
+        .find_prop(&local_ctx, &["root", "si", "name"])
+        .await;
+    fallout_bag
+        .update_attribute_value_for_prop(&local_ctx, *name_prop.id(), Some(json!["source-updated"]))
+        .await;
+
+    local_ctx
+        .blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // The domain name is computed from the si name by the dependent values update
+    let properties = fallout_bag
+        .component_view_properties(&local_ctx)
+        .await
+        .to_value()
+        .expect("could not convert to value");
+    assert_eq!(
+        Some(&json!["source-updated"]),
+        properties.pointer("/domain/name")
+    );
+
+    shutdown_request_tx
+        .send(())
+        .expect("could not request council shutdown");
+    council_task
+        .await
+        .expect("council task panicked")
+        .expect("council failed");
+}
@@ -1,14 +1,21 @@
 mod action_prototype;
+mod api_token;
 mod attribute;
+mod audit_log;
 mod change_set;
 mod component;
 mod diagram;
 mod edge;
+mod fix_batch;
 mod func;
 mod func_execution;
 mod graph;
 mod history_event;
+mod jetstream_processor;
+mod job_dead_letter;
+mod job_schedule;
 mod key_pair;
+mod local_processor;
 mod node;
 mod node_menu;
 mod pkg;
@@ -21,6 +28,7 @@ mod secret;
 mod socket;
 mod standard_model;
 mod status_update;
+mod symmetric_reencryption;
 mod tenancy;
 mod user;
 mod validation_prototype;
@@ -1,6 +1,11 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{extract::Path as AxumPath, http::StatusCode, routing::get, Json, Router};
 use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
 use dal::BuiltinsResult;
 use dal::{
+    edge::EdgeKind,
     func::{
         argument::FuncArgumentKind, backend::validation::FuncBackendValidationArgs,
         intrinsics::IntrinsicFunc,
@@ -9,19 +14,25 @@ use dal::{
     pkg::*,
     prop::PropPath,
     schema::variant::leaves::LeafKind,
+    socket::SocketEdgeKind,
     validation::Validation,
-    ActionKind, ChangeSet, ChangeSetPk, DalContext, ExternalProvider, Func, InternalProvider,
-    PropKind, Schema, SchemaVariant, StandardModel, ValidationPrototype,
+    ActionKind, ChangeSet, ChangeSetPk, Component, Connection, DalContext, Edge, ExternalProvider,
+    Func, InternalProvider, Node, PropKind, Schema, SchemaVariant, Socket, StandardModel,
+    ValidationPrototype, Workspace,
 };
-use dal_test::{test, DalContextHeadRef};
+use dal_test::{helpers::component_bag::ComponentBagger, test, DalContextHeadRef};
+use module_index_client::{IndexClient, ModuleVersionResponse};
+use si_crypto::SigningKey;
 use si_pkg::{
-    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, FuncArgumentSpec, FuncSpec,
-    FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, LeafFunctionSpec,
+    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, DependencySpec, FuncArgumentSpec,
+    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, LeafFunctionSpec,
     LeafInputLocation as PkgLeafInputLocation, LeafKind as PkgLeafKind, PkgSpec, PropSpec,
     PropSpecKind, SchemaSpec, SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecData, SiPkg,
-    SocketSpec, SocketSpecArity, SocketSpecData, SocketSpecKind, ValidationSpec,
+    SiPkgKind, SocketSpec, SocketSpecArity, SocketSpecData, SocketSpecKind, ValidationSpec,
     ValidationSpecKind,
 };
+use ulid::Ulid;
+use url::Url;
 
 async fn make_stellarfield(ctx: &DalContext) -> BuiltinsResult<()> {
     let mut stellarfield_builder = PkgSpec::builder();
@@ -876,3 +887,867 @@ async fn test_install_pkg(ctx: &DalContext) {
         .expect("func is there");
     assert_eq!(func.name(), "groucho");
 }
+
+fn module_with_dependency(name: &str, version: &str, dependency: Option<(&str, &str)>) -> SiPkg {
+    let mut builder = PkgSpec::builder();
+    builder.name(name).version(version).created_by("Slothrop");
+    if let Some((dependency_name, version_req)) = dependency {
+        builder.dependency(
+            DependencySpec::builder()
+                .name(dependency_name)
+                .version_req(version_req)
+                .build()
+                .expect("able to build dependency spec"),
+        );
+    }
+
+    SiPkg::load_from_spec(builder.build().expect("able to build package spec"))
+        .expect("able to load pkg from spec")
+}
+
+#[test]
+async fn test_install_pkg_dependencies(ctx: &DalContext) {
+    let base = module_with_dependency("Schwarzgerat", "1.4.0", None);
+    let needs_missing = module_with_dependency("Imipolex G", "0.1.0", Some(("Jamf", "^1")));
+    let needs_newer = module_with_dependency("Rocket 00000", "0.1.0", Some(("Schwarzgerat", "^2")));
+    let needs_base =
+        module_with_dependency("Rocket 00001", "0.1.0", Some(("Schwarzgerat", "^1.2")));
+
+    // Without a module index, missing dependencies can't be fetched
+    assert!(matches!(
+        import_pkg_from_pkg(ctx, &needs_missing, None).await,
+        Err(PkgError::DependencyNotInstalled(..))
+    ));
+
+    import_pkg_from_pkg(ctx, &base, None)
+        .await
+        .expect("able to install pkg without dependencies");
+    let installed = InstalledPkg::find_by_name(ctx, "Schwarzgerat")
+        .await
+        .expect("able to find installed pkg");
+    assert_eq!(Some("1.4.0"), installed[0].version());
+
+    // An installed version outside of the range is not replaced
+    assert!(matches!(
+        import_pkg_from_pkg(ctx, &needs_newer, None).await,
+        Err(PkgError::DependencyUnsatisfiable(..))
+    ));
+
+    import_pkg_from_pkg(ctx, &needs_base, None)
+        .await
+        .expect("able to install pkg with a satisfied dependency");
+}
+
+/// A module as the stub module index publishes it.
+struct PublishedModule {
+    name: String,
+    version: String,
+    hash: String,
+    bytes: Vec<u8>,
+    yanked: bool,
+}
+
+impl PublishedModule {
+    fn new(pkg: &SiPkg) -> Self {
+        let metadata = pkg.metadata().expect("able to get pkg metadata");
+        Self {
+            name: metadata.name().to_owned(),
+            version: metadata.version().to_owned(),
+            hash: pkg.hash().expect("able to hash pkg").to_string(),
+            bytes: pkg.write_to_bytes().expect("able to write pkg to bytes"),
+            yanked: false,
+        }
+    }
+
+    fn yanked(mut self) -> Self {
+        self.yanked = true;
+        self
+    }
+
+    /// Published under the pkg's name and version, but can't be loaded once downloaded.
+    fn corrupted(mut self) -> Self {
+        self.bytes = b"not a module".to_vec();
+        self
+    }
+}
+
+/// Serves just enough of the module index for dependencies to be resolved from it.
+async fn stub_module_index(modules: Vec<PublishedModule>) -> IndexClient {
+    let modules: Arc<HashMap<Ulid, PublishedModule>> = Arc::new(
+        modules
+            .into_iter()
+            .map(|module| (Ulid::new(), module))
+            .collect(),
+    );
+
+    let versions = {
+        let modules = modules.clone();
+        move |AxumPath(name): AxumPath<String>| {
+            let modules = modules.clone();
+            async move {
+                let versions = modules
+                    .iter()
+                    .filter(|(_, module)| module.name == name)
+                    .map(|(module_id, module)| ModuleVersionResponse {
+                        id: Ulid::new().to_string(),
+                        module_id: module_id.to_string(),
+                        name: module.name.clone(),
+                        version: module.version.clone(),
+                        hash: module.hash.clone(),
+                        changelog: None,
+                        publisher_user_id: "Slothrop".to_owned(),
+                        publisher_display_name: None,
+                        created_at: Utc::now(),
+                        yanked_at: module.yanked.then(Utc::now),
+                        yanked_by_display_name: None,
+                        yank_reason: None,
+                    })
+                    .collect::<Vec<_>>();
+
+                Json(serde_json::json!({ "versions": versions }))
+            }
+        }
+    };
+    let download = move |AxumPath(module_id): AxumPath<String>| {
+        let modules = modules.clone();
+        async move {
+            Ulid::from_string(&module_id)
+                .ok()
+                .and_then(|module_id| modules.get(&module_id))
+                .map(|module| module.bytes.clone())
+                .ok_or(StatusCode::NOT_FOUND)
+        }
+    };
+    let app = Router::new()
+        .route("/module_versions/:module_name", get(versions))
+        .route("/modules/:module_id/download", get(download))
+        .route(
+            "/modules/:module_id/signature",
+            get(|| async { StatusCode::NOT_FOUND }),
+        );
+
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().expect("invalid address"))
+        .serve(app.into_make_service());
+    let base_url =
+        Url::parse(&format!("http://{}", server.local_addr())).expect("invalid base url");
+    tokio::spawn(server);
+
+    IndexClient::unauthenticated_client(base_url)
+}
+
+fn installed_version(installed: &[InstalledPkg]) -> Option<&str> {
+    installed
+        .iter()
+        .map(|installed_pkg| installed_pkg.version())
+        .next()
+        .flatten()
+}
+
+#[test]
+async fn test_install_pkg_dependencies_from_module_index(ctx: &DalContext) {
+    let oneirine = module_with_dependency("Oneirine", "0.1", None);
+    let jamf_1_0 = module_with_dependency("Jamf", "1.0.0", Some(("Oneirine", "^0.1")));
+    let jamf_1_2 = module_with_dependency("Jamf", "1.2.0", Some(("Oneirine", "^0.1")));
+    let jamf_1_3 = module_with_dependency("Jamf", "1.3.0", Some(("Oneirine", "^0.1")));
+    let jamf_2_0 = module_with_dependency("Jamf", "2.0.0", None);
+    let imipolex = module_with_dependency("Imipolex G", "0.1.0", Some(("Jamf", "^1")));
+
+    let module_index = stub_module_index(vec![
+        PublishedModule::new(&oneirine),
+        PublishedModule::new(&jamf_1_0),
+        PublishedModule::new(&jamf_1_2),
+        PublishedModule::new(&jamf_1_3).yanked(),
+        PublishedModule::new(&jamf_2_0),
+    ])
+    .await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &imipolex,
+        Some(ImportOptions {
+            module_index: Some(module_index),
+            ..Default::default()
+        }),
+    )
+    .await
+    .expect("able to install pkg with dependencies from the module index");
+
+    // The newest version satisfying the requirement which hasn't been yanked is installed, along
+    // with its own dependency, whose version isn't semver
+    let jamf = InstalledPkg::find_by_name(ctx, "Jamf")
+        .await
+        .expect("able to find installed pkg");
+    assert_eq!(Some("1.2.0"), installed_version(&jamf));
+    let oneirine = InstalledPkg::find_by_name(ctx, "Oneirine")
+        .await
+        .expect("able to find installed pkg");
+    assert_eq!(Some("0.1"), installed_version(&oneirine));
+    let imipolex = InstalledPkg::find_by_name(ctx, "Imipolex G")
+        .await
+        .expect("able to find installed pkg");
+    assert_eq!(Some("0.1.0"), installed_version(&imipolex));
+}
+
+#[test]
+async fn test_install_pkg_dependencies_before_dependents(ctx: &DalContext) {
+    let laszlo = module_with_dependency("Laszlo", "1.0.0", None);
+    let pokler = module_with_dependency("Pokler", "1.0.0", Some(("Laszlo", "^1")));
+    let kryptosam = module_with_dependency("Kryptosam", "0.1.0", Some(("Pokler", "^1")));
+
+    // The deepest dependency can't be installed, so neither can anything which depends on it
+    let module_index = stub_module_index(vec![
+        PublishedModule::new(&laszlo).corrupted(),
+        PublishedModule::new(&pokler),
+    ])
+    .await;
+
+    assert!(import_pkg_from_pkg(
+        ctx,
+        &kryptosam,
+        Some(ImportOptions {
+            module_index: Some(module_index),
+            ..Default::default()
+        }),
+    )
+    .await
+    .is_err());
+
+    for name in ["Laszlo", "Pokler", "Kryptosam"] {
+        assert!(
+            InstalledPkg::find_by_name(ctx, name)
+                .await
+                .expect("able to find installed pkgs")
+                .is_empty(),
+            "{name} was installed"
+        );
+    }
+}
+
+#[test]
+async fn test_install_pkg_dependency_cycle(ctx: &DalContext) {
+    let rainbow = module_with_dependency("Rainbow", "1.0.0", Some(("Gravity", "^1")));
+    let gravity = module_with_dependency("Gravity", "1.0.0", Some(("Rainbow", "^1")));
+
+    let module_index = stub_module_index(vec![
+        PublishedModule::new(&rainbow),
+        PublishedModule::new(&gravity),
+    ])
+    .await;
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &gravity,
+        Some(ImportOptions {
+            module_index: Some(module_index),
+            ..Default::default()
+        }),
+    )
+    .await;
+    assert!(
+        matches!(&result, Err(PkgError::DependencyCycle(cycle)) if cycle == "Gravity -> Rainbow -> Gravity"),
+        "unexpected result: {result:?}"
+    );
+}
+
+#[test]
+async fn test_install_pkg_depending_on_dated_version(ctx: &DalContext) {
+    let intrinsics = module_with_dependency("Mindless Pleasures", "2023-05-24", None);
+    let needs_newer = module_with_dependency(
+        "Rocket 00002",
+        "0.1.0",
+        Some(("Mindless Pleasures", ">=2023.6.1")),
+    );
+    let needs_dated = module_with_dependency(
+        "Rocket 00003",
+        "0.1.0",
+        Some(("Mindless Pleasures", ">=2023.5.1")),
+    );
+
+    import_pkg_from_pkg(ctx, &intrinsics, None)
+        .await
+        .expect("able to install pkg with a dated version");
+
+    assert!(matches!(
+        import_pkg_from_pkg(ctx, &needs_newer, None).await,
+        Err(PkgError::DependencyUnsatisfiable(..))
+    ));
+    import_pkg_from_pkg(ctx, &needs_dated, None)
+        .await
+        .expect("able to install pkg depending on a dated version");
+}
+
+#[test]
+async fn test_install_pkg_requires_trusted_signature(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk().expect("has a workspace");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("able to get workspace")
+        .expect("workspace exists");
+    workspace
+        .set_require_signed_modules(ctx, true)
+        .await
+        .expect("able to require signed modules");
+
+    let trusted_key = SigningKey::generate();
+    workspace
+        .trust_publisher_key(ctx, &trusted_key.verifying_key())
+        .await
+        .expect("able to trust publisher key");
+
+    let pkg = module_with_dependency("Mindless Pleasures", "1.0.0", None);
+
+    assert!(matches!(
+        import_pkg_from_pkg(ctx, &pkg, None).await,
+        Err(PkgError::UnsignedModule(_))
+    ));
+
+    let untrusted_signature = pkg.sign(&SigningKey::generate()).expect("able to sign pkg");
+    assert!(matches!(
+        import_pkg_from_pkg(
+            ctx,
+            &pkg,
+            Some(ImportOptions {
+                signature: Some(untrusted_signature),
+                ..Default::default()
+            }),
+        )
+        .await,
+        Err(PkgError::UntrustedModule(_))
+    ));
+
+    let trusted_signature = pkg.sign(&trusted_key).expect("able to sign pkg");
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions {
+            signature: Some(trusted_signature),
+            ..Default::default()
+        }),
+    )
+    .await
+    .expect("able to install pkg signed by a trusted key");
+
+    // Modules signed by a key which is no longer trusted can't be installed either
+    workspace
+        .distrust_publisher_key(ctx, &trusted_key.verifying_key())
+        .await
+        .expect("able to distrust publisher key");
+    assert!(workspace.trusted_publisher_keys().is_empty());
+    let other_pkg = module_with_dependency("Proverbs for Paranoids", "1.0.0", None);
+    assert!(matches!(
+        import_pkg_from_pkg(
+            ctx,
+            &other_pkg,
+            Some(ImportOptions {
+                signature: Some(other_pkg.sign(&trusted_key).expect("able to sign pkg")),
+                ..Default::default()
+            }),
+        )
+        .await,
+        Err(PkgError::UntrustedModule(_))
+    ));
+}
+
+fn rocket_module(version: &str, domain_props: Vec<PropSpec>, socket: SocketSpec) -> SiPkg {
+    let scaffold_func_spec = FuncSpec::builder()
+        .name("si:scaffoldRocket")
+        .unique_id("si:scaffoldRocket")
+        .data(
+            FuncSpecData::builder()
+                .name("si:scaffoldRocket")
+                .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+                .handler("createAsset")
+                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+                .build()
+                .expect("scaffold func data"),
+        )
+        .build()
+        .expect("able to build scaffold func spec");
+
+    let mut variant_builder = SchemaVariantSpec::builder();
+    variant_builder
+        .name("v0")
+        .data(
+            SchemaVariantSpecData::builder()
+                .name("v0")
+                .color("baddad")
+                .func_unique_id(&scaffold_func_spec.unique_id)
+                .build()
+                .expect("variant spec data"),
+        )
+        .socket(socket);
+    for prop in domain_props {
+        variant_builder.domain_prop(prop);
+    }
+
+    let schema = SchemaSpec::builder()
+        .name("Rocket 00000")
+        .data(
+            SchemaSpecData::builder()
+                .name("Rocket 00000")
+                .ui_hidden(false)
+                .category("Schwarzgerat")
+                .build()
+                .expect("schema spec data"),
+        )
+        .variant(
+            variant_builder
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+
+    let spec = PkgSpec::builder()
+        .name("Blicero")
+        .version(version)
+        .created_by("Enzian")
+        .schema(schema)
+        .func(
+            IntrinsicFunc::Identity
+                .to_spec()
+                .expect("identity func spec"),
+        )
+        .func(scaffold_func_spec)
+        .build()
+        .expect("able to build package spec");
+
+    SiPkg::load_from_spec(spec).expect("able to load pkg from spec")
+}
+
+fn rocket_prop(name: &str, kind: PropSpecKind) -> PropSpec {
+    PropSpec::builder()
+        .name(name)
+        .kind(kind)
+        .build()
+        .expect("able to make prop spec")
+}
+
+fn rocket_socket(name: &str, kind: SocketSpecKind) -> SocketSpec {
+    SocketSpec::builder()
+        .name(name)
+        .data(
+            SocketSpecData::builder()
+                .name(name)
+                .kind(kind)
+                .arity(SocketSpecArity::Many)
+                .ui_hidden(false)
+                .build()
+                .expect("build socket data"),
+        )
+        .build()
+        .expect("able to make socket spec")
+}
+
+#[test]
+async fn test_plan_pkg_upgrade(ctx: &DalContext) {
+    let installed = rocket_module(
+        "1.0.0",
+        vec![
+            rocket_prop("serial_number", PropSpecKind::String),
+            rocket_prop("payload", PropSpecKind::Number),
+            rocket_prop("launched", PropSpecKind::Boolean),
+        ],
+        rocket_socket("Fuel", SocketSpecKind::Input),
+    );
+    let target = PropSpec::builder()
+        .name("target")
+        .kind(PropSpecKind::Object)
+        .entry(rocket_prop("latitude", PropSpecKind::String))
+        .build()
+        .expect("able to make object prop spec");
+    let upgraded = rocket_module(
+        "2.0.0",
+        vec![
+            rocket_prop("serial", PropSpecKind::String),
+            rocket_prop("payload", PropSpecKind::String),
+            target,
+        ],
+        rocket_socket("Exhaust", SocketSpecKind::Output),
+    );
+
+    // The version being upgraded from has to be installed
+    assert!(matches!(
+        plan_upgrade(ctx, &installed, &upgraded).await,
+        Err(PkgError::UpgradeFromModuleNotInstalled(_))
+    ));
+
+    import_pkg_from_pkg(ctx, &installed, None)
+        .await
+        .expect("able to install pkg");
+
+    let plan = plan_upgrade(ctx, &installed, &upgraded)
+        .await
+        .expect("able to plan upgrade");
+
+    assert_eq!("1.0.0", plan.from_version);
+    assert_eq!("2.0.0", plan.to_version);
+    assert!(plan.func_changes.is_empty());
+    assert!(plan.removed_variants.is_empty());
+    assert_eq!(1, plan.variants.len());
+
+    let variant_plan = plan.variants.get(0).expect("variant should be there");
+    assert_eq!(
+        vec![
+            PropChange::PossiblyRenamed {
+                from: "/root/domain/serial_number".to_owned(),
+                to: "/root/domain/serial".to_owned(),
+                prop_kind: PropKind::String,
+            },
+            PropChange::Removed {
+                path: "/root/domain/launched".to_owned(),
+                prop_kind: PropKind::Boolean,
+            },
+            PropChange::Added {
+                path: "/root/domain/target".to_owned(),
+                prop_kind: PropKind::Object,
+            },
+            PropChange::KindChanged {
+                path: "/root/domain/payload".to_owned(),
+                from: PropKind::Integer,
+                to: PropKind::String,
+            },
+        ],
+        variant_plan.prop_changes
+    );
+    assert_eq!(
+        vec![
+            SocketChange::Removed {
+                name: "Fuel".to_owned(),
+                socket_kind: SocketSpecKind::Input,
+            },
+            SocketChange::Added {
+                name: "Exhaust".to_owned(),
+                socket_kind: SocketSpecKind::Output,
+            },
+        ],
+        variant_plan.socket_changes
+    );
+    assert!(variant_plan.components.is_empty());
+}
+
+#[test]
+async fn test_preview_and_selective_install(ctx: &DalContext) {
+    let pkg = rocket_module(
+        "1.0.0",
+        vec![rocket_prop("serial_number", PropSpecKind::String)],
+        rocket_socket("Fuel", SocketSpecKind::Input),
+    );
+
+    let preview = preview_import(ctx, &pkg, &ImportOptions::default())
+        .await
+        .expect("able to preview import");
+
+    let item = |preview: &ImportPreview, kind: ImportItemKind, name: &str| {
+        preview
+            .items
+            .iter()
+            .find(|item| item.key.kind == kind && item.name == name)
+            .cloned()
+            .expect("item should be in preview")
+    };
+
+    let schema = item(&preview, ImportItemKind::Schema, "Rocket 00000");
+    assert_eq!(ImportAction::Create, schema.action);
+    assert_eq!(None, schema.note);
+    let variant = item(&preview, ImportItemKind::SchemaVariant, "v0");
+    assert_eq!(ImportAction::Create, variant.action);
+    let scaffold = item(&preview, ImportItemKind::Func, "si:scaffoldRocket");
+    assert_eq!(ImportAction::Create, scaffold.action);
+
+    // Excluded items are reported as skipped, and aren't installed
+    let options = ImportOptions {
+        excluded_items: [variant.key.clone()].into_iter().collect(),
+        ..Default::default()
+    };
+    let preview = preview_import(ctx, &pkg, &options)
+        .await
+        .expect("able to preview import");
+    let excluded_variant = item(&preview, ImportItemKind::SchemaVariant, "v0");
+    assert_eq!(ImportAction::Skip, excluded_variant.action);
+    assert_eq!(Some(ImportPreviewNote::Excluded), excluded_variant.note);
+
+    let (_, schema_variant_ids, _) = import_pkg_from_pkg(ctx, &pkg, Some(options))
+        .await
+        .expect("able to install pkg");
+    assert!(schema_variant_ids.is_empty());
+
+    let installed_schema = Schema::find_by_name(ctx, "Rocket 00000")
+        .await
+        .expect("schema should be installed");
+    assert!(installed_schema
+        .variants(ctx)
+        .await
+        .expect("able to list variants")
+        .is_empty());
+
+    // Once installed, the schema is shared rather than created again
+    let preview = preview_import(ctx, &pkg, &ImportOptions::default())
+        .await
+        .expect("able to preview import");
+    let schema = item(&preview, ImportItemKind::Schema, "Rocket 00000");
+    assert_eq!(ImportAction::Skip, schema.action);
+    assert_eq!(Some(ImportPreviewNote::AlreadyInstalled), schema.note);
+}
+
+#[test]
+async fn test_preview_and_install_excluding_a_func_in_use(ctx: &DalContext) {
+    let pkg = rocket_module(
+        "1.0.0",
+        vec![rocket_prop("serial_number", PropSpecKind::String)],
+        rocket_socket("Fuel", SocketSpecKind::Input),
+    );
+    let scaffold_key = ImportItemKey::new(ImportItemKind::Func, "si:scaffoldRocket");
+    let excluding = |keys: Vec<ImportItemKey>| ImportOptions {
+        excluded_items: keys.into_iter().collect(),
+        ..Default::default()
+    };
+    let scaffold_note = |preview: ImportPreview| {
+        preview
+            .items
+            .into_iter()
+            .find(|item| item.key == scaffold_key)
+            .expect("scaffold func should be in preview")
+            .note
+    };
+
+    // The variant uses the scaffold func, and there is nothing to use in its place
+    let preview = preview_import(ctx, &pkg, &excluding(vec![scaffold_key.clone()]))
+        .await
+        .expect("able to preview import");
+    assert_eq!(
+        Some(ImportPreviewNote::ExcludedFuncInUse),
+        scaffold_note(preview)
+    );
+    let result = import_pkg_from_pkg(ctx, &pkg, Some(excluding(vec![scaffold_key.clone()]))).await;
+    assert!(
+        matches!(&result, Err(PkgError::ExcludedFuncInUse(name)) if name == "si:scaffoldRocket"),
+        "unexpected result: {result:?}"
+    );
+
+    // Excluding the variant as well leaves nothing which uses the func
+    let variant_key = ImportItemKey::new(ImportItemKind::SchemaVariant, "Rocket 00000/v0");
+    let preview = preview_import(
+        ctx,
+        &pkg,
+        &excluding(vec![scaffold_key.clone(), variant_key]),
+    )
+    .await
+    .expect("able to preview import");
+    assert_eq!(Some(ImportPreviewNote::Excluded), scaffold_note(preview));
+
+    // Once a func with the same name is installed, it is used in place of the excluded one
+    let newer_pkg = rocket_module(
+        "1.0.1",
+        vec![rocket_prop("serial_number", PropSpecKind::String)],
+        rocket_socket("Fuel", SocketSpecKind::Input),
+    );
+    import_pkg_from_pkg(ctx, &newer_pkg, None)
+        .await
+        .expect("able to install pkg");
+    let preview = preview_import(ctx, &pkg, &excluding(vec![scaffold_key.clone()]))
+        .await
+        .expect("able to preview import");
+    assert_eq!(
+        Some(ImportPreviewNote::ReplacedByExistingFunc),
+        scaffold_note(preview)
+    );
+}
+
+#[test]
+async fn test_template_export_and_import(ctx: &DalContext) {
+    let mut bagger = ComponentBagger::new();
+    let tail_bag = bagger.create_component(ctx, "tail", "fallout").await;
+    let head_bag = bagger.create_component(ctx, "head", "starfield").await;
+    let unselected_bag = bagger
+        .create_component(ctx, "unselected", "starfield")
+        .await;
+
+    for (node_id, x, y) in [
+        (tail_bag.node_id, "123", "-10"),
+        (head_bag.node_id, "424", "-11"),
+    ] {
+        Node::get_by_id(ctx, &node_id)
+            .await
+            .expect("could not find node")
+            .expect("node not found")
+            .set_geometry(ctx, x, y, Some("500"), Some("500"))
+            .await
+            .expect("cannot set node geometry");
+    }
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        tail_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    for input_bag in [&head_bag, &unselected_bag] {
+        let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+            ctx,
+            "bethesda",
+            SocketEdgeKind::ConfigurationInput,
+            input_bag.node_id,
+        )
+        .await
+        .expect("could not perform socket find")
+        .expect("could not find socket");
+        Connection::new(
+            ctx,
+            tail_bag.node_id,
+            *output_socket.id(),
+            input_bag.node_id,
+            *input_socket.id(),
+            EdgeKind::Configuration,
+        )
+        .await
+        .expect("could not create connection");
+    }
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let pkg = PkgExporter::new_template_exporter(
+        "bethesda games",
+        "1.0.0",
+        None::<String>,
+        "sally@systeminit.com",
+        vec![tail_bag.component_id, head_bag.component_id],
+    )
+    .export(ctx)
+    .await
+    .expect("able to export template");
+
+    let metadata = pkg.metadata().expect("get metadata");
+    assert_eq!(SiPkgKind::Template, metadata.kind());
+    let template = pkg
+        .change_sets()
+        .expect("get change sets")
+        .pop()
+        .expect("template has a change set");
+    assert_eq!(TEMPLATE_CHANGE_SET_NAME, template.name());
+
+    // Only the selected components, and the edge between them, are in the template, with their
+    // positions relative to each other
+    let components = template.components().expect("get components");
+    let mut positions: Vec<(String, String, String)> = vec![];
+    for component in &components {
+        let position = component
+            .position()
+            .expect("get position")
+            .pop()
+            .expect("has a position");
+        positions.push((
+            component.name().to_owned(),
+            position.x().to_owned(),
+            position.y().to_owned(),
+        ));
+    }
+    positions.sort();
+    assert_eq!(
+        vec![
+            ("head".to_owned(), "301".to_owned(), "0".to_owned()),
+            ("tail".to_owned(), "0".to_owned(), "1".to_owned()),
+        ],
+        positions
+    );
+    assert_eq!(1, template.edges().expect("get edges").len());
+
+    // Each import creates fresh copies of the components, wired together
+    let component_count = Component::list(ctx).await.expect("list components").len();
+    let edge_count = Edge::list(ctx).await.expect("list edges").len();
+    for copies in 1..=2 {
+        import_pkg_from_pkg(ctx, &pkg, None)
+            .await
+            .expect("able to import template");
+
+        assert_eq!(
+            component_count + 2 * copies,
+            Component::list(ctx).await.expect("list components").len()
+        );
+        assert_eq!(
+            edge_count + copies,
+            Edge::list(ctx).await.expect("list edges").len()
+        );
+    }
+
+    // Templates are instantiated rather than installed
+    assert!(
+        InstalledPkg::find_by_hash(ctx, &pkg.hash().expect("hash pkg").to_string())
+            .await
+            .expect("find installed pkg")
+            .is_none()
+    );
+}
+
+#[test]
+async fn test_template_import_requires_change_set(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let pkg = PkgExporter::new_template_exporter(
+        "empty",
+        "1.0.0",
+        None::<String>,
+        "sally@systeminit.com",
+        vec![],
+    )
+    .export(ctx)
+    .await
+    .expect("able to export template");
+
+    let error = import_pkg_from_pkg(ctx, &pkg, None)
+        .await
+        .expect_err("imported a template into head");
+    assert!(matches!(error, PkgError::TemplateImportInHead));
+}
+
+#[test]
+async fn test_template_import_requires_trusted_signature(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk().expect("has a workspace");
+    let mut workspace = Workspace::get_by_pk(ctx, &workspace_pk)
+        .await
+        .expect("able to get workspace")
+        .expect("workspace exists");
+    workspace
+        .set_require_signed_modules(ctx, true)
+        .await
+        .expect("able to require signed modules");
+
+    let trusted_key = SigningKey::generate();
+    workspace
+        .trust_publisher_key(ctx, &trusted_key.verifying_key())
+        .await
+        .expect("able to trust publisher key");
+
+    let pkg = PkgExporter::new_template_exporter(
+        "gravity's rainbow",
+        "1.0.0",
+        None::<String>,
+        "sally@systeminit.com",
+        vec![],
+    )
+    .export(ctx)
+    .await
+    .expect("able to export template");
+
+    // Templates carry func code, so they are held to the same trust as modules
+    assert!(matches!(
+        import_pkg_from_pkg(ctx, &pkg, None).await,
+        Err(PkgError::UnsignedModule(_))
+    ));
+
+    let signature = pkg.sign(&trusted_key).expect("able to sign pkg");
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions {
+            signature: Some(signature),
+            ..Default::default()
+        }),
+    )
+    .await
+    .expect("able to import signed template");
+}
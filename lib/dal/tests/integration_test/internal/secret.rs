@@ -1,10 +1,18 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
 use dal::{
-    DalContext, EncryptedSecret, Secret, SecretAlgorithm, SecretVersion, StandardModel,
-    WorkspaceSignup,
+    secret::{
+        provider::{LocalSecretProvider, LOCAL_SECRET_PROVIDER_NAME},
+        SecretUsage, SecretUsageSource,
+    },
+    ComponentId, DalContext, EncryptedSecret, FuncId, Secret, SecretAlgorithm, SecretExpiryStatus,
+    SecretKind, SecretProviders, SecretVersion, ServicesContext, StandardModel, WorkspaceSignup,
 };
 use dal_test::{
     test,
     test_harness::{create_secret, generate_fake_name},
+    DalContextHeadRef, TEST_SECRET_ENV_PREFIX,
 };
 
 #[test]
@@ -106,7 +114,7 @@ async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
         .await
         .expect("failed to fetch encrypted secret")
         .expect("failed to find encrypted secret for tenancy and/or visibility")
-        .decrypt(ctx)
+        .decrypt(ctx, Default::default())
         .await
         .expect("failed to decrypt encrypted secret");
     assert_eq!(decrypted.name(), secret.name());
@@ -119,3 +127,168 @@ async fn encrypt_decrypt_round_trip(ctx: &DalContext, nw: &WorkspaceSignup) {
         serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
     assert_eq!(decrypted_value["message"], message);
 }
+
+#[test]
+async fn reference_decrypt_resolves_provider(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let pkey = nw.key_pair.public_key();
+    let name = generate_fake_name();
+    let env_name = name.replace('-', "_").to_uppercase();
+
+    let message = serde_json::json!({"song": "Kiss From A Rose"});
+    let env = HashMap::from([(
+        format!("{TEST_SECRET_ENV_PREFIX}{}_{env_name}", nw.workspace.pk()),
+        serde_json::to_string(&message)
+            .expect("failed to serialize message")
+            .into(),
+    )]);
+
+    // The local provider only resolves the variables it was created with
+    let mut secret_providers = SecretProviders::default();
+    secret_providers.register(
+        LOCAL_SECRET_PROVIDER_NAME,
+        LocalSecretProvider::new(None, Some(TEST_SECRET_ENV_PREFIX.to_string()), env),
+    );
+    let services_context = ctx.services_context();
+    let ctx = &ServicesContext::new(
+        services_context.pg_pool().clone(),
+        services_context.nats_conn().clone(),
+        services_context.job_processor(),
+        services_context.veritech().clone(),
+        services_context.encryption_key(),
+        ctx.pkgs_path().cloned(),
+        services_context.module_index_url().clone(),
+        services_context.symmetric_crypto_service().clone(),
+        secret_providers,
+    )
+    .into_builder(ctx.blocking())
+    .build(ctx.access_builder().build(*ctx.visibility()))
+    .await
+    .expect("could not build context");
+
+    let reference = serde_json::json!({"provider": "local", "path": format!("env:{env_name}")});
+    let crypted = sodiumoxide::crypto::sealedbox::seal(
+        &serde_json::to_vec(&reference).expect("failed to serialize reference"),
+        pkey,
+    );
+
+    let secret = EncryptedSecret::new_reference(
+        ctx,
+        &name,
+        "imasecret".to_owned(),
+        None,
+        &crypted,
+        nw.key_pair.pk(),
+        Default::default(),
+        Default::default(),
+    )
+    .await
+    .expect("failed to create reference secret");
+    assert_eq!(*secret.kind(), SecretKind::Reference);
+
+    let decrypted = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility")
+        .decrypt(ctx, Default::default())
+        .await
+        .expect("failed to resolve reference secret");
+
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+}
+
+#[test]
+async fn decrypt_records_usage(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+    let func_id = FuncId::generate();
+    let component_id = ComponentId::generate();
+
+    let usages = SecretUsage::list_for_secret(ctx, *secret.id(), None, None)
+        .await
+        .expect("failed to list secret usages");
+    assert!(usages.is_empty());
+
+    EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility")
+        .decrypt(
+            ctx,
+            SecretUsageSource::default()
+                .with_func_id(func_id)
+                .with_component_id(component_id),
+        )
+        .await
+        .expect("failed to decrypt encrypted secret");
+
+    let usages = SecretUsage::list_for_secret(ctx, *secret.id(), None, None)
+        .await
+        .expect("failed to list secret usages");
+    assert_eq!(usages.len(), 1);
+    let usage = &usages[0];
+    assert_eq!(usage.secret_id(), *secret.id());
+    assert_eq!(usage.actor(), *ctx.history_actor());
+    assert_eq!(usage.source().func_id(), Some(func_id));
+    assert_eq!(usage.source().component_id(), Some(component_id));
+    assert_eq!(usage.source().fix_id(), None);
+
+    let usages = SecretUsage::list_for_secret(ctx, *secret.id(), Some(usage.created_at()), None)
+        .await
+        .expect("failed to list secret usages");
+    assert_eq!(usages.len(), 1);
+    let usages = SecretUsage::list_for_secret(ctx, *secret.id(), None, Some(usage.created_at()))
+        .await
+        .expect("failed to list secret usages");
+    assert!(usages.is_empty());
+}
+
+#[test]
+async fn secret_expiry(DalContextHeadRef(ctx): DalContextHeadRef<'_>, nw: &WorkspaceSignup) {
+    let secret = create_secret(ctx, nw.key_pair.pk()).await;
+    assert_eq!(secret.expires_at(), None);
+    assert_eq!(secret.rotate_by(), None);
+    assert_eq!(secret.expiry_status(), SecretExpiryStatus::Valid);
+
+    let cutoff = Utc::now() + Duration::days(7);
+    let expiring = Secret::list_expiring_in_all_workspaces(ctx, cutoff)
+        .await
+        .expect("failed to list expiring secrets");
+    assert!(!expiring.iter().any(|s| s.id() == secret.id()));
+
+    let mut encrypted_secret = EncryptedSecret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility");
+    encrypted_secret
+        .set_expiry(
+            ctx,
+            Some(Utc::now() + Duration::days(30)),
+            Some(Utc::now() + Duration::days(3)),
+        )
+        .await
+        .expect("failed to set secret expiry");
+
+    let secret = Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch secret")
+        .expect("failed to find secret for tenancy and/or visibility");
+    assert!(secret.expires_at().is_some());
+    assert!(secret.rotate_by().is_some());
+    assert_eq!(secret.expiry_status(), SecretExpiryStatus::Expiring);
+
+    let expiring = Secret::list_expiring_in_all_workspaces(ctx, cutoff)
+        .await
+        .expect("failed to list expiring secrets");
+    assert!(expiring.iter().any(|s| s.id() == secret.id()));
+
+    encrypted_secret
+        .set_expiry(ctx, Some(Utc::now() - Duration::days(1)), None)
+        .await
+        .expect("failed to set secret expiry");
+    let secret = Secret::get_by_id(ctx, secret.id())
+        .await
+        .expect("failed to fetch secret")
+        .expect("failed to find secret for tenancy and/or visibility");
+    assert_eq!(secret.expiry_status(), SecretExpiryStatus::Expired);
+}
@@ -0,0 +1,106 @@
+use dal::{
+    reencrypt_symmetric_data, DalContext, EncryptedSecret, KeyPair, ServicesContext,
+    WorkspaceSignup,
+};
+use dal_test::{
+    test,
+    test_harness::{create_key_pair, create_secret_with_message},
+    TestContext,
+};
+use si_crypto::{SymmetricCryptoService, SymmetricKey};
+
+/// Builds a [`DalContext`] for the same tenancy and visibility as `ctx` whose
+/// [`SymmetricCryptoService`] only holds the given keys.
+async fn ctx_with_keys(
+    ctx: &DalContext,
+    active_key: SymmetricKey,
+    extra_keys: Vec<SymmetricKey>,
+) -> DalContext {
+    let services_context = ctx.services_context();
+    ServicesContext::new(
+        services_context.pg_pool().clone(),
+        services_context.nats_conn().clone(),
+        services_context.job_processor(),
+        services_context.veritech().clone(),
+        services_context.encryption_key(),
+        ctx.pkgs_path().cloned(),
+        services_context.module_index_url().clone(),
+        SymmetricCryptoService::new(active_key, extra_keys),
+        services_context.secret_providers().clone(),
+    )
+    .into_builder(ctx.blocking())
+    .build(ctx.access_builder().build(*ctx.visibility()))
+    .await
+    .expect("could not build context")
+}
+
+#[test]
+async fn reencrypt_moves_data_off_retired_keys(ctx: &DalContext, _nw: &WorkspaceSignup) {
+    let active_key = TestContext::global(crate::TEST_PG_DBNAME)
+        .await
+        .expect("could not get test context")
+        .symmetric_crypto_service_active_key()
+        .await
+        .expect("could not load active symmetric key");
+    let retired_key = SymmetricCryptoService::generate_key();
+
+    // Seed a key pair and a secret sealed to it while the retired key was active
+    let retired_ctx = ctx_with_keys(ctx, retired_key.clone(), vec![]).await;
+    let key_pair = create_key_pair(&retired_ctx).await;
+    let message = serde_json::json!({"song": "Cold Sweat"});
+    let secret = create_secret_with_message(&retired_ctx, key_pair.pk(), &message).await;
+    retired_ctx
+        .commit()
+        .await
+        .expect("could not commit seeded rows");
+
+    let rotating_ctx = ctx_with_keys(ctx, active_key.clone(), vec![retired_key.clone()]).await;
+    let report = reencrypt_symmetric_data(&rotating_ctx)
+        .await
+        .expect("failed to re-encrypt symmetric data");
+    rotating_ctx
+        .commit()
+        .await
+        .expect("could not commit re-encrypted rows");
+
+    assert_eq!(
+        report.active_key_hash,
+        rotating_ctx
+            .symmetric_crypto_service()
+            .active_key_hash()
+            .to_string()
+    );
+    assert!(report.key_pairs_reencrypted >= 1);
+    assert!(report.encrypted_secrets_reencrypted >= 1);
+    assert!(report.retained_key_hashes().is_empty());
+
+    // The retired key alone can no longer open the rows
+    let retired_only_ctx = ctx_with_keys(ctx, retired_key, vec![]).await;
+    assert!(KeyPair::get_by_pk(&retired_only_ctx, key_pair.pk())
+        .await
+        .is_err());
+
+    // ...but the active key alone can
+    let active_only_ctx = ctx_with_keys(ctx, active_key, vec![]).await;
+    let reencrypted_key_pair = KeyPair::get_by_pk(&active_only_ctx, key_pair.pk())
+        .await
+        .expect("could not decrypt key pair with the active key");
+    assert_eq!(key_pair.secret_key(), reencrypted_key_pair.secret_key());
+
+    let decrypted = EncryptedSecret::get_by_id(&active_only_ctx, secret.id())
+        .await
+        .expect("failed to fetch encrypted secret")
+        .expect("failed to find encrypted secret for tenancy and/or visibility")
+        .decrypt(&active_only_ctx, Default::default())
+        .await
+        .expect("could not decrypt secret with the active key");
+    let decrypted_value =
+        serde_json::to_value(&decrypted).expect("failed to serial decrypted into Value");
+    assert_eq!(decrypted_value["message"], message);
+
+    let report = reencrypt_symmetric_data(&active_only_ctx)
+        .await
+        .expect("failed to re-encrypt symmetric data");
+    assert_eq!(report.key_pairs_reencrypted, 0);
+    assert_eq!(report.encrypted_secrets_reencrypted, 0);
+}
@@ -1,4 +1,6 @@
-use dal::{DalContext, User, UserPk, WorkspaceSignup};
+use dal::{
+    DalContext, User, UserError, UserPk, WorkspacePermission, WorkspaceRole, WorkspaceSignup,
+};
 use dal_test::test;
 
 #[test]
@@ -39,3 +41,76 @@ async fn authorize(_ctx: &DalContext, _nw: &WorkspaceSignup) {
     );
     */
 }
+
+#[test]
+async fn workspace_roles(ctx: &DalContext, nw: &WorkspaceSignup) {
+    let workspace_pk = *nw.workspace.pk();
+    assert_eq!(
+        Some(WorkspaceRole::Admin),
+        User::role_in_workspace(ctx, nw.user.pk(), workspace_pk)
+            .await
+            .expect("cannot get role of signup user")
+    );
+
+    let user = User::new(
+        ctx,
+        UserPk::generate(),
+        "funky",
+        "bobotclown@systeminit.com",
+        None::<String>,
+    )
+    .await
+    .expect("cannot create user");
+    assert!(
+        matches!(
+            User::set_role_in_workspace(ctx, user.pk(), workspace_pk, WorkspaceRole::Admin).await,
+            Err(UserError::NotAMember(_, _))
+        ),
+        "a user who is not a member can not be given a role"
+    );
+
+    user.associate_workspace(ctx, workspace_pk, WorkspaceRole::Viewer)
+        .await
+        .expect("cannot associate user with workspace");
+    assert!(User::authorize(ctx, &user.pk(), &workspace_pk)
+        .await
+        .expect("cannot authorize user"));
+    assert!(!User::authorize_permission(
+        ctx,
+        &user.pk(),
+        &workspace_pk,
+        WorkspacePermission::ApplyChangeSet
+    )
+    .await
+    .expect("cannot authorize user"));
+
+    User::set_role_in_workspace(ctx, user.pk(), workspace_pk, WorkspaceRole::Approver)
+        .await
+        .expect("cannot set role");
+    assert!(User::authorize_permission(
+        ctx,
+        &user.pk(),
+        &workspace_pk,
+        WorkspacePermission::ApplyChangeSet
+    )
+    .await
+    .expect("cannot authorize user"));
+
+    // Associating an existing member again keeps their role
+    user.associate_workspace(ctx, workspace_pk, WorkspaceRole::Viewer)
+        .await
+        .expect("cannot associate user with workspace");
+    let members = User::list_member_roles_for_workspace(ctx, workspace_pk)
+        .await
+        .expect("cannot list member roles");
+    assert_eq!(
+        vec![
+            (nw.user.pk(), WorkspaceRole::Admin),
+            (user.pk(), WorkspaceRole::Approver)
+        ],
+        members
+            .into_iter()
+            .map(|(member, role)| (member.pk(), role))
+            .collect::<Vec<_>>()
+    );
+}
@@ -2,8 +2,14 @@ use reqwest::StatusCode;
 use ulid::Ulid;
 use url::Url;
 
-use crate::types::{BuiltinsDetailsResponse, ModulePromotedResponse, ModuleRejectionResponse};
-use crate::{IndexClientResult, ModuleDetailsResponse};
+use crate::types::{
+    BuiltinsDetailsResponse, ModulePromotedResponse, ModuleRejectionResponse,
+    ModuleVersionHistoryResponse,
+};
+use crate::{
+    IndexClientResult, ModuleDetailsResponse, ModuleSearchParams, ModuleSearchResponse,
+    ModuleSignatureResponse, ModuleVersionResponse, PublisherKeyResponse, UploadModuleOptions,
+};
 
 #[derive(Debug, Clone)]
 pub struct IndexClient {
@@ -80,14 +86,59 @@ impl IndexClient {
         module_name: &str,
         module_version: &str,
         module_bytes: Vec<u8>,
+    ) -> IndexClientResult<ModuleDetailsResponse> {
+        self.upload_signed_module(module_name, module_version, module_bytes, None)
+            .await
+    }
+
+    /// Uploads a module along with a Base64 encoded detached signature over its root hash. The
+    /// signature must have been made by one of the uploader's registered publisher keys.
+    pub async fn upload_signed_module(
+        &self,
+        module_name: &str,
+        module_version: &str,
+        module_bytes: Vec<u8>,
+        signature: Option<&str>,
+    ) -> IndexClientResult<ModuleDetailsResponse> {
+        self.upload_module_with_options(
+            module_name,
+            module_version,
+            module_bytes,
+            &UploadModuleOptions {
+                signature: signature.map(ToOwned::to_owned),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Uploads a module, optionally signed, with tags it can be searched by and a changelog for
+    /// its version.
+    pub async fn upload_module_with_options(
+        &self,
+        module_name: &str,
+        module_version: &str,
+        module_bytes: Vec<u8>,
+        options: &UploadModuleOptions,
     ) -> IndexClientResult<ModuleDetailsResponse> {
         let module_upload_part = reqwest::multipart::Part::bytes(module_bytes)
             .file_name(format!("{module_name}_{module_version}.tar"));
 
+        let mut form = reqwest::multipart::Form::new().part("module bundle", module_upload_part);
+        if let Some(signature) = &options.signature {
+            form = form.text("signature", signature.clone());
+        }
+        if !options.tags.is_empty() {
+            form = form.text("tags", serde_json::to_string(&options.tags)?);
+        }
+        if let Some(changelog) = &options.changelog {
+            form = form.text("changelog", changelog.clone());
+        }
+
         let upload_url = self.base_url.join("modules")?;
         let upload_response = reqwest::Client::new()
             .post(upload_url)
-            .multipart(reqwest::multipart::Form::new().part("module bundle", module_upload_part))
+            .multipart(form)
             .bearer_auth(&self.auth_token)
             .send()
             .await?
@@ -114,6 +165,174 @@ impl IndexClient {
         Ok(bytes.to_vec())
     }
 
+    /// Fetches a module's detached signature and the publisher key which made it, or `None` if
+    /// the module is unsigned.
+    pub async fn get_module_signature(
+        &self,
+        module_id: Ulid,
+    ) -> IndexClientResult<Option<ModuleSignatureResponse>> {
+        let url = self
+            .base_url
+            .join("modules/")?
+            .join(&format!("{}/", module_id.to_string()))?
+            .join("signature")?;
+        let resp = reqwest::Client::new()
+            .get(url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?;
+
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            resp.error_for_status()?
+                .json::<ModuleSignatureResponse>()
+                .await?,
+        ))
+    }
+
+    /// Registers a Base64 encoded ed25519 public key as one of the caller's publisher keys.
+    pub async fn register_publisher_key(
+        &self,
+        public_key: &str,
+        owner_display_name: Option<&str>,
+    ) -> IndexClientResult<PublisherKeyResponse> {
+        let url = self.base_url.join("publisher_keys")?;
+        let resp = reqwest::Client::new()
+            .post(url)
+            .json(&serde_json::json!({
+                "publicKey": public_key,
+                "ownerDisplayName": owner_display_name,
+            }))
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(resp.json::<PublisherKeyResponse>().await?)
+    }
+
+    pub async fn revoke_publisher_key(
+        &self,
+        key_hash: &str,
+    ) -> IndexClientResult<PublisherKeyResponse> {
+        let url = self
+            .base_url
+            .join("publisher_keys/")?
+            .join(&format!("{key_hash}/"))?
+            .join("revoke")?;
+        let resp = reqwest::Client::new()
+            .post(url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(resp.json::<PublisherKeyResponse>().await?)
+    }
+
+    /// Searches the modules visible to the caller, one page at a time. Results are ordered by
+    /// relevance when a full-text query is given.
+    pub async fn search_modules(
+        &self,
+        params: &ModuleSearchParams,
+    ) -> IndexClientResult<ModuleSearchResponse> {
+        let url = self.base_url.join("modules")?;
+        let resp = reqwest::Client::new()
+            .get(url)
+            .query(params)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(resp.json::<ModuleSearchResponse>().await?)
+    }
+
+    /// Lists the full version history of the module named `module_name`, newest first,
+    /// including yanked versions.
+    pub async fn list_published_versions(
+        &self,
+        module_name: &str,
+    ) -> IndexClientResult<Vec<ModuleVersionResponse>> {
+        let url = self.base_url.join("module_versions/")?.join(module_name)?;
+        let resp = reqwest::Client::new()
+            .get(url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(resp.json::<ModuleVersionHistoryResponse>().await?.versions)
+    }
+
+    /// Downloads a specific published version of a module, even if it has been yanked. The
+    /// version is the one published by `publisher_user_id`, or by the caller if that is `None`.
+    pub async fn download_module_version(
+        &self,
+        module_name: &str,
+        version: &str,
+        publisher_user_id: Option<&str>,
+    ) -> IndexClientResult<Vec<u8>> {
+        let mut download_url = self
+            .base_url
+            .join("module_versions/")?
+            .join(&format!("{module_name}/"))?
+            .join(&format!("{version}/"))?
+            .join("download")?;
+        if let Some(publisher_user_id) = publisher_user_id {
+            download_url
+                .query_pairs_mut()
+                .append_pair("publisherUserId", publisher_user_id);
+        }
+        let response = reqwest::Client::new()
+            .get(download_url)
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let bytes = response.bytes().await?;
+
+        Ok(bytes.to_vec())
+    }
+
+    /// Yanks a published version of a module so it is no longer offered for new installs. The
+    /// version is the one published by `publisher_user_id`, or by the caller if that is `None`.
+    pub async fn yank_module_version(
+        &self,
+        module_name: &str,
+        version: &str,
+        publisher_user_id: Option<&str>,
+        yanked_by_display_name: Option<&str>,
+        reason: Option<&str>,
+    ) -> IndexClientResult<ModuleVersionResponse> {
+        let mut url = self
+            .base_url
+            .join("module_versions/")?
+            .join(&format!("{module_name}/"))?
+            .join(&format!("{version}/"))?
+            .join("yank")?;
+        if let Some(publisher_user_id) = publisher_user_id {
+            url.query_pairs_mut()
+                .append_pair("publisherUserId", publisher_user_id);
+        }
+        let resp = reqwest::Client::new()
+            .post(url)
+            .json(&serde_json::json!({
+                "yankedByDisplayName": yanked_by_display_name,
+                "reason": reason,
+            }))
+            .bearer_auth(&self.auth_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(resp.json::<ModuleVersionResponse>().await?)
+    }
+
     pub async fn list_builtins(&self) -> IndexClientResult<BuiltinsDetailsResponse> {
         let url = self.base_url.join("builtins")?;
         let resp = reqwest::Client::new()
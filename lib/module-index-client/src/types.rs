@@ -9,6 +9,8 @@ pub enum IndexClientError {
     InvalidHeaderValue(#[from] reqwest::header::InvalidHeaderValue),
     #[error("Request error: {0}")]
     Request(#[from] reqwest::Error),
+    #[error("JSON serialization error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("Upload error: {0}")]
     Upload(String),
     #[error("Url parse error: {0}")]
@@ -31,6 +33,100 @@ pub struct BuiltinsDetailsResponse {
     pub modules: Vec<ModuleDetailsResponse>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleVersionHistoryResponse {
+    pub versions: Vec<ModuleVersionResponse>,
+}
+
+/// A published version of a module.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleVersionResponse {
+    pub id: String,
+    /// The id of the module uploaded for this version.
+    pub module_id: String,
+    pub name: String,
+    pub version: String,
+    pub hash: String,
+    pub changelog: Option<String>,
+    pub publisher_user_id: String,
+    pub publisher_display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub yanked_at: Option<DateTime<Utc>>,
+    pub yanked_by_display_name: Option<String>,
+    pub yank_reason: Option<String>,
+}
+
+/// Optional extras for
+/// [`IndexClient::upload_module_with_options`](crate::IndexClient::upload_module_with_options).
+#[derive(Debug, Clone, Default)]
+pub struct UploadModuleOptions {
+    /// A Base64 encoded detached signature over the module's root hash.
+    pub signature: Option<String>,
+    pub tags: Vec<String>,
+    /// The changes made in this version of the module.
+    pub changelog: Option<String>,
+}
+
+/// Filters for [`IndexClient::search_modules`](crate::IndexClient::search_modules). Every filter
+/// which is set must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleSearchParams {
+    /// Full-text search over module names, descriptions and the names of the schemas in them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Tags which a module must all have.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "comma_separated"
+    )]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// The zero-based page to return. Every matching module is returned as a single page unless
+    /// either this or `page_size` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_size: Option<u64>,
+}
+
+mod comma_separated {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Vec<String>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(values) => serializer.serialize_str(&values.join(",")),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<String>>, D::Error> {
+        Ok(Option::<String>::deserialize(deserializer)?
+            .map(|values| values.split(',').map(ToOwned::to_owned).collect()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleSearchResponse {
+    pub modules: Vec<ModuleDetailsResponse>,
+    pub page: u64,
+    pub page_size: u64,
+    pub total_modules: u64,
+    pub total_pages: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModuleDetailsResponse {
@@ -43,6 +139,30 @@ pub struct ModuleDetailsResponse {
     pub latest_hash: String,
     pub latest_hash_created_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublisherKeyResponse {
+    pub key_hash: String,
+    pub owner_user_id: String,
+    pub owner_display_name: Option<String>,
+    /// The Base64 encoded ed25519 public key.
+    pub public_key: String,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleSignatureResponse {
+    /// The Base64 encoded detached signature over the module's root hash.
+    pub signature: String,
+    pub publisher_key: PublisherKeyResponse,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,14 @@
+CREATE TABLE publisher_keys
+(
+    key_hash                    text primary key,
+    owner_user_id               ident                    NOT NULL,
+    owner_display_name          text,
+    public_key                  text                     NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    revoked_at                  timestamp with time zone
+);
+CREATE INDEX ON publisher_keys (owner_user_id);
+
+ALTER TABLE modules
+    ADD signature text,
+    ADD signing_key_hash text;
@@ -0,0 +1,11 @@
+ALTER TABLE modules
+    ADD tags       jsonb NOT NULL DEFAULT '[]'::jsonb,
+    ADD categories jsonb NOT NULL DEFAULT '[]'::jsonb,
+    ADD search_document tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(metadata ->> 'schemas', '')), 'B') ||
+        setweight(to_tsvector('english', coalesce(description, '')), 'C')
+    ) STORED;
+CREATE INDEX ON modules USING GIN (search_document);
+CREATE INDEX ON modules USING GIN (tags);
+CREATE INDEX ON modules USING GIN (categories);
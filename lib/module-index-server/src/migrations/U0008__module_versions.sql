@@ -0,0 +1,30 @@
+CREATE TABLE module_versions
+(
+    id                          ident primary key default ident_create_v1(),
+    module_id                   ident                    NOT NULL REFERENCES modules (id),
+    name                        text                     NOT NULL,
+    version                     text                     NOT NULL,
+    hash                        char(64)                 NOT NULL,
+    changelog                   text,
+    publisher_user_id           ident                    NOT NULL,
+    publisher_display_name      text,
+    created_at                  timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    yanked_at                   timestamp with time zone,
+    yanked_by_display_name      text,
+    yank_reason                 text
+);
+CREATE INDEX ON module_versions (name, version);
+CREATE INDEX ON module_versions (module_id);
+
+-- Every module uploaded so far is a published version of the module with its name
+INSERT INTO module_versions (module_id, name, version, hash, publisher_user_id,
+                             publisher_display_name, created_at)
+SELECT id,
+       name,
+       coalesce(metadata ->> 'version', ''),
+       latest_hash,
+       owner_user_id,
+       owner_display_name,
+       created_at
+FROM modules
+WHERE kind = 'module';
@@ -0,0 +1,14 @@
+-- Each publisher versions their modules independently, so a version is unique per publisher and
+-- module name. The backfill in U0008 recorded every earlier upload of a module as a version, so
+-- re-uploads of the same version are dropped, keeping the latest upload, which is what listing
+-- modules resolved the name to.
+DELETE
+FROM module_versions older
+    USING module_versions newer
+WHERE older.publisher_user_id = newer.publisher_user_id
+  AND older.name = newer.name
+  AND older.version = newer.version
+  AND (older.created_at, older.id) < (newer.created_at, newer.id);
+
+CREATE UNIQUE INDEX module_versions_publisher_name_version
+    ON module_versions (publisher_user_id, name, version);
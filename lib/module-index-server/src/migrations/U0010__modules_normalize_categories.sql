@@ -0,0 +1,6 @@
+-- Categories are matched like tags, so they are stored lowercased like tags are
+UPDATE modules
+SET categories = (SELECT coalesce(jsonb_agg(DISTINCT lower(btrim(category))), '[]'::jsonb)
+                  FROM jsonb_array_elements_text(modules.categories) AS category
+                  WHERE btrim(category) <> '')
+WHERE categories <> '[]'::jsonb;
@@ -1 +1,3 @@
+pub mod module_version;
+pub mod publisher_key;
 pub mod si_module;
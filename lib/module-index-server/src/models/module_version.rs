@@ -0,0 +1,88 @@
+use sea_orm::{
+    entity::prelude::*,
+    sea_query::{Expr, SimpleExpr},
+};
+use serde::{Deserialize, Serialize};
+
+use super::si_module::ModuleId;
+
+/// A published version of a module. Each upload of a module is recorded as a version of the
+/// uploader's module with its name, and each version can only be published once per publisher.
+/// Versions are never deleted, but a bad release can be yanked so that it is no longer offered
+/// for new installs while existing installs can still download it.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "module_versions")]
+pub struct Model {
+    #[sea_orm(primary_key, column_type = r##"custom("ident")"##)]
+    pub id: String,
+    #[sea_orm(column_type = r##"custom("ident")"##)]
+    pub module_id: ModuleId,
+    #[sea_orm(column_type = "Text")]
+    pub name: String,
+    #[sea_orm(column_type = "Text")]
+    pub version: String,
+    pub hash: String,
+    #[sea_orm(column_type = "Text")]
+    pub changelog: Option<String>,
+    pub publisher_user_id: String,
+    pub publisher_display_name: Option<String>,
+    pub created_at: DateTimeWithTimeZone,
+    pub yanked_at: Option<DateTimeWithTimeZone>,
+    pub yanked_by_display_name: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub yank_reason: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// The query of the routes which address a version by its module's name and version string.
+/// Publishers version their modules independently, so `publisherUserId` names whose version is
+/// meant, defaulting to the caller's own.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublisherQuery {
+    pub publisher_user_id: Option<String>,
+}
+
+/// A filter for `modules` queries which leaves out modules whose version has been yanked.
+pub fn module_not_yanked() -> SimpleExpr {
+    Expr::cust(
+        "NOT EXISTS (SELECT 1 FROM module_versions \
+         WHERE module_versions.module_id = modules.id \
+         AND module_versions.yanked_at IS NOT NULL)",
+    )
+}
+
+/// A filter for `module_versions` queries which leaves out versions of modules that have been
+/// rejected, or that can't be installed as a dependency (such as workspace backups).
+pub fn version_of_accepted_module() -> SimpleExpr {
+    Expr::cust(
+        "EXISTS (SELECT 1 FROM modules \
+         WHERE modules.id = module_versions.module_id \
+         AND modules.rejected_at IS NULL \
+         AND modules.kind = 'module')",
+    )
+}
+
+impl From<Model> for module_index_client::ModuleVersionResponse {
+    fn from(value: Model) -> Self {
+        Self {
+            id: value.id,
+            module_id: value.module_id.to_string(),
+            name: value.name,
+            version: value.version,
+            hash: value.hash,
+            changelog: value.changelog,
+            publisher_user_id: value.publisher_user_id,
+            publisher_display_name: value.publisher_display_name,
+            created_at: value.created_at.into(),
+            yanked_at: value.yanked_at.map(Into::into),
+            yanked_by_display_name: value.yanked_by_display_name,
+            yank_reason: value.yank_reason,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The public half of a key which a module publisher signs their modules with. Keys are
+/// identified by their hash and are never deleted, only revoked, so that the signatures they
+/// made can still be explained.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[sea_orm(table_name = "publisher_keys")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub key_hash: String,
+    pub owner_user_id: String,
+    pub owner_display_name: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub public_key: String,
+    pub created_at: DateTimeWithTimeZone,
+    pub revoked_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<Model> for module_index_client::PublisherKeyResponse {
+    fn from(value: Model) -> Self {
+        Self {
+            key_hash: value.key_hash,
+            owner_user_id: value.owner_user_id,
+            owner_display_name: value.owner_display_name,
+            public_key: value.public_key,
+            created_at: value.created_at.into(),
+            revoked_at: value.revoked_at.map(Into::into),
+        }
+    }
+}
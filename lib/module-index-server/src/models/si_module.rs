@@ -11,6 +11,7 @@ use ulid::Ulid;
 #[serde(rename_all = "camelCase")]
 pub enum ModuleKind {
     Module,
+    Template,
     WorkspaceBackup,
 }
 
@@ -18,6 +19,7 @@ impl ModuleKind {
     pub fn to_db_kind(&self) -> String {
         match self {
             ModuleKind::Module => "module".into(),
+            ModuleKind::Template => "template".into(),
             ModuleKind::WorkspaceBackup => "workspaceBackup".into(),
         }
     }
@@ -29,6 +31,7 @@ impl FromStr for ModuleKind {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "module" => ModuleKind::Module,
+            "template" => ModuleKind::Template,
             "workspaceBackup" => ModuleKind::WorkspaceBackup,
             _ => return Err(sea_query::ValueTypeErr),
         })
@@ -97,6 +100,12 @@ pub struct Model {
     pub kind: ModuleKind,
     pub is_builtin_at: Option<DateTimeWithTimeZone>,
     pub is_builtin_at_by_display_name: Option<String>,
+    pub signature: Option<String>,
+    pub signing_key_hash: Option<String>,
+    /// A JSON array of the tags given by the uploader.
+    pub tags: Json,
+    /// A JSON array of the distinct categories of the schemas in the module.
+    pub categories: Json,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
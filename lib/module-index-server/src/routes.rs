@@ -13,12 +13,18 @@ use tower_http::cors::CorsLayer;
 
 mod download_builtin_route;
 mod download_module_route;
+mod download_module_version_route;
 mod get_module_details_route;
+mod get_module_signature_route;
 mod list_builtins_route;
 mod list_modules_route;
+mod list_published_versions_route;
 pub(crate) mod promote_builtin_route;
+mod register_publisher_key_route;
 pub(crate) mod reject_module_route;
+mod revoke_publisher_key_route;
 pub(crate) mod upsert_module_route;
+mod yank_module_version_route;
 
 use super::{app_state::AppState, server::ServerError};
 
@@ -41,6 +47,18 @@ pub fn routes(state: AppState) -> Router {
             "/modules/:module_id",
             get(get_module_details_route::get_module_details_route),
         )
+        .route(
+            "/modules/:module_id/signature",
+            get(get_module_signature_route::get_module_signature_route),
+        )
+        .route(
+            "/publisher_keys",
+            post(register_publisher_key_route::register_publisher_key_route),
+        )
+        .route(
+            "/publisher_keys/:key_hash/revoke",
+            post(revoke_publisher_key_route::revoke_publisher_key_route),
+        )
         .route(
             "/modules/:module_id/download",
             get(download_module_route::download_module_route),
@@ -53,6 +71,18 @@ pub fn routes(state: AppState) -> Router {
             "/modules/:module_id/reject",
             post(reject_module_route::reject_module),
         )
+        .route(
+            "/module_versions/:module_name",
+            get(list_published_versions_route::list_published_versions_route),
+        )
+        .route(
+            "/module_versions/:module_name/:version/download",
+            get(download_module_version_route::download_module_version_route),
+        )
+        .route(
+            "/module_versions/:module_name/:version/yank",
+            post(yank_module_version_route::yank_module_version_route),
+        )
         .layer(CorsLayer::permissive())
         .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES));
 
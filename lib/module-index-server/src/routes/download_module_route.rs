@@ -3,14 +3,14 @@ use axum::{
     response::{IntoResponse, Redirect, Response},
     Json,
 };
-use hyper::StatusCode;
-use s3::error::S3Error;
+use hyper::{header, StatusCode};
 use sea_orm::{DbErr, EntityTrait};
 use thiserror::Error;
 
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
+    extract::{Authorization, DbConnection, ExtractedStorage},
     models::si_module::{self, ModuleId},
+    storage::{ModuleStorage, StorageError, StoredModule},
 };
 
 #[remain::sorted]
@@ -20,15 +20,17 @@ pub enum DownloadModuleError {
     DbErr(#[from] DbErr),
     #[error(r#"Module "{0}" not found"#)]
     NotFound(ModuleId),
-    #[error("s3 error: {0}")]
-    S3Error(#[from] S3Error),
+    #[error("module storage error: {0}")]
+    Storage(#[from] StorageError),
 }
 
 // TODO: figure out how to not keep this serialization logic here
 impl IntoResponse for DownloadModuleError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            Self::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::NotFound(_) | Self::Storage(StorageError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -43,16 +45,25 @@ impl IntoResponse for DownloadModuleError {
 pub async fn download_module_route(
     Path(module_id): Path<ModuleId>,
     Authorization { .. }: Authorization,
-    ExtractedS3Bucket(s3_bucket): ExtractedS3Bucket,
+    ExtractedStorage(storage): ExtractedStorage,
     DbConnection(txn): DbConnection,
-) -> Result<Redirect, DownloadModuleError> {
+) -> Result<Response, DownloadModuleError> {
     let module = match si_module::Entity::find_by_id(module_id).one(&txn).await? {
         Some(module) => module,
         _ => return Err(DownloadModuleError::NotFound(module_id)),
     };
 
-    let download_url =
-        s3_bucket.presign_get(format!("{}.sipkg", module.latest_hash), 60 * 5, None)?;
-
-    Ok(Redirect::temporary(&download_url))
+    Ok(
+        match storage
+            .get(&ModuleStorage::module_key(&module.latest_hash))
+            .await?
+        {
+            StoredModule::Redirect(download_url) => {
+                Redirect::temporary(&download_url).into_response()
+            }
+            StoredModule::Bytes(bytes) => {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response()
+            }
+        },
+    )
 }
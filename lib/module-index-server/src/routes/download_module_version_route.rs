@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use hyper::{header, StatusCode};
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection, ExtractedStorage},
+    models::module_version::{self, PublisherQuery},
+    storage::{ModuleStorage, StorageError, StoredModule},
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum DownloadModuleVersionError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Version "{1}" of module "{0}" not found"#)]
+    NotFound(String, String),
+    #[error("module storage error: {0}")]
+    Storage(#[from] StorageError),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for DownloadModuleVersionError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::NotFound(_, _) | Self::Storage(StorageError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Downloads a specific published version of a module, by default one of the caller's own.
+/// Yanked versions can still be downloaded so that existing installs which depend on them keep
+/// working.
+pub async fn download_module_version_route(
+    Path((module_name, version)): Path<(String, String)>,
+    Query(query): Query<PublisherQuery>,
+    Authorization { user_claim, .. }: Authorization,
+    ExtractedStorage(storage): ExtractedStorage,
+    DbConnection(txn): DbConnection,
+) -> Result<Response, DownloadModuleVersionError> {
+    let publisher_user_id = query
+        .publisher_user_id
+        .unwrap_or_else(|| user_claim.user_pk.to_string());
+    let module_version = match module_version::Entity::find()
+        .filter(module_version::Column::PublisherUserId.eq(publisher_user_id))
+        .filter(module_version::Column::Name.eq(module_name.as_str()))
+        .filter(module_version::Column::Version.eq(version.as_str()))
+        .one(&txn)
+        .await?
+    {
+        Some(module_version) => module_version,
+        None => return Err(DownloadModuleVersionError::NotFound(module_name, version)),
+    };
+
+    Ok(
+        match storage
+            .get(&ModuleStorage::module_key(&module_version.hash))
+            .await?
+        {
+            StoredModule::Redirect(download_url) => {
+                Redirect::temporary(&download_url).into_response()
+            }
+            StoredModule::Bytes(bytes) => {
+                ([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response()
+            }
+        },
+    )
+}
@@ -0,0 +1,73 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::StatusCode;
+use module_index_client::ModuleSignatureResponse;
+use sea_orm::{DbErr, EntityTrait};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::{
+        publisher_key,
+        si_module::{self, ModuleId},
+    },
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum GetModuleSignatureError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Module "{0}" not found"#)]
+    NotFound(ModuleId),
+    #[error(r#"Module "{0}" is not signed"#)]
+    NotSigned(ModuleId),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for GetModuleSignatureError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::NotFound(_) | Self::NotSigned(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Returns a module's detached signature along with the publisher key which made it, so that
+/// the module can be verified once downloaded.
+pub async fn get_module_signature_route(
+    Path(module_id): Path<ModuleId>,
+    Authorization { .. }: Authorization,
+    DbConnection(txn): DbConnection,
+) -> Result<Json<ModuleSignatureResponse>, GetModuleSignatureError> {
+    let module = match si_module::Entity::find_by_id(module_id).one(&txn).await? {
+        Some(module) => module,
+        _ => return Err(GetModuleSignatureError::NotFound(module_id)),
+    };
+
+    let (signature, key) = match (module.signature, module.signing_key_hash) {
+        (Some(signature), Some(signing_key_hash)) => (
+            signature,
+            publisher_key::Entity::find_by_id(signing_key_hash)
+                .one(&txn)
+                .await?,
+        ),
+        _ => return Err(GetModuleSignatureError::NotSigned(module_id)),
+    };
+    let key = key.ok_or(GetModuleSignatureError::NotSigned(module_id))?;
+
+    Ok(Json(ModuleSignatureResponse {
+        signature,
+        publisher_key: key.into(),
+    }))
+}
@@ -9,7 +9,12 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::models::si_module::ModuleKind;
-use crate::{app_state::AppState, extract::DbConnection, models::si_module, whoami::WhoamiError};
+use crate::{
+    app_state::AppState,
+    extract::DbConnection,
+    models::{module_version, si_module},
+    whoami::WhoamiError,
+};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -58,7 +63,8 @@ pub async fn list_builtins_route(
 
     let query = query
         .filter(si_module::Column::RejectedAt.is_null())
-        .filter(si_module::Column::Kind.eq(ModuleKind::Module));
+        .filter(si_module::Column::Kind.eq(ModuleKind::Module))
+        .filter(module_version::module_not_yanked());
 
     // This should give us a list of builtin modules that are not rejected
     let modules: Vec<si_module::Model> = query.all(&txn).await?;
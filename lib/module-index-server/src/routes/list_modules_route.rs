@@ -4,14 +4,18 @@ use axum::{
     Json,
 };
 use hyper::StatusCode;
-use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use sea_orm::{
+    sea_query::{Expr, Order},
+    ColumnTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use super::upsert_module_route::normalize_labels;
 use crate::{
     app_state::AppState,
     extract::{Authorization, DbConnection},
-    models::si_module,
+    models::{module_version, si_module},
     whoami::{is_systeminit_auth_token, WhoamiError},
 };
 
@@ -37,18 +41,35 @@ impl IntoResponse for ListModulesError {
     }
 }
 
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ListModulesRequest {
     pub name: Option<String>,
     pub kind: Option<si_module::ModuleKind>,
     pub su: Option<bool>,
+    /// Full-text search over module names, descriptions and the names of the schemas in them,
+    /// using web search syntax (`"quoted phrases"`, `or`, `-excluded`).
+    pub query: Option<String>,
+    /// A comma separated list of tags, all of which a module must have.
+    pub tags: Option<String>,
+    pub category: Option<String>,
+    /// The zero-based page to return. Every matching module is returned as a single page unless
+    /// either this or `page_size` is given.
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ListModulesResponse {
     modules: Vec<si_module::Model>,
+    page: u64,
+    page_size: u64,
+    total_modules: u64,
+    total_pages: u64,
 }
 
 pub async fn list_module_route(
@@ -70,7 +91,8 @@ pub async fn list_module_route(
     // filters
     let query = query
         .filter(si_module::Column::RejectedAt.is_null())
-        .filter(si_module::Column::Kind.eq(kind.to_db_kind()));
+        .filter(si_module::Column::Kind.eq(kind.to_db_kind()))
+        .filter(module_version::module_not_yanked());
     let query = if !su {
         let user_id = user_claim.user_pk.to_string();
         dbg!(&user_id);
@@ -84,15 +106,89 @@ pub async fn list_module_route(
         query
     };
 
+    let tags = normalize_labels(
+        request
+            .tags
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(ToOwned::to_owned),
+    );
+    let query = if !tags.is_empty() {
+        query.filter(Expr::cust_with_values(
+            "tags @> ?",
+            [serde_json::json!(tags)],
+        ))
+    } else {
+        query
+    };
+    let categories = normalize_labels(request.category);
+    let query = if !categories.is_empty() {
+        query.filter(Expr::cust_with_values(
+            "categories @> ?",
+            [serde_json::json!(categories)],
+        ))
+    } else {
+        query
+    };
+
     // We want to filter out the builtins from the list as they will already be in our system
     let query = query.filter(si_module::Column::IsBuiltinAt.is_null());
 
+    // full-text search, with the best matches first
+    let search = request
+        .query
+        .map(|search| search.trim().to_owned())
+        .filter(|search| !search.is_empty());
+    let query = if let Some(search) = search {
+        query
+            .filter(Expr::cust_with_values(
+                "search_document @@ websearch_to_tsquery('english', ?)",
+                [search.clone()],
+            ))
+            .order_by(
+                Expr::cust_with_values(
+                    "ts_rank(search_document, websearch_to_tsquery('english', ?))",
+                    [search],
+                ),
+                Order::Desc,
+            )
+    } else {
+        query
+    };
+
     // ordering
     let query = query
         .order_by_desc(si_module::Column::OwnerUserId)
         .order_by_desc(si_module::Column::CreatedAt);
 
-    let modules: Vec<si_module::Model> = query.all(&txn).await?;
+    // Clients which predate pagination expect every module, so only page when asked to
+    if request.page.is_none() && request.page_size.is_none() {
+        let modules: Vec<si_module::Model> = query.all(&txn).await?;
+        let total_modules = modules.len() as u64;
+        return Ok(Json(ListModulesResponse {
+            modules,
+            page: 0,
+            page_size: total_modules,
+            total_modules,
+            total_pages: 1,
+        }));
+    }
+
+    let page = request.page.unwrap_or(0);
+    let page_size = request
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let paginator = query.paginate(&txn, page_size);
+    let totals = paginator.num_items_and_pages().await?;
+    let modules: Vec<si_module::Model> = paginator.fetch_page(page).await?;
 
-    Ok(Json(ListModulesResponse { modules }))
+    Ok(Json(ListModulesResponse {
+        modules,
+        page,
+        page_size,
+        total_modules: totals.number_of_items,
+        total_pages: totals.number_of_pages,
+    }))
 }
@@ -0,0 +1,63 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::StatusCode;
+use module_index_client::ModuleVersionResponse;
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::module_version,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum ListPublishedVersionsError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for ListPublishedVersionsError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPublishedVersionsResponse {
+    versions: Vec<ModuleVersionResponse>,
+}
+
+/// Lists the full version history of the module with exactly this name, newest first, so that
+/// clients can also pick one which satisfies a dependency's version requirement. Yanked versions
+/// are included so that the history is complete, but versions of rejected modules are not. Unlike
+/// listing modules, this isn't limited to the caller's own modules and includes builtins, as a
+/// module may depend on either.
+pub async fn list_published_versions_route(
+    Path(module_name): Path<String>,
+    Authorization { .. }: Authorization,
+    DbConnection(txn): DbConnection,
+) -> Result<Json<ListPublishedVersionsResponse>, ListPublishedVersionsError> {
+    let versions: Vec<module_version::Model> = module_version::Entity::find()
+        .filter(module_version::Column::Name.eq(module_name))
+        .filter(module_version::version_of_accepted_module())
+        .order_by_desc(module_version::Column::CreatedAt)
+        .all(&txn)
+        .await?;
+
+    Ok(Json(ListPublishedVersionsResponse {
+        versions: versions.into_iter().map(Into::into).collect(),
+    }))
+}
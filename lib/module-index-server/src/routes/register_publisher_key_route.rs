@@ -0,0 +1,86 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use hyper::StatusCode;
+use module_index_client::PublisherKeyResponse;
+use sea_orm::{ActiveModelTrait, DbErr, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use si_crypto::{SigningError, VerifyingKey};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::publisher_key,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum RegisterPublisherKeyError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error("publisher key {0} is already registered")]
+    KeyAlreadyRegistered(String),
+    #[error("invalid publisher key: {0}")]
+    Signing(#[from] SigningError),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for RegisterPublisherKeyError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::KeyAlreadyRegistered(_) => (StatusCode::CONFLICT, self.to_string()),
+            Self::Signing(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterPublisherKeyRequest {
+    /// A Base64 encoded ed25519 public key.
+    pub public_key: String,
+    pub owner_display_name: Option<String>,
+}
+
+pub async fn register_publisher_key_route(
+    Authorization { user_claim, .. }: Authorization,
+    DbConnection(txn): DbConnection,
+    Json(request): Json<RegisterPublisherKeyRequest>,
+) -> Result<Json<PublisherKeyResponse>, RegisterPublisherKeyError> {
+    let verifying_key = VerifyingKey::decode(&request.public_key)?;
+    let key_hash = verifying_key.key_hash().to_string();
+
+    if publisher_key::Entity::find_by_id(key_hash.clone())
+        .one(&txn)
+        .await?
+        .is_some()
+    {
+        return Err(RegisterPublisherKeyError::KeyAlreadyRegistered(key_hash));
+    }
+
+    let new_key = publisher_key::ActiveModel {
+        key_hash: Set(key_hash),
+        owner_user_id: Set(user_claim.user_pk.to_string()),
+        owner_display_name: Set(request.owner_display_name),
+        public_key: Set(verifying_key.encode()),
+        created_at: Set(DateTime::<FixedOffset>::from_utc(
+            Utc::now().naive_utc(),
+            Utc.fix(),
+        )),
+        revoked_at: Set(None),
+    };
+    let new_key: publisher_key::Model = new_key.insert(&txn).await?;
+
+    txn.commit().await?;
+
+    Ok(Json(new_key.into()))
+}
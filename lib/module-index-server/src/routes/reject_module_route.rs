@@ -13,7 +13,7 @@ use crate::app_state::AppState;
 use crate::routes::upsert_module_route::UpsertModuleError;
 use crate::whoami::{is_systeminit_auth_token, WhoamiError};
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
+    extract::{Authorization, DbConnection},
     models::si_module::{self, ModuleId},
 };
 
@@ -50,7 +50,6 @@ pub async fn reject_module(
         user_claim: _user_claim,
         auth_token,
     }: Authorization,
-    ExtractedS3Bucket(_s3_bucket): ExtractedS3Bucket,
     DbConnection(txn): DbConnection,
     State(state): State<AppState>,
     mut multipart: Multipart,
@@ -91,6 +90,10 @@ pub async fn reject_module(
         kind: Set(module.kind),
         is_builtin_at: Set(module.is_builtin_at),
         is_builtin_at_by_display_name: Set(module.is_builtin_at_by_display_name),
+        signature: Set(module.signature),
+        signing_key_hash: Set(module.signing_key_hash),
+        tags: Set(module.tags),
+        categories: Set(module.categories),
     };
 
     let updated_module: si_module::Model = dbg!(active_module.update(&txn).await)?;
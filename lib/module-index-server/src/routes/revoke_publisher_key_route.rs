@@ -0,0 +1,72 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use hyper::StatusCode;
+use module_index_client::PublisherKeyResponse;
+use sea_orm::{ActiveModelTrait, DbErr, EntityTrait, IntoActiveModel, Set};
+use thiserror::Error;
+
+use crate::{
+    extract::{Authorization, DbConnection},
+    models::publisher_key,
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum RevokePublisherKeyError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Publisher key "{0}" not found"#)]
+    NotFound(String),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for RevokePublisherKeyError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+/// Revokes one of the caller's publisher keys. Modules it signed are no longer trusted, and it
+/// can't be used to sign new uploads.
+pub async fn revoke_publisher_key_route(
+    Path(key_hash): Path<String>,
+    Authorization { user_claim, .. }: Authorization,
+    DbConnection(txn): DbConnection,
+) -> Result<Json<PublisherKeyResponse>, RevokePublisherKeyError> {
+    let key = match publisher_key::Entity::find_by_id(key_hash.clone())
+        .one(&txn)
+        .await?
+    {
+        // Keys belonging to others are reported as missing rather than forbidden
+        Some(key) if key.owner_user_id == user_claim.user_pk.to_string() => key,
+        _ => return Err(RevokePublisherKeyError::NotFound(key_hash)),
+    };
+
+    let key = if key.revoked_at.is_some() {
+        key
+    } else {
+        let mut active_key = key.into_active_model();
+        active_key.revoked_at = Set(Some(DateTime::<FixedOffset>::from_utc(
+            Utc::now().naive_utc(),
+            Utc.fix(),
+        )));
+        active_key.update(&txn).await?
+    };
+
+    txn.commit().await?;
+
+    Ok(Json(key.into()))
+}
@@ -6,18 +6,29 @@ use axum::{
 use chrono::{DateTime, FixedOffset, Offset, Utc};
 use hyper::StatusCode;
 use module_index_client::{FuncMetadata, ModuleDetailsResponse};
-use s3::error::S3Error;
-use sea_orm::{ActiveModelTrait, DbErr, Set};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseTransaction, DbErr, EntityTrait,
+    QueryFilter, Set,
+};
 use serde::{Deserialize, Serialize};
+use si_crypto::{DetachedSignature, SigningError, VerifyingKey};
 use si_pkg::{SiPkg, SiPkgError, SiPkgKind};
 use telemetry::prelude::*;
 use thiserror::Error;
 
 use crate::{
-    extract::{Authorization, DbConnection, ExtractedS3Bucket},
-    models::si_module,
+    extract::{Authorization, DbConnection, ExtractedStorage},
+    models::{module_version, publisher_key, si_module},
+    storage::{ModuleStorage, StorageError},
 };
 
+/// The name of the optional multipart field holding the module's signature.
+pub const SIGNATURE_FIELD_NAME: &str = "signature";
+/// The name of the optional multipart field holding a JSON array of the module's tags.
+pub const TAGS_FIELD_NAME: &str = "tags";
+/// The name of the optional multipart field holding the changes made in this version.
+pub const CHANGELOG_FIELD_NAME: &str = "changelog";
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UpsertModuleRequest {
@@ -33,20 +44,32 @@ pub enum UpsertModuleError {
     IoError(#[from] std::io::Error),
     #[error("multipart decode error: {0}")]
     Multipart(#[from] MultipartError),
-    #[error("s3 error: {0}")]
-    S3Error(#[from] S3Error),
     #[error("JSON serialization/deserialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("signature was not made by any of the uploader's publisher keys")]
+    SignatureNotVerified,
     #[error("module parsing error: {0}")]
     SiPkgError(#[from] SiPkgError),
+    #[error("signing error: {0}")]
+    Signing(#[from] SigningError),
+    #[error("module storage error: {0}")]
+    Storage(#[from] StorageError),
     #[error("upload is required")]
     UploadRequiredError,
+    #[error("version {1} of module {0} has already been published")]
+    VersionAlreadyPublished(String, String),
 }
 
 // TODO: figure out how to not keep this serialization logic here
 impl IntoResponse for UpsertModuleError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let (status, error_message) = match self {
+            Self::SignatureNotVerified | Self::Signing(_) => {
+                (StatusCode::BAD_REQUEST, self.to_string())
+            }
+            Self::VersionAlreadyPublished(_, _) => (StatusCode::CONFLICT, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -59,7 +82,7 @@ impl IntoResponse for UpsertModuleError {
 // #[debug_handler]
 pub async fn upsert_module_route(
     Authorization { user_claim, .. }: Authorization,
-    ExtractedS3Bucket(s3_bucket): ExtractedS3Bucket,
+    ExtractedStorage(storage): ExtractedStorage,
     DbConnection(txn): DbConnection,
     mut multipart: Multipart,
 ) -> Result<Json<ModuleDetailsResponse>, UpsertModuleError> {
@@ -72,6 +95,26 @@ pub async fn upsert_module_route(
     let data = field.bytes().await?;
     info!("Got part data");
 
+    // A detached signature over the module's root hash, the module's tags and a changelog may
+    // follow the module bundle
+    let mut signature = None;
+    let mut tags = vec![];
+    let mut changelog = None;
+    while let Some(field) = multipart.next_field().await? {
+        match field.name().map(ToOwned::to_owned).as_deref() {
+            Some(SIGNATURE_FIELD_NAME) => {
+                signature = Some(DetachedSignature::decode(field.text().await?)?);
+            }
+            Some(TAGS_FIELD_NAME) => {
+                tags = normalize_labels(serde_json::from_str::<Vec<String>>(&field.text().await?)?);
+            }
+            Some(CHANGELOG_FIELD_NAME) => {
+                changelog = Some(field.text().await?).filter(|text| !text.trim().is_empty());
+            }
+            _ => {}
+        }
+    }
+
     // SiPkg using old term "package" but we are dealing with a "module"
     let loaded_module = dbg!(SiPkg::load_from_bytes(data.to_vec()))?;
     let module_metadata = dbg!(loaded_module.metadata())?;
@@ -80,13 +123,38 @@ pub async fn upsert_module_route(
     let module_kind = match module_metadata.kind() {
         SiPkgKind::WorkspaceBackup => si_module::ModuleKind::WorkspaceBackup,
         SiPkgKind::Module => si_module::ModuleKind::Module,
+        SiPkgKind::Template => si_module::ModuleKind::Template,
     };
 
+    // Versions are addressed by publisher, name and version string, so each can only be published
+    // once. This only saves uploading a module which is sure to be refused: the unique index on
+    // `module_versions` refuses concurrent uploads of the same version.
+    if module_kind == si_module::ModuleKind::Module
+        && module_version::Entity::find()
+            .filter(module_version::Column::PublisherUserId.eq(user_claim.user_pk.to_string()))
+            .filter(module_version::Column::Name.eq(module_metadata.name()))
+            .filter(module_version::Column::Version.eq(version.as_str()))
+            .one(&txn)
+            .await?
+            .is_some()
+    {
+        return Err(UpsertModuleError::VersionAlreadyPublished(
+            module_metadata.name().to_owned(),
+            version,
+        ));
+    }
+
     let schemas: Vec<String> = loaded_module
         .schemas()?
         .iter()
         .map(|s| s.name().to_owned())
         .collect();
+    let categories = normalize_labels(
+        loaded_module
+            .schemas()?
+            .iter()
+            .filter_map(|s| s.data().map(|data| data.category().to_owned())),
+    );
     let funcs: Vec<FuncMetadata> = loaded_module
         .funcs()?
         .iter()
@@ -97,6 +165,19 @@ pub async fn upsert_module_route(
         })
         .collect();
 
+    let signing_key_hash = match &signature {
+        Some(signature) => Some(
+            verify_module_signature(
+                &txn,
+                &user_claim.user_pk.to_string(),
+                &loaded_module,
+                signature,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
     let new_module = si_module::ActiveModel {
         name: Set(module_metadata.name().to_owned()),
         description: Set(Some(module_metadata.description().to_owned())),
@@ -114,22 +195,94 @@ pub async fn upsert_module_route(
             funcs,
         })?),
         kind: Set(module_kind),
+        signature: Set(signature.map(|signature| signature.encode())),
+        signing_key_hash: Set(signing_key_hash),
+        tags: Set(serde_json::to_value(tags)?),
+        categories: Set(serde_json::to_value(categories)?),
         ..Default::default() // all other attributes are `NotSet`
     };
 
     // TODO: put below
-    // upload to s3
-    s3_bucket
-        .put_object(format!("{}.sipkg", module_metadata.hash()), &data)
+    storage
+        .put(&ModuleStorage::module_key(module_metadata.hash()), &data)
         .await?;
 
     let new_module: si_module::Model = dbg!(new_module.insert(&txn).await)?;
 
+    if new_module.kind == si_module::ModuleKind::Module {
+        module_version::Entity::insert(module_version::ActiveModel {
+            module_id: Set(new_module.id),
+            name: Set(new_module.name.clone()),
+            version: Set(module_metadata.version().to_owned()),
+            hash: Set(new_module.latest_hash.clone()),
+            changelog: Set(changelog),
+            publisher_user_id: Set(new_module.owner_user_id.clone()),
+            publisher_display_name: Set(new_module.owner_display_name.clone()),
+            created_at: Set(new_module.created_at),
+            ..Default::default() // all other attributes are `NotSet`
+        })
+        .on_conflict(
+            OnConflict::columns([
+                module_version::Column::PublisherUserId,
+                module_version::Column::Name,
+                module_version::Column::Version,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec(&txn)
+        .await
+        .map_err(|err| match err {
+            DbErr::RecordNotInserted => UpsertModuleError::VersionAlreadyPublished(
+                new_module.name.clone(),
+                module_metadata.version().to_owned(),
+            ),
+            err => err.into(),
+        })?;
+    }
+
     txn.commit().await?;
 
     Ok(dbg!(Json(new_module.try_into()?)))
 }
 
+/// Finds the uploader's unrevoked publisher key which made `signature` over the module's root
+/// hash, returning that key's hash.
+async fn verify_module_signature(
+    txn: &DatabaseTransaction,
+    owner_user_id: &str,
+    module: &SiPkg,
+    signature: &DetachedSignature,
+) -> Result<String, UpsertModuleError> {
+    let keys: Vec<publisher_key::Model> = publisher_key::Entity::find()
+        .filter(publisher_key::Column::OwnerUserId.eq(owner_user_id))
+        .filter(publisher_key::Column::RevokedAt.is_null())
+        .all(txn)
+        .await?;
+
+    for key in keys {
+        let verifying_key = VerifyingKey::decode(&key.public_key)?;
+        if module.verify_signature(signature, &verifying_key).is_ok() {
+            return Ok(key.key_hash);
+        }
+    }
+
+    Err(UpsertModuleError::SignatureNotVerified)
+}
+
+/// Tags and categories are matched exactly when searching, so they are trimmed, lowercased and
+/// deduplicated, both when a module is uploaded and when searching for them.
+pub(crate) fn normalize_labels(labels: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut labels: Vec<String> = labels
+        .into_iter()
+        .map(|label| label.trim().to_lowercase())
+        .filter(|label| !label.is_empty())
+        .collect();
+    labels.sort();
+    labels.dedup();
+    labels
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtraMetadata {
     pub version: String,
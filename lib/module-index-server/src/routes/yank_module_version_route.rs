@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use hyper::StatusCode;
+use module_index_client::ModuleVersionResponse;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, IntoActiveModel, QueryFilter, Set,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    app_state::AppState,
+    extract::{Authorization, DbConnection},
+    models::module_version::{self, PublisherQuery},
+    whoami::{is_systeminit_auth_token, WhoamiError},
+};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum YankModuleVersionError {
+    #[error("db error: {0}")]
+    DbErr(#[from] DbErr),
+    #[error(r#"Version "{1}" of module "{0}" can only be yanked by its publisher"#)]
+    Forbidden(String, String),
+    #[error(r#"Version "{1}" of module "{0}" not found"#)]
+    NotFound(String, String),
+    #[error("whoami error: {0}")]
+    Whoami(#[from] WhoamiError),
+}
+
+// TODO: figure out how to not keep this serialization logic here
+impl IntoResponse for YankModuleVersionError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            Self::Forbidden(_, _) => (StatusCode::FORBIDDEN, self.to_string()),
+            Self::NotFound(_, _) => (StatusCode::NOT_FOUND, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct YankModuleVersionRequest {
+    pub yanked_by_display_name: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Yanks a published version of a module, by default one of the caller's own, so that it is no
+/// longer offered for new installs. Only the version's publisher, or a System Initiative user,
+/// may yank it. Yanking an already yanked version leaves it as it was.
+pub async fn yank_module_version_route(
+    Path((module_name, version)): Path<(String, String)>,
+    Query(query): Query<PublisherQuery>,
+    Authorization {
+        user_claim,
+        auth_token,
+    }: Authorization,
+    DbConnection(txn): DbConnection,
+    State(state): State<AppState>,
+    Json(request): Json<YankModuleVersionRequest>,
+) -> Result<Json<ModuleVersionResponse>, YankModuleVersionError> {
+    let publisher_user_id = query
+        .publisher_user_id
+        .unwrap_or_else(|| user_claim.user_pk.to_string());
+    let module_version = match module_version::Entity::find()
+        .filter(module_version::Column::PublisherUserId.eq(publisher_user_id))
+        .filter(module_version::Column::Name.eq(module_name.as_str()))
+        .filter(module_version::Column::Version.eq(version.as_str()))
+        .one(&txn)
+        .await?
+    {
+        Some(module_version) => module_version,
+        None => return Err(YankModuleVersionError::NotFound(module_name, version)),
+    };
+
+    if module_version.publisher_user_id != user_claim.user_pk.to_string()
+        && !is_systeminit_auth_token(&auth_token, state.token_emails()).await?
+    {
+        return Err(YankModuleVersionError::Forbidden(module_name, version));
+    }
+
+    let module_version = if module_version.yanked_at.is_some() {
+        module_version
+    } else {
+        let mut active_version = module_version.into_active_model();
+        active_version.yanked_at = Set(Some(DateTime::<FixedOffset>::from_utc(
+            Utc::now().naive_utc(),
+            Utc.fix(),
+        )));
+        active_version.yanked_by_display_name = Set(request.yanked_by_display_name);
+        active_version.yank_reason = Set(request.reason);
+        active_version.update(&txn).await?
+    };
+
+    txn.commit().await?;
+
+    Ok(Json(module_version.into()))
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use s3::{
+    creds::Credentials as AwsCredentials, error::S3Error, Bucket as S3Bucket, Region as AwsRegion,
+};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::fs;
+use ulid::Ulid;
+
+use crate::s3::S3Config;
+
+/// How long a presigned S3 download url is valid for, in seconds.
+const PRESIGNED_URL_EXPIRY_SECS: u32 = 60 * 5;
+
+/// Where module bundles are stored. S3 settings live in their own `s3` config section.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(tag = "backend", rename_all = "camelCase")]
+pub enum StorageConfig {
+    #[default]
+    S3,
+    /// Keeps module bundles in a directory on the local filesystem, for self-hosted and
+    /// air-gapped module indexes.
+    LocalDirectory { path: PathBuf },
+}
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("invalid aws region {0}: {1}")]
+    InvalidRegion(String, String),
+    #[error("invalid module storage key: {0}")]
+    InvalidStorageKey(String),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("module not found in storage: {0}")]
+    NotFound(String),
+    #[error("s3 error: {0}")]
+    S3(#[from] S3Error),
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// A stored module bundle, ready to be handed to a client.
+#[derive(Debug)]
+pub enum StoredModule {
+    /// A url the client can download the bundle from directly.
+    Redirect(String),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Clone, Debug)]
+pub enum ModuleStorage {
+    S3(S3Bucket),
+    LocalDirectory(PathBuf),
+}
+
+impl ModuleStorage {
+    pub fn s3(config: &S3Config, aws_creds: AwsCredentials) -> StorageResult<Self> {
+        let region = config
+            .region
+            .parse::<AwsRegion>()
+            .map_err(|err| StorageError::InvalidRegion(config.region.clone(), err.to_string()))?;
+
+        Ok(Self::S3(S3Bucket::new(&config.bucket, region, aws_creds)?))
+    }
+
+    /// Stores module bundles under `path`, creating the directory if it does not exist.
+    pub fn local_directory(path: impl Into<PathBuf>) -> StorageResult<Self> {
+        let path = path.into();
+        std::fs::create_dir_all(&path)?;
+        info!(path = %path.display(), "storing modules in local directory");
+
+        Ok(Self::LocalDirectory(path))
+    }
+
+    /// The key a module bundle is stored under.
+    pub fn module_key(hash: impl std::fmt::Display) -> String {
+        format!("{hash}.sipkg")
+    }
+
+    pub async fn put(&self, key: &str, bytes: &[u8]) -> StorageResult<()> {
+        match self {
+            Self::S3(bucket) => {
+                bucket.put_object(key, bytes).await?;
+            }
+            Self::LocalDirectory(dir) => {
+                let path = local_path(dir, key)?;
+                // Write to a temporary file first so a partial upload is never served
+                let tmp_path = dir.join(format!(".{key}.{}.tmp", Ulid::new()));
+                fs::write(&tmp_path, bytes).await?;
+                fs::rename(&tmp_path, &path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> StorageResult<StoredModule> {
+        match self {
+            Self::S3(bucket) => Ok(StoredModule::Redirect(bucket.presign_get(
+                key,
+                PRESIGNED_URL_EXPIRY_SECS,
+                None,
+            )?)),
+            Self::LocalDirectory(dir) => match fs::read(local_path(dir, key)?).await {
+                Ok(bytes) => Ok(StoredModule::Bytes(bytes)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    Err(StorageError::NotFound(key.to_owned()))
+                }
+                Err(err) => Err(err.into()),
+            },
+        }
+    }
+}
+
+/// Keys are file names built from module hashes, so anything which could escape the storage
+/// directory is rejected.
+fn local_path(dir: &Path, key: &str) -> StorageResult<PathBuf> {
+    if key.is_empty()
+        || key.starts_with('.')
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+    {
+        return Err(StorageError::InvalidStorageKey(key.to_owned()));
+    }
+
+    Ok(dir.join(key))
+}
@@ -0,0 +1,589 @@
+//! End to end tests of the module index, storing modules in a local directory.
+//!
+//! These need the `si_test_module_index` Postgres database (or the one named by
+//! `SI_TEST_PG_DBNAME`) to be reachable.
+
+use std::{collections::HashMap, env, net::SocketAddr, path::PathBuf};
+
+use buck2_resources::Buck2Resources;
+use jwt_simple::{
+    algorithms::{RS256KeyPair, RSAKeyPairLike},
+    claims::Claims,
+    reexports::coarsetime::Duration,
+};
+use module_index_client::{IndexClient, ModuleSearchParams, UploadModuleOptions};
+use module_index_server::{server::build_service_for_tests, ModuleStorage, Server};
+use serde::Serialize;
+use si_data_pg::PgPoolConfig;
+use si_pkg::{PkgSpec, SchemaSpec, SchemaSpecData, SiPkg};
+use tempfile::TempDir;
+use tokio::sync::OnceCell;
+use ulid::Ulid;
+
+const ENV_VAR_PG_HOSTNAME: &str = "SI_TEST_PG_HOSTNAME";
+const ENV_VAR_PG_DBNAME: &str = "SI_TEST_PG_DBNAME";
+const DEFAULT_PG_DBNAME: &str = "si_test_module_index";
+
+static MIGRATIONS: OnceCell<()> = OnceCell::const_new();
+
+#[derive(Serialize)]
+struct UserClaim {
+    user_pk: Ulid,
+    workspace_pk: Ulid,
+}
+
+/// A module index listening on a local port, with a client for a regular user and one for a
+/// System Initiative user.
+struct TestModuleIndex {
+    client: IndexClient,
+    su_client: IndexClient,
+    base_url: url::Url,
+    storage_dir: TempDir,
+}
+
+#[allow(clippy::disallowed_methods)] // Test configuration comes from the environment
+fn pg_pool_config() -> PgPoolConfig {
+    let mut config = PgPoolConfig {
+        dbname: env::var(ENV_VAR_PG_DBNAME).unwrap_or_else(|_| DEFAULT_PG_DBNAME.to_owned()),
+        application_name: "si-module-index-test".to_owned(),
+        ..Default::default()
+    };
+    if let Ok(hostname) = env::var(ENV_VAR_PG_HOSTNAME) {
+        config.hostname = hostname;
+    }
+    config
+}
+
+#[allow(clippy::disallowed_methods)] // Used to find the development keys
+fn key_path(name: &str) -> PathBuf {
+    if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
+        Buck2Resources::read()
+            .expect("able to read buck2 resources")
+            .get_ends_with(name)
+            .expect("able to find key in buck2 resources")
+    } else {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("../../config/keys/{name}"))
+    }
+}
+
+fn auth_token(key_pair: &RS256KeyPair) -> String {
+    let claim = UserClaim {
+        user_pk: Ulid::new(),
+        workspace_pk: Ulid::new(),
+    };
+    let subject = claim.user_pk.to_string();
+    let claims = Claims::with_custom_claims(claim, Duration::from_days(1))
+        .with_audience("https://app.systeminit.com")
+        .with_issuer("https://app.systeminit.com")
+        .with_subject(subject);
+
+    key_pair.sign(claims).expect("able to sign jwt")
+}
+
+async fn start_module_index() -> TestModuleIndex {
+    let pg_pool_config = pg_pool_config();
+    MIGRATIONS
+        .get_or_init(|| async {
+            let pg_pool = Server::create_pg_pool(&pg_pool_config)
+                .await
+                .expect("able to create pg pool");
+            Server::run_migrations(&pg_pool)
+                .await
+                .expect("able to run migrations");
+        })
+        .await;
+    let db = Server::create_db_connection(&pg_pool_config)
+        .await
+        .expect("able to connect to database");
+
+    let jwt_public_signing_key =
+        Server::load_jwt_public_signing_key(key_path("dev.jwt_signing_public_key.pem"))
+            .await
+            .expect("able to load jwt public signing key");
+    let key_pair = RS256KeyPair::from_pem(
+        &tokio::fs::read_to_string(key_path("dev.jwt_signing_private_key.pem"))
+            .await
+            .expect("able to read jwt private signing key"),
+    )
+    .expect("able to parse jwt private signing key");
+    let user_token = auth_token(&key_pair);
+    let su_token = auth_token(&key_pair);
+
+    let (posthog_client, _posthog_sender) = si_posthog::new()
+        .enabled(false)
+        .build()
+        .expect("able to build disabled posthog client");
+
+    let storage_dir = TempDir::new().expect("able to create storage dir");
+    let storage =
+        ModuleStorage::local_directory(storage_dir.path()).expect("able to use storage dir");
+
+    let token_emails = HashMap::from([
+        (
+            format!("Bearer {user_token}"),
+            "pointsman@example.com".to_owned(),
+        ),
+        (
+            format!("Bearer {su_token}"),
+            "slothrop@systeminit.com".to_owned(),
+        ),
+    ]);
+    let (service, _, _) = build_service_for_tests(
+        db,
+        jwt_public_signing_key,
+        posthog_client,
+        storage,
+        token_emails,
+    )
+    .expect("able to build service");
+
+    let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+        .serve(service.into_make_service());
+    let base_url: url::Url = format!("http://{}", server.local_addr())
+        .parse()
+        .expect("able to parse server url");
+    tokio::spawn(server);
+
+    TestModuleIndex {
+        client: IndexClient::new(base_url.clone(), &user_token),
+        su_client: IndexClient::new(base_url.clone(), &su_token),
+        base_url,
+        storage_dir,
+    }
+}
+
+fn module(name: &str) -> SiPkg {
+    module_version(name, "1.0.0")
+}
+
+fn module_version(name: &str, version: &str) -> SiPkg {
+    SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name(name)
+            .version(version)
+            .created_by("Enzian")
+            .build()
+            .expect("able to build package spec"),
+    )
+    .expect("able to load pkg from spec")
+}
+
+fn module_in_category(name: &str, category: &str) -> SiPkg {
+    SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name(name)
+            .version("1.0.0")
+            .created_by("Enzian")
+            .schema(
+                SchemaSpec::builder()
+                    .name(name)
+                    .data(
+                        SchemaSpecData::builder()
+                            .name(name)
+                            .category(category)
+                            .build()
+                            .expect("able to build schema spec data"),
+                    )
+                    .build()
+                    .expect("able to build schema spec"),
+            )
+            .build()
+            .expect("able to build package spec"),
+    )
+    .expect("able to load pkg from spec")
+}
+
+async fn upload(index: &TestModuleIndex, name: &str) -> (Ulid, Vec<u8>) {
+    let bytes = module(name).write_to_bytes().expect("able to write pkg");
+    let details = index
+        .client
+        .upload_module(name, "1.0.0", bytes.clone())
+        .await
+        .expect("able to upload module");
+
+    (
+        Ulid::from_string(&details.id).expect("module id is a ulid"),
+        bytes,
+    )
+}
+
+#[tokio::test]
+async fn upload_and_download_module() {
+    let index = start_module_index().await;
+    let name = format!("Schwarzgerat {}", Ulid::new());
+
+    let (module_id, bytes) = upload(&index, &name).await;
+
+    let hash = module(&name).hash().expect("able to hash pkg").to_string();
+    assert!(index
+        .storage_dir
+        .path()
+        .join(format!("{hash}.sipkg"))
+        .is_file());
+
+    let downloaded = index
+        .client
+        .download_module(module_id)
+        .await
+        .expect("able to download module");
+    assert_eq!(bytes, downloaded);
+
+    assert!(index.client.download_module(Ulid::new()).await.is_err());
+}
+
+#[tokio::test]
+async fn promote_module_to_builtin() {
+    let index = start_module_index().await;
+    let name = format!("Rocket 00000 {}", Ulid::new());
+
+    let (module_id, bytes) = upload(&index, &name).await;
+
+    // Builtins can be downloaded without authenticating
+    let unauthenticated = IndexClient::unauthenticated_client(index.base_url.clone());
+    assert!(unauthenticated.get_builtin(module_id).await.is_err());
+
+    index
+        .su_client
+        .promote_to_builtin(module_id, "Slothrop".to_owned())
+        .await
+        .expect("able to promote module");
+
+    let builtins = unauthenticated
+        .list_builtins()
+        .await
+        .expect("able to list builtins");
+    assert!(builtins
+        .modules
+        .iter()
+        .any(|module| module.id == module_id.to_string()));
+
+    let downloaded = unauthenticated
+        .get_builtin(module_id)
+        .await
+        .expect("able to download builtin");
+    assert_eq!(bytes, downloaded);
+}
+
+#[tokio::test]
+async fn reject_module() {
+    let index = start_module_index().await;
+    let name = format!("Imipolex G {}", Ulid::new());
+
+    let (module_id, _) = upload(&index, &name).await;
+
+    let search = ModuleSearchParams {
+        name: Some(name.clone()),
+        ..Default::default()
+    };
+    let found = index
+        .client
+        .search_modules(&search)
+        .await
+        .expect("able to search modules");
+    assert_eq!(1, found.total_modules);
+
+    index
+        .su_client
+        .reject_module(module_id, "Pointsman".to_owned())
+        .await
+        .expect("able to reject module");
+
+    let found = index
+        .client
+        .search_modules(&search)
+        .await
+        .expect("able to search modules");
+    assert!(found.modules.is_empty());
+}
+
+#[tokio::test]
+async fn search_modules() {
+    let index = start_module_index().await;
+    let suffix = Ulid::new();
+
+    for (name, category, tags) in [
+        ("Rocket", "Weapons", vec!["V2 ", "Rocket"]),
+        ("Octopus", "animals", vec!["grigori"]),
+        ("Banana", "Food", vec!["rocket", ""]),
+    ] {
+        let name = format!("{name} {suffix}");
+        index
+            .client
+            .upload_module_with_options(
+                &name,
+                "1.0.0",
+                module_in_category(&name, category)
+                    .write_to_bytes()
+                    .expect("able to write pkg"),
+                &UploadModuleOptions {
+                    tags: tags.into_iter().map(ToOwned::to_owned).collect(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect("able to upload module");
+    }
+
+    let search = |params: ModuleSearchParams| {
+        let client = &index.client;
+        async move {
+            let mut names = client
+                .search_modules(&params)
+                .await
+                .expect("able to search modules")
+                .modules
+                .into_iter()
+                .map(|module| module.name)
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        }
+    };
+
+    assert_eq!(
+        vec![format!("Octopus {suffix}")],
+        search(ModuleSearchParams {
+            query: Some("octopus".to_owned()),
+            ..Default::default()
+        })
+        .await
+    );
+
+    // Tags and categories are matched regardless of case and surrounding whitespace
+    assert_eq!(
+        vec![format!("Banana {suffix}"), format!("Rocket {suffix}")],
+        search(ModuleSearchParams {
+            tags: Some(vec!["ROCKET".to_owned()]),
+            ..Default::default()
+        })
+        .await
+    );
+    assert_eq!(
+        vec![format!("Rocket {suffix}")],
+        search(ModuleSearchParams {
+            tags: Some(vec!["rocket".to_owned(), "v2".to_owned()]),
+            ..Default::default()
+        })
+        .await
+    );
+    assert_eq!(
+        vec![format!("Rocket {suffix}")],
+        search(ModuleSearchParams {
+            category: Some(" weapons".to_owned()),
+            ..Default::default()
+        })
+        .await
+    );
+    assert_eq!(
+        vec![format!("Octopus {suffix}")],
+        search(ModuleSearchParams {
+            category: Some("Animals".to_owned()),
+            ..Default::default()
+        })
+        .await
+    );
+
+    // Every module is listed unless a page is asked for
+    let all = index
+        .client
+        .search_modules(&ModuleSearchParams::default())
+        .await
+        .expect("able to search modules");
+    assert_eq!(3, all.modules.len());
+    assert_eq!((3, 1), (all.total_modules, all.total_pages));
+
+    let mut paged = vec![];
+    for page in 0..2 {
+        let found = index
+            .client
+            .search_modules(&ModuleSearchParams {
+                page: Some(page),
+                page_size: Some(2),
+                ..Default::default()
+            })
+            .await
+            .expect("able to search modules");
+        assert_eq!((3, 2), (found.total_modules, found.total_pages));
+        paged.extend(found.modules.into_iter().map(|module| module.id));
+    }
+    assert_eq!(3, paged.len());
+    paged.sort();
+    paged.dedup();
+    assert_eq!(3, paged.len());
+}
+
+#[tokio::test]
+async fn yank_module_version() {
+    let index = start_module_index().await;
+    let name = format!("Rocket 00001 {}", Ulid::new());
+
+    let first = module_version(&name, "1.0.0")
+        .write_to_bytes()
+        .expect("able to write pkg");
+    index
+        .client
+        .upload_module(&name, "1.0.0", first.clone())
+        .await
+        .expect("able to upload first version");
+    let second = module_version(&name, "1.1.0")
+        .write_to_bytes()
+        .expect("able to write pkg");
+    index
+        .client
+        .upload_module_with_options(
+            &name,
+            "1.1.0",
+            second,
+            &UploadModuleOptions {
+                changelog: Some("Adds a second stage".to_owned()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("able to upload second version");
+
+    // Each version can only be published once
+    assert!(index
+        .client
+        .upload_module(&name, "1.1.0", first.clone())
+        .await
+        .is_err());
+
+    let versions = index
+        .client
+        .list_published_versions(&name)
+        .await
+        .expect("able to list versions");
+    assert_eq!(
+        vec!["1.1.0", "1.0.0"],
+        versions
+            .iter()
+            .map(|version| version.version.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some("Adds a second stage"),
+        versions[0].changelog.as_deref()
+    );
+
+    let yanked = index
+        .client
+        .yank_module_version(&name, "1.1.0", None, Some("Pointsman"), Some("Falls short"))
+        .await
+        .expect("able to yank version");
+    assert!(yanked.yanked_at.is_some());
+    assert_eq!(Some("Falls short"), yanked.yank_reason.as_deref());
+
+    // Yanked versions aren't offered for new installs, but remain in the history and can still
+    // be downloaded
+    let installable = index
+        .client
+        .list_published_versions(&name)
+        .await
+        .expect("able to list versions")
+        .into_iter()
+        .filter(|version| version.yanked_at.is_none())
+        .map(|version| version.version)
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["1.0.0"], installable);
+    assert_eq!(
+        2,
+        index
+            .client
+            .list_published_versions(&name)
+            .await
+            .expect("able to list versions")
+            .len()
+    );
+    assert_eq!(
+        first,
+        index
+            .client
+            .download_module_version(&name, "1.0.0", None)
+            .await
+            .expect("able to download version")
+    );
+    assert!(index
+        .client
+        .download_module_version(&name, "1.1.0", None)
+        .await
+        .is_ok());
+    assert!(index
+        .client
+        .download_module_version(&name, "2.0.0", None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn versions_are_scoped_to_their_publisher() {
+    let index = start_module_index().await;
+    let name = format!("Imipolex {}", Ulid::new());
+
+    let bytes = module_version(&name, "1.0.0")
+        .write_to_bytes()
+        .expect("able to write pkg");
+    index
+        .client
+        .upload_module(&name, "1.0.0", bytes.clone())
+        .await
+        .expect("able to upload version");
+    // Another publisher can publish the same version of a module with the same name
+    index
+        .su_client
+        .upload_module(&name, "1.0.0", bytes.clone())
+        .await
+        .expect("able to upload the same version as another publisher");
+    assert!(index
+        .su_client
+        .upload_module(&name, "1.0.0", bytes)
+        .await
+        .is_err());
+
+    let versions = index
+        .client
+        .list_published_versions(&name)
+        .await
+        .expect("able to list versions");
+    assert_eq!(2, versions.len());
+
+    // Yanking and downloading address the caller's own version unless told otherwise
+    let yanked = index
+        .client
+        .yank_module_version(&name, "1.0.0", None, None, None)
+        .await
+        .expect("able to yank own version");
+    let own_publisher_user_id = yanked.publisher_user_id.clone();
+    let other_publisher_user_id = versions
+        .iter()
+        .map(|version| version.publisher_user_id.clone())
+        .find(|publisher_user_id| *publisher_user_id != own_publisher_user_id)
+        .expect("able to find the other publisher's version");
+    assert_eq!(
+        vec![other_publisher_user_id.as_str()],
+        index
+            .client
+            .list_published_versions(&name)
+            .await
+            .expect("able to list versions")
+            .iter()
+            .filter(|version| version.yanked_at.is_none())
+            .map(|version| version.publisher_user_id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(index
+        .client
+        .yank_module_version(&name, "1.0.0", Some(&other_publisher_user_id), None, None)
+        .await
+        .is_err());
+    assert!(index
+        .client
+        .download_module_version(&name, "1.0.0", Some(&other_publisher_user_id))
+        .await
+        .is_ok());
+    assert!(index
+        .client
+        .download_module_version(&name, "2.0.0", Some(&own_publisher_user_id))
+        .await
+        .is_err());
+}
@@ -15,7 +15,7 @@ use futures::{Stream, StreamExt};
 use futures_lite::future::FutureExt;
 use pin_project_lite::pin_project;
 use serde::de::DeserializeOwned;
-use si_data_nats::NatsError;
+use si_data_nats::{propagation, HeaderMap, NatsError};
 use telemetry::prelude::*;
 use thiserror::Error;
 
@@ -50,6 +50,8 @@ pub struct Request<T> {
     pub payload: T,
     /// An optional reply mailbox.
     pub reply_mailbox: Option<String>,
+    /// The headers of the message, if any.
+    pub headers: Option<HeaderMap>,
 }
 
 impl<T> Request<T> {
@@ -57,6 +59,12 @@ impl<T> Request<T> {
     pub fn into_parts(self) -> (T, Option<String>) {
         (self.payload, self.reply_mailbox)
     }
+
+    /// Continues the trace of whoever published this request by making their span the parent of
+    /// `span`. This should be called before any child spans of `span` are created.
+    pub fn continue_trace(&self, span: &Span) {
+        propagation::set_parent_from_headers(span, self.headers.as_ref());
+    }
 }
 
 pin_project! {
@@ -141,6 +149,7 @@ where
                     }
                 }
 
+                let headers = nats_msg.headers().cloned();
                 let (data, reply) = nats_msg.into_parts();
                 let reply_mailbox = reply;
 
@@ -163,6 +172,7 @@ where
                 Poll::Ready(Some(Ok(Request {
                     payload,
                     reply_mailbox,
+                    headers,
                 })))
             }
             // We see no more messages on the subject, so let's decide what to do
@@ -1,6 +1,7 @@
-use std::{env, path::Path};
+use std::{env, path::Path, time::Duration};
 
 use buck2_resources::Buck2Resources;
+use dal::SecretProvidersConfig;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_crypto::{SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile};
@@ -10,11 +11,15 @@ use si_std::{CanonicalFile, CanonicalFileError};
 use telemetry::prelude::*;
 use thiserror::Error;
 
+pub use dal::JobQueueTransport;
 pub use si_crypto::CycloneKeyPair;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 use ulid::Ulid;
 
 const DEFAULT_CONCURRENCY_LIMIT: usize = 5;
+const DEFAULT_SCHEDULE_POLL_INTERVAL_SECS: u64 = 10;
+const DEFAULT_JETSTREAM_MAX_DELIVER: i64 = 5;
+const DEFAULT_JETSTREAM_ACK_WAIT_SECS: u64 = 30;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -37,6 +42,26 @@ impl ConfigError {
 
 type Result<T> = std::result::Result<T, ConfigError>;
 
+/// Settings for the durable JetStream consumer used when jobs are received over
+/// [`JobQueueTransport::JetStream`].
+#[derive(Clone, Copy, Debug)]
+pub struct JetStreamConsumerConfig {
+    /// How many times a job is delivered before JetStream gives up on it.
+    pub max_deliver: i64,
+    /// How long JetStream waits for a job to be acknowledged before delivering it again. Jobs
+    /// that take longer than this are kept alive with progress acknowledgements.
+    pub ack_wait: Duration,
+}
+
+impl Default for JetStreamConsumerConfig {
+    fn default() -> Self {
+        Self {
+            max_deliver: DEFAULT_JETSTREAM_MAX_DELIVER,
+            ack_wait: Duration::from_secs(DEFAULT_JETSTREAM_ACK_WAIT_SECS),
+        }
+    }
+}
+
 #[derive(Debug, Builder)]
 pub struct Config {
     #[builder(default = "PgPoolConfig::default()")]
@@ -53,7 +78,19 @@ pub struct Config {
     #[builder(default = "random_instance_id()")]
     instance_id: String,
 
+    #[builder(default = "Duration::from_secs(default_schedule_poll_interval_secs())")]
+    schedule_poll_interval: Duration,
+
+    #[builder(default = "JobQueueTransport::default()")]
+    job_queue: JobQueueTransport,
+
+    #[builder(default = "JetStreamConsumerConfig::default()")]
+    jetstream_consumer: JetStreamConsumerConfig,
+
     symmetric_crypto_service: SymmetricCryptoServiceConfig,
+
+    #[builder(default = "SecretProvidersConfig::default()")]
+    secret_providers: SecretProvidersConfig,
 }
 
 impl StandardConfig for Config {
@@ -88,6 +125,11 @@ impl Config {
         &self.symmetric_crypto_service
     }
 
+    /// Gets the config for the providers which resolve secret references.
+    pub fn secret_providers(&self) -> &SecretProvidersConfig {
+        &self.secret_providers
+    }
+
     /// Gets the config's concurrency limit.
     pub fn concurrency(&self) -> usize {
         self.concurrency
@@ -97,6 +139,21 @@ impl Config {
     pub fn instance_id(&self) -> &str {
         self.instance_id.as_ref()
     }
+
+    /// Gets how often the server polls for due job schedules.
+    pub fn schedule_poll_interval(&self) -> Duration {
+        self.schedule_poll_interval
+    }
+
+    /// Gets the transport jobs are received and sent over.
+    pub fn job_queue(&self) -> JobQueueTransport {
+        self.job_queue
+    }
+
+    /// Gets the settings for the JetStream consumer, used with [`JobQueueTransport::JetStream`].
+    pub fn jetstream_consumer(&self) -> JetStreamConsumerConfig {
+        self.jetstream_consumer
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -111,8 +168,18 @@ pub struct ConfigFile {
     concurrency_limit: usize,
     #[serde(default = "random_instance_id")]
     instance_id: String,
+    #[serde(default = "default_schedule_poll_interval_secs")]
+    schedule_poll_interval_secs: u64,
+    #[serde(default)]
+    job_queue: JobQueueTransport,
+    #[serde(default = "default_jetstream_max_deliver")]
+    jetstream_max_deliver: i64,
+    #[serde(default = "default_jetstream_ack_wait_secs")]
+    jetstream_ack_wait_secs: u64,
     #[serde(default = "default_symmetric_crypto_config")]
     symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
+    #[serde(default)]
+    secret_providers: SecretProvidersConfig,
 }
 
 impl Default for ConfigFile {
@@ -123,7 +190,12 @@ impl Default for ConfigFile {
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             concurrency_limit: default_concurrency_limit(),
             instance_id: random_instance_id(),
+            schedule_poll_interval_secs: default_schedule_poll_interval_secs(),
+            job_queue: Default::default(),
+            jetstream_max_deliver: default_jetstream_max_deliver(),
+            jetstream_ack_wait_secs: default_jetstream_ack_wait_secs(),
             symmetric_crypto_service: default_symmetric_crypto_config(),
+            secret_providers: Default::default(),
         }
     }
 }
@@ -144,7 +216,16 @@ impl TryFrom<ConfigFile> for Config {
         config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
         config.concurrency(value.concurrency_limit);
         config.instance_id(value.instance_id);
+        config.schedule_poll_interval(Duration::from_secs(value.schedule_poll_interval_secs));
+        config.job_queue(value.job_queue);
+        config.jetstream_consumer(JetStreamConsumerConfig {
+            max_deliver: value.jetstream_max_deliver,
+            ack_wait: Duration::from_secs(value.jetstream_ack_wait_secs),
+        });
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
+        let mut secret_providers = value.secret_providers;
+        secret_providers.snapshot_env(secret_provider_env());
+        config.secret_providers(secret_providers);
         config.build().map_err(Into::into)
     }
 }
@@ -168,6 +249,24 @@ fn default_concurrency_limit() -> usize {
     DEFAULT_CONCURRENCY_LIMIT
 }
 
+fn default_schedule_poll_interval_secs() -> u64 {
+    DEFAULT_SCHEDULE_POLL_INTERVAL_SECS
+}
+
+fn default_jetstream_max_deliver() -> i64 {
+    DEFAULT_JETSTREAM_MAX_DELIVER
+}
+
+fn default_jetstream_ack_wait_secs() -> u64 {
+    DEFAULT_JETSTREAM_ACK_WAIT_SECS
+}
+
+#[allow(clippy::disallowed_methods)] // Snapshots the variables `env:` secret references resolve to
+fn secret_provider_env() -> impl Iterator<Item = (String, String)> {
+    env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
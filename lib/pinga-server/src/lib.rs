@@ -4,7 +4,7 @@ pub mod server;
 pub use crate::{
     config::{
         detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
-        StandardConfig, StandardConfigFile,
+        JetStreamConsumerConfig, JobQueueTransport, StandardConfig, StandardConfigFile,
     },
     server::{Server, ServerError},
 };
@@ -1,18 +1,28 @@
-use std::{io, path::Path, sync::Arc};
+use std::{future::Future, io, path::Path, sync::Arc, time::Duration};
 
 use dal::{
     job::{
         consumer::{JobConsumer, JobConsumerError, JobInfo},
-        definition::{FixesJob, RefreshJob},
-        producer::BlockingJobError,
+        definition::job_consumer_from_job_info,
+        processor::JETSTREAM_REPLY_MAILBOX_HEADER,
+        producer::{BlockingJobError, BlockingJobResult},
     },
-    DalContext, DalContextBuilder, DependentValuesUpdate, InitializationError, JobFailure,
-    JobFailureError, JobQueueProcessor, NatsProcessor, ServicesContext, TransactionsError,
+    DalContext, DalContextBuilder, InitializationError, JetStreamProcessor, JobDeadLetter,
+    JobDeadLetterError, JobFailure, JobFailureError, JobQueueProcessor, JobQueueProcessorError,
+    JobQueueTransport, JobSchedule, JobScheduleError, SecretProviders, SecretProvidersConfig,
+    ServicesContext, TransactionsError,
 };
-use futures::{FutureExt, Stream, StreamExt};
+use futures::{stream::BoxStream, FutureExt, StreamExt};
 use nats_subscriber::{Request, SubscriberError};
 use si_crypto::{SymmetricCryptoError, SymmetricCryptoService, SymmetricCryptoServiceConfig};
-use si_data_nats::{NatsClient, NatsConfig, NatsError};
+use si_data_nats::{
+    jetstream::{
+        self,
+        consumer::{pull, AckPolicy},
+        AckKind,
+    },
+    NatsClient, NatsConfig, NatsError,
+};
 use si_data_pg::{PgPool, PgPoolConfig, PgPoolError};
 use stream_cancel::StreamExt as StreamCancelStreamExt;
 use telemetry::prelude::*;
@@ -24,11 +34,15 @@ use tokio::{
         oneshot, watch,
     },
     task,
+    time::{self, Instant, MissedTickBehavior},
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use veritech_client::{Client as VeritechClient, CycloneEncryptionKey, CycloneEncryptionKeyError};
 
-use crate::{nats_jobs_subject, Config, NATS_JOBS_DEFAULT_QUEUE};
+use crate::{nats_jobs_subject, Config, JetStreamConsumerConfig, NATS_JOBS_DEFAULT_QUEUE};
+
+/// The maximum number of due job schedules claimed in a single database transaction.
+const SCHEDULED_JOBS_BATCH_SIZE: i64 = 100;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -37,15 +51,27 @@ pub enum ServerError {
     EncryptionKey(#[from] CycloneEncryptionKeyError),
     #[error(transparent)]
     Initialization(#[from] InitializationError),
+    #[error("jetstream error: {0}")]
+    JetStream(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
     #[error(transparent)]
     JobConsumer(#[from] JobConsumerError),
     #[error(transparent)]
+    JobDeadLetter(#[from] Box<JobDeadLetterError>),
+    #[error(transparent)]
     JobFailure(#[from] Box<JobFailureError>),
     #[error(transparent)]
+    JobQueueProcessor(#[from] Box<JobQueueProcessorError>),
+    #[error(transparent)]
+    JobSchedule(#[from] Box<JobScheduleError>),
+    #[error("job task failed to run to completion: {0}")]
+    JobTask(#[from] task::JoinError),
+    #[error(transparent)]
     Nats(#[from] NatsError),
     #[error(transparent)]
     PgPool(#[from] Box<PgPoolError>),
     #[error(transparent)]
+    SecretProvider(#[from] dal::secret::SecretProviderError),
+    #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
@@ -55,8 +81,12 @@ pub enum ServerError {
     SymmetricCryptoService(#[from] SymmetricCryptoError),
     #[error(transparent)]
     Transactions(#[from] Box<TransactionsError>),
-    #[error("unknown job kind {0}")]
-    UnknownJobKind(String),
+}
+
+impl ServerError {
+    fn jetstream(err: impl std::error::Error + 'static + Sync + Send) -> Self {
+        Self::JetStream(Box::new(err))
+    }
 }
 
 impl From<PgPoolError> for ServerError {
@@ -65,12 +95,30 @@ impl From<PgPoolError> for ServerError {
     }
 }
 
+impl From<JobDeadLetterError> for ServerError {
+    fn from(e: JobDeadLetterError) -> Self {
+        Self::JobDeadLetter(Box::new(e))
+    }
+}
+
 impl From<JobFailureError> for ServerError {
     fn from(e: JobFailureError) -> Self {
         Self::JobFailure(Box::new(e))
     }
 }
 
+impl From<JobQueueProcessorError> for ServerError {
+    fn from(e: JobQueueProcessorError) -> Self {
+        Self::JobQueueProcessor(Box::new(e))
+    }
+}
+
+impl From<JobScheduleError> for ServerError {
+    fn from(e: JobScheduleError) -> Self {
+        Self::JobSchedule(Box::new(e))
+    }
+}
+
 impl From<TransactionsError> for ServerError {
     fn from(e: TransactionsError) -> Self {
         Self::Transactions(Box::new(e))
@@ -81,6 +129,11 @@ type Result<T> = std::result::Result<T, ServerError>;
 
 pub struct Server {
     concurrency_limit: usize,
+    /// How often due [`JobSchedules`](JobSchedule) are polled for and fired.
+    schedule_poll_interval: Duration,
+    /// The transport jobs are received over.
+    job_queue: JobQueueTransport,
+    jetstream_consumer: JetStreamConsumerConfig,
     services_context: ServicesContext,
     /// An internal shutdown watch receiver handle which can be provided to internal tasks which
     /// want to be notified when a shutdown event is in progress.
@@ -104,9 +157,10 @@ impl Server {
         let nats = Self::connect_to_nats(config.nats()).await?;
         let pg_pool = Self::create_pg_pool(config.pg_pool()).await?;
         let veritech = Self::create_veritech_client(nats.clone());
-        let job_processor = Self::create_job_processor(nats.clone());
+        let job_processor = Self::create_job_processor(config.job_queue(), nats.clone()).await?;
         let symmetric_crypto_service =
             Self::create_symmetric_crypto_service(config.symmetric_crypto_service()).await?;
+        let secret_providers = Self::create_secret_providers(config.secret_providers())?;
 
         let services_context = ServicesContext::new(
             pg_pool,
@@ -117,11 +171,15 @@ impl Server {
             None,
             None,
             symmetric_crypto_service,
+            secret_providers,
         );
 
         Self::from_services(
             config.instance_id().to_string(),
             config.concurrency(),
+            config.schedule_poll_interval(),
+            config.job_queue(),
+            config.jetstream_consumer(),
             services_context,
         )
     }
@@ -131,6 +189,9 @@ impl Server {
     pub fn from_services(
         instance_id: impl Into<String>,
         concurrency_limit: usize,
+        schedule_poll_interval: Duration,
+        job_queue: JobQueueTransport,
+        jetstream_consumer: JetStreamConsumerConfig,
         services_context: ServicesContext,
     ) -> Result<Self> {
         // An mpsc channel which can be used to externally shut down the server.
@@ -152,6 +213,9 @@ impl Server {
 
         Ok(Server {
             concurrency_limit,
+            schedule_poll_interval,
+            job_queue,
+            jetstream_consumer,
             services_context,
             shutdown_watch_rx,
             external_shutdown_tx,
@@ -169,12 +233,22 @@ impl Server {
             self.concurrency_limit,
         )));
 
+        // Spawn a task to periodically claim due job schedules and enqueue their jobs
+        drop(task::spawn(fire_scheduled_jobs_task(
+            self.metadata.clone(),
+            self.services_context.clone(),
+            self.schedule_poll_interval,
+            self.shutdown_watch_rx.clone(),
+        )));
+
         // Run "the main loop" which pulls message from a subscription off NATS and forwards each
         // request to an unbounded channel
         receive_job_requests_task(
             tx,
             self.metadata,
             self.services_context,
+            self.job_queue,
+            self.jetstream_consumer,
             self.shutdown_watch_rx,
         )
         .await;
@@ -218,8 +292,11 @@ impl Server {
     }
 
     #[instrument(name = "pinga.init.create_job_processor", skip_all)]
-    fn create_job_processor(nats: NatsClient) -> Box<dyn JobQueueProcessor + Send + Sync> {
-        Box::new(NatsProcessor::new(nats)) as Box<dyn JobQueueProcessor + Send + Sync>
+    async fn create_job_processor(
+        job_queue: JobQueueTransport,
+        nats: NatsClient,
+    ) -> Result<Box<dyn JobQueueProcessor + Send + Sync>> {
+        Ok(job_queue.processor(nats).await?)
     }
 
     #[instrument(name = "pinga.init.create_symmetric_crypto_service", skip_all)]
@@ -230,6 +307,11 @@ impl Server {
             .await
             .map_err(Into::into)
     }
+
+    #[instrument(name = "pinga.init.create_secret_providers", skip_all)]
+    fn create_secret_providers(config: &SecretProvidersConfig) -> Result<SecretProviders> {
+        SecretProviders::from_config(config).map_err(Into::into)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -267,6 +349,87 @@ pub struct JobItem {
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Result<Request<JobInfo>>,
+    /// Set when the job was received over JetStream and must be acknowledged once processed.
+    delivery: Option<JetStreamDelivery>,
+}
+
+/// What should happen to a job's message once pinga is done with it.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JobDisposition {
+    /// The job ran, or it could not and that was recorded as a dead letter, so it must not be
+    /// delivered again.
+    Done,
+    /// The job could not be run, or its failure could not be recorded, because of an
+    /// infrastructure error, so it should be delivered again.
+    Redeliver,
+}
+
+/// How a job ended when it could be run and its outcome could be recorded.
+enum JobOutcome {
+    Succeeded,
+    /// The job failed, and was recorded as a [`JobFailure`] and [`JobDeadLetter`].
+    Failed(ServerError),
+}
+
+/// A job message received from a JetStream consumer, which is redelivered (up to the consumer's
+/// max deliver) unless it is acknowledged in time.
+pub struct JetStreamDelivery {
+    message: jetstream::Message,
+    progress_interval: Duration,
+    max_deliver: i64,
+}
+
+impl JetStreamDelivery {
+    pub fn new(message: jetstream::Message, progress_interval: Duration, max_deliver: i64) -> Self {
+        Self {
+            message,
+            progress_interval,
+            max_deliver,
+        }
+    }
+
+    /// Whether JetStream gives up on the job if this delivery doesn't settle it, in which case a
+    /// job that can't be processed must be dead-lettered now rather than redelivered.
+    pub fn is_last_delivery(&self) -> bool {
+        self.max_deliver > 0
+            && self
+                .message
+                .info()
+                .map(|info| info.delivered >= self.max_deliver)
+                .unwrap_or(false)
+    }
+
+    /// Acknowledges a job that is done with, or asks JetStream to deliver it again right away.
+    pub async fn settle(&self, disposition: JobDisposition) {
+        match disposition {
+            JobDisposition::Done => self.ack_with(AckKind::Ack).await,
+            JobDisposition::Redeliver => self.ack_with(AckKind::Nak(None)).await,
+        }
+    }
+
+    /// Drives `future` to completion, periodically telling JetStream that the job is still being
+    /// worked on so that long running jobs aren't redelivered to another instance.
+    pub async fn keep_alive<F: Future>(&self, future: F) -> F::Output {
+        tokio::pin!(future);
+        let mut progress = time::interval_at(
+            Instant::now() + self.progress_interval,
+            self.progress_interval,
+        );
+
+        loop {
+            tokio::select! {
+                output = &mut future => return output,
+                _ = progress.tick() => self.ack_with(AckKind::Progress).await,
+            }
+        }
+    }
+
+    async fn ack_with(&self, kind: AckKind) {
+        if let Err(err) = self.message.ack_with(kind).await {
+            warn!(error = ?err, ?kind, "failed to acknowledge job message");
+        }
+    }
 }
 
 pub struct Subscriber;
@@ -275,7 +438,7 @@ impl Subscriber {
     pub async fn jobs(
         metadata: Arc<ServerMetadata>,
         services_context: ServicesContext,
-    ) -> Result<impl Stream<Item = JobItem>> {
+    ) -> Result<BoxStream<'static, JobItem>> {
         let nats = services_context.nats_conn().clone();
 
         let subject = nats_jobs_subject(nats.metadata().subject_prefix());
@@ -299,7 +462,83 @@ impl Subscriber {
                 messaging_destination: messaging_destination.clone(),
                 ctx_builder: ctx_builder.clone(),
                 request: request.map_err(Into::into),
-            }))
+                delivery: None,
+            })
+            .boxed())
+    }
+
+    /// Pulls jobs from a durable JetStream consumer which is shared by every `pinga` instance, so
+    /// each job is processed by one instance and jobs published while no instance is running are
+    /// kept until one is.
+    pub async fn jetstream_jobs(
+        metadata: Arc<ServerMetadata>,
+        services_context: ServicesContext,
+        consumer_config: JetStreamConsumerConfig,
+    ) -> Result<BoxStream<'static, JobItem>> {
+        let processor = JetStreamProcessor::new(services_context.nats_conn().clone());
+        let stream = processor.get_or_create_stream().await?;
+
+        let subject = processor.subject().to_owned();
+        debug!(
+            messaging.destination = &subject.as_str(),
+            jetstream.stream = processor.stream_name(),
+            "consuming job requests"
+        );
+
+        let consumer = stream
+            .get_or_create_consumer(
+                NATS_JOBS_DEFAULT_QUEUE,
+                pull::Config {
+                    durable_name: Some(NATS_JOBS_DEFAULT_QUEUE.to_owned()),
+                    ack_policy: AckPolicy::Explicit,
+                    ack_wait: consumer_config.ack_wait,
+                    max_deliver: consumer_config.max_deliver,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(ServerError::jetstream)?;
+        let messages = consumer.messages().await.map_err(ServerError::jetstream)?;
+
+        // Make non blocking context here, and update it for each job
+        // Since the any blocking job should block on its child jobs
+        let ctx_builder = DalContext::builder(services_context, false);
+
+        let messaging_destination = Arc::new(subject);
+        let progress_interval = consumer_config.ack_wait / 2;
+        let max_deliver = consumer_config.max_deliver;
+
+        Ok(messages
+            .map(move |message| {
+                let (request, delivery) = match message {
+                    Ok(message) => {
+                        let request = serde_json::from_slice::<JobInfo>(&message.payload)
+                            .map(|payload| Request {
+                                payload,
+                                reply_mailbox: message
+                                    .headers
+                                    .as_ref()
+                                    .and_then(|headers| headers.get(JETSTREAM_REPLY_MAILBOX_HEADER))
+                                    .map(|mailbox| mailbox.as_str().to_owned()),
+                                headers: message.headers.clone(),
+                            })
+                            .map_err(Into::into);
+                        let delivery =
+                            JetStreamDelivery::new(message, progress_interval, max_deliver);
+                        (request, Some(delivery))
+                    }
+                    Err(err) => (Err(ServerError::jetstream(err)), None),
+                };
+
+                JobItem {
+                    metadata: metadata.clone(),
+                    messaging_destination: messaging_destination.clone(),
+                    ctx_builder: ctx_builder.clone(),
+                    request,
+                    delivery,
+                }
+            })
+            .boxed())
     }
 }
 
@@ -307,9 +546,19 @@ async fn receive_job_requests_task(
     tx: UnboundedSender<JobItem>,
     metadata: Arc<ServerMetadata>,
     services_context: ServicesContext,
+    job_queue: JobQueueTransport,
+    jetstream_consumer: JetStreamConsumerConfig,
     shutdown_watch_rx: watch::Receiver<()>,
 ) {
-    if let Err(err) = receive_job_requests(tx, metadata, services_context, shutdown_watch_rx).await
+    if let Err(err) = receive_job_requests(
+        tx,
+        metadata,
+        services_context,
+        job_queue,
+        jetstream_consumer,
+        shutdown_watch_rx,
+    )
+    .await
     {
         warn!(error = ?err, "processing job requests failed");
     }
@@ -319,11 +568,17 @@ async fn receive_job_requests(
     tx: UnboundedSender<JobItem>,
     metadata: Arc<ServerMetadata>,
     services_context: ServicesContext,
+    job_queue: JobQueueTransport,
+    jetstream_consumer: JetStreamConsumerConfig,
     mut shutdown_watch_rx: watch::Receiver<()>,
 ) -> Result<()> {
-    let mut requests = Subscriber::jobs(metadata, services_context)
-        .await?
-        .take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
+    let requests = match job_queue {
+        JobQueueTransport::JetStream => {
+            Subscriber::jetstream_jobs(metadata, services_context, jetstream_consumer).await?
+        }
+        JobQueueTransport::Nats => Subscriber::jobs(metadata, services_context).await?,
+    };
+    let mut requests = requests.take_until_if(Box::pin(shutdown_watch_rx.changed().map(|_| true)));
 
     // Forward each request off the stream to a consuming task via an *unbounded* channel so we
     // buffer requests until we run out of memory. Have fun!
@@ -336,6 +591,44 @@ async fn receive_job_requests(
     Ok(())
 }
 
+async fn fire_scheduled_jobs_task(
+    metadata: Arc<ServerMetadata>,
+    services_context: ServicesContext,
+    poll_interval: Duration,
+    mut shutdown_watch_rx: watch::Receiver<()>,
+) {
+    let mut interval = time::interval(poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = shutdown_watch_rx.changed() => {
+                trace!("scheduled jobs task received shutdown");
+                break;
+            }
+            _ = interval.tick() => {
+                if let Err(err) = fire_scheduled_jobs(&metadata, &services_context).await {
+                    warn!(error = ?err, "firing scheduled jobs failed");
+                }
+            }
+        }
+    }
+}
+
+#[instrument(name = "pinga.fire_scheduled_jobs", skip_all, level = "debug")]
+async fn fire_scheduled_jobs(
+    metadata: &ServerMetadata,
+    services_context: &ServicesContext,
+) -> Result<()> {
+    JobSchedule::enqueue_due(
+        services_context,
+        &metadata.job_instance,
+        SCHEDULED_JOBS_BATCH_SIZE,
+    )
+    .await?;
+    Ok(())
+}
+
 async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_limit: usize) {
     UnboundedReceiverStream::new(rx)
         .for_each_concurrent(concurrency_limit, |job| async move {
@@ -344,27 +637,64 @@ async fn process_job_requests_task(rx: UnboundedReceiver<JobItem>, concurrency_l
 
             match job.request {
                 Ok(request) => {
+                    // Jobs received over core NATS are never redelivered, so every delivery of
+                    // them is the last
+                    let last_delivery = job
+                        .delivery
+                        .as_ref()
+                        .map_or(true, JetStreamDelivery::is_last_delivery);
+                    let job_info = request.payload.clone();
+                    let reply_mailbox = request.reply_mailbox.clone();
+
                     // Spawn a task and process the request
                     let join_handle = task::spawn(execute_job_task(
                         job.metadata,
                         job.messaging_destination,
-                        job.ctx_builder,
+                        job.ctx_builder.clone(),
                         request,
+                        last_delivery,
                     ));
-                    if let Err(err) = join_handle.await {
-                        // NOTE(fnichol): This likely happens when there is contention or
-                        // an error in the Tokio runtime so we will be loud and log an
-                        // error under the assumptions that 1) this event rarely
-                        // happens and 2) the task code did not contribute to trigger
-                        // the `JoinError`.
-                        error!(
-                            error = ?err,
-                            "execute-job-task failed to execute to completion"
-                        );
+                    let join_result = match &job.delivery {
+                        Some(delivery) => delivery.keep_alive(join_handle).await,
+                        None => join_handle.await,
+                    };
+                    let disposition = match join_result {
+                        Ok(disposition) => disposition,
+                        Err(err) => {
+                            // NOTE(fnichol): This likely happens when there is contention or
+                            // an error in the Tokio runtime so we will be loud and log an
+                            // error under the assumptions that 1) this event rarely
+                            // happens and 2) the task code did not contribute to trigger
+                            // the `JoinError`.
+                            error!(
+                                error = ?err,
+                                "execute-job-task failed to execute to completion"
+                            );
+                            if last_delivery {
+                                let err = ServerError::from(err);
+                                dead_letter_last_delivery(&job.ctx_builder, &job_info, &err).await;
+                                reply(
+                                    &job.ctx_builder,
+                                    reply_mailbox,
+                                    Err(BlockingJobError::JobExecution(err.to_string())),
+                                )
+                                .await;
+                                JobDisposition::Done
+                            } else {
+                                JobDisposition::Redeliver
+                            }
+                        }
                     };
+                    if let Some(delivery) = &job.delivery {
+                        delivery.settle(disposition).await;
+                    }
                 }
                 Err(err) => {
                     warn!(error = ?err, "next job request had an error, job will not be executed");
+                    // A message that can't be decoded never will be, so don't redeliver it
+                    if let Some(delivery) = &job.delivery {
+                        delivery.ack_with(AckKind::Term).await;
+                    }
                 }
             }
         })
@@ -395,8 +725,10 @@ async fn execute_job_task(
     messaging_destination: Arc<String>,
     ctx_builder: DalContextBuilder,
     request: Request<JobInfo>,
-) {
+    last_delivery: bool,
+) -> JobDisposition {
     let span = Span::current();
+    request.continue_trace(&span);
     let id = request.payload.id.clone();
 
     let arg_str = serde_json::to_string(&request.payload.arg)
@@ -410,6 +742,7 @@ async fn execute_job_task(
     );
 
     let maybe_reply_channel = request.reply_mailbox.clone();
+    let job_info = request.payload.clone();
     let reply_message = match execute_job(
         &metadata,
         messaging_destination,
@@ -418,11 +751,11 @@ async fn execute_job_task(
     )
     .await
     {
-        Ok(_) => {
+        Ok(JobOutcome::Succeeded) => {
             span.record_ok();
             Ok(())
         }
-        Err(err) => {
+        Ok(JobOutcome::Failed(err)) => {
             error!(
                 error = ?err,
                 job.invocation_id = %id,
@@ -432,10 +765,46 @@ async fn execute_job_task(
             let new_err = Err(BlockingJobError::JobExecution(err.to_string()));
             span.record_err(err);
 
+            new_err
+        }
+        // The job is redelivered, so whoever is blocking on it waits for a later attempt
+        Err(err) if !last_delivery => {
+            warn!(
+                error = ?err,
+                job.invocation_id = %id,
+                job.instance = &metadata.job_instance,
+                "job could not be processed, it will be redelivered"
+            );
+            span.record_err(err);
+
+            return JobDisposition::Redeliver;
+        }
+        Err(err) => {
+            error!(
+                error = ?err,
+                job.invocation_id = %id,
+                job.instance = &metadata.job_instance,
+                "job could not be processed on its last delivery"
+            );
+            dead_letter_last_delivery(&ctx_builder, &job_info, &err).await;
+            let new_err = Err(BlockingJobError::JobExecution(err.to_string()));
+            span.record_err(err);
+
             new_err
         }
     };
 
+    reply(&ctx_builder, maybe_reply_channel, reply_message).await;
+
+    JobDisposition::Done
+}
+
+/// Notifies whoever is blocking on a job (if anyone) that it is done with.
+async fn reply(
+    ctx_builder: &DalContextBuilder,
+    maybe_reply_channel: Option<String>,
+    reply_message: BlockingJobResult,
+) {
     if let Some(reply_channel) = maybe_reply_channel {
         if let Ok(message) = serde_json::to_vec(&reply_message) {
             if let Err(err) = ctx_builder
@@ -449,12 +818,28 @@ async fn execute_job_task(
     }
 }
 
+/// Records a job which is not going to be delivered again as a [`JobDeadLetter`], so that it can
+/// still be replayed. If even that fails there is nothing left to do but be loud about it.
+async fn dead_letter_last_delivery(
+    ctx_builder: &DalContextBuilder,
+    job_info: &JobInfo,
+    err: &ServerError,
+) {
+    if let Err(dead_letter_err) = record_dead_letter(ctx_builder.clone(), job_info, err).await {
+        error!(
+            error = ?dead_letter_err,
+            job.invocation_id = %job_info.id,
+            "unable to record a dead letter for a job on its last delivery, the job is lost"
+        );
+    }
+}
+
 async fn execute_job(
     _metadata: &Arc<ServerMetadata>,
     _messaging_destination: Arc<String>,
     mut ctx_builder: DalContextBuilder,
     request: Request<JobInfo>,
-) -> Result<()> {
+) -> Result<JobOutcome> {
     let (job_info, _) = request.into_parts();
     if job_info.blocking {
         ctx_builder.set_blocking();
@@ -477,36 +862,54 @@ async fn execute_job(
         tracing::Span::current().record("job_info.blocking", job_info.blocking);
     }
 
-    let job =
-        match job_info.kind.as_str() {
-            stringify!(DependentValuesUpdate) => {
-                Box::new(DependentValuesUpdate::try_from(job_info.clone())?)
-                    as Box<dyn JobConsumer + Send + Sync>
-            }
-            stringify!(FixesJob) => Box::new(FixesJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            stringify!(RefreshJob) => Box::new(RefreshJob::try_from(job_info.clone())?)
-                as Box<dyn JobConsumer + Send + Sync>,
-            kind => return Err(ServerError::UnknownJobKind(kind.to_owned())),
-        };
+    let job = match job_consumer_from_job_info(job_info.clone()) {
+        Ok(job) => job,
+        Err(err) => {
+            let err = ServerError::from(err);
+            record_dead_letter(ctx_builder, &job_info, &err).await?;
+            return Ok(JobOutcome::Failed(err));
+        }
+    };
 
     info!("Processing job");
 
     if let Err(err) = job.run_job(ctx_builder.clone()).await {
         // The missing part is this, should we execute subsequent jobs if the one they depend on fail or not?
-        record_job_failure(ctx_builder, job, err).await?;
+        let err = record_job_failure(ctx_builder, &job_info, job, err).await?;
+        return Ok(JobOutcome::Failed(err));
     }
 
     info!("Finished processing job");
 
+    Ok(JobOutcome::Succeeded)
+}
+
+/// Records a job that could not even be turned into a [`JobConsumer`] (because of an unknown
+/// kind or bad arguments) as a [`JobDeadLetter`], so that it can be replayed once fixed.
+async fn record_dead_letter(
+    ctx_builder: DalContextBuilder,
+    job_info: &JobInfo,
+    err: &ServerError,
+) -> Result<()> {
+    warn!(error = ?err, "job could not be loaded, recording a dead letter to the database");
+
+    let ctx = ctx_builder
+        .build(job_info.access_builder.build(job_info.visibility))
+        .await?;
+
+    JobDeadLetter::new(&ctx, job_info, err).await?;
+
+    ctx.commit().await?;
+
     Ok(())
 }
 
 async fn record_job_failure(
     ctx_builder: DalContextBuilder,
+    job_info: &JobInfo,
     job: Box<dyn JobConsumer + Send + Sync>,
     err: JobConsumerError,
-) -> Result<()> {
+) -> Result<ServerError> {
     warn!(error = ?err, "job execution failed, recording a job failure to the database");
 
     let access_builder = job.access_builder();
@@ -514,10 +917,11 @@ async fn record_job_failure(
     let ctx = ctx_builder.build(access_builder.build(visibility)).await?;
 
     JobFailure::new(&ctx, job.type_name(), err.to_string()).await?;
+    JobDeadLetter::new(&ctx, job_info, &err).await?;
 
     ctx.commit().await?;
 
-    Err(err.into())
+    Ok(err.into())
 }
 
 fn prepare_graceful_shutdown(
@@ -16,6 +16,7 @@ pub mod server;
 pub use server::{
     build_service, build_service_for_tests, detect_and_configure_development,
     job_processor::JobProcessorClientCloser, job_processor::JobProcessorConnector, service, Config,
-    ConfigError, ConfigFile, IncomingStream, JobQueueProcessor, MigrationMode, NatsProcessor,
-    Server, ServicesContext, StandardConfig, StandardConfigFile,
+    ConfigError, ConfigFile, IncomingStream, JetStreamProcessor, JobQueueProcessor,
+    JobQueueTransport, LocalProcessor, MigrationMode, NatsProcessor, Server, ServicesContext,
+    StandardConfig, StandardConfigFile,
 };
@@ -1,8 +1,11 @@
 pub use config::{
     detect_and_configure_development, Config, ConfigBuilder, ConfigError, ConfigFile,
-    IncomingStream, StandardConfig, StandardConfigFile,
+    IncomingStream, JobQueueTransport, StandardConfig, StandardConfigFile,
+};
+pub use dal::{
+    JetStreamProcessor, JobQueueProcessor, LocalProcessor, MigrationMode, NatsProcessor,
+    ServicesContext,
 };
-pub use dal::{JobQueueProcessor, MigrationMode, NatsProcessor, ServicesContext};
 pub use routes::{routes, AppError};
 pub use server::{build_service, build_service_for_tests, Server};
 pub use uds::{UdsIncomingStream, UdsIncomingStreamError};
@@ -5,6 +5,7 @@ use std::{
 };
 
 use buck2_resources::Buck2Resources;
+use dal::SecretProvidersConfig;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use si_crypto::{SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile};
@@ -15,7 +16,7 @@ use si_std::{CanonicalFile, CanonicalFileError, SensitiveString};
 use telemetry::prelude::*;
 use thiserror::Error;
 
-pub use dal::MigrationMode;
+pub use dal::{JobQueueTransport, MigrationMode};
 pub use si_crypto::CycloneKeyPair;
 pub use si_settings::{StandardConfig, StandardConfigFile};
 
@@ -66,9 +67,21 @@ pub struct Config {
 
     symmetric_crypto_service: SymmetricCryptoServiceConfig,
 
+    #[builder(default = "SecretProvidersConfig::default()")]
+    secret_providers: SecretProvidersConfig,
+
     #[builder(default = "MigrationMode::default()")]
     migration_mode: MigrationMode,
 
+    #[builder(default = "JobQueueTransport::default()")]
+    job_queue: JobQueueTransport,
+
+    #[builder(default)]
+    standalone_jobs: bool,
+
+    #[builder(default)]
+    resource_refresh_cron: Option<String>,
+
     jwt_signing_public_key_path: CanonicalFile,
 
     cyclone_encryption_key_path: CanonicalFile,
@@ -105,6 +118,25 @@ impl Config {
         &self.nats
     }
 
+    /// Gets the transport jobs are sent to pinga over.
+    #[must_use]
+    pub fn job_queue(&self) -> JobQueueTransport {
+        self.job_queue
+    }
+
+    /// Whether jobs are run inside sdf itself, rather than by pinga and council.
+    #[must_use]
+    pub fn standalone_jobs(&self) -> bool {
+        self.standalone_jobs
+    }
+
+    /// Gets the cron expression resources are periodically refreshed on, if refreshing is turned
+    /// on at all.
+    #[must_use]
+    pub fn resource_refresh_cron(&self) -> Option<&str> {
+        self.resource_refresh_cron.as_deref()
+    }
+
     /// Gets a reference to the config's jwt signing public key path.
     #[must_use]
     pub fn jwt_signing_public_key_path(&self) -> &Path {
@@ -139,6 +171,11 @@ impl Config {
         &self.symmetric_crypto_service
     }
 
+    /// Gets the config for the providers which resolve secret references.
+    pub fn secret_providers(&self) -> &SecretProvidersConfig {
+        &self.secret_providers
+    }
+
     /// URL to the module index service
     #[must_use]
     pub fn module_index_url(&self) -> &str {
@@ -164,6 +201,12 @@ pub struct ConfigFile {
     pub nats: NatsConfig,
     #[serde(default)]
     pub migration_mode: MigrationMode,
+    #[serde(default)]
+    pub job_queue: JobQueueTransport,
+    #[serde(default)]
+    pub standalone_jobs: bool,
+    #[serde(default)]
+    pub resource_refresh_cron: Option<String>,
     #[serde(default = "default_jwt_signing_public_key_path")]
     pub jwt_signing_public_key_path: String,
     #[serde(default = "default_cyclone_encryption_key_path")]
@@ -178,6 +221,8 @@ pub struct ConfigFile {
     pub module_index_url: String,
     #[serde(default = "default_symmetric_crypto_config")]
     symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
+    #[serde(default)]
+    secret_providers: SecretProvidersConfig,
 }
 
 impl Default for ConfigFile {
@@ -186,6 +231,9 @@ impl Default for ConfigFile {
             pg: Default::default(),
             nats: Default::default(),
             migration_mode: Default::default(),
+            job_queue: Default::default(),
+            standalone_jobs: false,
+            resource_refresh_cron: None,
             jwt_signing_public_key_path: default_jwt_signing_public_key_path(),
             cyclone_encryption_key_path: default_cyclone_encryption_key_path(),
             signup_secret: default_signup_secret(),
@@ -193,6 +241,7 @@ impl Default for ConfigFile {
             posthog: Default::default(),
             module_index_url: default_module_index_url(),
             symmetric_crypto_service: default_symmetric_crypto_config(),
+            secret_providers: Default::default(),
         }
     }
 }
@@ -211,6 +260,9 @@ impl TryFrom<ConfigFile> for Config {
         config.pg_pool(value.pg);
         config.nats(value.nats);
         config.migration_mode(value.migration_mode);
+        config.job_queue(value.job_queue);
+        config.standalone_jobs(value.standalone_jobs);
+        config.resource_refresh_cron(value.resource_refresh_cron);
         config.jwt_signing_public_key_path(value.jwt_signing_public_key_path.try_into()?);
         config.cyclone_encryption_key_path(value.cyclone_encryption_key_path.try_into()?);
         config.signup_secret(value.signup_secret);
@@ -218,6 +270,9 @@ impl TryFrom<ConfigFile> for Config {
         config.posthog(value.posthog);
         config.module_index_url(value.module_index_url);
         config.symmetric_crypto_service(value.symmetric_crypto_service.try_into()?);
+        let mut secret_providers = value.secret_providers;
+        secret_providers.snapshot_env(secret_provider_env());
+        config.secret_providers(secret_providers);
         config.build().map_err(Into::into)
     }
 }
@@ -278,6 +333,12 @@ fn default_module_index_url() -> String {
     DEFAULT_MODULE_INDEX_URL.into()
 }
 
+#[allow(clippy::disallowed_methods)] // Snapshots the variables `env:` secret references resolve to
+fn secret_provider_env() -> impl Iterator<Item = (String, String)> {
+    env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 pub fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
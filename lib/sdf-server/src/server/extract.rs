@@ -1,14 +1,16 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, marker::PhantomData};
 
 use axum::{
     async_trait,
     extract::{FromRequestParts, Query},
     http::request::Parts,
+    response::{IntoResponse, Response},
     Json,
 };
 use dal::{
+    api_token::API_TOKEN_PREFIX,
     context::{self, DalContextBuilder},
-    User, UserClaim,
+    ApiToken, ApiTokenPk, User, UserClaim, WorkspacePermission, WorkspaceRole,
 };
 use hyper::StatusCode;
 
@@ -26,11 +28,12 @@ impl FromRequestParts<AppState> for AccessBuilder {
     ) -> Result<Self, Self::Rejection> {
         let Authorization(claim) = Authorization::from_request_parts(parts, state).await?;
         let Tenancy(tenancy) = tenancy_from_claim(&claim).await?;
+        let history_actor = match api_token_from_parts(parts, state).await? {
+            Some(api_token) => api_token.history_actor(),
+            None => dal::HistoryActor::from(claim.user_pk),
+        };
 
-        Ok(Self(context::AccessBuilder::new(
-            tenancy,
-            dal::HistoryActor::from(claim.user_pk),
-        )))
+        Ok(Self(context::AccessBuilder::new(tenancy, history_actor)))
     }
 }
 
@@ -70,14 +73,20 @@ impl FromRequestParts<AppState> for HandlerContext {
     type Rejection = (StatusCode, Json<serde_json::Value>);
 
     async fn from_request_parts(
-        _parts: &mut Parts,
+        parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        let builder = state
+        let mut builder = state
             .services_context()
             .clone()
             .into_inner()
             .into_builder(state.for_tests());
+        if let Some(change_set_pks) = api_token_from_parts(parts, state)
+            .await?
+            .and_then(|api_token| api_token.change_set_pks().map(<[_]>::to_vec))
+        {
+            builder.set_change_set_scope(change_set_pks);
+        }
         Ok(Self(builder))
     }
 }
@@ -132,9 +141,25 @@ impl FromRequestParts<AppState> for Authorization {
         let authorization = authorization_header_value
             .to_str()
             .map_err(internal_error)?;
-        let claim = UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
-            .await
-            .map_err(|_| unauthorized_error())?;
+        let claim = match api_token_from_parts(parts, state).await? {
+            // An api token acts on behalf of the user who created it, and can't be used at all
+            // unless it may read the workspace
+            Some(api_token) => {
+                if !api_token.allows(WorkspacePermission::Read) {
+                    return Err(api_token_forbidden_error(
+                        api_token.pk(),
+                        WorkspacePermission::Read,
+                    ));
+                }
+                UserClaim::new(
+                    api_token.created_by_user_pk(),
+                    api_token.workspace_pk().ok_or_else(unauthorized_error)?,
+                )
+            }
+            None => UserClaim::from_bearer_token(jwt_public_signing_key, authorization)
+                .await
+                .map_err(|_| unauthorized_error())?,
+        };
         ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
 
         let is_authorized = User::authorize(&ctx, &claim.user_pk, &claim.workspace_pk)
@@ -149,6 +174,43 @@ impl FromRequestParts<AppState> for Authorization {
     }
 }
 
+/// Returns the [`ApiToken`] a request is authenticated with, or `None` if it is authenticated
+/// with a session token instead. The token is only looked up once per request.
+async fn api_token_from_parts(
+    parts: &mut Parts,
+    state: &AppState,
+) -> Result<Option<ApiToken>, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(api_token) = parts.extensions.get::<ApiToken>() {
+        return Ok(Some(api_token.clone()));
+    }
+
+    let raw_token = match parts
+        .headers
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.strip_prefix("Bearer ").unwrap_or(value))
+    {
+        Some(raw_token) if raw_token.starts_with(API_TOKEN_PREFIX) => raw_token.to_owned(),
+        _ => return Ok(None),
+    };
+
+    let ctx = state
+        .services_context()
+        .clone()
+        .into_inner()
+        .into_builder(state.for_tests())
+        .build_default()
+        .await
+        .map_err(internal_error)?;
+    let api_token = ApiToken::find_by_token(&ctx, raw_token)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(unauthorized_error)?;
+
+    parts.extensions.insert(api_token.clone());
+    Ok(Some(api_token))
+}
+
 pub struct WsAuthorization(pub UserClaim);
 
 #[async_trait]
@@ -185,6 +247,151 @@ impl FromRequestParts<AppState> for WsAuthorization {
     }
 }
 
+/// A [`WorkspacePermission`] required by an [`Authorized`] extractor. The implementors live in
+/// [`permission`].
+pub trait RequiredPermission {
+    const PERMISSION: WorkspacePermission;
+}
+
+macro_rules! required_permissions {
+    ($($name:ident),+ $(,)?) => {
+        /// One type per [`WorkspacePermission`](dal::WorkspacePermission), to name the permission
+        /// an [`Authorized`](super::Authorized) extractor requires.
+        pub mod permission {
+            $(
+                pub struct $name;
+
+                impl super::RequiredPermission for $name {
+                    const PERMISSION: dal::WorkspacePermission =
+                        dal::WorkspacePermission::$name;
+                }
+            )+
+        }
+    };
+}
+
+required_permissions!(
+    ApplyChangeSet,
+    EditChangeSet,
+    InstallModules,
+    ManageApiTokens,
+    ManageJobs,
+    ManageMembers,
+    ManageSecrets,
+    ManageWorkspace,
+    Read,
+    RunFix,
+    ViewAuditLog,
+);
+
+/// Like [`Authorization`], but additionally requires the user's [`WorkspaceRole`] to grant the
+/// permission `P`, for example `Authorized<permission::RunFix>`. A request authenticated with an
+/// [`ApiToken`] also requires the token to have been given `P`.
+pub struct Authorized<P: RequiredPermission> {
+    pub claim: UserClaim,
+    pub role: WorkspaceRole,
+    _permission: PhantomData<P>,
+}
+
+#[async_trait]
+impl<P: RequiredPermission> FromRequestParts<AppState> for Authorized<P> {
+    type Rejection = AuthorizedRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Authorization(claim) = Authorization::from_request_parts(parts, state)
+            .await
+            .map_err(AuthorizedRejection::Unauthorized)?;
+
+        let HandlerContext(builder) = HandlerContext::from_request_parts(parts, state)
+            .await
+            .map_err(AuthorizedRejection::Unauthorized)?;
+        let mut ctx = builder
+            .build_default()
+            .await
+            .map_err(|err| AuthorizedRejection::Unauthorized(internal_error(err)))?;
+        ctx.update_tenancy(dal::Tenancy::new(claim.workspace_pk));
+
+        let role = User::role_in_workspace(&ctx, claim.user_pk, claim.workspace_pk)
+            .await
+            .map_err(|err| AuthorizedRejection::Unauthorized(internal_error(err)))?
+            .ok_or_else(|| AuthorizedRejection::Unauthorized(unauthorized_error()))?;
+
+        if !role.allows(P::PERMISSION) {
+            return Err(AuthorizedRejection::Forbidden {
+                permission: P::PERMISSION,
+                role,
+            });
+        }
+        if let Some(api_token) = api_token_from_parts(parts, state)
+            .await
+            .map_err(AuthorizedRejection::Unauthorized)?
+        {
+            if !api_token.allows(P::PERMISSION) {
+                return Err(AuthorizedRejection::ApiTokenForbidden {
+                    permission: P::PERMISSION,
+                    api_token_pk: api_token.pk(),
+                });
+            }
+        }
+
+        Ok(Self {
+            claim,
+            role,
+            _permission: PhantomData,
+        })
+    }
+}
+
+/// Why an [`Authorized`] extractor rejected a request.
+pub enum AuthorizedRejection {
+    /// The api token the request is authenticated with was not given the required permission
+    /// (403)
+    ApiTokenForbidden {
+        permission: WorkspacePermission,
+        api_token_pk: ApiTokenPk,
+    },
+    /// The user's role does not grant the required permission (403)
+    Forbidden {
+        permission: WorkspacePermission,
+        role: WorkspaceRole,
+    },
+    /// The request could not be authorized at all, as with [`Authorization`]
+    Unauthorized((StatusCode, Json<serde_json::Value>)),
+}
+
+impl IntoResponse for AuthorizedRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::ApiTokenForbidden {
+                permission,
+                api_token_pk,
+            } => api_token_forbidden_error(api_token_pk, permission).into_response(),
+            Self::Forbidden { permission, role } => {
+                let status_code = StatusCode::FORBIDDEN;
+                (
+                    status_code,
+                    Json(serde_json::json!({
+                        "error": {
+                            "message": format!(
+                                "the {role} role does not have the {permission} permission"
+                            ),
+                            "statusCode": status_code.as_u16(),
+                            "code": "FORBIDDEN",
+                            "permission": permission,
+                            "role": role,
+                        },
+                    })),
+                )
+                    .into_response()
+            }
+            Self::Unauthorized(rejection) => rejection.into_response(),
+        }
+    }
+}
+
 pub struct Tenancy(pub dal::Tenancy);
 
 #[async_trait]
@@ -220,6 +427,27 @@ fn internal_error(message: impl fmt::Display) -> (StatusCode, Json<serde_json::V
     )
 }
 
+fn api_token_forbidden_error(
+    api_token_pk: ApiTokenPk,
+    permission: WorkspacePermission,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let status_code = StatusCode::FORBIDDEN;
+    (
+        status_code,
+        Json(serde_json::json!({
+            "error": {
+                "message": format!(
+                    "api token {api_token_pk} does not have the {permission} permission"
+                ),
+                "statusCode": status_code.as_u16(),
+                "code": "FORBIDDEN",
+                "permission": permission,
+                "apiTokenPk": api_token_pk,
+            },
+        })),
+    )
+}
+
 fn unauthorized_error() -> (StatusCode, Json<serde_json::Value>) {
     let status_code = StatusCode::UNAUTHORIZED;
     (
@@ -9,7 +9,7 @@ pub async fn feature_is_enabled(
 ) -> bool {
     match ctx.history_actor() {
         HistoryActor::SystemInit => false,
-        HistoryActor::User(user_pk) => posthog_client
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => posthog_client
             .check_feature_flag(feature, user_pk.to_string())
             .await
             .unwrap_or(false),
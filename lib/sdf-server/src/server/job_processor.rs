@@ -1,6 +1,6 @@
 use crate::{server::server::ServerError, Config, Server};
 use async_trait::async_trait;
-use dal::{JobQueueProcessor, NatsProcessor};
+use dal::{JetStreamProcessor, JobQueueProcessor, NatsProcessor};
 use si_data_nats::NatsClient;
 
 #[async_trait]
@@ -37,3 +37,20 @@ impl JobProcessorConnector for NatsProcessor {
         Ok((job_client, job_processor))
     }
 }
+
+#[async_trait]
+impl JobProcessorConnector for JetStreamProcessor {
+    type Client = NatsClient;
+
+    async fn connect(
+        config: &Config,
+    ) -> Result<(Self::Client, Box<dyn JobQueueProcessor + Send + Sync>), ServerError> {
+        let job_client = Server::connect_to_nats(config.nats()).await?;
+        let job_processor = JetStreamProcessor::new(job_client.clone());
+        job_processor.get_or_create_stream().await?;
+        Ok((
+            job_client,
+            Box::new(job_processor) as Box<dyn JobQueueProcessor + Send + Sync>,
+        ))
+    }
+}
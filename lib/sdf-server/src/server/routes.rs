@@ -22,6 +22,15 @@ pub fn routes(state: AppState) -> Router {
             "/api/",
             Router::new().route("/", get(system_status_route).layer(CorsLayer::permissive())),
         )
+        .nest("/api/admin", crate::server::service::admin::routes())
+        .nest(
+            "/api/api_token",
+            crate::server::service::api_token::routes(),
+        )
+        .nest(
+            "/api/audit_log",
+            crate::server::service::audit_log::routes(),
+        )
         .nest(
             "/api/change_set",
             crate::server::service::change_set::routes(),
@@ -32,6 +41,7 @@ pub fn routes(state: AppState) -> Router {
         )
         .nest("/api/fix", crate::server::service::fix::routes())
         .nest("/api/func", crate::server::service::func::routes())
+        .nest("/api/job", crate::server::service::job::routes())
         .nest("/api/pkg", crate::server::service::pkg::routes())
         .nest("/api/provider", crate::server::service::provider::routes())
         .nest(
@@ -47,6 +57,10 @@ pub fn routes(state: AppState) -> Router {
             "/api/variant_def",
             crate::server::service::variant_definition::routes(),
         )
+        .nest(
+            "/api/workspace",
+            crate::server::service::workspace::routes(),
+        )
         .nest("/api/ws", crate::server::service::ws::routes());
 
     // Load dev routes if we are in dev mode (decided by "opt-level" at the moment).
@@ -3,26 +3,35 @@ use std::{io, net::SocketAddr, path::Path, path::PathBuf};
 
 use axum::routing::IntoMakeService;
 use axum::Router;
+use council_server::LocalCouncil;
 use hyper::server::{accept::Accept, conn::AddrIncoming};
 use thiserror::Error;
 use tokio::time::Instant;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     signal,
-    sync::{broadcast, mpsc, oneshot},
+    sync::{broadcast, mpsc, oneshot, watch},
     task::{JoinError, JoinSet},
-    time,
+    time::{self, MissedTickBehavior},
 };
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use ulid::Ulid;
 
 use dal::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
-use dal::tasks::{StatusReceiver, StatusReceiverError};
+use dal::tasks::{AuditLogForwarder, StatusReceiver, StatusReceiverError};
 use dal::{
-    builtins, BuiltinsError, DalContext, JwtPublicSigningKey, Tenancy, TransactionsError,
-    Workspace, WorkspaceError,
+    builtins, AuditLogError, BuiltinsError, DalContext, JwtPublicSigningKey, SecretProviders,
+    SecretProvidersConfig, SymmetricReencryptionReport, Tenancy, TransactionsError, Workspace,
+    WorkspaceError,
+};
+use dal::{
+    job::definition::{
+        ScheduledRefreshJob, SecretExpiryCheckJob, SCHEDULED_REFRESH_SCHEDULE_NAME,
+        SECRET_EXPIRY_CHECK_SCHEDULE_NAME,
+    },
+    CronSchedule, JobQueueProcessorError, JobSchedule, JobScheduleError, LocalProcessor,
+    ServicesContext,
 };
-use dal::{tasks::ResourceScheduler, ServicesContext};
 use module_index_client::types::BuiltinsDetailsResponse;
 use module_index_client::{IndexClient, ModuleDetailsResponse};
 use si_crypto::{
@@ -44,8 +53,12 @@ use super::{routes, Config, IncomingStream, UdsIncomingStream, UdsIncomingStream
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ServerError {
+    #[error(transparent)]
+    AuditLog(#[from] AuditLogError),
     #[error("intrinsics installation error")]
     Builtins(#[from] BuiltinsError),
+    #[error("embedded council failed to start")]
+    CouncilStartup,
     #[error("cyclone public key already set")]
     CyclonePublicKeyAlreadySet,
     #[error("cyclone public key error: {0}")]
@@ -59,6 +72,10 @@ pub enum ServerError {
     #[error("error initializing the server")]
     Init,
     #[error(transparent)]
+    JobQueueProcessor(#[from] JobQueueProcessorError),
+    #[error(transparent)]
+    JobSchedule(#[from] JobScheduleError),
+    #[error(transparent)]
     Join(#[from] JoinError),
     #[error("jwt secret key error")]
     JwtSecretKey(#[from] dal::jwt_key::JwtKeyError),
@@ -80,6 +97,8 @@ pub enum ServerError {
     PkgInstall,
     #[error(transparent)]
     Posthog(#[from] si_posthog::PosthogError),
+    #[error(transparent)]
+    SecretProvider(#[from] dal::secret::SecretProviderError),
     #[error("failed to setup signal handler")]
     Signal(#[source] io::Error),
     #[error(transparent)]
@@ -88,6 +107,8 @@ pub enum ServerError {
     StatusReceiver(#[from] StatusReceiverError),
     #[error(transparent)]
     SymmetricCryptoService(#[from] SymmetricCryptoError),
+    #[error(transparent)]
+    SymmetricReencryption(#[from] dal::SymmetricReencryptionError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error(transparent)]
@@ -108,6 +129,17 @@ impl From<PgPoolError> for ServerError {
 
 pub type Result<T, E = ServerError> = std::result::Result<T, E>;
 
+/// How often workspaces are warned about their expiring secrets, as a [`CronSchedule`]
+/// expression.
+const SECRET_EXPIRY_CHECK_CRON: &str = "0 */6 * * *";
+
+/// The maximum number of queued jobs run at once when running jobs in-process.
+const STANDALONE_JOBS_CONCURRENCY_LIMIT: usize = 50;
+/// How often due job schedules are polled for when running jobs in-process.
+const STANDALONE_SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// The maximum number of due job schedules claimed in a single database transaction.
+const STANDALONE_SCHEDULED_JOBS_BATCH_SIZE: i64 = 100;
+
 pub struct Server<I, S> {
     config: Config,
     inner: axum::Server<I, IntoMakeService<Router>>,
@@ -230,12 +262,163 @@ impl Server<(), ()> {
         Ok(())
     }
 
-    /// Start the basic resource refresh scheduler
-    pub async fn start_resource_refresh_scheduler(
+    /// Re-encrypts all data under the active symmetric crypto key and logs which other key hashes
+    /// are still referenced, i.e. which keys can not be retired yet.
+    #[instrument(name = "sdf.init.reencrypt_symmetric_data", skip_all)]
+    pub async fn reencrypt_symmetric_data(
+        services_context: &ServicesContext,
+    ) -> Result<SymmetricReencryptionReport> {
+        let ctx = services_context
+            .clone()
+            .into_builder(false)
+            .build_default()
+            .await?;
+
+        let report = dal::reencrypt_symmetric_data(&ctx).await?;
+        ctx.commit().await?;
+
+        info!(
+            active_key_hash = %report.active_key_hash,
+            key_pairs_reencrypted = report.key_pairs_reencrypted,
+            encrypted_secrets_reencrypted = report.encrypted_secrets_reencrypted,
+            "re-encrypted symmetric data under the active key"
+        );
+        for key_hash in report.retained_key_hashes() {
+            warn!(key_hash, "symmetric key hash is still referenced");
+        }
+
+        Ok(report)
+    }
+
+    /// Ensures the periodic resource refresh [`JobSchedule`] exists when a `cron` expression is
+    /// configured, and that it doesn't when refreshing is turned off (as it is by default). The
+    /// schedule itself is fired by pinga, so it is safe for every sdf replica to call this on
+    /// startup.
+    #[instrument(name = "sdf.init.ensure_resource_refresh_schedule", skip_all)]
+    pub async fn ensure_resource_refresh_schedule(
         services_context: ServicesContext,
-        shutdown_broadcast_rx: broadcast::Receiver<()>,
+        cron: Option<&str>,
+    ) -> Result<()> {
+        let ctx = services_context.into_builder(false).build_default().await?;
+
+        match cron {
+            Some(cron) => {
+                JobSchedule::upsert_named_cron(
+                    &ctx,
+                    SCHEDULED_REFRESH_SCHEDULE_NAME,
+                    ScheduledRefreshJob::new(),
+                    cron.parse::<CronSchedule>()
+                        .map_err(|err| ServerError::JobSchedule(err.into()))?,
+                )
+                .await?;
+            }
+            None => JobSchedule::delete_named(&ctx, SCHEDULED_REFRESH_SCHEDULE_NAME).await?,
+        }
+
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    /// Ensures the periodic secret expiry check [`JobSchedule`] exists. Like the resource
+    /// refresh schedule, it is safe for every sdf replica to call this on startup.
+    #[instrument(name = "sdf.init.ensure_secret_expiry_check_schedule", skip_all)]
+    pub async fn ensure_secret_expiry_check_schedule(
+        services_context: ServicesContext,
+    ) -> Result<()> {
+        let ctx = services_context.into_builder(false).build_default().await?;
+
+        JobSchedule::upsert_named_cron(
+            &ctx,
+            SECRET_EXPIRY_CHECK_SCHEDULE_NAME,
+            SecretExpiryCheckJob::new(),
+            SECRET_EXPIRY_CHECK_CRON
+                .parse::<CronSchedule>()
+                .map_err(|err| ServerError::JobSchedule(err.into()))?,
+        )
+        .await?;
+
+        ctx.commit().await?;
+        Ok(())
+    }
+
+    pub fn create_local_job_processor() -> LocalProcessor {
+        LocalProcessor::new(STANDALONE_JOBS_CONCURRENCY_LIMIT)
+    }
+
+    /// Creates the in-process council which standalone jobs coordinate dependent values updates
+    /// with. It is served by [`Self::start_standalone_jobs`].
+    pub fn create_local_council() -> LocalCouncil {
+        LocalCouncil::new()
+    }
+
+    /// Starts running jobs inside this process rather than in `pinga`: the [`LocalProcessor`]
+    /// is started, and the [`ServicesContext`]'s [`LocalCouncil`] is served to schedule dependent
+    /// values updates, so that neither pinga nor council are needed. Due job schedules are polled
+    /// for separately, by [`Self::start_standalone_job_schedules`], once the database has been
+    /// migrated.
+    #[instrument(name = "sdf.init.start_standalone_jobs", skip_all)]
+    pub async fn start_standalone_jobs(
+        job_processor: LocalProcessor,
+        services_context: ServicesContext,
+    ) -> Result<()> {
+        let local_council = services_context
+            .local_council()
+            .cloned()
+            .ok_or(ServerError::CouncilStartup)?;
+        job_processor.start(services_context)?;
+
+        let council = council_server::Server::from_local(local_council);
+        let (shutdown_request_tx, shutdown_request_rx) = watch::channel(());
+        let (subscriber_started_tx, mut subscriber_started_rx) = watch::channel(());
+        tokio::spawn(async move {
+            // Council stops on its own when the process receives a shutdown signal, so the
+            // sender only needs to outlive it
+            let _shutdown_request_tx = shutdown_request_tx;
+            if let Err(err) = council
+                .run(subscriber_started_tx, shutdown_request_rx)
+                .await
+            {
+                error!(error = ?err, "embedded council failed");
+            }
+        });
+        subscriber_started_rx
+            .changed()
+            .await
+            .map_err(|_| ServerError::CouncilStartup)?;
+
+        Ok(())
+    }
+
+    /// Polls for due job schedules and enqueues their jobs on the [`ServicesContext`]'s job
+    /// processor until shutdown, for when jobs run in-process and `pinga` is not there to fire
+    /// them.
+    pub fn start_standalone_job_schedules(
+        services_context: ServicesContext,
+        mut shutdown_broadcast_rx: broadcast::Receiver<()>,
     ) {
-        ResourceScheduler::new(services_context).start(shutdown_broadcast_rx);
+        let claimed_by = format!("sdf-{}", Ulid::new());
+        tokio::spawn(async move {
+            let mut interval = time::interval(STANDALONE_SCHEDULE_POLL_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = shutdown_broadcast_rx.recv() => {
+                        trace!("the standalone job schedules task received shutdown");
+                        break;
+                    }
+                    _ = interval.tick() => {}
+                }
+                if let Err(err) = JobSchedule::enqueue_due(
+                    &services_context,
+                    &claimed_by,
+                    STANDALONE_SCHEDULED_JOBS_BATCH_SIZE,
+                )
+                .await
+                {
+                    warn!(error = ?err, "firing scheduled jobs failed");
+                }
+            }
+        });
     }
 
     pub async fn start_status_updater(
@@ -248,6 +431,17 @@ impl Server<(), ()> {
         Ok(())
     }
 
+    /// Starts forwarding audit log entries to the sinks their workspaces are configured with.
+    pub async fn start_audit_log_forwarder(
+        services_context: ServicesContext,
+        shutdown_broadcast_rx: broadcast::Receiver<()>,
+    ) -> Result<()> {
+        AuditLogForwarder::new(services_context)
+            .await?
+            .start(shutdown_broadcast_rx);
+        Ok(())
+    }
+
     #[instrument(name = "sdf.init.create_pg_pool", skip_all)]
     pub async fn create_pg_pool(pg_pool_config: &PgPoolConfig) -> Result<PgPool> {
         let pool = PgPool::new(pg_pool_config).await?;
@@ -274,6 +468,11 @@ impl Server<(), ()> {
             .await
             .map_err(Into::into)
     }
+
+    #[instrument(name = "sdf.init.create_secret_providers", skip_all)]
+    pub fn create_secret_providers(config: &SecretProvidersConfig) -> Result<SecretProviders> {
+        SecretProviders::from_config(config).map_err(Into::into)
+    }
 }
 
 impl<I, IO, IE, S> Server<I, S>
@@ -384,6 +583,9 @@ async fn install_builtins(
                         skip_import_funcs: None,
                         no_record: false,
                         is_builtin: true,
+                        module_index: Some(module_index_client.clone()),
+                        signature: None,
+                        excluded_items: Default::default(),
                     }),
                 )
                 .await
@@ -1,8 +1,12 @@
+pub mod admin;
+pub mod api_token;
+pub mod audit_log;
 pub mod change_set;
 pub mod component;
 pub mod diagram;
 pub mod fix;
 pub mod func;
+pub mod job;
 pub mod pkg;
 pub mod provider;
 pub mod qualification;
@@ -11,6 +15,7 @@ pub mod secret;
 pub mod session;
 pub mod status;
 pub mod variant_definition;
+pub mod workspace;
 pub mod ws;
 
 /// A module containing dev routes for local development only.
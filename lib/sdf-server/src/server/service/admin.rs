@@ -0,0 +1,43 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use dal::{KeyPairError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod rotate_key_pair;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum AdminError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    KeyPair(#[from] KeyPairError),
+}
+
+pub type AdminResult<T> = Result<T, AdminError>;
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": error_message,
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/rotate_key_pair", post(rotate_key_pair::rotate_key_pair))
+}
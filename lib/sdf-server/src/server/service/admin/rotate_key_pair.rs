@@ -0,0 +1,48 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{job::definition::KeyPairRotationJob, PublicKey};
+use serde::{Deserialize, Serialize};
+
+use super::AdminResult;
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateKeyPairResponse {
+    pub success: bool,
+}
+
+/// Enqueues a job to rotate the workspace's key pair. Progress is reported through a status
+/// update as its secrets are re-sealed to the new key.
+pub async fn rotate_key_pair(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageSecrets>,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+) -> AdminResult<Json<RotateKeyPairResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    // Make sure there is a key pair to rotate before handing off to the job
+    let current = PublicKey::get_current(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "rotate_key_pair",
+        serde_json::json!({
+            "key_pair_pk": current.pk(),
+        }),
+    );
+
+    ctx.enqueue_job(KeyPairRotationJob::new(ctx.access_builder()))
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RotateKeyPairResponse { success: true }))
+}
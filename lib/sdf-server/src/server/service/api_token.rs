@@ -0,0 +1,61 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use dal::{ApiTokenError as DalApiTokenError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod create_api_token;
+pub mod list_api_tokens;
+pub mod revoke_api_token;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ApiTokenError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DalApiToken(#[from] DalApiTokenError),
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+impl IntoResponse for ApiTokenError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ApiTokenError::DalApiToken(DalApiTokenError::NotCreatedByUser)
+            | ApiTokenError::DalApiToken(DalApiTokenError::PermissionNotGranted(_, _)) => {
+                StatusCode::FORBIDDEN
+            }
+            ApiTokenError::DalApiToken(DalApiTokenError::NotFound(_)) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/create_api_token",
+            post(create_api_token::create_api_token),
+        )
+        .route("/list_api_tokens", get(list_api_tokens::list_api_tokens))
+        .route(
+            "/revoke_api_token",
+            post(revoke_api_token::revoke_api_token),
+        )
+}
@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{ApiToken, ChangeSetPk, WorkspacePermission};
+use serde::{Deserialize, Serialize};
+
+use super::ApiTokenResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    pub permissions: Vec<WorkspacePermission>,
+    /// When set, the token can only be used in these change sets (and on head).
+    #[serde(default)]
+    pub change_set_pks: Option<Vec<ChangeSetPk>>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiTokenResponse {
+    pub api_token: ApiToken,
+    /// The bearer token itself. It is only ever returned here.
+    pub token: String,
+}
+
+pub async fn create_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageApiTokens>,
+    Json(request): Json<CreateApiTokenRequest>,
+) -> ApiTokenResult<Json<CreateApiTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let (api_token, token) = ApiToken::new(
+        &ctx,
+        request.name,
+        request.permissions,
+        request.change_set_pks,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateApiTokenResponse { api_token, token }))
+}
@@ -0,0 +1,24 @@
+use axum::Json;
+use dal::ApiToken;
+use serde::{Deserialize, Serialize};
+
+use super::ApiTokenResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListApiTokensResponse {
+    pub api_tokens: Vec<ApiToken>,
+}
+
+pub async fn list_api_tokens(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageApiTokens>,
+) -> ApiTokenResult<Json<ListApiTokensResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let api_tokens = ApiToken::list(&ctx).await?;
+
+    Ok(Json(ListApiTokensResponse { api_tokens }))
+}
@@ -0,0 +1,33 @@
+use axum::Json;
+use dal::{ApiToken, ApiTokenPk};
+use serde::{Deserialize, Serialize};
+
+use super::ApiTokenResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiTokenRequest {
+    pub pk: ApiTokenPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiTokenResponse {
+    pub api_token: ApiToken,
+}
+
+pub async fn revoke_api_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageApiTokens>,
+    Json(request): Json<RevokeApiTokenRequest>,
+) -> ApiTokenResult<Json<RevokeApiTokenResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let api_token = ApiToken::revoke(&ctx, request.pk).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(RevokeApiTokenResponse { api_token }))
+}
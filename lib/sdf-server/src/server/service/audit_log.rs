@@ -0,0 +1,49 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use dal::{AuditLogError as DalAuditLogError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod export_audit_log;
+pub mod list_audit_log;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum AuditLogError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DalAuditLog(#[from] DalAuditLogError),
+}
+
+pub type AuditLogResult<T> = Result<T, AuditLogError>;
+
+impl IntoResponse for AuditLogError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            AuditLogError::DalAuditLog(DalAuditLogError::NoWorkspace) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/list_audit_log", get(list_audit_log::list_audit_log))
+        .route("/export_audit_log", get(export_audit_log::export_audit_log))
+}
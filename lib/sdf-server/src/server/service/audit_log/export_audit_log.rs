@@ -0,0 +1,66 @@
+use std::io;
+
+use axum::body::StreamBody;
+use axum::extract::Query;
+use axum::http::header;
+use axum::response::IntoResponse;
+use dal::{AuditLogEntry, AuditLogError as DalAuditLogError};
+use futures::{stream, TryStreamExt};
+
+use super::list_audit_log::ListAuditLogRequest;
+use super::AuditLogResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+/// How many entries are listed, and written to the body, at a time.
+const PAGE_SIZE: i64 = 500;
+
+/// Exports the audit log as [NDJSON](http://ndjson.org), one entry per line, oldest first.
+///
+/// The body is streamed a page of entries at a time, so exporting a large audit log doesn't hold
+/// it all in memory.
+pub async fn export_audit_log(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ViewAuditLog>,
+    Query(request): Query<ListAuditLogRequest>,
+) -> AuditLogResult<impl IntoResponse> {
+    let ctx = builder.build_head(access_builder).await?;
+    let (since, until) = (request.since, request.until);
+
+    // The first page is listed before responding, so a request that can't be listed at all fails
+    // with its own status rather than partway through the body.
+    let first_page = AuditLogEntry::list_page(&ctx, since, until, None, PAGE_SIZE).await?;
+
+    let pages = stream::try_unfold((ctx, Some(first_page)), move |(ctx, page)| async move {
+        let page = match page {
+            Some(page) => page,
+            None => return Ok(None),
+        };
+
+        let mut chunk = String::new();
+        for entry in &page.entries {
+            chunk.push_str(&entry.to_ndjson_line()?);
+        }
+
+        let next_page = match page.next {
+            Some(after) => {
+                Some(AuditLogEntry::list_page(&ctx, since, until, Some(&after), PAGE_SIZE).await?)
+            }
+            None => None,
+        };
+
+        Ok::<_, DalAuditLogError>(Some((chunk, (ctx, next_page))))
+    })
+    .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"audit-log.ndjson\"",
+            ),
+        ],
+        StreamBody::new(pages),
+    ))
+}
@@ -0,0 +1,30 @@
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::AuditLogEntry;
+use serde::{Deserialize, Serialize};
+
+use super::AuditLogResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAuditLogRequest {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+pub type ListAuditLogResponse = Vec<AuditLogEntry>;
+
+pub async fn list_audit_log(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ViewAuditLog>,
+    Query(request): Query<ListAuditLogRequest>,
+) -> AuditLogResult<Json<ListAuditLogResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let entries = AuditLogEntry::list(&ctx, request.since, request.until).await?;
+
+    Ok(Json(entries))
+}
@@ -6,7 +6,7 @@ use axum::{
 };
 use dal::{
     change_status::ChangeStatusError, ActionError, ActionId, ChangeSetError as DalChangeSetError,
-    ComponentError as DalComponentError, FixError, StandardModelError, TransactionsError,
+    ComponentError as DalComponentError, FixError, HistoryEventError, StandardModelError, TransactionsError,
     UserError, UserPk, WsEventError,
 };
 use module_index_client::IndexClientError;
@@ -49,6 +49,8 @@ pub enum ChangeSetError {
     #[error(transparent)]
     Fix(#[from] FixError),
     #[error(transparent)]
+    HistoryEvent(#[from] HistoryEventError),
+    #[error(transparent)]
     IndexClient(#[from] IndexClientError),
     #[error("invalid user {0}")]
     InvalidUser(UserPk),
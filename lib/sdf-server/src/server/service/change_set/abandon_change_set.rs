@@ -1,5 +1,7 @@
 use super::ChangeSetResult;
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::service::change_set::ChangeSetError;
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
@@ -22,6 +24,7 @@ pub struct AbandonChangeSetResponse {
 pub async fn abandon_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<AbandonChangeSetRequest>,
@@ -46,9 +49,11 @@ pub async fn abandon_change_set(
     ctx.blocking_commit().await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(ChangeSetError::InvalidUser(*user_pk))?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(ChangeSetError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(ChangeSetError::InvalidUserSystemInit),
     };
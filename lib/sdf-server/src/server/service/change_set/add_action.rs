@@ -1,5 +1,7 @@
 use super::{ChangeSetError, ChangeSetResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use axum::extract::{Json, OriginalUri};
 use dal::{Action, ActionPrototypeId, ChangeSet, ComponentId, StandardModel, Visibility, WsEvent};
@@ -19,6 +21,7 @@ pub async fn add_action(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<AddActionRequest>,
 ) -> ChangeSetResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
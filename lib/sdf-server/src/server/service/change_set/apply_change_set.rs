@@ -1,11 +1,16 @@
 use super::ChangeSetResult;
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::service::change_set::ChangeSetError;
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::Json;
 use dal::job::definition::{FixItem, FixesJob};
-use dal::{ChangeSet, ChangeSetPk, Fix, FixBatch, HistoryActor, StandardModel, User};
+use dal::{
+    ChangeSet, ChangeSetPk, Fix, FixBatch, FixBatchId, FixConfirmation, HistoryActor,
+    StandardModel, User,
+};
 use serde::{Deserialize, Serialize};
 //use telemetry::tracing::{info_span, Instrument, log::warn};
 
@@ -19,11 +24,16 @@ pub struct ApplyChangeSetRequest {
 #[serde(rename_all = "camelCase")]
 pub struct ApplyChangeSetResponse {
     pub change_set: ChangeSet,
+    /// The fix batch running the change set's actions, if it has any.
+    pub fix_batch_id: Option<FixBatchId>,
+    /// Set if the fix batch deletes resources and will not run until confirmed this way.
+    pub pending_confirmation: Option<FixConfirmation>,
 }
 
 pub async fn apply_change_set(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ApplyChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<ApplyChangeSetRequest>,
@@ -50,16 +60,20 @@ pub async fn apply_change_set(
     ctx.blocking_commit().await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(ChangeSetError::InvalidUser(*user_pk))?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(ChangeSetError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(ChangeSetError::InvalidUserSystemInit),
     };
 
+    let mut fix_batch_id = None;
+    let mut pending_confirmation = None;
     if !actions.is_empty() {
         let actors_delimited_string = actors.join(",");
-        let batch = FixBatch::new(&ctx, user.email(), &actors_delimited_string).await?;
+        let mut batch = FixBatch::new(&ctx, user.email(), &actors_delimited_string).await?;
         let mut fixes = Vec::with_capacity(actions.len());
 
         for action in actions {
@@ -78,6 +92,9 @@ pub async fn apply_change_set(
             });
         }
 
+        pending_confirmation = batch.require_confirmation_if_destructive(&ctx).await?;
+        fix_batch_id = Some(*batch.id());
+
         track(
             &posthog_client,
             &ctx,
@@ -87,11 +104,15 @@ pub async fn apply_change_set(
                 "fix_batch_id": batch.id(),
                 "number_of_fixes_in_batch": fixes.len(),
                 "fixes_applied": fixes,
+                "pending_confirmation": pending_confirmation,
             }),
         );
 
-        ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
-            .await?;
+        // A batch pending confirmation is enqueued once confirmed, see `fix/confirm`.
+        if pending_confirmation.is_none() {
+            ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
+                .await?;
+        }
     }
 
     ctx.commit().await?;
@@ -105,5 +126,9 @@ pub async fn apply_change_set(
     );
     */
 
-    Ok(Json(ApplyChangeSetResponse { change_set }))
+    Ok(Json(ApplyChangeSetResponse {
+        change_set,
+        fix_batch_id,
+        pending_confirmation,
+    }))
 }
@@ -1,4 +1,6 @@
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use crate::service::change_set::{ChangeSetError, ChangeSetResult};
 use axum::extract::OriginalUri;
@@ -25,6 +27,7 @@ pub async fn begin_approval_process(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<BeginMergeFlow>,
 ) -> ChangeSetResult<Json<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -35,7 +38,7 @@ pub async fn begin_approval_process(
     change_set.begin_approval_flow(&mut ctx).await?;
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(ChangeSetError::InvalidUser(*user_pk))?;
@@ -82,6 +85,7 @@ pub async fn cancel_approval_process(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<CancelMergeFlow>,
 ) -> ChangeSetResult<Json<()>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -92,7 +96,7 @@ pub async fn cancel_approval_process(
     change_set.cancel_approval_flow(&mut ctx).await?;
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(ChangeSetError::InvalidUser(*user_pk))?;
@@ -56,7 +56,9 @@ pub async fn list_open_change_sets(
                     let history_actor = history_event::HistoryActor::User(*created_at_user);
                     let actor = ActorView::from_history_actor(&ctx, history_actor).await?;
                     match actor {
-                        ActorView::System { label } => actor_email = Some(label),
+                        ActorView::ApiToken { label, .. } | ActorView::System { label } => {
+                            actor_email = Some(label)
+                        }
                         ActorView::User { label, email, .. } => {
                             if let Some(em) = email {
                                 actor_email = Some(em)
@@ -1,9 +1,11 @@
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use crate::service::change_set::{ChangeSetError, ChangeSetResult};
 use axum::extract::OriginalUri;
 use axum::Json;
-use dal::{HistoryActor, User, Visibility, WsEvent};
+use dal::{HistoryActor, HistoryEvent, User, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -19,14 +21,17 @@ pub async fn merge_vote(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::ApplyChangeSet>,
     Json(request): Json<MergeVoteRequest>,
 ) -> ChangeSetResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(ChangeSetError::InvalidUser(*user_pk))?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(ChangeSetError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(ChangeSetError::InvalidUserSystemInit),
     };
@@ -44,6 +49,18 @@ pub async fn merge_vote(
         }),
     );
 
+    let _history_event = HistoryEvent::new(
+        &ctx,
+        "change_set.merge_vote",
+        "Change Set merge vote",
+        &serde_json::json!({
+            "changeSetPk": ctx.visibility().change_set_pk,
+            "userPk": user.pk(),
+            "vote": request.vote,
+        }),
+    )
+    .await?;
+
     WsEvent::change_set_merge_vote(
         &ctx,
         ctx.visibility().change_set_pk,
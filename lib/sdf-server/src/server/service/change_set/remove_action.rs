@@ -1,5 +1,5 @@
 use super::ChangeSetResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 use crate::server::service::change_set::ChangeSetError;
 use axum::Json;
 use dal::{Action, ActionId, StandardModel, Visibility, WsEvent};
@@ -16,6 +16,7 @@ pub struct RemoveActionRequest {
 pub async fn remove_action(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<RemoveActionRequest>,
 ) -> ChangeSetResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
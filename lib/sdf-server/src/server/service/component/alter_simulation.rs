@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::ComponentResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +25,7 @@ pub struct AlterSimulationResponse {
 pub async fn alter_simulation(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<AlterSimulationRequest>,
 ) -> ComponentResult<Json<AlterSimulationResponse>> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
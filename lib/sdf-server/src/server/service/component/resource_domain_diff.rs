@@ -7,6 +7,7 @@ use dal::func::backend::js_reconciliation::{
     ReconciliationDiff, ReconciliationDiffDomain, ReconciliationResult,
 };
 use dal::func::before::before_funcs_for_component;
+use dal::secret::SecretUsageSource;
 use dal::{
     AttributeReadContext, AttributeValue, AttributeView, Component, ComponentId,
     ExternalProviderId, FuncBinding, InternalProviderId, Prop, ReconciliationPrototype,
@@ -158,7 +159,12 @@ pub async fn get_diff(
         {
             let func = reconciliation_prototype.func(ctx).await?;
 
-            let before = before_funcs_for_component(ctx, component.id()).await?;
+            let before = before_funcs_for_component(
+                ctx,
+                component.id(),
+                SecretUsageSource::default().with_func_id(*func.id()),
+            )
+            .await?;
 
             let (_, func_binding_return_value) = FuncBinding::create_and_execute(
                 ctx,
@@ -8,7 +8,9 @@ use dal::{
 use serde::{Deserialize, Serialize};
 
 use super::{DiagramError, DiagramResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -109,6 +111,7 @@ async fn delete_single_component(
 pub async fn delete_component(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     posthog_client: PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<DeleteComponentRequest>,
@@ -154,6 +157,7 @@ pub struct DeleteComponentsRequest {
 pub async fn delete_components(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     posthog_client: PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<DeleteComponentsRequest>,
@@ -1,5 +1,5 @@
 use super::DiagramResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 use crate::service::diagram::DiagramError;
 use axum::Json;
 use dal::node::NodeId;
@@ -28,6 +28,7 @@ pub struct SetNodePositionResponse {
 pub async fn set_node_position(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<SetNodePositionRequest>,
 ) -> DiagramResult<Json<SetNodePositionResponse>> {
     let visibility = Visibility::new_change_set(request.visibility.change_set_pk, true);
@@ -9,12 +9,13 @@ use thiserror::Error;
 use dal::fix::FixError as DalFixError;
 use dal::schema::SchemaError as DalSchemaError;
 use dal::{
-    ComponentError, ComponentId, FixResolverError, FuncBindingReturnValueError, StandardModelError,
-    TransactionsError, UserError, UserPk,
+    ComponentError, ComponentId, FixBatchId, FixResolverError, FuncBindingReturnValueError,
+    StandardModelError, TransactionsError, UserError, UserPk,
 };
 
 use crate::server::state::AppState;
 
+pub mod confirm;
 pub mod list;
 pub mod run;
 
@@ -29,6 +30,8 @@ pub enum FixError {
     DalFix(#[from] DalFixError),
     #[error(transparent)]
     DalSchema(#[from] DalSchemaError),
+    #[error("fix batch {0} not found")]
+    FixBatchNotFound(FixBatchId),
     #[error(transparent)]
     FixResolver(#[from] FixResolverError),
     #[error(transparent)]
@@ -41,6 +44,8 @@ pub enum FixError {
     NoSchemaForComponent(ComponentId),
     #[error("no schema variant found for component {0}")]
     NoSchemaVariantForComponent(ComponentId),
+    #[error("fix batch {0} requires you to sign in again before confirming it")]
+    ReauthenticationRequired(FixBatchId),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
     #[error(transparent)]
@@ -53,7 +58,17 @@ pub type FixResult<T> = std::result::Result<T, FixError>;
 
 impl IntoResponse for FixError {
     fn into_response(self) -> Response {
-        let (status, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let status = match self {
+            FixError::DalFix(DalFixError::ConfirmationNotByUser(_))
+            | FixError::DalFix(DalFixError::ConfirmedByAuthor(_))
+            | FixError::ReauthenticationRequired(_) => StatusCode::FORBIDDEN,
+            FixError::DalFix(DalFixError::AlreadyConfirmed(_))
+            | FixError::DalFix(DalFixError::AlreadyStarted)
+            | FixError::DalFix(DalFixError::ConfirmationNotRequired(_)) => StatusCode::CONFLICT,
+            FixError::FixBatchNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
 
         let body = Json(
             serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
@@ -65,6 +80,7 @@ impl IntoResponse for FixError {
 
 pub fn routes() -> Router<AppState> {
     Router::new()
+        .route("/confirm", post(confirm::confirm))
         .route("/list", get(list::list))
         .route("/run", post(run::run))
 }
@@ -0,0 +1,101 @@
+use axum::extract::{OriginalUri, State};
+use axum::Json;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{FixError, FixResult};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient, RawAccessToken,
+};
+use crate::server::tracking::track;
+use dal::job::definition::{FixItem, FixesJob};
+use dal::{
+    FixBatch, FixBatchId, FixConfirmation, JwtPublicSigningKey, StandardModel, UserClaim,
+    Visibility,
+};
+
+/// How recently a user must have signed in to confirm a fix batch requiring
+/// [`Reauthenticate`](FixConfirmation::Reauthenticate).
+const REAUTHENTICATION_MAX_AGE_MINUTES: i64 = 5;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesConfirmRequest {
+    pub id: FixBatchId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FixesConfirmResponse {
+    pub id: FixBatchId,
+}
+
+/// Confirms a fix batch which is pending confirmation, because it deletes resources, and runs it.
+pub async fn confirm(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::RunFix>,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    State(jwt_public_signing_key): State<JwtPublicSigningKey>,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<FixesConfirmRequest>,
+) -> FixResult<Json<FixesConfirmResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let mut batch = FixBatch::get_by_id_for_update(&ctx, &request.id)
+        .await?
+        .ok_or(FixError::FixBatchNotFound(request.id))?;
+
+    if batch.required_confirmation() == Some(&FixConfirmation::Reauthenticate) {
+        // Anything but a session token (such as an api token) can not prove a recent sign in.
+        let issued_at = UserClaim::issued_at_from_bearer_token(
+            jwt_public_signing_key,
+            format!("Bearer {raw_access_token}"),
+        )
+        .await
+        .ok()
+        .flatten();
+        let reauthenticated = issued_at.map_or(false, |issued_at| {
+            Utc::now().signed_duration_since(issued_at)
+                <= Duration::minutes(REAUTHENTICATION_MAX_AGE_MINUTES)
+        });
+        if !reauthenticated {
+            return Err(FixError::ReauthenticationRequired(request.id));
+        }
+    }
+
+    batch.confirm(&ctx).await?;
+
+    let fixes: Vec<FixItem> = batch
+        .fixes(&ctx)
+        .await?
+        .into_iter()
+        .map(|fix| FixItem {
+            id: *fix.id(),
+            component_id: *fix.component_id(),
+            action_prototype_id: *fix.action_prototype_id(),
+        })
+        .collect();
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "confirm_fix",
+        serde_json::json!({
+            "fix_batch_id": batch.id(),
+            "confirmation": batch.required_confirmation(),
+            "number_of_fixes_in_batch": fixes.len(),
+        }),
+    );
+
+    ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(FixesConfirmResponse { id: *batch.id() }))
+}
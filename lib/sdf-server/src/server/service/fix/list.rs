@@ -1,7 +1,7 @@
 use axum::{extract::Query, Json};
 use chrono::Utc;
 use dal::fix::FixHistoryView;
-use dal::{FixBatch, FixBatchId, FixCompletionStatus};
+use dal::{FixBatch, FixBatchId, FixCompletionStatus, FixConfirmation, UserPk};
 use dal::{StandardModel, Visibility};
 use serde::{Deserialize, Serialize};
 
@@ -25,6 +25,8 @@ pub struct BatchHistoryView {
     fixes: Vec<FixHistoryView>,
     started_at: Option<String>,
     finished_at: Option<String>,
+    pending_confirmation: Option<FixConfirmation>,
+    confirmed_by: Option<UserPk>,
 }
 
 pub type ListFixesResponse = Vec<BatchHistoryView>;
@@ -40,12 +42,19 @@ pub async fn list(
     let mut batch_views = Vec::new();
     for batch in FixBatch::list(&ctx).await? {
         let mut batch_timed_out = false;
+        // A confirmed batch only started waiting to run when it was confirmed.
+        let waiting_since = if batch.confirmed_by().is_some() {
+            batch.timestamp().updated_at
+        } else {
+            batch.timestamp().created_at
+        };
         // FIXME(paulo): hardcoding 5 minutes timeout to avoid hiding broken batches forever
         let completion_status = if let Some(status) = batch.completion_status() {
             Some(*status)
-        } else if Utc::now().signed_duration_since(batch.timestamp().created_at)
-            > chrono::Duration::minutes(5)
-        {
+        } else if batch.is_pending_confirmation() {
+            // A batch waiting to be confirmed has not timed out, it has not started yet.
+            Some(FixCompletionStatus::Unstarted)
+        } else if Utc::now().signed_duration_since(waiting_since) > chrono::Duration::minutes(5) {
             batch_timed_out = true;
             Some(FixCompletionStatus::Failure)
         } else {
@@ -78,6 +87,11 @@ pub async fn list(
             actors: fix_actors,
             started_at: batch.started_at().map(|s| s.to_string()),
             finished_at: batch.finished_at().map(|s| s.to_string()),
+            pending_confirmation: batch
+                .required_confirmation()
+                .copied()
+                .filter(|_| batch.is_pending_confirmation()),
+            confirmed_by: batch.confirmed_by().copied(),
         })
     }
 
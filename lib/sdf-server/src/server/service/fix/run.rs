@@ -3,12 +3,14 @@ use axum::Json;
 use serde::{Deserialize, Serialize};
 
 use super::{FixError, FixResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use dal::job::definition::{FixItem, FixesJob};
 use dal::{
-    ActionPrototypeId, ComponentId, Fix, FixBatch, FixBatchId, HistoryActor, StandardModel, User,
-    Visibility,
+    ActionPrototypeId, ComponentId, Fix, FixBatch, FixBatchId, FixConfirmation, HistoryActor,
+    StandardModel, User, Visibility,
 };
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -30,11 +32,14 @@ pub struct FixesRunRequest {
 #[serde(rename_all = "camelCase")]
 pub struct FixesRunResponse {
     pub id: FixBatchId,
+    /// Set if the batch deletes resources and will not run until confirmed this way.
+    pub pending_confirmation: Option<FixConfirmation>,
 }
 
 pub async fn run(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::RunFix>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<FixesRunRequest>,
@@ -42,13 +47,15 @@ pub async fn run(
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(FixError::InvalidUser(*user_pk))?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(FixError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => return Err(FixError::InvalidUserSystemInit),
     };
-    let batch = FixBatch::new(&ctx, user.email(), "").await?;
+    let mut batch = FixBatch::new(&ctx, user.email(), "").await?;
     let mut fixes = Vec::with_capacity(request.list.len());
 
     for fix_run_request in request.list {
@@ -67,6 +74,8 @@ pub async fn run(
         });
     }
 
+    let pending_confirmation = batch.require_confirmation_if_destructive(&ctx).await?;
+
     track(
         &posthog_client,
         &ctx,
@@ -76,13 +85,20 @@ pub async fn run(
             "fix_batch_id": batch.id(),
             "number_of_fixes_in_batch": fixes.len(),
             "fixes_applied": fixes,
+            "pending_confirmation": pending_confirmation,
         }),
     );
 
-    ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
-        .await?;
+    // A batch pending confirmation is enqueued once confirmed, see `confirm`.
+    if pending_confirmation.is_none() {
+        ctx.enqueue_job(FixesJob::new(&ctx, fixes, *batch.id()))
+            .await?;
+    }
 
     ctx.commit().await?;
 
-    Ok(Json(FixesRunResponse { id: *batch.id() }))
+    Ok(Json(FixesRunResponse {
+        id: *batch.id(),
+        pending_confirmation,
+    }))
 }
@@ -1,5 +1,7 @@
 use super::FuncResult;
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use crate::service::func::{get_func_view, FuncAssociations, FuncError};
 use axum::extract::OriginalUri;
@@ -24,6 +26,7 @@ pub struct DeleteFuncResponse {
 pub async fn delete_func(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<DeleteFuncRequest>,
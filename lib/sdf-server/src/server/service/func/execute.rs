@@ -1,10 +1,10 @@
 use super::FuncResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 use axum::Json;
 use dal::{
     func::before::before_funcs_for_component, func::binding::FuncBindingResult,
-    func::binding::LogLinePayload, ComponentId, DalContext, Func, FuncBinding, FuncBindingError,
-    FuncError, FuncId, StandardModel, Visibility, WsEvent,
+    func::binding::LogLinePayload, secret::SecretUsageSource, ComponentId, DalContext, Func,
+    FuncBinding, FuncBindingError, FuncError, FuncId, StandardModel, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 use veritech_client::OutputStream;
@@ -33,6 +33,7 @@ pub struct ExecuteResponse {
 pub async fn execute(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(req): Json<ExecuteRequest>,
 ) -> FuncResult<Json<ExecuteResponse>> {
     let ctx = builder.build(request_ctx.build(req.visibility)).await?;
@@ -43,7 +44,12 @@ pub async fn execute(
 
     // We need the associated [`ComponentId`] for this function--this is how we resolve and
     // prepare before functions
-    let before = before_funcs_for_component(&ctx, &req.component_id).await?;
+    let before = before_funcs_for_component(
+        &ctx,
+        &req.component_id,
+        SecretUsageSource::default().with_func_id(req.id),
+    )
+    .await?;
 
     let func_binding =
         FuncBinding::new(&ctx, req.args.clone(), req.id, *func.backend_kind()).await?;
@@ -2,13 +2,13 @@ use super::{
     save_func::{do_save_func, SaveFuncRequest},
     FuncError, FuncResult,
 };
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 use axum::{response::IntoResponse, Json};
 use dal::{
-    job::definition::DependentValuesUpdate, ActionPrototype, AttributePrototype, AttributeValue,
-    AttributeValueError, AttributeValueId, ChangeSet, Component, DalContext, Func, FuncBackendKind,
-    FuncBackendResponseType, PropId, RootPropChild, SchemaVariant, StandardModel,
-    ValidationPrototype, WsEvent,
+    job::definition::DependentValuesUpdate, secret::SecretUsageSource, ActionPrototype,
+    AttributePrototype, AttributeValue, AttributeValueError, AttributeValueId, ChangeSet,
+    Component, DalContext, Func, FuncBackendKind, FuncBackendResponseType, PropId, RootPropChild,
+    SchemaVariant, StandardModel, ValidationPrototype, WsEvent,
 };
 use serde_json::Value;
 use std::collections::HashMap;
@@ -150,7 +150,9 @@ async fn run_actions(ctx: &DalContext, func: &Func) -> FuncResult<()> {
         }
         let components = Component::list_for_schema_variant(ctx, schema_variant_id).await?;
         for component in components {
-            proto.run(ctx, *component.id()).await?;
+            proto
+                .run(ctx, *component.id(), SecretUsageSource::default())
+                .await?;
         }
     }
 
@@ -160,6 +162,7 @@ async fn run_actions(ctx: &DalContext, func: &Func) -> FuncResult<()> {
 pub async fn save_and_exec(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<SaveFuncRequest>,
 ) -> FuncResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
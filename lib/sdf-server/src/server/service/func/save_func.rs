@@ -17,7 +17,9 @@ use dal::{
 };
 use dal::{FuncBackendResponseType, PropKind, SchemaVariant, ValidationPrototype};
 
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 
 use super::ValidationPrototypeView;
@@ -726,6 +728,7 @@ pub async fn do_save_func(
 pub async fn save_func<'a>(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<SaveFuncRequest>,
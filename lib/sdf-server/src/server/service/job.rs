@@ -0,0 +1,60 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use dal::{JobDeadLetterError, TransactionsError};
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod discard_dead_letter;
+pub mod get_dead_letter;
+pub mod list_dead_letters;
+pub mod replay_dead_letter;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    JobDeadLetter(#[from] JobDeadLetterError),
+}
+
+pub type JobResult<T> = Result<T, JobError>;
+
+impl IntoResponse for JobError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            JobError::JobDeadLetter(JobDeadLetterError::NotFound(_)) => {
+                (StatusCode::NOT_FOUND, self.to_string())
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        };
+
+        let body = Json(
+            serde_json::json!({ "error": { "message": error_message, "code": 42, "statusCode": status.as_u16() } }),
+        );
+
+        (status, body).into_response()
+    }
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/list_dead_letters",
+            get(list_dead_letters::list_dead_letters),
+        )
+        .route("/get_dead_letter", get(get_dead_letter::get_dead_letter))
+        .route(
+            "/replay_dead_letter",
+            post(replay_dead_letter::replay_dead_letter),
+        )
+        .route(
+            "/discard_dead_letter",
+            post(discard_dead_letter::discard_dead_letter),
+        )
+}
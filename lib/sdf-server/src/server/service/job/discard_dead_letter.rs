@@ -0,0 +1,36 @@
+use axum::Json;
+use dal::{JobDeadLetter, JobDeadLetterPk};
+use serde::{Deserialize, Serialize};
+
+use super::JobResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardDeadLetterRequest {
+    pub pk: JobDeadLetterPk,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardDeadLetterResponse {
+    pub success: bool,
+}
+
+pub async fn discard_dead_letter(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageJobs>,
+    Json(request): Json<DiscardDeadLetterRequest>,
+) -> JobResult<Json<DiscardDeadLetterResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    JobDeadLetter::get_by_pk(&ctx, request.pk)
+        .await?
+        .discard(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(DiscardDeadLetterResponse { success: true }))
+}
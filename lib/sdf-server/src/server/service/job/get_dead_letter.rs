@@ -0,0 +1,27 @@
+use axum::{extract::Query, Json};
+use dal::{JobDeadLetter, JobDeadLetterPk};
+use serde::{Deserialize, Serialize};
+
+use super::JobResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDeadLetterRequest {
+    pub pk: JobDeadLetterPk,
+}
+
+pub type GetDeadLetterResponse = JobDeadLetter;
+
+pub async fn get_dead_letter(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageJobs>,
+    Query(request): Query<GetDeadLetterRequest>,
+) -> JobResult<Json<GetDeadLetterResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let dead_letter = JobDeadLetter::get_by_pk(&ctx, request.pk).await?;
+
+    Ok(Json(dead_letter))
+}
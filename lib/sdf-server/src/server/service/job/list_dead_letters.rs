@@ -0,0 +1,19 @@
+use axum::Json;
+use dal::JobDeadLetter;
+
+use super::JobResult;
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+pub type ListDeadLettersResponse = Vec<JobDeadLetter>;
+
+pub async fn list_dead_letters(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageJobs>,
+) -> JobResult<Json<ListDeadLettersResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let dead_letters = JobDeadLetter::list(&ctx).await?;
+
+    Ok(Json(dead_letters))
+}
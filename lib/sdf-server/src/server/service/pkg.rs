@@ -23,13 +23,16 @@ const MAX_NAME_SEARCH_ATTEMPTS: usize = 100;
 mod approval_process;
 pub mod builtin_module_spec;
 pub mod export_pkg;
+pub mod export_template;
 pub mod export_workspace;
 pub mod get_pkg;
 pub mod import_workspace_vote;
 pub mod install_pkg;
 pub mod list_pkgs;
+pub mod preview_upgrade_pkg;
 mod reject_pkg;
 pub mod remote_module_spec;
+pub mod upgrade_pkg;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -89,6 +92,8 @@ pub enum PkgError {
     SiPkg(#[from] SiPkgError),
     #[error(transparent)]
     StandardModel(#[from] StandardModelError),
+    #[error("No components added to template export")]
+    TemplateExportEmpty,
     #[error("tenancy error: {0}")]
     Tenancy(#[from] TenancyError),
     #[error(transparent)]
@@ -200,6 +205,7 @@ pub async fn pkg_open(builder: &DalContextBuilder, file_name: &str) -> PkgResult
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/export_pkg", post(export_pkg::export_pkg))
+        .route("/export_template", post(export_template::export_template))
         .route(
             "/export_workspace",
             post(export_workspace::export_workspace),
@@ -207,6 +213,15 @@ pub fn routes() -> Router<AppState> {
         .route("/get_module_by_hash", get(get_pkg::get_module_by_hash))
         .route("/install_pkg", post(install_pkg::install_pkg))
         .route("/list_pkgs", get(list_pkgs::list_pkgs))
+        .route(
+            "/preview_install_pkg",
+            get(install_pkg::preview_install_pkg),
+        )
+        .route(
+            "/preview_upgrade_pkg",
+            get(preview_upgrade_pkg::preview_upgrade_pkg),
+        )
+        .route("/upgrade_pkg", post(upgrade_pkg::upgrade_pkg))
         .route(
             "/remote_module_spec",
             get(remote_module_spec::remote_module_spec),
@@ -1,4 +1,6 @@
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient, RawAccessToken};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient, RawAccessToken,
+};
 use crate::server::tracking::track;
 use crate::service::pkg::{PkgError, PkgResult};
 use axum::extract::OriginalUri;
@@ -29,6 +31,7 @@ pub async fn begin_approval_process(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
     RawAccessToken(raw_access_token): RawAccessToken,
     Json(request): Json<BeginImportFlow>,
 ) -> PkgResult<Json<()>> {
@@ -46,7 +49,7 @@ pub async fn begin_approval_process(
     let metadata = pkg.metadata()?;
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(PkgError::InvalidUser(*user_pk))?;
@@ -100,12 +103,13 @@ pub async fn cancel_approval_process(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
     Json(request): Json<CancelImportFlow>,
 ) -> PkgResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(PkgError::InvalidUser(*user_pk))?;
@@ -1,5 +1,5 @@
 use super::PkgResult;
-use crate::server::extract::RawAccessToken;
+use crate::server::extract::{permission, Authorized, RawAccessToken};
 use crate::server::tracking::track;
 use crate::{
     server::extract::{AccessBuilder, HandlerContext, PosthogClient},
@@ -29,6 +29,7 @@ pub struct PromoteToBuiltinPkgResponse {
 pub async fn promote_to_builtin(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
     RawAccessToken(raw_access_token): RawAccessToken,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
@@ -42,7 +43,9 @@ pub async fn promote_to_builtin(
     };
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk).await?
+        }
         _ => None,
     };
 
@@ -0,0 +1,108 @@
+use super::{PkgError, PkgResult};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient, RawAccessToken,
+};
+use crate::server::tracking::track;
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{ComponentId, HistoryActor, User, Visibility};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTemplateRequest {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    /// The components to put in the template. Edges between them are included too.
+    pub component_ids: Vec<ComponentId>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTemplateResponse {
+    pub success: bool,
+    pub hash: String,
+}
+
+pub async fn export_template(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<ExportTemplateRequest>,
+) -> PkgResult<Json<ExportTemplateResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    if request.name.trim().is_empty() {
+        return Err(PkgError::PackageNameEmpty);
+    }
+
+    if request.version.trim().is_empty() {
+        return Err(PkgError::PackageVersionEmpty);
+    }
+
+    if request.component_ids.is_empty() {
+        return Err(PkgError::TemplateExportEmpty);
+    }
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(PkgError::ModuleIndexNotConfigured),
+    };
+
+    let created_by_email = match ctx.history_actor() {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .map(|user| user.email().to_owned())
+        }
+        _ => None,
+    }
+    .unwrap_or("unauthenticated user email".into());
+
+    info!("Packaging template");
+
+    let mut exporter = dal::pkg::PkgExporter::new_template_exporter(
+        &request.name,
+        &request.version,
+        request.description.as_ref(),
+        &created_by_email,
+        request.component_ids.clone(),
+    );
+
+    let template_payload = exporter.export_as_bytes(&ctx).await?;
+
+    let index_client =
+        module_index_client::IndexClient::new(module_index_url.try_into()?, &raw_access_token);
+    let response = index_client
+        .upload_module(
+            request.name.trim(),
+            request.version.trim(),
+            template_payload,
+        )
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "export_template",
+        serde_json::json!({
+                    "template_name": request.name,
+                    "template_version": request.version,
+                    "template_component_count": request.component_ids.len(),
+                    "template_hash": response.latest_hash,
+        }),
+    );
+
+    Ok(Json(ExportTemplateResponse {
+        success: true,
+        hash: response.latest_hash,
+    }))
+}
@@ -1,4 +1,6 @@
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use crate::service::pkg::{PkgError, PkgResult};
 use axum::extract::OriginalUri;
@@ -19,14 +21,17 @@ pub async fn import_workspace_vote(
     PosthogClient(posthog_client): PosthogClient,
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
     Json(request): Json<ImportVoteRequest>,
 ) -> PkgResult<Json<()>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk)
-            .await?
-            .ok_or(PkgError::InvalidUser(*user_pk))?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk)
+                .await?
+                .ok_or(PkgError::InvalidUser(*user_pk))?
+        }
 
         HistoryActor::SystemInit => {
             return Err(PkgError::InvalidUserSystemInit);
@@ -4,12 +4,18 @@ use super::PkgResult;
 use crate::server::extract::RawAccessToken;
 use crate::server::tracking::track;
 use crate::{
-    server::extract::{AccessBuilder, HandlerContext, PosthogClient},
+    server::extract::{permission, AccessBuilder, Authorized, HandlerContext, PosthogClient},
     service::pkg::PkgError,
 };
-use axum::extract::OriginalUri;
+use axum::extract::{OriginalUri, Query};
 use axum::Json;
-use dal::{pkg::import_pkg_from_pkg, Visibility, WsEvent};
+use dal::{
+    pkg::{
+        fetch_module_signature, import_pkg_from_pkg, preview_import, ImportItemKey, ImportOptions,
+        ImportPreview,
+    },
+    Visibility, WsEvent,
+};
 use dal::{HistoryActor, User, WorkspacePk};
 use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
@@ -20,10 +26,46 @@ use ulid::Ulid;
 #[serde(rename_all = "camelCase")]
 pub struct InstallPkgRequest {
     pub id: Ulid,
+    /// Items from the install preview which should not be installed.
+    #[serde(default)]
+    pub excluded_items: Vec<ImportItemKey>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewInstallPkgRequest {
+    pub id: Ulid,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type PreviewInstallPkgResponse = ImportPreview;
+
+/// Shows what installing a module would create, update or skip in the current change set, so
+/// that items can be excluded before installing it.
+pub async fn preview_install_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    Query(request): Query<PreviewInstallPkgRequest>,
+) -> PkgResult<Json<PreviewInstallPkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(PkgError::ModuleIndexNotConfigured),
+    };
+
+    let module_index_client = IndexClient::new(module_index_url.try_into()?, &raw_access_token);
+    let pkg = SiPkg::load_from_bytes(module_index_client.download_module(request.id).await?)?;
+
+    let preview = preview_import(&ctx, &pkg, &ImportOptions::default()).await?;
+
+    Ok(Json(preview))
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InstallPkgResponse {
@@ -35,6 +77,7 @@ pub struct InstallPkgResponse {
 pub async fn install_pkg(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
     RawAccessToken(raw_access_token): RawAccessToken,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
@@ -50,9 +93,21 @@ pub async fn install_pkg(
     let module_index_client = IndexClient::new(module_index_url.try_into()?, &raw_access_token);
     let pkg_data = module_index_client.download_module(request.id).await?;
 
+    let signature = fetch_module_signature(&module_index_client, request.id).await?;
+
     let pkg = SiPkg::load_from_bytes(pkg_data)?;
     let metadata = pkg.metadata()?;
-    let (_, svs, import_skips) = import_pkg_from_pkg(&ctx, &pkg, None).await?;
+    let (_, svs, import_skips) = import_pkg_from_pkg(
+        &ctx,
+        &pkg,
+        Some(ImportOptions {
+            module_index: Some(module_index_client),
+            signature,
+            excluded_items: request.excluded_items.into_iter().collect(),
+            ..Default::default()
+        }),
+    )
+    .await?;
 
     track(
         &posthog_client,
@@ -65,7 +120,7 @@ pub async fn install_pkg(
     );
 
     let user_pk = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => {
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
             let user = User::get_by_pk(&ctx, *user_pk)
                 .await?
                 .ok_or(PkgError::InvalidUser(*user_pk))?;
@@ -77,6 +132,12 @@ pub async fn install_pkg(
     };
 
     match metadata.kind() {
+        SiPkgKind::Template => {
+            WsEvent::change_set_written(&ctx)
+                .await?
+                .publish_on_commit(&ctx)
+                .await?;
+        }
         SiPkgKind::Module => {
             WsEvent::module_imported(&ctx, svs)
                 .await?
@@ -0,0 +1,45 @@
+use axum::{extract::Query, Json};
+use dal::{
+    pkg::{fetch_installed_pkg, plan_upgrade, UpgradePlan},
+    Visibility,
+};
+use module_index_client::IndexClient;
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use ulid::Ulid;
+
+use super::{PkgError, PkgResult};
+use crate::server::extract::{AccessBuilder, HandlerContext, RawAccessToken};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewUpgradePkgRequest {
+    pub id: Ulid,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type PreviewUpgradePkgResponse = UpgradePlan;
+
+pub async fn preview_upgrade_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    Query(request): Query<PreviewUpgradePkgRequest>,
+) -> PkgResult<Json<PreviewUpgradePkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(PkgError::ModuleIndexNotConfigured),
+    };
+
+    let module_index_client = IndexClient::new(module_index_url.try_into()?, &raw_access_token);
+    let new_pkg = SiPkg::load_from_bytes(module_index_client.download_module(request.id).await?)?;
+    let installed_pkg =
+        fetch_installed_pkg(&ctx, &module_index_client, new_pkg.metadata()?.name()).await?;
+
+    let plan = plan_upgrade(&ctx, &installed_pkg, &new_pkg).await?;
+
+    Ok(Json(plan))
+}
@@ -0,0 +1,102 @@
+use axum::extract::OriginalUri;
+use axum::Json;
+use dal::{
+    pkg::{fetch_installed_pkg, fetch_module_signature, upgrade_pkg, ImportOptions},
+    pkg::{MigratedComponent, PropRename, UpgradePlan},
+    Visibility, WsEvent,
+};
+use module_index_client::IndexClient;
+use serde::{Deserialize, Serialize};
+use si_pkg::SiPkg;
+use ulid::Ulid;
+
+use super::{PkgError, PkgResult};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient, RawAccessToken,
+};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradePkgRequest {
+    pub id: Ulid,
+    /// The possible renames of the upgrade's plan which are renames, so that the values of the
+    /// renamed props are carried over.
+    #[serde(default)]
+    pub confirmed_renames: Vec<PropRename>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradePkgResponse {
+    pub plan: UpgradePlan,
+    pub migrated_components: Vec<MigratedComponent>,
+}
+
+pub async fn upgrade_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::InstallModules>,
+    RawAccessToken(raw_access_token): RawAccessToken,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<UpgradePkgRequest>,
+) -> PkgResult<Json<UpgradePkgResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let module_index_url = match ctx.module_index_url() {
+        Some(url) => url,
+        None => return Err(PkgError::ModuleIndexNotConfigured),
+    };
+
+    let module_index_client = IndexClient::new(module_index_url.try_into()?, &raw_access_token);
+    let new_pkg = SiPkg::load_from_bytes(module_index_client.download_module(request.id).await?)?;
+    let metadata = new_pkg.metadata()?;
+    let installed_pkg = fetch_installed_pkg(&ctx, &module_index_client, metadata.name()).await?;
+
+    let signature = fetch_module_signature(&module_index_client, request.id).await?;
+
+    let (plan, schema_variant_ids, migrated_components) = upgrade_pkg(
+        &ctx,
+        &installed_pkg,
+        &new_pkg,
+        Some(ImportOptions {
+            module_index: Some(module_index_client),
+            signature,
+            ..Default::default()
+        }),
+        &request.confirmed_renames,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "upgrade_pkg",
+        serde_json::json!({
+                    "pkg_name": metadata.name().to_owned(),
+                    "from_version": plan.from_version.to_owned(),
+                    "to_version": plan.to_version.to_owned(),
+                    "migrated_components": migrated_components.len(),
+        }),
+    );
+
+    WsEvent::module_imported(&ctx, schema_variant_ids)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    WsEvent::change_set_written(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(UpgradePkgResponse {
+        plan,
+        migrated_components,
+    }))
+}
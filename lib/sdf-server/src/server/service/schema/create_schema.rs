@@ -1,5 +1,5 @@
 use super::SchemaResult;
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 use axum::Json;
 use dal::{component::ComponentKind, Schema, Visibility, WsEvent};
 use serde::{Deserialize, Serialize};
@@ -21,6 +21,7 @@ pub struct CreateSchemaResponse {
 pub async fn create_schema(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     Json(request): Json<CreateSchemaRequest>,
 ) -> SchemaResult<Json<CreateSchemaResponse>> {
     let ctx = builder.build(request_ctx.build(request.visibility)).await?;
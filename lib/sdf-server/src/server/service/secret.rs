@@ -14,6 +14,7 @@ use crate::server::state::AppState;
 
 pub mod create_secret;
 pub mod get_public_key;
+pub mod list_secret_usages;
 pub mod list_secrets;
 pub mod update_secret;
 
@@ -69,4 +70,8 @@ pub fn routes() -> Router<AppState> {
         .route("/", post(create_secret::create_secret))
         .route("/", get(list_secrets::list_secrets))
         .route("/", patch(update_secret::update_secret))
+        .route(
+            "/list_secret_usages",
+            get(list_secret_usages::list_secret_usages),
+        )
 }
@@ -1,11 +1,13 @@
 use axum::Json;
+use chrono::{DateTime, Utc};
 use dal::secret::SecretView;
 use dal::{
-    key_pair::KeyPairPk, EncryptedSecret, SecretAlgorithm, SecretVersion, Visibility, WsEvent,
+    key_pair::KeyPairPk, EncryptedSecret, SecretAlgorithm, SecretError, SecretKind, SecretVersion,
+    StandardModel, Visibility, WsEvent,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 
 use super::SecretResult;
 
@@ -19,6 +21,10 @@ pub struct CreateSecretRequest {
     pub key_pair_pk: KeyPairPk,
     pub version: SecretVersion,
     pub algorithm: SecretAlgorithm,
+    #[serde(default)]
+    pub kind: SecretKind,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub rotate_by: Option<DateTime<Utc>>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -28,21 +34,48 @@ pub type CreateSecretResponse = SecretView;
 pub async fn create_secret(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_tx): AccessBuilder,
+    _: Authorized<permission::ManageSecrets>,
     Json(request): Json<CreateSecretRequest>,
 ) -> SecretResult<Json<CreateSecretResponse>> {
     let ctx = builder.build(request_tx.build(request.visibility)).await?;
 
-    let secret = EncryptedSecret::new(
-        &ctx,
-        request.name,
-        request.definition,
-        request.description,
-        &request.crypted,
-        request.key_pair_pk,
-        request.version,
-        request.algorithm,
-    )
-    .await?;
+    let mut secret = match request.kind {
+        SecretKind::Reference => {
+            EncryptedSecret::new_reference(
+                &ctx,
+                request.name,
+                request.definition,
+                request.description,
+                &request.crypted,
+                request.key_pair_pk,
+                request.version,
+                request.algorithm,
+            )
+            .await?
+        }
+        SecretKind::Stored => {
+            EncryptedSecret::new(
+                &ctx,
+                request.name,
+                request.definition,
+                request.description,
+                &request.crypted,
+                request.key_pair_pk,
+                request.version,
+                request.algorithm,
+            )
+            .await?
+        }
+    };
+    if request.expires_at.is_some() || request.rotate_by.is_some() {
+        let mut encrypted_secret = EncryptedSecret::get_by_id(&ctx, secret.id())
+            .await?
+            .ok_or(SecretError::SecretNotFound(*secret.id()))?;
+        encrypted_secret
+            .set_expiry(&ctx, request.expires_at, request.rotate_by)
+            .await?;
+        secret = encrypted_secret.into();
+    }
 
     WsEvent::change_set_written(&ctx)
         .await?
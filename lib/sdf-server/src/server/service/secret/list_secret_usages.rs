@@ -0,0 +1,35 @@
+use axum::extract::Query;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use dal::{secret::SecretUsage, SecretId, Visibility};
+use serde::{Deserialize, Serialize};
+
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+use super::SecretResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSecretUsagesRequest {
+    pub secret_id: SecretId,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub type ListSecretUsagesResponse = Vec<SecretUsage>;
+
+pub async fn list_secret_usages(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::ViewAuditLog>,
+    Query(request): Query<ListSecretUsagesRequest>,
+) -> SecretResult<Json<ListSecretUsagesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let usages =
+        SecretUsage::list_for_secret(&ctx, request.secret_id, request.since, request.until).await?;
+
+    Ok(Json(usages))
+}
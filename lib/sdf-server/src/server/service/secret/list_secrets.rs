@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use axum::extract::Query;
 use axum::Json;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use dal::secret::{SecretDefinitionView, SecretView};
@@ -15,6 +16,9 @@ use super::SecretResult;
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ListSecretRequest {
+    /// When set, only secrets which expire or are to be rotated at or before this time
+    /// (including those which already have) are listed.
+    pub expiring_before: Option<DateTime<Utc>>,
     #[serde(flatten)]
     pub visibility: Visibility,
 }
@@ -52,6 +56,17 @@ pub async fn list_secrets(
         .collect::<HashMap<_, _>>();
 
     for secret in Secret::list(&ctx).await? {
+        if let Some(expiring_before) = request.expiring_before {
+            let expiring = secret
+                .expires_at()
+                .into_iter()
+                .chain(secret.rotate_by())
+                .any(|date| date <= expiring_before);
+            if !expiring {
+                continue;
+            }
+        }
+
         hash_map
             .get_mut(secret.definition())
             .ok_or(SecretError::SecretWithInvalidDefinition(*secret.id()))?
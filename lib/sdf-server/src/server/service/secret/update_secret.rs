@@ -1,4 +1,5 @@
 use axum::Json;
+use chrono::{DateTime, Utc};
 use dal::secret::SecretView;
 use dal::{
     key_pair::KeyPairPk, EncryptedSecret, SecretAlgorithm, SecretVersion, Visibility, WsEvent,
@@ -6,7 +7,7 @@ use dal::{
 use dal::{HistoryActor, SecretError, SecretId, StandardModel};
 use serde::{Deserialize, Serialize};
 
-use crate::server::extract::{AccessBuilder, HandlerContext};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
 
 use super::SecretResult;
 
@@ -25,6 +26,8 @@ pub struct UpdateSecretRequest {
     pub id: SecretId,
     pub name: String,
     pub description: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub rotate_by: Option<DateTime<Utc>>,
     pub new_secret_data: Option<NewSecretData>,
     #[serde(flatten)]
     pub visibility: Visibility,
@@ -35,6 +38,7 @@ pub type UpdateSecretResponse = SecretView;
 pub async fn update_secret(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_tx): AccessBuilder,
+    _: Authorized<permission::ManageSecrets>,
     Json(request): Json<UpdateSecretRequest>,
 ) -> SecretResult<Json<UpdateSecretResponse>> {
     let ctx = builder.build(request_tx.build(request.visibility)).await?;
@@ -46,9 +50,12 @@ pub async fn update_secret(
     // UPDATE SECRET METADATA
     secret.set_name(&ctx, request.name).await?;
     secret.set_description(&ctx, request.description).await?;
+    secret
+        .set_expiry(&ctx, request.expires_at, request.rotate_by)
+        .await?;
     match ctx.history_actor() {
         HistoryActor::SystemInit => {}
-        HistoryActor::User(id) => {
+        HistoryActor::User(id) | HistoryActor::ApiToken { user_pk: id, .. } => {
             println!("before - {id}");
             secret.set_updated_by(&ctx, Some(*id)).await?;
             println!("done - {id}");
@@ -13,15 +13,19 @@ use thiserror::Error;
 use crate::server::state::AppState;
 
 pub mod auth_connect;
+pub mod list_workspace_member_roles;
 pub mod load_workspaces;
 mod refresh_workspace_members;
 pub mod restore_authentication;
+pub mod set_workspace_member_role;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum SessionError {
     #[error("auth api error: {0}")]
     AuthApiError(String),
+    #[error("a member can not change their own role")]
+    CannotChangeOwnRole,
     #[error(transparent)]
     ContextTransactions(#[from] TransactionsError),
     #[error("Invalid user: {0}")]
@@ -60,6 +64,12 @@ impl IntoResponse for SessionError {
     fn into_response(self) -> Response {
         let (status, error_code, error_message) = match self {
             SessionError::LoginFailed => (StatusCode::CONFLICT, None, None),
+            SessionError::CannotChangeOwnRole => {
+                (StatusCode::CONFLICT, Some("CANNOT_CHANGE_OWN_ROLE"), None)
+            }
+            SessionError::User(UserError::NotAMember(_, _)) => {
+                (StatusCode::NOT_FOUND, Some("NOT_A_MEMBER"), None)
+            }
             SessionError::InvalidWorkspace(_) => (
                 StatusCode::CONFLICT,
                 Some("WORKSPACE_NOT_INITIALIZED"),
@@ -93,4 +103,12 @@ pub fn routes() -> Router<AppState> {
             "/refresh_workspace_members",
             post(refresh_workspace_members::refresh_workspace_members),
         )
+        .route(
+            "/list_workspace_member_roles",
+            get(list_workspace_member_roles::list_workspace_member_roles),
+        )
+        .route(
+            "/set_workspace_member_role",
+            post(set_workspace_member_role::set_workspace_member_role),
+        )
 }
@@ -2,7 +2,9 @@ use super::{SessionError, SessionResult};
 use crate::server::extract::{HandlerContext, RawAccessToken};
 use crate::service::session::AuthApiErrBody;
 use axum::Json;
-use dal::{DalContext, HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk};
+use dal::{
+    DalContext, HistoryActor, KeyPair, Tenancy, User, UserPk, Workspace, WorkspacePk, WorkspaceRole,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
@@ -110,8 +112,14 @@ async fn find_or_create_user_and_workspace(
         }
     };
 
-    // ensure workspace is associated to user
-    user.associate_workspace(&ctx, *workspace.pk()).await?;
+    // ensure workspace is associated to user, the creator of the workspace administers it
+    let role = if auth_api_workspace.creator_user_id == user.pk() {
+        WorkspaceRole::Admin
+    } else {
+        WorkspaceRole::default()
+    };
+    user.associate_workspace(&ctx, *workspace.pk(), role)
+        .await?;
 
     ctx.commit().await?;
 
@@ -0,0 +1,35 @@
+use axum::Json;
+use dal::{User, WorkspaceRole};
+use serde::{Deserialize, Serialize};
+
+use super::SessionResult;
+use crate::server::extract::{AccessBuilder, Authorization, HandlerContext};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMemberRole {
+    pub user: User,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWorkspaceMemberRolesResponse {
+    pub members: Vec<WorkspaceMemberRole>,
+}
+
+pub async fn list_workspace_member_roles(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Authorization(claim): Authorization,
+) -> SessionResult<Json<ListWorkspaceMemberRolesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let members = User::list_member_roles_for_workspace(&ctx, claim.workspace_pk)
+        .await?
+        .into_iter()
+        .map(|(user, role)| WorkspaceMemberRole { user, role })
+        .collect();
+
+    Ok(Json(ListWorkspaceMemberRolesResponse { members }))
+}
@@ -1,5 +1,7 @@
 use super::{SessionError, SessionResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, RawAccessToken};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, RawAccessToken,
+};
 use crate::service::session::AuthApiErrBody;
 use axum::Json;
 use dal::User;
@@ -29,6 +31,7 @@ pub struct RefreshWorkspaceMembersResponse {
 pub async fn refresh_workspace_members(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageMembers>,
     RawAccessToken(raw_access_token): RawAccessToken,
     Json(request): Json<RefreshWorkspaceMembersRequest>,
 ) -> SessionResult<Json<RefreshWorkspaceMembersResponse>> {
@@ -0,0 +1,45 @@
+use axum::Json;
+use dal::{User, UserPk, WorkspaceRole};
+use serde::{Deserialize, Serialize};
+
+use super::{SessionError, SessionResult};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspaceMemberRoleRequest {
+    pub user_pk: UserPk,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetWorkspaceMemberRoleResponse {
+    pub success: bool,
+}
+
+pub async fn set_workspace_member_role(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    authorized: Authorized<permission::ManageMembers>,
+    Json(request): Json<SetWorkspaceMemberRoleRequest>,
+) -> SessionResult<Json<SetWorkspaceMemberRoleResponse>> {
+    // An admin demoting themselves could leave the workspace without anyone able to manage it
+    if request.user_pk == authorized.claim.user_pk {
+        return Err(SessionError::CannotChangeOwnRole);
+    }
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    User::set_role_in_workspace(
+        &ctx,
+        request.user_pk,
+        authorized.claim.workspace_pk,
+        request.role,
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetWorkspaceMemberRoleResponse { success: true }))
+}
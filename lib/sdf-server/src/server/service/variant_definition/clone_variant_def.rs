@@ -1,5 +1,7 @@
 use super::{SchemaVariantDefinitionError, SchemaVariantDefinitionResult};
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
@@ -31,6 +33,7 @@ pub struct CloneVariantDefResponse {
 pub async fn clone_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<CloneVariantDefRequest>,
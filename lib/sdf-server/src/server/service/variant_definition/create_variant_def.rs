@@ -1,5 +1,7 @@
 use super::SchemaVariantDefinitionResult;
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
@@ -37,6 +39,7 @@ pub struct CreateVariantDefResponse {
 pub async fn create_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<CreateVariantDefRequest>,
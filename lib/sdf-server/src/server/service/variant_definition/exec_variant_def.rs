@@ -6,7 +6,9 @@ use super::{
     SaveVariantDefRequest, SchemaVariantDefinitionError, SchemaVariantDefinitionResult,
     ValidationPrototypeDefinition,
 };
-use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::extract::{
+    permission, AccessBuilder, Authorized, HandlerContext, PosthogClient,
+};
 use crate::server::tracking::track;
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
@@ -53,6 +55,7 @@ pub struct ExecVariantDefResponse {
 pub async fn exec_variant_def(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(request_ctx): AccessBuilder,
+    _: Authorized<permission::EditChangeSet>,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Json(request): Json<ExecVariantDefRequest>,
@@ -81,7 +84,9 @@ pub async fn exec_variant_def(
     super::save_variant_def(&ctx, &request, Some(scaffold_func_name)).await?;
 
     let user = match ctx.history_actor() {
-        HistoryActor::User(user_pk) => User::get_by_pk(&ctx, *user_pk).await?,
+        HistoryActor::User(user_pk) | HistoryActor::ApiToken { user_pk, .. } => {
+            User::get_by_pk(&ctx, *user_pk).await?
+        }
         _ => None,
     };
     let user_email = user
@@ -203,6 +208,8 @@ pub async fn exec_variant_def(
             )])),
             no_record: true,
             is_builtin: false,
+            module_index: None,
+            signature: None,
         }),
     )
     .await?;
@@ -0,0 +1,88 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use dal::{
+    DalContext, TransactionsError, Workspace, WorkspaceError as DalWorkspaceError, WorkspacePk,
+};
+use si_crypto::SigningError;
+use thiserror::Error;
+
+use crate::server::state::AppState;
+
+pub mod distrust_publisher_key;
+pub mod set_audit_log_sink;
+pub mod set_destructive_fix_confirmation;
+pub mod set_require_signed_modules;
+pub mod trust_publisher_key;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum WorkspaceError {
+    #[error(transparent)]
+    ContextTransactions(#[from] TransactionsError),
+    #[error(transparent)]
+    DalWorkspace(#[from] DalWorkspaceError),
+    #[error("invalid publisher key: {0}")]
+    InvalidPublisherKey(#[from] SigningError),
+    #[error("workspace not found: {0}")]
+    WorkspaceNotFound(WorkspacePk),
+}
+
+pub type WorkspaceResult<T> = Result<T, WorkspaceError>;
+
+impl IntoResponse for WorkspaceError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            // The audit log sink failed validation
+            WorkspaceError::DalWorkspace(DalWorkspaceError::AuditLog(_)) => StatusCode::BAD_REQUEST,
+            WorkspaceError::InvalidPublisherKey(_) => StatusCode::BAD_REQUEST,
+            WorkspaceError::WorkspaceNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        let body = Json(serde_json::json!({
+            "error": {
+                "message": self.to_string(),
+                "code": 42,
+                "statusCode": status.as_u16()
+            }
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+/// Returns the workspace of the [`DalContext`]'s tenancy.
+async fn tenancy_workspace(ctx: &DalContext) -> WorkspaceResult<Workspace> {
+    let workspace_pk = ctx.tenancy().workspace_pk().unwrap_or(WorkspacePk::NONE);
+    Workspace::get_by_pk(ctx, &workspace_pk)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound(workspace_pk))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/set_audit_log_sink",
+            post(set_audit_log_sink::set_audit_log_sink),
+        )
+        .route(
+            "/set_destructive_fix_confirmation",
+            post(set_destructive_fix_confirmation::set_destructive_fix_confirmation),
+        )
+        .route(
+            "/set_require_signed_modules",
+            post(set_require_signed_modules::set_require_signed_modules),
+        )
+        .route(
+            "/trust_publisher_key",
+            post(trust_publisher_key::trust_publisher_key),
+        )
+        .route(
+            "/distrust_publisher_key",
+            post(distrust_publisher_key::distrust_publisher_key),
+        )
+}
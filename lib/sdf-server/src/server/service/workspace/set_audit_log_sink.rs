@@ -0,0 +1,37 @@
+use axum::Json;
+use dal::{AuditLogSinkConfig, Workspace};
+use serde::{Deserialize, Serialize};
+
+use super::{tenancy_workspace, WorkspaceResult};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAuditLogSinkRequest {
+    /// Where to forward the workspace's audit log entries to, or `None` to stop forwarding them.
+    pub audit_log_sink: Option<AuditLogSinkConfig>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAuditLogSinkResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn set_audit_log_sink(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageWorkspace>,
+    Json(request): Json<SetAuditLogSinkRequest>,
+) -> WorkspaceResult<Json<SetAuditLogSinkResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut workspace = tenancy_workspace(&ctx).await?;
+    workspace
+        .set_audit_log_sink(&ctx, request.audit_log_sink)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetAuditLogSinkResponse { workspace }))
+}
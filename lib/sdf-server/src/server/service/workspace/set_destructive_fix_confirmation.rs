@@ -0,0 +1,38 @@
+use axum::Json;
+use dal::{FixConfirmation, Workspace};
+use serde::{Deserialize, Serialize};
+
+use super::{tenancy_workspace, WorkspaceResult};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDestructiveFixConfirmationRequest {
+    /// How fix batches which delete resources must be confirmed, or `None` to run them without
+    /// confirmation.
+    pub destructive_fix_confirmation: Option<FixConfirmation>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDestructiveFixConfirmationResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn set_destructive_fix_confirmation(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageWorkspace>,
+    Json(request): Json<SetDestructiveFixConfirmationRequest>,
+) -> WorkspaceResult<Json<SetDestructiveFixConfirmationResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut workspace = tenancy_workspace(&ctx).await?;
+    workspace
+        .set_destructive_fix_confirmation(&ctx, request.destructive_fix_confirmation)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetDestructiveFixConfirmationResponse { workspace }))
+}
@@ -0,0 +1,36 @@
+use axum::Json;
+use dal::Workspace;
+use serde::{Deserialize, Serialize};
+
+use super::{tenancy_workspace, WorkspaceResult};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRequireSignedModulesRequest {
+    pub require_signed_modules: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetRequireSignedModulesResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn set_require_signed_modules(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageWorkspace>,
+    Json(request): Json<SetRequireSignedModulesRequest>,
+) -> WorkspaceResult<Json<SetRequireSignedModulesResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let mut workspace = tenancy_workspace(&ctx).await?;
+    workspace
+        .set_require_signed_modules(&ctx, request.require_signed_modules)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(SetRequireSignedModulesResponse { workspace }))
+}
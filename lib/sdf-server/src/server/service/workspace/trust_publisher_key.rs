@@ -0,0 +1,37 @@
+use axum::Json;
+use dal::Workspace;
+use serde::{Deserialize, Serialize};
+use si_crypto::VerifyingKey;
+
+use super::{tenancy_workspace, WorkspaceResult};
+use crate::server::extract::{permission, AccessBuilder, Authorized, HandlerContext};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustPublisherKeyRequest {
+    /// The Base64 encoded publisher key.
+    pub public_key: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustPublisherKeyResponse {
+    pub workspace: Workspace,
+}
+
+pub async fn trust_publisher_key(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    _: Authorized<permission::ManageWorkspace>,
+    Json(request): Json<TrustPublisherKeyRequest>,
+) -> WorkspaceResult<Json<TrustPublisherKeyResponse>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let key = VerifyingKey::decode(request.public_key.trim())?;
+    let mut workspace = tenancy_workspace(&ctx).await?;
+    workspace.trust_publisher_key(&ctx, &key).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(TrustPublisherKeyResponse { workspace }))
+}
@@ -0,0 +1,40 @@
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{DalContext, JobDeadLetterPk, User, WorkspaceRole, WorkspaceSignup};
+use dal_test::{sdf_test, AuthTokenRef};
+use sdf_server::service::job::replay_dead_letter::ReplayDeadLetterRequest;
+
+use crate::service_tests::api_request_auth_status;
+
+#[sdf_test]
+async fn approver_cannot_replay_dead_letter(
+    ctx: DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    User::set_role_in_workspace(
+        &ctx,
+        nw.user.pk(),
+        *nw.workspace.pk(),
+        WorkspaceRole::Approver,
+    )
+    .await
+    .expect("cannot set role of user");
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = ReplayDeadLetterRequest {
+        pk: JobDeadLetterPk::NONE,
+    };
+    let status = api_request_auth_status(
+        app,
+        Method::POST,
+        "/api/job/replay_dead_letter",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
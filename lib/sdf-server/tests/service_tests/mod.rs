@@ -10,6 +10,7 @@ mod change_set;
 mod component;
 mod crdt;
 mod functions;
+mod job;
 mod scenario;
 mod schema;
 mod secret;
@@ -163,3 +164,29 @@ pub async fn api_request_auth_no_response<Req: Serialize>(
 
     assert_eq!(body, "", "response is not empty");
 }
+
+/// Sends a request with a json body and returns only the response's status, for tests which
+/// expect the request to be rejected.
+pub async fn api_request_auth_status<Req: Serialize>(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: impl AsRef<str>,
+    request: &Req,
+) -> StatusCode {
+    let auth_token = auth_token.as_ref();
+    let uri = uri.as_ref();
+    let api_request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"));
+
+    let api_request = api_request
+        .body(Body::from(
+            serde_json::to_vec(&serde_json::json!(&request)).expect("cannot turn request to json"),
+        ))
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    response.status()
+}
@@ -1,7 +1,10 @@
 use std::collections::HashSet;
 
-use axum::{http::Method, Router};
-use dal::{DalContext, StandardModel};
+use axum::{
+    http::{Method, StatusCode},
+    Router,
+};
+use dal::{DalContext, StandardModel, User, WorkspaceRole, WorkspaceSignup};
 use dal_test::{sdf_test, test_harness::create_schema as dal_create_schema, AuthTokenRef};
 use sdf_server::service::schema::{
     create_schema::{CreateSchemaRequest, CreateSchemaResponse},
@@ -9,7 +12,9 @@ use sdf_server::service::schema::{
     list_schemas::{ListSchemaRequest, ListSchemaResponse},
 };
 
-use crate::service_tests::{api_request_auth_json_body, api_request_auth_query};
+use crate::service_tests::{
+    api_request_auth_json_body, api_request_auth_query, api_request_auth_status,
+};
 
 #[sdf_test]
 async fn create_schema(ctx: DalContext, app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
@@ -30,6 +35,39 @@ async fn create_schema(ctx: DalContext, app: Router, AuthTokenRef(auth_token): A
     assert_eq!(response.schema.name(), "fancyPants");
 }
 
+#[sdf_test]
+async fn viewer_cannot_create_schema(
+    ctx: DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+    nw: WorkspaceSignup,
+) {
+    User::set_role_in_workspace(
+        &ctx,
+        nw.user.pk(),
+        *nw.workspace.pk(),
+        WorkspaceRole::Viewer,
+    )
+    .await
+    .expect("cannot set role of user");
+    let visibility = *ctx.visibility();
+    ctx.commit().await.expect("cannot commit txn");
+
+    let request = CreateSchemaRequest {
+        name: "fancyPants".to_string(),
+        visibility,
+    };
+    let status = api_request_auth_status(
+        app,
+        Method::POST,
+        "/api/schema/create_schema",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(StatusCode::FORBIDDEN, status);
+}
+
 #[sdf_test]
 async fn list_schemas(ctx: DalContext, app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
     let rand_schema1 = dal_create_schema(&ctx).await;
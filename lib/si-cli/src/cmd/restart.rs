@@ -15,7 +15,7 @@ impl AppState {
 
 async fn invoke(app: &AppState) -> CliResult<()> {
     app.stop().await?;
-    app.start().await?;
+    app.start(false).await?;
 
     Ok(())
 }
@@ -6,17 +6,21 @@ use crate::state::AppState;
 use crate::{CliResult, CONTAINER_NAMES};
 
 impl AppState {
-    pub async fn start(&self) -> CliResult<()> {
+    pub async fn start(&self, rotate_symmetric_crypto_key: bool) -> CliResult<()> {
         self.track(
             get_user_email().await?,
             serde_json::json!({"command-name": "start-system"}),
         );
-        invoke(self, self.is_preview()).await?;
+        invoke(self, self.is_preview(), rotate_symmetric_crypto_key).await?;
         Ok(())
     }
 }
 
-async fn invoke(app: &AppState, is_preview: bool) -> CliResult<()> {
+async fn invoke(
+    app: &AppState,
+    is_preview: bool,
+    rotate_symmetric_crypto_key: bool,
+) -> CliResult<()> {
     app.configure(false).await?;
     app.check(false).await?;
     app.install().await?;
@@ -26,12 +30,38 @@ async fn invoke(app: &AppState, is_preview: bool) -> CliResult<()> {
     }
 
     ensure_encryption_keys().await?;
-    ensure_symmetric_crypto_key().await?;
+    let rotated_symmetric_crypto_key =
+        ensure_symmetric_crypto_key(rotate_symmetric_crypto_key && !is_preview).await?;
     ensure_jwt_public_signing_key().await?;
     let si_data_dir = get_si_data_dir().await?;
 
     app.container_engine().create_network().await?;
 
+    // The services load their symmetric crypto keys on creation, so any existing containers have
+    // to be replaced to pick up a rotated key
+    if rotated_symmetric_crypto_key {
+        for name in ["pinga", "sdf"] {
+            let container_name = format!("local-{0}-1", name);
+            if let Some(existing) = app
+                .container_engine()
+                .get_existing_container(container_name.clone())
+                .await?
+            {
+                println!(
+                    "Symmetric crypto key was rotated so recreating {0}",
+                    container_name.clone()
+                );
+                let id = existing.id.as_ref().unwrap().to_string();
+                if existing.state.as_ref().unwrap() == "running" {
+                    app.container_engine().stop_container(id.clone()).await?;
+                }
+                app.container_engine()
+                    .delete_container(id, container_name)
+                    .await?;
+            }
+        }
+    }
+
     for name in CONTAINER_NAMES.iter() {
         let container = format!("systeminit/{0}", name);
         let container_name = format!("local-{0}-1", name);
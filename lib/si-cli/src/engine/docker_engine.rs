@@ -1,4 +1,7 @@
 use crate::engine::{ContainerEngine, ContainerReleaseInfo, SiContainerSummary, SiImageSummary};
+use crate::key_management::{
+    symmetric_crypto_key_args, PREVIOUS_SYMMETRIC_CRYPTO_KEY, SYMMETRIC_CRYPTO_KEY,
+};
 use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 use async_trait::async_trait;
 use color_eyre::eyre::eyre;
@@ -424,6 +427,7 @@ impl ContainerEngine for DockerEngine {
                 "OTEL_EXPORTER_OTLP_ENDPOINT=http://otelcol:4317",
             ])
             .volumes([format!("{}:/run/pinga:z", data_dir.display())])
+            .command(symmetric_crypto_key_args(&data_dir, "/run/pinga"))
             .build();
 
         let container = self.docker.containers().create(&create_opts).await?;
@@ -439,6 +443,28 @@ impl ContainerEngine for DockerEngine {
         host_port: u32,
         data_dir: PathBuf,
     ) -> CliResult<()> {
+        let mut volumes = vec![
+            format!(
+                "{}:/run/sdf/cyclone_encryption.key:z",
+                data_dir.join("cyclone_encryption.key").display()
+            ),
+            format!(
+                "{}:/run/sdf/{SYMMETRIC_CRYPTO_KEY}:z",
+                data_dir.join(SYMMETRIC_CRYPTO_KEY).display()
+            ),
+            format!(
+                "{}:/run/sdf/jwt_signing_public_key.pem:z",
+                data_dir.join("jwt_signing_public_key.pem").display()
+            ),
+        ];
+        let previous_symmetric_crypto_key = data_dir.join(PREVIOUS_SYMMETRIC_CRYPTO_KEY);
+        if previous_symmetric_crypto_key.exists() {
+            volumes.push(format!(
+                "{}:/run/sdf/{PREVIOUS_SYMMETRIC_CRYPTO_KEY}:z",
+                previous_symmetric_crypto_key.display()
+            ));
+        }
+
         let create_opts = ContainerCreateOpts::builder()
             .name(name)
             .image(format!("{0}:stable", image))
@@ -457,20 +483,8 @@ impl ContainerEngine for DockerEngine {
                 PublishPort::tcp(5156),
                 HostPort::with_ip(host_port, host_ip),
             )
-            .volumes([
-                format!(
-                    "{}:/run/sdf/cyclone_encryption.key:z",
-                    data_dir.join("cyclone_encryption.key").display()
-                ),
-                format!(
-                    "{}:/run/sdf/donkey.key:z",
-                    data_dir.join("donkey.key").display()
-                ),
-                format!(
-                    "{}:/run/sdf/jwt_signing_public_key.pem:z",
-                    data_dir.join("jwt_signing_public_key.pem").display()
-                ),
-            ])
+            .volumes(volumes)
+            .command(symmetric_crypto_key_args(&data_dir, "/run/sdf"))
             .build();
 
         let container = self.docker.containers().create(&create_opts).await?;
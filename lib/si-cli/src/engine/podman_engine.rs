@@ -1,4 +1,7 @@
 use crate::engine::{ContainerEngine, ContainerReleaseInfo, SiContainerSummary, SiImageSummary};
+use crate::key_management::{
+    symmetric_crypto_key_args, PREVIOUS_SYMMETRIC_CRYPTO_KEY, SYMMETRIC_CRYPTO_KEY,
+};
 use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 use async_trait::async_trait;
 use color_eyre::eyre::eyre;
@@ -611,6 +614,7 @@ impl ContainerEngine for PodmanEngine {
                 uid_mappings: None,
                 gid_mappings: None,
             }])
+            .command(symmetric_crypto_key_args(&data_dir, "/run/pinga"))
             .build();
 
         let container = self.podman.containers().create(&create_opts).await?;
@@ -630,6 +634,54 @@ impl ContainerEngine for PodmanEngine {
         host_port: u32,
         data_dir: PathBuf,
     ) -> CliResult<()> {
+        let mut mounts = vec![
+            ContainerMount {
+                destination: Some("/run/sdf/cyclone_encryption.key".to_owned()),
+                source: Some(
+                    data_dir
+                        .join("cyclone_encryption.key")
+                        .display()
+                        .to_string(),
+                ),
+                options: Some(get_container_mount_opts()),
+                _type: Some("bind".to_owned()),
+                uid_mappings: None,
+                gid_mappings: None,
+            },
+            ContainerMount {
+                destination: Some(format!("/run/sdf/{SYMMETRIC_CRYPTO_KEY}")),
+                source: Some(data_dir.join(SYMMETRIC_CRYPTO_KEY).display().to_string()),
+                options: Some(get_container_mount_opts()),
+                _type: Some("bind".to_owned()),
+                uid_mappings: None,
+                gid_mappings: None,
+            },
+            ContainerMount {
+                destination: Some("/run/sdf/jwt_signing_public_key.pem".to_owned()),
+                source: Some(
+                    data_dir
+                        .join("jwt_signing_public_key.pem")
+                        .display()
+                        .to_string(),
+                ),
+                options: Some(get_container_mount_opts()),
+                _type: Some("bind".to_owned()),
+                uid_mappings: None,
+                gid_mappings: None,
+            },
+        ];
+        let previous_symmetric_crypto_key = data_dir.join(PREVIOUS_SYMMETRIC_CRYPTO_KEY);
+        if previous_symmetric_crypto_key.exists() {
+            mounts.push(ContainerMount {
+                destination: Some(format!("/run/sdf/{PREVIOUS_SYMMETRIC_CRYPTO_KEY}")),
+                source: Some(previous_symmetric_crypto_key.display().to_string()),
+                options: Some(get_container_mount_opts()),
+                _type: Some("bind".to_owned()),
+                uid_mappings: None,
+                gid_mappings: None,
+            });
+        }
+
         let create_opts = ContainerCreateOpts::builder()
             .name(name.clone())
             .image(format!("{0}:stable", image.clone()))
@@ -658,42 +710,8 @@ impl ContainerEngine for PodmanEngine {
                 protocol: None,
                 range: None,
             }])
-            .mounts(vec![
-                ContainerMount {
-                    destination: Some("/run/sdf/cyclone_encryption.key".to_owned()),
-                    source: Some(
-                        data_dir
-                            .join("cyclone_encryption.key")
-                            .display()
-                            .to_string(),
-                    ),
-                    options: Some(get_container_mount_opts()),
-                    _type: Some("bind".to_owned()),
-                    uid_mappings: None,
-                    gid_mappings: None,
-                },
-                ContainerMount {
-                    destination: Some("/run/sdf/donkey.key".to_owned()),
-                    source: Some(data_dir.join("donkey.key").display().to_string()),
-                    options: Some(get_container_mount_opts()),
-                    _type: Some("bind".to_owned()),
-                    uid_mappings: None,
-                    gid_mappings: None,
-                },
-                ContainerMount {
-                    destination: Some("/run/sdf/jwt_signing_public_key.pem".to_owned()),
-                    source: Some(
-                        data_dir
-                            .join("jwt_signing_public_key.pem")
-                            .display()
-                            .to_string(),
-                    ),
-                    options: Some(get_container_mount_opts()),
-                    _type: Some("bind".to_owned()),
-                    uid_mappings: None,
-                    gid_mappings: None,
-                },
-            ])
+            .mounts(mounts)
+            .command(symmetric_crypto_key_args(&data_dir, "/run/sdf"))
             .build();
 
         let container = self.podman.containers().create(&create_opts).await?;
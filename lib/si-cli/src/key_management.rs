@@ -6,7 +6,7 @@ use si_crypto::SymmetricCryptoService;
 use sodiumoxide::crypto::box_;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 #[derive(Default, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -19,6 +19,9 @@ pub struct Credentials {
     pub si_email: Option<String>,
 }
 
+pub const SYMMETRIC_CRYPTO_KEY: &str = "donkey.key";
+pub const PREVIOUS_SYMMETRIC_CRYPTO_KEY: &str = "donkey.previous.key";
+
 const JWT_SIGNING_KEY: &str = "-----BEGIN PUBLIC KEY-----
 MIICIjANBgkqhkiG9w0BAQEFAAOCAg8AMIICCgKCAgEAuoogz71y+EO3tmEAiHCD
 90A/AnF6idrD31VY8bkpwYS51UGIlkdUna/yQo6XiXj10rhNkVEAzjwmfoGJbj59
@@ -52,16 +55,50 @@ pub async fn ensure_encryption_keys() -> CliResult<()> {
     Ok(())
 }
 
-pub async fn ensure_symmetric_crypto_key() -> CliResult<()> {
+/// Ensures there is an active symmetric crypto key, generating one if needed.
+///
+/// When `rotate` is set, the current active key is kept as the previous key and a new active key
+/// is generated. The previous key is still loaded (as an extra key) by the services, so that data
+/// encrypted with it can be decrypted until it has been re-encrypted. Returns whether the key was
+/// rotated.
+pub async fn ensure_symmetric_crypto_key(rotate: bool) -> CliResult<bool> {
     let si_data_dir = get_si_data_dir().await?;
-    let active_key_path = si_data_dir.join("donkey.key");
+    let active_key_path = si_data_dir.join(SYMMETRIC_CRYPTO_KEY);
+
+    let mut rotated = false;
+    if rotate && active_key_path.exists() {
+        let previous_key_path = si_data_dir.join(PREVIOUS_SYMMETRIC_CRYPTO_KEY);
+        if previous_key_path.exists() {
+            return Err(SiCliError::PreviousSymmetricCryptoKeyPresent(
+                previous_key_path,
+            ));
+        }
+        fs::rename(&active_key_path, previous_key_path)?;
+        rotated = true;
+    }
+
     if !active_key_path.exists() {
         SymmetricCryptoService::generate_key()
             .save(active_key_path)
             .await?;
     }
 
-    Ok(())
+    Ok(rotated)
+}
+
+/// Returns the arguments a service needs to load the previous symmetric crypto key, if there is
+/// one, where `key_dir` is the directory the keys are found in inside the service's container.
+pub fn symmetric_crypto_key_args(data_dir: &Path, key_dir: &str) -> Vec<String> {
+    if !data_dir.join(PREVIOUS_SYMMETRIC_CRYPTO_KEY).exists() {
+        return vec![];
+    }
+
+    vec![
+        "--symmetric-crypto-active-key".to_owned(),
+        format!("{key_dir}/{SYMMETRIC_CRYPTO_KEY}"),
+        "--symmetric-crypto-extra-key".to_owned(),
+        format!("{key_dir}/{PREVIOUS_SYMMETRIC_CRYPTO_KEY}"),
+    ]
 }
 
 pub async fn ensure_jwt_public_signing_key() -> CliResult<()> {
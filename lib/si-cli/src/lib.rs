@@ -39,6 +39,8 @@ pub enum SiCliError {
     MissingDataDir(),
     #[error("podman api: {0}")]
     Podman(#[from] podman_api::Error),
+    #[error("previous symmetric crypto key still present at {0}; re-encrypt data with `sdf --reencrypt-symmetric-data` and remove it before rotating again")]
+    PreviousSymmetricCryptoKeyPresent(std::path::PathBuf),
     #[error("reqwest: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("symmetric crypto: {0}")]
@@ -17,12 +17,15 @@
 )]
 
 mod cyclone;
+mod signing;
 mod symmetric;
 
 pub use cyclone::decryption_key::{CycloneDecryptionKey, CycloneDecryptionKeyError};
 pub use cyclone::encryption_key::{CycloneEncryptionKey, CycloneEncryptionKeyError};
 pub use cyclone::key_pair::{CycloneKeyPair, CycloneKeyPairError};
 
+pub use signing::{DetachedSignature, SigningError, SigningKey, VerifyingKey};
+
 pub use symmetric::{
     SymmetricCryptoError, SymmetricCryptoResult, SymmetricCryptoService,
     SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile, SymmetricKey, SymmetricNonce,
@@ -0,0 +1,233 @@
+use std::{io, path::Path};
+
+use base64::{engine::general_purpose, Engine};
+use si_hash::Hash;
+use sodiumoxide::crypto::sign::{self, PublicKey, SecretKey, Signature};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncWriteExt},
+};
+
+/// An error that can be returned when signing or verifying messages.
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum SigningError {
+    /// When a key or signature fails to be decoded from Base64
+    #[error("failed to decode base64: {0}")]
+    Decode(#[from] base64::DecodeError),
+    /// When an error is returned while reading from or writing to a key file
+    #[error("key file io error: {0}")]
+    KeyIo(#[from] io::Error),
+    /// When a key fails to be parsed from bytes
+    #[error("failed to load key from bytes")]
+    KeyParse,
+    /// When a signature fails to be parsed from bytes
+    #[error("failed to load signature from bytes")]
+    SignatureParse,
+    /// When a signature was not made over the message by the key's signing key
+    #[error("signature verification failed for key {0}")]
+    VerificationFailed(Hash),
+}
+
+/// A key which makes detached ed25519 signatures, for example over a module's root hash.
+#[derive(Clone, Debug)]
+pub struct SigningKey {
+    secret_key: SecretKey,
+}
+
+impl SigningKey {
+    /// Generates a new signing key.
+    pub fn generate() -> Self {
+        let (_, secret_key) = sign::gen_keypair();
+
+        Self { secret_key }
+    }
+
+    /// Loads a [`SigningKey`] from a file path.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if:
+    ///
+    /// - A key file was not readable (i.e. incorrect permission and/or ownership)
+    /// - A key file could not be successfuly parsed
+    pub async fn load(signing_key_path: impl AsRef<Path>) -> Result<Self, SigningError> {
+        trace!(
+            signing_key_path = %signing_key_path.as_ref().display(),
+            "loading signing key from disk",
+        );
+        let mut file = File::open(signing_key_path).await?;
+        let mut buf: Vec<u8> = Vec::with_capacity(sign::SECRETKEYBYTES);
+        file.read_to_end(&mut buf).await?;
+        let secret_key = SecretKey::from_slice(&buf).ok_or(SigningError::KeyParse)?;
+
+        Ok(Self { secret_key })
+    }
+
+    /// Writes this key to a file path.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the key file path cannot be created or is not writable (i.e. incorrect
+    /// permission and/or ownership)
+    pub async fn write_file(&self, signing_key_path: impl AsRef<Path>) -> Result<(), SigningError> {
+        let mut file = File::create(signing_key_path).await?;
+        file.write_all(&self.secret_key.0).await?;
+
+        Ok(())
+    }
+
+    /// Returns the key which verifies this key's signatures.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.secret_key.public_key().into()
+    }
+
+    /// Signs a message, returning a signature which is kept apart from the message.
+    pub fn sign(&self, message: impl AsRef<[u8]>) -> DetachedSignature {
+        DetachedSignature(sign::sign_detached(message.as_ref(), &self.secret_key))
+    }
+}
+
+/// A key which verifies the detached signatures made by a [`SigningKey`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    public_key: PublicKey,
+    key_hash: Hash,
+}
+
+impl VerifyingKey {
+    /// Decodes a [`VerifyingKey`] from a Base64 string, as made by [`VerifyingKey::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the string is not Base64 or does not hold an ed25519 public key.
+    pub fn decode(encoded: impl AsRef<[u8]>) -> Result<Self, SigningError> {
+        let bytes = general_purpose::STANDARD_NO_PAD.decode(encoded)?;
+        let public_key = PublicKey::from_slice(&bytes).ok_or(SigningError::KeyParse)?;
+
+        Ok(public_key.into())
+    }
+
+    /// Encodes this key as a Base64 string.
+    pub fn encode(&self) -> String {
+        general_purpose::STANDARD_NO_PAD.encode(self.public_key)
+    }
+
+    /// Verifies that `signature` was made over `message` by this key's [`SigningKey`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the signature does not match.
+    pub fn verify(
+        &self,
+        message: impl AsRef<[u8]>,
+        signature: &DetachedSignature,
+    ) -> Result<(), SigningError> {
+        if sign::verify_detached(&signature.0, message.as_ref(), &self.public_key) {
+            Ok(())
+        } else {
+            Err(SigningError::VerificationFailed(self.key_hash))
+        }
+    }
+
+    /// Returns a [`Hash`] of this key, which identifies it.
+    pub fn key_hash(&self) -> &Hash {
+        &self.key_hash
+    }
+}
+
+impl From<PublicKey> for VerifyingKey {
+    fn from(value: PublicKey) -> Self {
+        let key_hash = Hash::new(value.as_ref());
+
+        Self {
+            public_key: value,
+            key_hash,
+        }
+    }
+}
+
+/// An ed25519 signature which is distributed separately from the message it signs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DetachedSignature(Signature);
+
+impl DetachedSignature {
+    /// Decodes a [`DetachedSignature`] from a Base64 string, as made by
+    /// [`DetachedSignature::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if the string is not Base64 or does not hold an ed25519 signature.
+    pub fn decode(encoded: impl AsRef<[u8]>) -> Result<Self, SigningError> {
+        let bytes = general_purpose::STANDARD_NO_PAD.decode(encoded)?;
+        let signature =
+            Signature::try_from(bytes.as_slice()).map_err(|_| SigningError::SignatureParse)?;
+
+        Ok(Self(signature))
+    }
+
+    /// Encodes this signature as a Base64 string.
+    pub fn encode(&self) -> String {
+        general_purpose::STANDARD_NO_PAD.encode(self.0.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_and_verify() {
+        sodiumoxide::init().expect("failed to init sodiumoxide");
+
+        let signing_key = SigningKey::generate();
+        let verifying_key = signing_key.verifying_key();
+
+        let signature = signing_key.sign("sunshine-on-leith");
+        verifying_key
+            .verify("sunshine-on-leith", &signature)
+            .expect("failed to verify signature");
+
+        // Both the key and the signature survive being encoded for transport
+        let verifying_key =
+            VerifyingKey::decode(verifying_key.encode()).expect("failed to decode key");
+        let signature =
+            DetachedSignature::decode(signature.encode()).expect("failed to decode signature");
+        verifying_key
+            .verify("sunshine-on-leith", &signature)
+            .expect("failed to verify decoded signature");
+
+        assert!(matches!(
+            verifying_key.verify("letter-from-america", &signature),
+            Err(SigningError::VerificationFailed(_))
+        ));
+        assert!(matches!(
+            SigningKey::generate()
+                .verifying_key()
+                .verify("sunshine-on-leith", &signature),
+            Err(SigningError::VerificationFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn write_and_load() {
+        sodiumoxide::init().expect("failed to init sodiumoxide");
+
+        let signing_key_path = tempfile::NamedTempFile::new()
+            .expect("failed to create named tempfile")
+            .into_temp_path();
+
+        let signing_key = SigningKey::generate();
+        signing_key
+            .write_file(&signing_key_path)
+            .await
+            .expect("failed to write key");
+        let loaded = SigningKey::load(&signing_key_path)
+            .await
+            .expect("failed to load key");
+
+        assert_eq!(signing_key.verifying_key(), loaded.verifying_key());
+    }
+}
@@ -131,6 +131,11 @@ impl SymmetricCryptoService {
         Ok(Self::new(active_key, extra_keys))
     }
 
+    /// Returns the [`Hash`] of the active [`SymmetricKey`], which is used for all encryption.
+    pub fn active_key_hash(&self) -> &Hash {
+        self.active_key_hash.as_ref()
+    }
+
     /// Generates a new [`SymmetricKey`].
     pub fn generate_key() -> SymmetricKey {
         SymmetricKey(secretbox::gen_key())
@@ -293,6 +298,34 @@ mod tests {
         assert_eq!(message.as_slice(), decrypted);
     }
 
+    #[test]
+    fn reencryption_under_active_key() {
+        let old_key = SymmetricCryptoService::generate_key();
+        let old_service = SymmetricCryptoService::new(old_key.clone(), vec![]);
+
+        let message = b"Keep your friends close, but your enemies closer.";
+
+        let (ciphertext, nonce, old_key_hash) = old_service.encrypt(message);
+        assert_eq!(old_service.active_key_hash(), old_key_hash);
+
+        let new_key = SymmetricCryptoService::generate_key();
+        let new_service = SymmetricCryptoService::new(new_key.clone(), vec![old_key]);
+        assert_ne!(new_service.active_key_hash(), old_key_hash);
+
+        let decrypted = new_service
+            .decrypt(ciphertext.as_ref(), &nonce, old_key_hash)
+            .expect("Should be able to decrypt");
+        let (ciphertext, nonce, new_key_hash) = new_service.encrypt(&decrypted);
+        assert_eq!(new_service.active_key_hash(), new_key_hash);
+
+        let retired_service = SymmetricCryptoService::new(new_key, vec![]);
+        let decrypted = retired_service
+            .decrypt(ciphertext.as_ref(), &nonce, new_key_hash)
+            .expect("Should be able to decrypt");
+
+        assert_eq!(message.as_slice(), decrypted);
+    }
+
     #[test]
     fn missing_key() {
         let old_key = SymmetricCryptoService::generate_key();
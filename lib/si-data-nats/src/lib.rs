@@ -17,9 +17,10 @@ use tokio::sync::Mutex;
 
 mod connect_options;
 mod message;
+pub mod propagation;
 mod subscriber;
 
-pub use async_nats::{header::HeaderMap, rustls};
+pub use async_nats::{header::HeaderMap, jetstream, rustls};
 pub use connect_options::ConnectOptions;
 pub use message::Message;
 pub use subscriber::Subscriber;
@@ -363,10 +364,15 @@ impl Client {
         let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} send", &subject).as_str());
-        self.inner
-            .publish(subject, msg.into())
-            .await
-            .map_err(|err| span.record_err(Error::NatsPublish(err)))?;
+        let headers = propagation::trace_context_headers();
+        if headers.is_empty() {
+            self.inner.publish(subject, msg.into()).await
+        } else {
+            self.inner
+                .publish_with_headers(subject, headers, msg.into())
+                .await
+        }
+        .map_err(|err| span.record_err(Error::NatsPublish(err)))?;
 
         span.record_ok();
         Ok(())
@@ -390,6 +396,24 @@ impl Client {
         self.inner.new_inbox()
     }
 
+    /// Creates a [JetStream](https://docs.nats.io/nats-concepts/jetstream) context which shares
+    /// this client's underlying connection.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), si_data_nats::Error> {
+    /// let client = si_data_nats::Client::connect_with_options("demo.nats.io", None, Default::default()).await?;
+    /// let jetstream = client.jetstream();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn jetstream(&self) -> jetstream::Context {
+        jetstream::new(self.inner.clone())
+    }
+
     /// Sends the request with headers.
     ///
     /// # Examples
@@ -430,11 +454,15 @@ impl Client {
         let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} send", &subject).as_str());
-        let msg = self
-            .inner
-            .request(subject, msg.into())
-            .await
-            .map_err(|err| span.record_err(Error::NatsRequest(err)))?;
+        let headers = propagation::trace_context_headers();
+        let msg = if headers.is_empty() {
+            self.inner.request(subject, msg.into()).await
+        } else {
+            self.inner
+                .request_with_headers(subject, headers, msg.into())
+                .await
+        }
+        .map_err(|err| span.record_err(Error::NatsRequest(err)))?;
 
         span.record_ok();
         Ok(Message::new(msg, self.metadata.clone()))
@@ -528,16 +556,24 @@ impl Client {
         let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} send", &subject).as_str());
-        self.inner
-            .publish_with_reply(subject, reply.into(), msg.into())
-            .await
-            .map_err(|err| span.record_err(Error::NatsPublish(err)))?;
+        let headers = propagation::trace_context_headers();
+        if headers.is_empty() {
+            self.inner
+                .publish_with_reply(subject, reply.into(), msg.into())
+                .await
+        } else {
+            self.inner
+                .publish_with_reply_and_headers(subject, reply.into(), headers, msg.into())
+                .await
+        }
+        .map_err(|err| span.record_err(Error::NatsPublish(err)))?;
 
         span.record_ok();
         Ok(())
     }
 
-    /// Publish a [Message] with headers to a given subject.
+    /// Publish a [Message] with headers to a given subject. The trace context of the current span
+    /// is added to the headers, as it is for every other publish.
     ///
     /// # Examples
     /// ```
@@ -577,7 +613,7 @@ impl Client {
     pub async fn publish_with_headers(
         &self,
         subject: impl Into<String>,
-        headers: HeaderMap,
+        mut headers: HeaderMap,
         msg: impl Into<Vec<u8>>,
     ) -> Result<()> {
         let span = Span::current();
@@ -586,6 +622,7 @@ impl Client {
         let msg = msg.into();
         span.record("messaging.destination", subject.as_str());
         span.record("otel.name", format!("{} send", &subject).as_str());
+        propagation::inject_headers(&mut headers);
         self.inner
             .publish_with_headers(subject, headers, msg.into())
             .await
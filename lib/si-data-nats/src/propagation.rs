@@ -0,0 +1,111 @@
+//! Propagation of trace context in NATS message headers.
+//!
+//! Every publish and request made through a [`Client`](crate::Client) carries the trace context
+//! of the current span, so a consumer can [continue the trace](set_parent_from_headers) when
+//! processing the message.
+
+use telemetry::{
+    prelude::*,
+    propagation::{self, Extractor, Injector},
+};
+
+use crate::HeaderMap;
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.insert(key, value.as_str());
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|value| value.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.iter().map(|(key, _)| key.as_ref()).collect()
+    }
+}
+
+/// Adds the trace context of the current span to `headers`.
+pub fn inject_headers(headers: &mut HeaderMap) {
+    propagation::inject(&Span::current(), &mut HeaderInjector(headers));
+}
+
+/// Returns headers holding only the trace context of the current span, which are empty if there
+/// is no trace to propagate.
+#[must_use]
+pub fn trace_context_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    inject_headers(&mut headers);
+    headers
+}
+
+/// Makes the span which published a message with the given `headers` the parent of `span`. The
+/// span is left as is if the message didn't carry a trace context.
+pub fn set_parent_from_headers(span: &Span, headers: Option<&HeaderMap>) {
+    if let Some(headers) = headers {
+        propagation::set_parent(span, &HeaderExtractor(headers));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use telemetry::opentelemetry::{
+        global,
+        sdk::{propagation::TraceContextPropagator, trace as sdktrace},
+        trace::{TraceContextExt, TracerProvider as _},
+    };
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn with_tracing(f: impl FnOnce()) {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = sdktrace::TracerProvider::builder()
+            .build()
+            .tracer("si-data-nats");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        tracing::subscriber::with_default(subscriber, f);
+    }
+
+    fn trace_id(span: &Span) -> telemetry::opentelemetry::trace::TraceId {
+        span.context().span().span_context().trace_id()
+    }
+
+    #[test]
+    fn trace_context_round_trips_through_headers() {
+        with_tracing(|| {
+            let publisher = info_span!("publisher");
+            let headers = publisher.in_scope(trace_context_headers);
+            assert!(headers.get("traceparent").is_some());
+
+            let consumer = info_span!("consumer");
+            set_parent_from_headers(&consumer, Some(&headers));
+
+            assert_eq!(trace_id(&publisher), trace_id(&consumer));
+        });
+    }
+
+    #[test]
+    fn missing_trace_context_leaves_span_as_is() {
+        with_tracing(|| {
+            let publisher = info_span!("publisher");
+
+            let consumer = info_span!("consumer");
+            set_parent_from_headers(&consumer, None);
+            assert_ne!(trace_id(&publisher), trace_id(&consumer));
+
+            let unrelated = info_span!("unrelated");
+            set_parent_from_headers(&unrelated, Some(&HeaderMap::new()));
+            assert_ne!(trace_id(&publisher), trace_id(&unrelated));
+        });
+    }
+}
@@ -0,0 +1,28 @@
+use std::env::args;
+
+use si_pkg::SiPkg;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = args();
+    let before = args
+        .nth(1)
+        .expect("usage: program <OLD_PKG_FILE> <NEW_PKG_FILE>");
+    let after = args
+        .next()
+        .expect("usage: program <OLD_PKG_FILE> <NEW_PKG_FILE>");
+
+    println!("--- Reading pkgs from: {before} and {after}");
+    let before = SiPkg::load_from_file(before).await?;
+    let after = SiPkg::load_from_file(after).await?;
+
+    let diff = before.diff(&after)?;
+    if diff.is_empty() {
+        println!("--- No differences.");
+    } else {
+        print!("{diff}");
+    }
+
+    println!("--- Done.");
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use std::env::args;
+
+use si_pkg::SiPkg;
+use tokio::fs;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = args();
+    let input = args.nth(1).expect("usage: program <SOURCE_DIR> <PKG_FILE>");
+    let dst = args.next().expect("usage: program <SOURCE_DIR> <PKG_FILE>");
+
+    println!("--- Reading pkg source from: {input}");
+    let pkg = SiPkg::load_from_source_dir(input).await?;
+
+    println!("--- Writing pkg to: {dst}");
+    fs::write(&dst, pkg.write_to_bytes()?).await?;
+
+    println!("--- Done.");
+    Ok(())
+}
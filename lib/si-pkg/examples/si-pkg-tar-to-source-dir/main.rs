@@ -0,0 +1,19 @@
+use std::env::args;
+
+use si_pkg::SiPkg;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = args();
+    let input = args.nth(1).expect("usage: program <PKG_FILE> <DEST_DIR>");
+    let dst = args.next().expect("usage: program <PKG_FILE> <DEST_DIR>");
+
+    println!("--- Reading pkg from file: {input}");
+    let pkg = SiPkg::load_from_file(input).await?;
+
+    println!("--- Writing pkg source to: {dst}");
+    pkg.write_to_source_dir(&dst).await?;
+
+    println!("--- Done.");
+    Ok(())
+}
@@ -1,8 +1,11 @@
 pub(crate) mod node;
 mod pkg;
+mod pkg_diff;
+mod source_dir;
 mod spec;
 
 pub use pkg::*;
+pub use pkg_diff::*;
 pub use spec::*;
 
 #[cfg(test)]
@@ -154,4 +157,368 @@ mod tests {
 
         let _ = dbg!(props.lock().await);
     }
+
+    #[tokio::test]
+    async fn pkg_dependencies_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let hash_without_dependencies = SiPkg::load_from_spec(spec.clone())
+            .expect("failed to load spec")
+            .hash()
+            .expect("failed to hash pkg");
+
+        let mut spec_with_dependencies = spec.clone();
+        spec_with_dependencies.dependencies.push(
+            DependencySpec::builder()
+                .name("kubernetes-basics")
+                .version_req("^1.2")
+                .build()
+                .expect("failed to build dependency"),
+        );
+        let pkg = SiPkg::load_from_spec(spec_with_dependencies).expect("failed to load spec");
+
+        // Modules without dependencies keep the hash they had before dependencies existed
+        assert_eq!(
+            hash_without_dependencies,
+            SiPkg::load_from_spec(spec)
+                .expect("failed to load spec")
+                .hash()
+                .expect("failed to hash pkg")
+        );
+        assert_ne!(
+            hash_without_dependencies,
+            pkg.hash().expect("failed to hash pkg")
+        );
+
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(pkg_data).expect("failed to load pkg from bytes");
+
+        let dependencies = read_pkg.dependencies().expect("failed to get dependencies");
+        assert_eq!(1, dependencies.len());
+        let dependency = dependencies.get(0).expect("has a dependency");
+        assert_eq!("kubernetes-basics", dependency.name());
+        assert!(dependency
+            .version_req()
+            .matches(&semver::Version::new(1, 4, 0)));
+        assert!(!dependency
+            .version_req()
+            .matches(&semver::Version::new(2, 0, 0)));
+
+        let read_spec = read_pkg.to_spec().await.expect("failed to convert to spec");
+        assert_eq!(1, read_spec.dependencies.len());
+    }
+
+    #[tokio::test]
+    async fn pkg_dependencies_keep_version_req_as_written() {
+        let mut spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        spec.dependencies.push(
+            DependencySpec::builder()
+                .name("kubernetes-basics")
+                .version_req(">= 1.2, < 2")
+                .build()
+                .expect("failed to build dependency"),
+        );
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+
+        let read_spec = pkg.to_spec().await.expect("failed to convert to spec");
+        assert_eq!(
+            vec![">= 1.2, < 2"],
+            read_spec
+                .dependencies
+                .iter()
+                .map(|dependency| dependency.version_req.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            pkg.hash().expect("failed to hash pkg"),
+            SiPkg::load_from_spec(read_spec)
+                .expect("failed to load spec")
+                .hash()
+                .expect("failed to hash read pkg")
+        );
+
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        pkg.write_to_source_dir(dir.path())
+            .await
+            .expect("failed to write pkg to source dir");
+        assert_eq!(
+            pkg.hash().expect("failed to hash pkg"),
+            SiPkg::load_from_source_dir(dir.path())
+                .await
+                .expect("failed to load pkg from source dir")
+                .hash()
+                .expect("failed to hash read pkg")
+        );
+    }
+
+    #[tokio::test]
+    async fn pkg_source_dir_round_trip() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+
+        pkg.write_to_source_dir(dir.path())
+            .await
+            .expect("failed to write pkg to source dir");
+
+        // Func code is kept as plain text next to the func
+        let code = std::fs::read_to_string(dir.path().join("funcs").join("si_truthy.ts"))
+            .expect("failed to read func code");
+        assert!(!code.is_empty());
+        assert!(dir.path().join("funcs").join("si_truthy.yaml").is_file());
+
+        let read_pkg = SiPkg::load_from_source_dir(dir.path())
+            .await
+            .expect("failed to load pkg from source dir");
+        assert_eq!(
+            pkg.hash().expect("failed to hash pkg"),
+            read_pkg.hash().expect("failed to hash read pkg")
+        );
+        assert_eq!(
+            vec!["si:truthy", "si:falsey"],
+            read_pkg
+                .funcs()
+                .expect("failed to get funcs")
+                .iter()
+                .map(|func| func.name().to_owned())
+                .collect::<Vec<_>>()
+        );
+
+        // Writing again replaces what was there, and gives the same package
+        pkg.write_to_source_dir(dir.path())
+            .await
+            .expect("failed to write pkg to source dir again");
+        assert_eq!(
+            pkg.hash().expect("failed to hash pkg"),
+            SiPkg::load_from_source_dir(dir.path())
+                .await
+                .expect("failed to load pkg from source dir")
+                .hash()
+                .expect("failed to hash read pkg")
+        );
+    }
+
+    #[tokio::test]
+    async fn pkg_source_dir_rejects_paths_outside_of_it() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        pkg.write_to_source_dir(dir.path())
+            .await
+            .expect("failed to write pkg to source dir");
+
+        let pkg_file = dir.path().join("pkg.yaml");
+        let mut pkg_yaml: serde_yaml::Mapping = serde_yaml::from_str(
+            &std::fs::read_to_string(&pkg_file).expect("failed to read pkg.yaml"),
+        )
+        .expect("failed to parse pkg.yaml");
+        for schema_file_name in ["", ".", "..", "k8sDeployment/..", "..\\k8sDeployment"] {
+            pkg_yaml.insert("schemas".into(), vec![schema_file_name].into());
+            std::fs::write(
+                &pkg_file,
+                serde_yaml::to_string(&pkg_yaml).expect("failed to serialize pkg.yaml"),
+            )
+            .expect("failed to write pkg.yaml");
+
+            let err = SiPkg::load_from_source_dir(dir.path())
+                .await
+                .expect_err("schema file name outside of the schemas dir was accepted");
+            assert!(
+                matches!(err, SiPkgError::SourceDirInvalid(_)),
+                "unexpected error for {schema_file_name:?}: {err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn pkg_source_dir_rejects_code_files_outside_of_funcs() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let dir = tempfile::tempdir().expect("failed to create temp dir");
+        pkg.write_to_source_dir(dir.path())
+            .await
+            .expect("failed to write pkg to source dir");
+        std::fs::write(dir.path().join("secret.ts"), "outside of funcs")
+            .expect("failed to write file outside of funcs");
+
+        let func_file = std::fs::read_dir(dir.path().join("funcs"))
+            .expect("failed to read funcs dir")
+            .map(|entry| entry.expect("failed to read funcs dir entry").path())
+            .find(|path| {
+                path.extension().map_or(false, |ext| ext == "yaml")
+                    && std::fs::read_to_string(path)
+                        .expect("failed to read func file")
+                        .contains("codeFile")
+            })
+            .expect("no func with a code file was written");
+        let func_yaml = std::fs::read_to_string(&func_file).expect("failed to read func file");
+        let mut func_yaml: serde_yaml::Mapping =
+            serde_yaml::from_str(&func_yaml).expect("failed to parse func file");
+
+        let data = func_yaml
+            .get_mut("data")
+            .and_then(|data| data.as_mapping_mut())
+            .expect("func has no data")
+            .clone();
+        for code_file in [
+            "../secret.ts",
+            "../../../../../../../etc/passwd",
+            "/etc/passwd",
+            "..",
+            "",
+            "README.md",
+        ] {
+            let mut data = data.clone();
+            data.insert("codeFile".into(), code_file.into());
+            func_yaml.insert("data".into(), data.into());
+            std::fs::write(
+                &func_file,
+                serde_yaml::to_string(&func_yaml).expect("failed to serialize func file"),
+            )
+            .expect("failed to write func file");
+
+            let err = SiPkg::load_from_source_dir(dir.path())
+                .await
+                .expect_err("code file outside of the funcs dir was accepted");
+            assert!(
+                matches!(err, SiPkgError::SourceDirInvalid(_)),
+                "unexpected error for {code_file:?}: {err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn pkg_diff() {
+        use base64::{engine::general_purpose, Engine};
+
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+
+        assert!(pkg.diff(&pkg).expect("failed to diff pkg").is_empty());
+
+        let mut changed: serde_json::Value = serde_json::from_str(PACKAGE_JSON).unwrap();
+        changed["funcs"][0]["codeBase64"] = general_purpose::STANDARD_NO_PAD
+            .encode("function truth() {\n  return false;\n}")
+            .into();
+        changed["funcs"]
+            .as_array_mut()
+            .expect("funcs is an array")
+            .remove(1);
+        let variant = &mut changed["schemas"][0]["variants"][0];
+        variant["domain"]["entries"][0]["validations"][0]["upper_bound"] = 8080.into();
+        variant["domain"]["entries"]
+            .as_array_mut()
+            .expect("entries is an array")
+            .remove(1);
+        variant["sockets"] = serde_json::json!([{
+            "name": "image",
+            "data": { "kind": "input", "name": "image" },
+        }]);
+        let changed_pkg =
+            SiPkg::load_from_spec(serde_json::from_value::<PkgSpec>(changed).unwrap())
+                .expect("failed to load changed spec");
+
+        let diff = pkg.diff(&changed_pkg).expect("failed to diff pkgs");
+
+        let find = |node_kind: PkgDiffNodeKind, path: &str| {
+            diff.entries()
+                .iter()
+                .find(|entry| entry.node_kind() == node_kind && entry.path() == path)
+                .unwrap_or_else(|| panic!("no diff entry for {node_kind} {path}"))
+        };
+
+        let truthy = find(PkgDiffNodeKind::Func, "funcs/si:truthy");
+        assert_eq!(PkgDiffKind::Changed, truthy.kind());
+        assert!(truthy.fields().is_empty());
+        assert_eq!(
+            Some(
+                &[
+                    CodeDiffLine::Removed("function truth() { return true; }".to_owned()),
+                    CodeDiffLine::Added("function truth() {".to_owned()),
+                    CodeDiffLine::Added("  return false;".to_owned()),
+                    CodeDiffLine::Added("}".to_owned()),
+                ][..]
+            ),
+            truthy.code_diff()
+        );
+
+        assert_eq!(
+            PkgDiffKind::Removed,
+            find(PkgDiffNodeKind::Func, "funcs/si:falsey").kind()
+        );
+        assert_eq!(
+            PkgDiffKind::Removed,
+            find(
+                PkgDiffNodeKind::Prop,
+                "schemas/k8sDeployment/v0/domain/kind"
+            )
+            .kind()
+        );
+        assert_eq!(
+            PkgDiffKind::Added,
+            find(PkgDiffNodeKind::Socket, "schemas/k8sDeployment/v0/image").kind()
+        );
+
+        let validation = find(
+            PkgDiffNodeKind::Validation,
+            "schemas/k8sDeployment/v0/domain/apiVersion/validation",
+        );
+        assert_eq!(
+            vec![PkgDiffField {
+                name: "upper_bound".to_owned(),
+                before: Some("31337".to_owned()),
+                after: Some("8080".to_owned()),
+            }],
+            validation.fields()
+        );
+        assert_eq!(None, validation.code_diff());
+
+        // The diff renders each entry with its changed fields
+        let rendered_validation = concat!(
+            "~ validation schemas/k8sDeployment/v0/domain/apiVersion/validation\n",
+            "    upper_bound: 31337 -> 8080",
+        );
+        assert_eq!(rendered_validation, validation.to_string());
+        assert!(diff.to_string().contains(rendered_validation));
+
+        // Unchanged subtrees are skipped entirely
+        assert!(!diff.entries().iter().any(|entry| entry
+            .path()
+            .starts_with("schemas/k8sDeployment/v0/domain/metadata")));
+    }
+
+    #[test]
+    fn dependency_spec_rejects_invalid_version_req() {
+        DependencySpec::builder()
+            .name("kubernetes-basics")
+            .version_req("not a range")
+            .build()
+            .expect_err("invalid version requirement was accepted");
+    }
+
+    #[tokio::test]
+    async fn pkg_signature_verification() {
+        let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+
+        let signing_key = si_crypto::SigningKey::generate();
+        let signature = pkg.sign(&signing_key).expect("failed to sign pkg");
+
+        pkg.verify_signature(&signature, &signing_key.verifying_key())
+            .expect("failed to verify signature");
+
+        // The signature is over the root hash, so it holds for the pkg once written and read back
+        let read_pkg = SiPkg::load_from_bytes(pkg.write_to_bytes().expect("failed to write pkg"))
+            .expect("failed to load pkg from bytes");
+        read_pkg
+            .verify_signature(&signature, &signing_key.verifying_key())
+            .expect("failed to verify signature of read pkg");
+
+        assert!(matches!(
+            pkg.verify_signature(
+                &signature,
+                &si_crypto::SigningKey::generate().verifying_key()
+            ),
+            Err(SiPkgError::Signing(_))
+        ));
+    }
 }
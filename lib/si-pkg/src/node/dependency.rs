@@ -0,0 +1,61 @@
+use std::io::{BufRead, Write};
+
+use object_tree::{
+    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
+    NodeWithChildren, ReadBytes, WriteBytes,
+};
+
+use crate::DependencySpec;
+
+use super::PkgNode;
+
+const KEY_NAME_STR: &str = "name";
+const KEY_VERSION_REQ_STR: &str = "version_req";
+
+#[derive(Clone, Debug)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version_req: String,
+}
+
+impl NameStr for DependencyNode {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl WriteBytes for DependencyNode {
+    fn write_bytes<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        write_key_value_line(writer, KEY_NAME_STR, self.name())?;
+        write_key_value_line(writer, KEY_VERSION_REQ_STR, &self.version_req)?;
+
+        Ok(())
+    }
+}
+
+impl ReadBytes for DependencyNode {
+    fn read_bytes<R: BufRead>(reader: &mut R) -> Result<Option<Self>, GraphError>
+    where
+        Self: std::marker::Sized,
+    {
+        let name = read_key_value_line(reader, KEY_NAME_STR)?;
+        let version_req = read_key_value_line(reader, KEY_VERSION_REQ_STR)?;
+
+        Ok(Some(Self { name, version_req }))
+    }
+}
+
+impl NodeChild for DependencySpec {
+    type NodeType = PkgNode;
+
+    fn as_node_with_children(&self) -> NodeWithChildren<Self::NodeType> {
+        NodeWithChildren::new(
+            NodeKind::Leaf,
+            Self::NodeType::Dependency(DependencyNode {
+                name: self.name.to_owned(),
+                version_req: self.version_req.to_owned(),
+            }),
+            vec![],
+        )
+    }
+}
@@ -117,13 +117,24 @@ impl NodeChild for PkgSpec {
                 workspace_name: self.workspace_name.to_owned(),
             }),
             match self.kind {
-                SiPkgKind::Module => vec![
-                    Box::new(PackageCategory::Schemas(self.schemas.clone()))
-                        as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                    Box::new(PackageCategory::Funcs(self.funcs.clone()))
-                        as Box<dyn NodeChild<NodeType = Self::NodeType>>,
-                ],
-                SiPkgKind::WorkspaceBackup => {
+                SiPkgKind::Module => {
+                    let mut children = vec![
+                        Box::new(PackageCategory::Schemas(self.schemas.clone()))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+                        Box::new(PackageCategory::Funcs(self.funcs.clone()))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>,
+                    ];
+                    // Only written when present so that modules without dependencies keep the
+                    // same root hash they had before dependencies existed
+                    if !self.dependencies.is_empty() {
+                        children.push(Box::new(PackageCategory::Dependencies(
+                            self.dependencies.clone(),
+                        ))
+                            as Box<dyn NodeChild<NodeType = Self::NodeType>>);
+                    }
+                    children
+                }
+                SiPkgKind::Template | SiPkgKind::WorkspaceBackup => {
                     vec![
                         Box::new(PackageCategory::ChangeSets(self.change_sets.clone()))
                             as Box<dyn NodeChild<NodeType = Self::NodeType>>,
@@ -8,6 +8,7 @@ use object_tree::{
 };
 use petgraph::prelude::*;
 use serde::{Deserialize, Serialize};
+use si_crypto::{DetachedSignature, SigningError, SigningKey, VerifyingKey};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 use thiserror::Error;
 
@@ -17,6 +18,7 @@ mod attribute_value;
 mod auth_func;
 mod change_set;
 mod component;
+mod dependency;
 mod edge;
 mod func;
 mod leaf_function;
@@ -32,13 +34,15 @@ mod variant;
 
 pub use {
     action_func::*, attr_func_input::*, attribute_value::*, auth_func::*, change_set::*,
-    component::*, edge::*, func::*, leaf_function::*, map_key_func::*, position::*, prop::*,
-    root_prop_func::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
+    component::*, dependency::*, edge::*, func::*, leaf_function::*, map_key_func::*, position::*,
+    prop::*, root_prop_func::*, schema::*, si_prop_func::*, socket::*, validation::*, variant::*,
 };
 
 use crate::{
     node::{CategoryNode, PkgNode},
-    spec::{FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
+    pkg_diff::{self, PkgDiff},
+    source_dir,
+    spec::{DependencySpec, FuncSpec, PkgSpec, SchemaVariantSpecPropRoot, SpecError},
 };
 
 #[remain::sorted]
@@ -48,10 +52,14 @@ pub enum SiPkgError {
     ComponentMissingPosition(String),
     #[error(transparent)]
     Graph(#[from] GraphError),
+    #[error("dependency {0} has an invalid version requirement: {1}")]
+    InvalidVersionReq(String, #[source] semver::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Memory(#[from] TarWriterError),
+    #[error("could not read the fields of a {0} pkg node")]
+    NodeBytesInvalid(&'static str),
     #[error("node not found with hash={0}")]
     NodeWithHashNotFound(Hash),
     #[error("node not found with name={0}")]
@@ -67,6 +75,12 @@ pub enum SiPkgError {
     #[error(transparent)]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
+    SerdeYaml(#[from] serde_yaml::Error),
+    #[error(transparent)]
+    Signing(#[from] SigningError),
+    #[error("package source directory is invalid: {0}")]
+    SourceDirInvalid(String),
+    #[error(transparent)]
     Spec(#[from] SpecError),
     #[error(transparent)]
     TarRead(#[from] TarReadError),
@@ -82,6 +96,10 @@ impl SiPkgError {
     fn prop_tree_invalid(message: impl Into<String>) -> Self {
         Self::PropTreeInvalid(message.into())
     }
+
+    pub(crate) fn source_dir_invalid(message: impl Into<String>) -> Self {
+        Self::SourceDirInvalid(message.into())
+    }
 }
 
 pub type PkgResult<T> = Result<T, SiPkgError>;
@@ -130,10 +148,30 @@ impl SiPkg {
         })
     }
 
+    /// Loads a package from a source directory written by [`SiPkg::write_to_source_dir`].
+    pub async fn load_from_source_dir(path: impl AsRef<Path>) -> PkgResult<Self> {
+        Self::load_from_spec(source_dir::read_spec(path.as_ref()).await?)
+    }
+
     pub fn write_to_bytes(&self) -> PkgResult<Vec<u8>> {
         Ok(TarWriter::new(&self.tree)?.bytes())
     }
 
+    /// Writes the package as a tree of YAML files with each func's code in its own `.ts` file,
+    /// which can be reviewed and diffed in version control. Loading it back with
+    /// [`SiPkg::load_from_source_dir`] gives a package with the same hash.
+    pub async fn write_to_source_dir(&self, path: impl AsRef<Path>) -> PkgResult<()> {
+        let path = path.as_ref();
+        tokio::fs::create_dir_all(path).await?;
+        source_dir::write_spec(&self.to_spec().await?, path).await
+    }
+
+    /// Compares this package with `other`, which is treated as the newer of the two. Subtrees with
+    /// the same hash on both sides are skipped without being walked.
+    pub fn diff(&self, other: &SiPkg) -> PkgResult<PkgDiff> {
+        pkg_diff::diff_pkgs(self, other)
+    }
+
     pub fn metadata(&self) -> PkgResult<SiPkgMetadata> {
         let (graph, root_idx) = self.as_petgraph();
 
@@ -144,6 +182,22 @@ impl SiPkg {
         Ok(self.metadata()?.hash())
     }
 
+    /// Signs the package's root hash. The signature is kept apart from the package, so signing
+    /// doesn't change the package or its hash.
+    pub fn sign(&self, signing_key: &SigningKey) -> PkgResult<DetachedSignature> {
+        Ok(signing_key.sign(self.hash()?.to_string()))
+    }
+
+    /// Verifies that `signature` was made over the package's root hash by the signing key of
+    /// `verifying_key`.
+    pub fn verify_signature(
+        &self,
+        signature: &DetachedSignature,
+        verifying_key: &VerifyingKey,
+    ) -> PkgResult<()> {
+        Ok(verifying_key.verify(self.hash()?.to_string(), signature)?)
+    }
+
     pub fn funcs_by_unique_id(&self) -> PkgResult<HashMap<String, SiPkgFunc>> {
         let func_map: HashMap<String, SiPkgFunc> = self
             .funcs()?
@@ -193,6 +247,20 @@ impl SiPkg {
         Ok(change_sets)
     }
 
+    pub fn dependencies(&self) -> PkgResult<Vec<SiPkgDependency>> {
+        let (graph, root_idx) = self.as_petgraph();
+
+        let node_idxs = category_node_idxs(CategoryNode::Dependencies, graph, root_idx)?;
+
+        let mut dependencies = Vec::with_capacity(node_idxs.len());
+
+        for node_idx in node_idxs {
+            dependencies.push(SiPkgDependency::from_graph(graph, node_idx)?);
+        }
+
+        Ok(dependencies)
+    }
+
     pub fn schema_by_name(&self, name: impl AsRef<str>) -> PkgResult<SiPkgSchema> {
         let (graph, root_idx) = self.as_petgraph();
 
@@ -234,6 +302,10 @@ impl SiPkg {
             builder.workspace_name(workspace_name);
         }
 
+        for dependency in self.dependencies()? {
+            builder.dependency(DependencySpec::try_from(dependency)?);
+        }
+
         for func in self.funcs()? {
             builder.func(FuncSpec::try_from(func)?);
         }
@@ -242,7 +314,7 @@ impl SiPkg {
             builder.schema(schema.to_spec().await?);
         }
 
-        if let SiPkgKind::WorkspaceBackup = metadata.kind() {
+        if let SiPkgKind::Template | SiPkgKind::WorkspaceBackup = metadata.kind() {
             if let Some(default_change_set) = metadata.default_change_set() {
                 builder.default_change_set(default_change_set);
             }
@@ -351,6 +423,9 @@ impl<'a> fmt::Debug for Source<'a> {
 #[strum(serialize_all = "camelCase")]
 pub enum SiPkgKind {
     Module,
+    /// Components and the edges between them, to be instantiated in any workspace. Laid out
+    /// like a workspace backup with a single change set.
+    Template,
     WorkspaceBackup,
 }
 
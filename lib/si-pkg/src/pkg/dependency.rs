@@ -0,0 +1,80 @@
+use object_tree::{Hash, HashedNode};
+use petgraph::prelude::*;
+use semver::VersionReq;
+
+use super::{PkgResult, SiPkgError, Source};
+
+use crate::{node::PkgNode, DependencySpec};
+
+#[derive(Clone, Debug)]
+pub struct SiPkgDependency<'a> {
+    name: String,
+    version_req: VersionReq,
+    /// The version requirement as written in the package. Formatting `version_req` normalizes
+    /// it, which would change the package's hash.
+    version_req_str: String,
+
+    hash: Hash,
+    source: Source<'a>,
+}
+
+impl<'a> SiPkgDependency<'a> {
+    pub fn from_graph(
+        graph: &'a Graph<HashedNode<PkgNode>, ()>,
+        node_idx: NodeIndex,
+    ) -> PkgResult<Self> {
+        let hashed_node = &graph[node_idx];
+        let node = match hashed_node.inner() {
+            PkgNode::Dependency(node) => node.clone(),
+            unexpected => {
+                return Err(SiPkgError::UnexpectedPkgNodeType(
+                    PkgNode::DEPENDENCY_KIND_STR,
+                    unexpected.node_kind_str(),
+                ))
+            }
+        };
+
+        let version_req = VersionReq::parse(&node.version_req)
+            .map_err(|err| SiPkgError::InvalidVersionReq(node.name.clone(), err))?;
+
+        Ok(Self {
+            name: node.name,
+            version_req,
+            version_req_str: node.version_req,
+
+            hash: hashed_node.hash(),
+            source: Source::new(graph, node_idx),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn version_req(&self) -> &VersionReq {
+        &self.version_req
+    }
+
+    pub fn version_req_str(&self) -> &str {
+        self.version_req_str.as_str()
+    }
+
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn source(&self) -> &Source<'a> {
+        &self.source
+    }
+}
+
+impl<'a> TryFrom<SiPkgDependency<'a>> for DependencySpec {
+    type Error = SiPkgError;
+
+    fn try_from(value: SiPkgDependency<'a>) -> Result<Self, Self::Error> {
+        Ok(DependencySpec::builder()
+            .name(value.name())
+            .version_req(value.version_req_str())
+            .build()?)
+    }
+}
@@ -0,0 +1,488 @@
+//! Structural diffs between two packages.
+//!
+//! Every node in a package carries a hash of its own contents and of all of its children, so two
+//! packages are compared by walking both trees from the root and only descending into nodes whose
+//! hashes differ. Children are matched up by node kind and name.
+
+use core::fmt;
+
+use base64::Engine;
+use object_tree::{HashedNode, NameStr, WriteBytes};
+use petgraph::prelude::*;
+
+use crate::{node::PkgNode, source_dir::LENIENT_BASE64, PkgResult, SiPkg, SiPkgError};
+
+const FIELD_CODE_BASE64: &str = "code_base64";
+const FIELD_NODE_KIND: &str = "node_kind";
+
+type PkgGraph = Graph<HashedNode<PkgNode>, ()>;
+
+/// The differences between two packages, in tree order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PkgDiff {
+    entries: Vec<PkgDiffEntry>,
+}
+
+impl PkgDiff {
+    pub fn entries(&self) -> &[PkgDiffEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for PkgDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PkgDiffKind {
+    Added,
+    Changed,
+    Removed,
+}
+
+impl PkgDiffKind {
+    fn marker(&self) -> char {
+        match self {
+            Self::Added => '+',
+            Self::Changed => '~',
+            Self::Removed => '-',
+        }
+    }
+}
+
+/// The kind of package node a [`PkgDiffEntry`] is about. Nodes without a variant of their own are
+/// reported as [`PkgDiffNodeKind::Other`] with their node kind.
+#[remain::sorted]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PkgDiffNodeKind {
+    Func,
+    FuncArgument,
+    Other(&'static str),
+    Package,
+    Prop,
+    Schema,
+    SchemaVariant,
+    Socket,
+    Validation,
+}
+
+impl fmt::Display for PkgDiffNodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Func => "func",
+            Self::FuncArgument => "func argument",
+            Self::Other(node_kind) => node_kind,
+            Self::Package => "package",
+            Self::Prop => "prop",
+            Self::Schema => "schema",
+            Self::SchemaVariant => "schema variant",
+            Self::Socket => "socket",
+            Self::Validation => "validation",
+        })
+    }
+}
+
+/// A node which was added, removed or changed. Added and removed nodes are reported once, for the
+/// top of the subtree. A changed node is only reported when its own fields differ; nodes whose
+/// changes are all further down the tree are not.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PkgDiffEntry {
+    kind: PkgDiffKind,
+    node_kind: PkgDiffNodeKind,
+    path: String,
+    fields: Vec<PkgDiffField>,
+    code_diff: Option<Vec<CodeDiffLine>>,
+}
+
+impl PkgDiffEntry {
+    pub fn kind(&self) -> PkgDiffKind {
+        self.kind
+    }
+
+    pub fn node_kind(&self) -> PkgDiffNodeKind {
+        self.node_kind
+    }
+
+    /// The names of the node and its ancestors, joined by `/`, such as
+    /// `schemas/<schema>/<variant>/domain/<prop>`.
+    pub fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    /// The fields which differ, for a changed node.
+    pub fn fields(&self) -> &[PkgDiffField] {
+        &self.fields
+    }
+
+    /// A line diff of the decoded code, for a func whose code changed.
+    pub fn code_diff(&self) -> Option<&[CodeDiffLine]> {
+        self.code_diff.as_deref()
+    }
+}
+
+impl fmt::Display for PkgDiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.kind.marker(), self.node_kind, self.path)?;
+        for field in &self.fields {
+            write!(
+                f,
+                "\n    {}: {} -> {}",
+                field.name,
+                field.before.as_deref().unwrap_or("(none)"),
+                field.after.as_deref().unwrap_or("(none)"),
+            )?;
+        }
+        if let Some(code_diff) = &self.code_diff {
+            write!(f, "\n    code:")?;
+            for line in code_diff {
+                write!(f, "\n      {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PkgDiffField {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[remain::sorted]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CodeDiffLine {
+    Added(String),
+    Removed(String),
+    Unchanged(String),
+}
+
+impl fmt::Display for CodeDiffLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(line) => write!(f, "+{line}"),
+            Self::Removed(line) => write!(f, "-{line}"),
+            Self::Unchanged(line) => write!(f, " {line}"),
+        }
+    }
+}
+
+pub(crate) fn diff_pkgs(before: &SiPkg, after: &SiPkg) -> PkgResult<PkgDiff> {
+    let (before_graph, before_root) = before.as_petgraph();
+    let (after_graph, after_root) = after.as_petgraph();
+
+    let mut differ = Differ {
+        before: before_graph,
+        after: after_graph,
+        entries: vec![],
+    };
+    differ.diff_nodes(before_root, after_root, "")?;
+
+    Ok(PkgDiff {
+        entries: differ.entries,
+    })
+}
+
+struct Differ<'a> {
+    before: &'a PkgGraph,
+    after: &'a PkgGraph,
+    entries: Vec<PkgDiffEntry>,
+}
+
+impl Differ<'_> {
+    fn diff_nodes(
+        &mut self,
+        before_idx: NodeIndex,
+        after_idx: NodeIndex,
+        parent_path: &str,
+    ) -> PkgResult<()> {
+        let before = &self.before[before_idx];
+        let after = &self.after[after_idx];
+        if before.hash() == after.hash() {
+            return Ok(());
+        }
+
+        let path = child_path(parent_path, before.inner());
+        if let Some(node_kind) = diff_node_kind(before.inner()) {
+            let (fields, code_diff) = diff_fields(before.inner(), after.inner())?;
+            if !fields.is_empty() || code_diff.is_some() {
+                self.entries.push(PkgDiffEntry {
+                    kind: PkgDiffKind::Changed,
+                    node_kind,
+                    path: entry_path(&path, before.inner()),
+                    fields,
+                    code_diff,
+                });
+            }
+        }
+
+        let before_children: Vec<NodeIndex> = children(self.before, before_idx);
+        let mut after_children: Vec<Option<NodeIndex>> = children(self.after, after_idx)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        // Identical children first, so that a changed child is never paired with the copy of a
+        // same-named sibling which didn't change
+        let mut unmatched = vec![];
+        for before_child in before_children {
+            match self.take_matching(&mut after_children, before_child, true) {
+                Some(_) => {}
+                None => unmatched.push(before_child),
+            }
+        }
+
+        for before_child in unmatched {
+            match self.take_matching(&mut after_children, before_child, false) {
+                Some(after_child) => self.diff_nodes(before_child, after_child, &path)?,
+                None => report_subtree(
+                    &mut self.entries,
+                    self.before,
+                    before_child,
+                    &path,
+                    PkgDiffKind::Removed,
+                ),
+            }
+        }
+
+        for after_child in after_children.into_iter().flatten() {
+            report_subtree(
+                &mut self.entries,
+                self.after,
+                after_child,
+                &path,
+                PkgDiffKind::Added,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn take_matching(
+        &self,
+        after_children: &mut [Option<NodeIndex>],
+        before_child: NodeIndex,
+        same_hash: bool,
+    ) -> Option<NodeIndex> {
+        let before = &self.before[before_child];
+        after_children
+            .iter_mut()
+            .find(|slot| match slot {
+                Some(after_child) => {
+                    let after = &self.after[*after_child];
+                    same_key(before.inner(), after.inner())
+                        && (!same_hash || before.hash() == after.hash())
+                }
+                None => false,
+            })
+            .and_then(Option::take)
+    }
+}
+
+/// Reports a node which only exists on one side. Container nodes are looked through, so that
+/// their children are reported instead.
+fn report_subtree(
+    entries: &mut Vec<PkgDiffEntry>,
+    graph: &PkgGraph,
+    idx: NodeIndex,
+    parent_path: &str,
+    kind: PkgDiffKind,
+) {
+    let node = graph[idx].inner();
+    let path = child_path(parent_path, node);
+
+    match diff_node_kind(node) {
+        Some(node_kind) => entries.push(PkgDiffEntry {
+            kind,
+            node_kind,
+            path: entry_path(&path, node),
+            fields: vec![],
+            code_diff: None,
+        }),
+        None => {
+            for child in children(graph, idx) {
+                report_subtree(entries, graph, child, &path, kind);
+            }
+        }
+    }
+}
+
+fn children(graph: &PkgGraph, idx: NodeIndex) -> Vec<NodeIndex> {
+    let mut children: Vec<NodeIndex> = graph.neighbors_directed(idx, Outgoing).collect();
+    // Neighbors are returned most recently added first
+    children.reverse();
+    children
+}
+
+fn same_key(before: &PkgNode, after: &PkgNode) -> bool {
+    before.node_kind_str() == after.node_kind_str() && before.name() == after.name()
+}
+
+/// Returns `None` for the nodes which only group their children and aren't worth reporting.
+fn diff_node_kind(node: &PkgNode) -> Option<PkgDiffNodeKind> {
+    Some(match node {
+        PkgNode::AttributeValueChild(_)
+        | PkgNode::Category(_)
+        | PkgNode::ChangeSetChild(_)
+        | PkgNode::ComponentChild(_)
+        | PkgNode::PropChild(_)
+        | PkgNode::SchemaVariantChild(_) => return None,
+        PkgNode::Func(_) => PkgDiffNodeKind::Func,
+        PkgNode::FuncArgument(_) => PkgDiffNodeKind::FuncArgument,
+        PkgNode::Package(_) => PkgDiffNodeKind::Package,
+        PkgNode::Prop(_) => PkgDiffNodeKind::Prop,
+        PkgNode::Schema(_) => PkgDiffNodeKind::Schema,
+        PkgNode::SchemaVariant(_) => PkgDiffNodeKind::SchemaVariant,
+        PkgNode::Socket(_) => PkgDiffNodeKind::Socket,
+        PkgNode::Validation(_) => PkgDiffNodeKind::Validation,
+        other => PkgDiffNodeKind::Other(other.node_kind_str()),
+    })
+}
+
+/// The package and the containers below categories don't add a path segment; everything else
+/// adds its name.
+fn child_path(parent_path: &str, node: &PkgNode) -> String {
+    match node {
+        PkgNode::Package(_)
+        | PkgNode::AttributeValueChild(_)
+        | PkgNode::ChangeSetChild(_)
+        | PkgNode::ComponentChild(_)
+        | PkgNode::PropChild(_)
+        | PkgNode::SchemaVariantChild(_) => parent_path.to_owned(),
+        _ if parent_path.is_empty() => node.name().to_owned(),
+        _ => format!("{parent_path}/{}", node.name()),
+    }
+}
+
+fn entry_path(path: &str, node: &PkgNode) -> String {
+    if path.is_empty() {
+        node.name().to_owned()
+    } else {
+        path.to_owned()
+    }
+}
+
+fn diff_fields(
+    before: &PkgNode,
+    after: &PkgNode,
+) -> PkgResult<(Vec<PkgDiffField>, Option<Vec<CodeDiffLine>>)> {
+    let mut before_fields = node_fields(before)?;
+    let mut after_fields = node_fields(after)?;
+
+    let code_diff = match (
+        take_field(&mut before_fields, FIELD_CODE_BASE64),
+        take_field(&mut after_fields, FIELD_CODE_BASE64),
+    ) {
+        (Some(before_code), Some(after_code)) if before_code == after_code => None,
+        (None, None) => None,
+        (before_code, after_code) => {
+            match (decode_code(&before_code), decode_code(&after_code)) {
+                (Some(before_code), Some(after_code)) => Some(
+                    diff::lines(&before_code, &after_code)
+                        .into_iter()
+                        .map(|line| match line {
+                            diff::Result::Left(line) => CodeDiffLine::Removed(line.to_owned()),
+                            diff::Result::Both(line, _) => CodeDiffLine::Unchanged(line.to_owned()),
+                            diff::Result::Right(line) => CodeDiffLine::Added(line.to_owned()),
+                        })
+                        .collect(),
+                ),
+                // Code which isn't valid text is compared like any other field
+                _ => {
+                    before_fields.extend(before_code.map(|code| (FIELD_CODE_BASE64.into(), code)));
+                    after_fields.extend(after_code.map(|code| (FIELD_CODE_BASE64.into(), code)));
+                    None
+                }
+            }
+        }
+    };
+
+    let mut fields = vec![];
+    for (name, before_value) in &before_fields {
+        let after_value = after_fields
+            .iter()
+            .find(|(after_name, _)| after_name == name)
+            .map(|(_, value)| value);
+        if after_value != Some(before_value) {
+            fields.push(PkgDiffField {
+                name: name.to_owned(),
+                before: Some(before_value.to_owned()),
+                after: after_value.cloned(),
+            });
+        }
+    }
+    for (name, after_value) in &after_fields {
+        if !before_fields
+            .iter()
+            .any(|(before_name, _)| before_name == name)
+        {
+            fields.push(PkgDiffField {
+                name: name.to_owned(),
+                before: None,
+                after: Some(after_value.to_owned()),
+            });
+        }
+    }
+
+    Ok((fields, code_diff))
+}
+
+fn take_field(fields: &mut Vec<(String, String)>, name: &str) -> Option<String> {
+    let position = fields.iter().position(|(field, _)| field == name)?;
+    Some(fields.remove(position).1)
+}
+
+/// A missing code field decodes as empty code, so that adding or removing code still gives a
+/// line diff.
+fn decode_code(code_base64: &Option<String>) -> Option<String> {
+    match code_base64 {
+        Some(code_base64) => LENIENT_BASE64
+            .decode(code_base64)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok()),
+        None => Some(String::new()),
+    }
+}
+
+/// Splits a node's serialized form back into its `key:len=value` fields.
+fn node_fields(node: &PkgNode) -> PkgResult<Vec<(String, String)>> {
+    let mut bytes = vec![];
+    node.write_bytes(&mut bytes)?;
+
+    let invalid = || SiPkgError::NodeBytesInvalid(node.node_kind_str());
+    let mut fields = vec![];
+    let mut rest = bytes.as_slice();
+    while !rest.is_empty() {
+        let colon = rest.iter().position(|b| *b == b':').ok_or_else(invalid)?;
+        let key = String::from_utf8_lossy(&rest[..colon]).into_owned();
+        rest = &rest[colon + 1..];
+
+        let equals = rest.iter().position(|b| *b == b'=').ok_or_else(invalid)?;
+        let len: usize = std::str::from_utf8(&rest[..equals])
+            .ok()
+            .and_then(|len| len.parse().ok())
+            .ok_or_else(invalid)?;
+        rest = &rest[equals + 1..];
+
+        let value = rest.get(..len).ok_or_else(invalid)?;
+        let value = String::from_utf8_lossy(value).into_owned();
+        rest = &rest[len..];
+        rest = rest.strip_prefix(b"\n").ok_or_else(invalid)?;
+
+        if key != FIELD_NODE_KIND {
+            fields.push((key, value));
+        }
+    }
+
+    Ok(fields)
+}
@@ -0,0 +1,324 @@
+//! A human-readable, diffable on-disk layout for packages, meant to be kept in version control.
+//!
+//! ```text
+//! <dir>/
+//!   pkg.yaml                                  package metadata, dependencies and change sets
+//!   funcs/<func>.yaml                         a func, without its code
+//!   funcs/<func>.ts                           the func's code, as plain text
+//!   schemas/<schema>/schema.yaml              a schema, without its variants
+//!   schemas/<schema>/variants/<variant>/variant.yaml
+//!   schemas/<schema>/variants/<variant>/domain.yaml
+//!   schemas/<schema>/variants/<variant>/secrets.yaml
+//!   schemas/<schema>/variants/<variant>/resource_value.yaml
+//!   schemas/<schema>/variants/<variant>/secret_definition.yaml   (if the variant has one)
+//! ```
+//!
+//! `pkg.yaml` and `schema.yaml` list the file names of their funcs, schemas and variants, which
+//! keeps their order stable across a round trip.
+
+use std::path::Path;
+
+use base64::{
+    alphabet,
+    engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use tokio::fs;
+
+use crate::{PkgResult, PkgSpec, SiPkgError};
+
+const PKG_FILE: &str = "pkg.yaml";
+const SCHEMA_FILE: &str = "schema.yaml";
+const VARIANT_FILE: &str = "variant.yaml";
+const FUNCS_DIR: &str = "funcs";
+const SCHEMAS_DIR: &str = "schemas";
+const VARIANTS_DIR: &str = "variants";
+
+/// The prop trees of a variant which are kept in their own files, by spec field and file name.
+const PROP_TREE_FILES: &[(&str, &str)] = &[
+    ("domain", "domain.yaml"),
+    ("secrets", "secrets.yaml"),
+    ("resourceValue", "resource_value.yaml"),
+    ("secretDefinition", "secret_definition.yaml"),
+];
+
+/// Func code is stored both with and without padding, so decoding must accept either.
+pub(crate) const LENIENT_BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Writes `spec` to `dir`. Any `funcs` and `schemas` directories already in `dir` are replaced so
+/// that removed funcs and schemas don't linger.
+pub(crate) async fn write_spec(spec: &PkgSpec, dir: &Path) -> PkgResult<()> {
+    let mut pkg = to_object(spec)?;
+
+    let funcs_dir = dir.join(FUNCS_DIR);
+    let schemas_dir = dir.join(SCHEMAS_DIR);
+    for managed_dir in [&funcs_dir, &schemas_dir] {
+        if fs::try_exists(managed_dir).await? {
+            fs::remove_dir_all(managed_dir).await?;
+        }
+        fs::create_dir_all(managed_dir).await?;
+    }
+
+    let mut func_names = FileNames::default();
+    for func in take_array(&mut pkg, "funcs") {
+        let mut func = into_object(func)?;
+        let file_name = func_names.unique(string_field(&func, "name"));
+        extract_code(&mut func, &funcs_dir, &file_name).await?;
+        write_yaml(&funcs_dir.join(format!("{file_name}.yaml")), &func).await?;
+        func_names.push(file_name);
+    }
+
+    let mut schema_names = FileNames::default();
+    for schema in take_array(&mut pkg, "schemas") {
+        let mut schema = into_object(schema)?;
+        let file_name = schema_names.unique(string_field(&schema, "name"));
+        let schema_dir = schemas_dir.join(&file_name);
+        let variants_dir = schema_dir.join(VARIANTS_DIR);
+        fs::create_dir_all(&variants_dir).await?;
+
+        let mut variant_names = FileNames::default();
+        for variant in take_array(&mut schema, "variants") {
+            let mut variant = into_object(variant)?;
+            let variant_file_name = variant_names.unique(string_field(&variant, "name"));
+            let variant_dir = variants_dir.join(&variant_file_name);
+            fs::create_dir_all(&variant_dir).await?;
+
+            for (field, prop_tree_file) in PROP_TREE_FILES {
+                match variant.remove(*field) {
+                    None | Some(Value::Null) => {}
+                    Some(prop_tree) => {
+                        write_yaml(&variant_dir.join(prop_tree_file), &prop_tree).await?
+                    }
+                }
+            }
+            write_yaml(&variant_dir.join(VARIANT_FILE), &variant).await?;
+            variant_names.push(variant_file_name);
+        }
+
+        schema.insert("variants".to_owned(), variant_names.into_value());
+        write_yaml(&schema_dir.join(SCHEMA_FILE), &schema).await?;
+        schema_names.push(file_name);
+    }
+
+    pkg.insert("funcs".to_owned(), func_names.into_value());
+    pkg.insert("schemas".to_owned(), schema_names.into_value());
+    write_yaml(&dir.join(PKG_FILE), &pkg).await?;
+
+    Ok(())
+}
+
+/// Reads a spec written by [`write_spec`] from `dir`.
+pub(crate) async fn read_spec(dir: &Path) -> PkgResult<PkgSpec> {
+    let mut pkg: Map<String, Value> = read_yaml(&dir.join(PKG_FILE)).await?;
+
+    let funcs_dir = dir.join(FUNCS_DIR);
+    let mut funcs = vec![];
+    for file_name in file_names(&mut pkg, "funcs", PKG_FILE)? {
+        let mut func: Map<String, Value> =
+            read_yaml(&funcs_dir.join(format!("{file_name}.yaml"))).await?;
+        restore_code(&mut func, &funcs_dir).await?;
+        funcs.push(Value::Object(func));
+    }
+
+    let mut schemas = vec![];
+    for file_name in file_names(&mut pkg, "schemas", PKG_FILE)? {
+        let schema_dir = dir.join(SCHEMAS_DIR).join(&file_name);
+        let mut schema: Map<String, Value> = read_yaml(&schema_dir.join(SCHEMA_FILE)).await?;
+
+        let mut variants = vec![];
+        for variant_file_name in file_names(&mut schema, "variants", SCHEMA_FILE)? {
+            let variant_dir = schema_dir.join(VARIANTS_DIR).join(&variant_file_name);
+            let mut variant: Map<String, Value> =
+                read_yaml(&variant_dir.join(VARIANT_FILE)).await?;
+            for (field, prop_tree_file) in PROP_TREE_FILES {
+                let path = variant_dir.join(prop_tree_file);
+                if fs::try_exists(&path).await? {
+                    variant.insert((*field).to_owned(), read_yaml(&path).await?);
+                }
+            }
+            variants.push(Value::Object(variant));
+        }
+
+        schema.insert("variants".to_owned(), Value::Array(variants));
+        schemas.push(Value::Object(schema));
+    }
+
+    pkg.insert("funcs".to_owned(), Value::Array(funcs));
+    pkg.insert("schemas".to_owned(), Value::Array(schemas));
+
+    Ok(serde_json::from_value(Value::Object(pkg))?)
+}
+
+/// Moves a func's code out of its spec and into a `.ts` file next to it. Code which can't be
+/// re-encoded exactly as it was is left in the spec, so that the func's hash is preserved.
+async fn extract_code(
+    func: &mut Map<String, Value>,
+    funcs_dir: &Path,
+    file_name: &str,
+) -> PkgResult<()> {
+    let data = match func.get_mut("data") {
+        Some(Value::Object(data)) => data,
+        _ => return Ok(()),
+    };
+    let code_base64 = match data.get("codeBase64") {
+        Some(Value::String(code_base64)) => code_base64.clone(),
+        _ => return Ok(()),
+    };
+    let code = match LENIENT_BASE64
+        .decode(&code_base64)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+    {
+        Some(code) => code,
+        None => return Ok(()),
+    };
+
+    let padded = if general_purpose::STANDARD_NO_PAD.encode(&code) == code_base64 {
+        false
+    } else if general_purpose::STANDARD.encode(&code) == code_base64 {
+        true
+    } else {
+        return Ok(());
+    };
+
+    let code_file = format!("{file_name}.ts");
+    fs::write(funcs_dir.join(&code_file), code).await?;
+
+    data.remove("codeBase64");
+    data.insert("codeFile".to_owned(), Value::String(code_file));
+    if padded {
+        data.insert("codeBase64Padded".to_owned(), Value::Bool(true));
+    }
+
+    Ok(())
+}
+
+async fn restore_code(func: &mut Map<String, Value>, funcs_dir: &Path) -> PkgResult<()> {
+    let data = match func.get_mut("data") {
+        Some(Value::Object(data)) => data,
+        _ => return Ok(()),
+    };
+    let code_file = match data.remove("codeFile") {
+        Some(Value::String(code_file))
+            if is_file_name(&code_file) && code_file.ends_with(".ts") =>
+        {
+            code_file
+        }
+        Some(_) => {
+            return Err(SiPkgError::source_dir_invalid(
+                "codeFile must be the name of a .ts file in the funcs dir",
+            ))
+        }
+        None => return Ok(()),
+    };
+    let padded = matches!(data.remove("codeBase64Padded"), Some(Value::Bool(true)));
+
+    let code = fs::read(funcs_dir.join(&code_file)).await?;
+    let code_base64 = if padded {
+        general_purpose::STANDARD.encode(code)
+    } else {
+        general_purpose::STANDARD_NO_PAD.encode(code)
+    };
+    data.insert("codeBase64".to_owned(), Value::String(code_base64));
+
+    Ok(())
+}
+
+/// Unique, filesystem safe file names derived from spec names, in the order they were taken.
+#[derive(Default)]
+struct FileNames(Vec<String>);
+
+impl FileNames {
+    fn unique(&self, name: &str) -> String {
+        let mut base: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if base.is_empty() || base.starts_with('.') {
+            base.insert(0, '_');
+        }
+
+        let mut file_name = base.clone();
+        let mut suffix = 1;
+        while self.0.contains(&file_name) {
+            suffix += 1;
+            file_name = format!("{base}_{suffix}");
+        }
+
+        file_name
+    }
+
+    fn push(&mut self, file_name: String) {
+        self.0.push(file_name);
+    }
+
+    fn into_value(self) -> Value {
+        Value::Array(self.0.into_iter().map(Value::String).collect())
+    }
+}
+
+fn file_names(object: &mut Map<String, Value>, field: &str, file: &str) -> PkgResult<Vec<String>> {
+    match object.remove(field) {
+        None => Ok(vec![]),
+        Some(Value::Array(names)) => names
+            .into_iter()
+            .map(|name| match name {
+                Value::String(name) if is_file_name(&name) => Ok(name),
+                _ => Err(SiPkgError::source_dir_invalid(format!(
+                    "{field} in {file} must be a list of file names"
+                ))),
+            })
+            .collect(),
+        Some(_) => Err(SiPkgError::source_dir_invalid(format!(
+            "{field} in {file} must be a list of file names"
+        ))),
+    }
+}
+
+/// Whether `name` names an entry of a directory, rather than the directory itself, its parent or
+/// a path somewhere else.
+fn is_file_name(name: &str) -> bool {
+    !matches!(name, "" | "." | "..") && !name.contains(['/', '\\'])
+}
+
+fn to_object(value: impl Serialize) -> PkgResult<Map<String, Value>> {
+    into_object(serde_json::to_value(value)?)
+}
+
+fn into_object(value: Value) -> PkgResult<Map<String, Value>> {
+    match value {
+        Value::Object(object) => Ok(object),
+        _ => Err(SiPkgError::source_dir_invalid("expected a mapping")),
+    }
+}
+
+fn take_array(object: &mut Map<String, Value>, field: &str) -> Vec<Value> {
+    match object.remove(field) {
+        Some(Value::Array(values)) => values,
+        _ => vec![],
+    }
+}
+
+fn string_field<'a>(object: &'a Map<String, Value>, field: &str) -> &'a str {
+    object.get(field).and_then(Value::as_str).unwrap_or("")
+}
+
+async fn write_yaml(path: &Path, value: &impl Serialize) -> PkgResult<()> {
+    fs::write(path, serde_yaml::to_string(value)?).await?;
+    Ok(())
+}
+
+async fn read_yaml<T: DeserializeOwned>(path: &Path) -> PkgResult<T> {
+    Ok(serde_yaml::from_str(&fs::read_to_string(path).await?)?)
+}
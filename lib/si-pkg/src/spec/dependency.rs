@@ -0,0 +1,38 @@
+use derive_builder::Builder;
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+
+use super::SpecError;
+
+/// A module which must be installed before the package declaring it, named by its module name
+/// and constrained by a semver range (such as `^1.2`) on its version.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError", validate = "Self::validate"))]
+pub struct DependencySpec {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub version_req: String,
+}
+
+impl DependencySpec {
+    #[must_use]
+    pub fn builder() -> DependencySpecBuilder {
+        DependencySpecBuilder::default()
+    }
+}
+
+impl DependencySpecBuilder {
+    fn validate(&self) -> Result<(), String> {
+        match &self.version_req {
+            Some(version_req) => VersionReq::parse(version_req).map(|_| ()).map_err(|err| {
+                format!(
+                    "dependency {} has invalid version requirement {version_req}: {err}",
+                    self.name.as_deref().unwrap_or_default()
+                )
+            }),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use derive_builder::UninitializedFieldError;
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
@@ -113,6 +115,49 @@ impl PropSpec {
     pub fn builder() -> PropSpecBuilder {
         PropSpecBuilder::default()
     }
+
+    /// Adds the unique ids of the funcs used by this prop and the props beneath it.
+    pub(crate) fn collect_func_unique_ids<'a>(&'a self, func_unique_ids: &mut HashSet<&'a str>) {
+        let data = match self {
+            PropSpec::Array { data, .. }
+            | PropSpec::Boolean { data, .. }
+            | PropSpec::Map { data, .. }
+            | PropSpec::Number { data, .. }
+            | PropSpec::Object { data, .. }
+            | PropSpec::String { data, .. } => data,
+        };
+        if let Some(data) = data {
+            func_unique_ids.extend(data.func_unique_id.as_deref());
+            for validation in data.validations.iter().flatten() {
+                if let ValidationSpec::CustomValidation { func_unique_id, .. } = validation {
+                    func_unique_ids.insert(func_unique_id.as_str());
+                }
+            }
+        }
+
+        match self {
+            PropSpec::Array { type_prop, .. } => type_prop.collect_func_unique_ids(func_unique_ids),
+            PropSpec::Map {
+                type_prop,
+                map_key_funcs,
+                ..
+            } => {
+                func_unique_ids.extend(
+                    map_key_funcs
+                        .iter()
+                        .flatten()
+                        .map(|func| func.func_unique_id.as_str()),
+                );
+                type_prop.collect_func_unique_ids(func_unique_ids);
+            }
+            PropSpec::Object { entries, .. } => {
+                for entry in entries {
+                    entry.collect_func_unique_ids(func_unique_ids);
+                }
+            }
+            PropSpec::Boolean { .. } | PropSpec::Number { .. } | PropSpec::String { .. } => {}
+        }
+    }
 }
 
 #[remain::sorted]
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::spec::authentication_func::AuthenticationFuncSpec;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -158,6 +160,53 @@ impl SchemaVariantSpec {
     pub fn builder() -> SchemaVariantSpecBuilder {
         SchemaVariantSpecBuilder::default()
     }
+
+    /// The unique ids of every func the variant uses, from its asset func to the funcs of its
+    /// props and sockets.
+    pub fn func_unique_ids(&self) -> HashSet<&str> {
+        let mut func_unique_ids = HashSet::new();
+        if let Some(data) = &self.data {
+            func_unique_ids.insert(data.func_unique_id.as_str());
+        }
+        func_unique_ids.extend(
+            self.action_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        func_unique_ids.extend(
+            self.auth_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        func_unique_ids.extend(
+            self.leaf_functions
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        func_unique_ids.extend(
+            self.si_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        func_unique_ids.extend(
+            self.root_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.as_str()),
+        );
+        func_unique_ids.extend(
+            self.sockets
+                .iter()
+                .filter_map(|socket| socket.data.as_ref()?.func_unique_id.as_deref()),
+        );
+        for prop in [&self.domain, &self.secrets, &self.resource_value]
+            .into_iter()
+            .chain(self.secret_definition.as_ref())
+        {
+            prop.collect_func_unique_ids(&mut func_unique_ids);
+        }
+
+        func_unique_ids
+    }
 }
 
 impl SchemaVariantSpecBuilder {
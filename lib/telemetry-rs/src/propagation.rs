@@ -0,0 +1,29 @@
+//! Propagation of trace context across process boundaries, so that the spans of one user action
+//! form a single trace no matter how many services it passes through.
+//!
+//! The format is whichever propagator the application installed, which is
+//! [W3C trace context](https://www.w3.org/TR/trace-context/) for applications set up with
+//! `telemetry-application`. Without an OpenTelemetry layer, nothing is injected or extracted.
+
+use opentelemetry::{global, trace::TraceContextExt};
+use tracing::Span;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+pub use opentelemetry::propagation::{Extractor, Injector};
+
+/// Writes the trace context of `span` (i.e. `traceparent` and `tracestate`) through `injector`.
+pub fn inject(span: &Span, injector: &mut dyn Injector) {
+    let context = span.context();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, injector));
+}
+
+/// Makes the remote span whose trace context is read through `extractor` the parent of `span`,
+/// continuing its trace. The span is left as is if there is no valid trace context to read.
+///
+/// This should be called before any child spans of `span` are created.
+pub fn set_parent(span: &Span, extractor: &dyn Extractor) {
+    let context = global::get_text_map_propagator(|propagator| propagator.extract(extractor));
+    if context.span().span_context().is_valid() {
+        span.set_parent(context);
+    }
+}
@@ -270,11 +270,18 @@ async fn process_resolver_function_requests(
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.resolver_function_request",
+    skip_all,
+    level = "info",
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn resolver_function_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ResolverFunctionRequest>,
 ) {
+    request.continue_trace(&Span::current());
     let (cyclone_request, reply_mailbox) = request.into_parts();
     let reply_mailbox = match reply_mailbox {
         Some(reply_mailbox) => reply_mailbox,
@@ -425,11 +432,18 @@ async fn process_validation_requests(
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.validation_request",
+    skip_all,
+    level = "info",
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn validation_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ValidationRequest>,
 ) {
+    request.continue_trace(&Span::current());
     if let Err(err) = validation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "validation execution failed");
     }
@@ -544,11 +558,18 @@ async fn process_schema_variant_definition_requests(
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.schema_variant_definition_request",
+    skip_all,
+    level = "info",
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn schema_variant_definition_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<SchemaVariantDefinitionRequest>,
 ) {
+    request.continue_trace(&Span::current());
     if let Err(err) = schema_variant_definition_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "schema variant definition execution failed");
     }
@@ -658,11 +679,18 @@ async fn process_action_run_requests(
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.action_run_request",
+    skip_all,
+    level = "info",
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn action_run_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ActionRunRequest>,
 ) {
+    request.continue_trace(&Span::current());
     if let Err(err) = action_run_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "action run execution failed");
     }
@@ -773,11 +801,18 @@ async fn process_reconciliation_requests(
     Ok(())
 }
 
+#[instrument(
+    name = "veritech.reconciliation_request",
+    skip_all,
+    level = "info",
+    fields(otel.kind = %FormattedSpanKind(SpanKind::Consumer))
+)]
 async fn reconciliation_request_task(
     nats: NatsClient,
     cyclone_pool: Pool<LocalUdsInstanceSpec>,
     request: Request<ReconciliationRequest>,
 ) {
+    request.continue_trace(&Span::current());
     if let Err(err) = reconciliation_request(nats, cyclone_pool, request).await {
         warn!(error = ?err, "reconciliation execution failed");
     }